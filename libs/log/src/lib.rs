@@ -97,7 +97,7 @@ pub struct CommonFields<'a> {
 ///
 /// ## Basic Usage
 ///
-/// ```rust
+/// ```rust,ignore
 /// use your_crate::log::{self, CommonFields};
 /// use your_crate::config::LoggingConfig;
 ///
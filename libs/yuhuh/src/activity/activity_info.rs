@@ -0,0 +1,457 @@
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use utoipa::ToSchema;
+
+use crate::{
+    activity::model::{ActivityType, CustomActivityType},
+    error::ActivityInfoError,
+};
+
+/// Highest assist level an e-bike can report.
+const MAX_ASSIST_LEVEL: u8 = 5;
+
+/// Highest rating of perceived exertion.
+const MAX_RPE: f32 = 10.0;
+
+// =============================================================================
+// Weight lifting
+// =============================================================================
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct WeightLiftingInfo {
+    pub exercises: Vec<Exercise>,
+    /// Length of the whole session, used to estimate calories burned.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_seconds: Option<u32>,
+    /// Rating of perceived exertion from 1 to 10, used for training load.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rpe: Option<f32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Exercise {
+    /// Name of the exercise, e.g. "bench press".
+    pub name: String,
+    pub sets: Vec<ExerciseSet>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ExerciseSet {
+    pub reps: u32,
+    /// Weight lifted in kilograms, zero for bodyweight exercises.
+    pub weight_kg: f32,
+}
+
+// =============================================================================
+// Running, jogging and walking
+// =============================================================================
+
+/// Info for activities on foot, requiring at least a distance or duration.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct DistanceInfo {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub distance_km: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_seconds: Option<u32>,
+    /// Worked out from distance and duration when not given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pace_seconds_per_km: Option<f32>,
+    /// Rating of perceived exertion from 1 to 10, used for training load.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rpe: Option<f32>,
+}
+
+// =============================================================================
+// Cycling, e-biking and mountain biking
+// =============================================================================
+
+/// Info for rides, requiring at least a distance or duration.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct CyclingInfo {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub distance_km: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_seconds: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub elevation_gain_m: Option<f32>,
+    /// Motor assistance from 0 (none) to 5, for e-bikes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub assist_level: Option<u8>,
+    /// Rating of perceived exertion from 1 to 10, used for training load.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rpe: Option<f32>,
+}
+
+// =============================================================================
+// Activity info
+// =============================================================================
+
+/// Typed `activity_info`, with the shape decided by the entry's
+/// `activity_type`.
+///
+/// `Other` activities are free-form and accept any JSON object, as do custom
+/// types, which are further checked against their own JSON Schema.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(untagged)]
+pub enum ActivityInfo {
+    WeightLifting(WeightLiftingInfo),
+    Distance(DistanceInfo),
+    Cycling(CyclingInfo),
+    #[schema(value_type = Object)]
+    Other(serde_json::Map<String, serde_json::Value>),
+}
+
+fn parse<T: DeserializeOwned>(
+    activity_type: &ActivityType,
+    value: serde_json::Value,
+) -> Result<T, ActivityInfoError> {
+    serde_json::from_value(value).map_err(|e| {
+        ActivityInfoError::new(format!(
+            "activity_info does not match {} - {}",
+            activity_type, e
+        ))
+    })
+}
+
+fn positive(name: &str, value: Option<f32>) -> Result<(), ActivityInfoError> {
+    match value {
+        Some(v) if !(v.is_finite() && v > 0.0) => Err(ActivityInfoError::new(format!(
+            "{} must be positive, but got {} instead",
+            name, v
+        ))),
+        _ => Ok(()),
+    }
+}
+
+fn rpe(value: Option<f32>) -> Result<(), ActivityInfoError> {
+    match value {
+        Some(v) if !(1.0..=MAX_RPE).contains(&v) => Err(ActivityInfoError::new(format!(
+            "rpe must be between 1 and {}, but got {} instead",
+            MAX_RPE, v
+        ))),
+        _ => Ok(()),
+    }
+}
+
+impl WeightLiftingInfo {
+    fn validate(&self) -> Result<(), ActivityInfoError> {
+        if self.exercises.is_empty() {
+            return Err(ActivityInfoError::new("at least one exercise is required"));
+        }
+
+        positive("duration_seconds", self.duration_seconds.map(|d| d as f32))?;
+        rpe(self.rpe)?;
+
+        for exercise in &self.exercises {
+            if exercise.name.trim().is_empty() {
+                return Err(ActivityInfoError::new("exercise name must not be empty"));
+            }
+
+            if exercise.sets.is_empty() {
+                return Err(ActivityInfoError::new(format!(
+                    "{} needs at least one set",
+                    exercise.name
+                )));
+            }
+
+            for set in &exercise.sets {
+                if set.reps == 0 {
+                    return Err(ActivityInfoError::new(format!(
+                        "{} has a set with no reps",
+                        exercise.name
+                    )));
+                }
+
+                if !(set.weight_kg.is_finite() && set.weight_kg >= 0.0) {
+                    return Err(ActivityInfoError::new(format!(
+                        "{} has a set with an invalid weight {}",
+                        exercise.name, set.weight_kg
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl DistanceInfo {
+    fn validate(&mut self) -> Result<(), ActivityInfoError> {
+        if self.distance_km.is_none() && self.duration_seconds.is_none() {
+            return Err(ActivityInfoError::new(
+                "either distance_km or duration_seconds is required",
+            ));
+        }
+
+        positive("distance_km", self.distance_km)?;
+        positive("duration_seconds", self.duration_seconds.map(|d| d as f32))?;
+        positive("pace_seconds_per_km", self.pace_seconds_per_km)?;
+        rpe(self.rpe)?;
+
+        if self.pace_seconds_per_km.is_none()
+            && let (Some(distance_km), Some(duration_seconds)) =
+                (self.distance_km, self.duration_seconds)
+        {
+            self.pace_seconds_per_km = Some(duration_seconds as f32 / distance_km);
+        }
+
+        Ok(())
+    }
+}
+
+impl CyclingInfo {
+    fn validate(&self) -> Result<(), ActivityInfoError> {
+        if self.distance_km.is_none() && self.duration_seconds.is_none() {
+            return Err(ActivityInfoError::new(
+                "either distance_km or duration_seconds is required",
+            ));
+        }
+
+        positive("distance_km", self.distance_km)?;
+        positive("duration_seconds", self.duration_seconds.map(|d| d as f32))?;
+        rpe(self.rpe)?;
+
+        if let Some(elevation_gain_m) = self.elevation_gain_m
+            && !(elevation_gain_m.is_finite() && elevation_gain_m >= 0.0)
+        {
+            return Err(ActivityInfoError::new(format!(
+                "elevation_gain_m must not be negative, but got {} instead",
+                elevation_gain_m
+            )));
+        }
+
+        if let Some(assist_level) = self.assist_level
+            && assist_level > MAX_ASSIST_LEVEL
+        {
+            return Err(ActivityInfoError::new(format!(
+                "assist_level must be between 0 and {}, but got {} instead",
+                MAX_ASSIST_LEVEL, assist_level
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+impl ActivityInfo {
+    /// Parses and validates raw `activity_info` against the schema for
+    /// `activity_type`, filling in anything that can be derived.
+    pub fn parse(
+        activity_type: &ActivityType,
+        value: serde_json::Value,
+    ) -> Result<ActivityInfo, ActivityInfoError> {
+        match activity_type {
+            ActivityType::WeightLifting => {
+                let info: WeightLiftingInfo = parse(activity_type, value)?;
+                info.validate()?;
+                Ok(ActivityInfo::WeightLifting(info))
+            }
+            ActivityType::Running | ActivityType::Jogging | ActivityType::Walking => {
+                let mut info: DistanceInfo = parse(activity_type, value)?;
+                info.validate()?;
+                Ok(ActivityInfo::Distance(info))
+            }
+            ActivityType::Cycling | ActivityType::Ebiking | ActivityType::MountainBiking => {
+                let info: CyclingInfo = parse(activity_type, value)?;
+                info.validate()?;
+                Ok(ActivityInfo::Cycling(info))
+            }
+            ActivityType::Other | ActivityType::Custom(_) => match value {
+                serde_json::Value::Object(map) => Ok(ActivityInfo::Other(map)),
+                _ => Err(ActivityInfoError::new("activity_info must be an object")),
+            },
+        }
+    }
+
+    /// Length of the activity in seconds, worked out from distance and pace
+    /// when no duration was recorded.
+    pub fn duration_seconds(&self) -> Option<f32> {
+        let seconds = match self {
+            ActivityInfo::WeightLifting(info) => info.duration_seconds.map(|d| d as f32),
+            ActivityInfo::Distance(info) => info
+                .duration_seconds
+                .map(|d| d as f32)
+                .or_else(|| Some(info.distance_km? * info.pace_seconds_per_km?)),
+            ActivityInfo::Cycling(info) => info.duration_seconds.map(|d| d as f32),
+            ActivityInfo::Other(info) => info
+                .get("duration_seconds")
+                .and_then(serde_json::Value::as_f64)
+                .map(|d| d as f32),
+        }?;
+
+        (seconds.is_finite() && seconds > 0.0).then_some(seconds)
+    }
+
+    /// Rating of perceived exertion, if one was recorded.
+    pub fn rpe(&self) -> Option<f32> {
+        let rpe = match self {
+            ActivityInfo::WeightLifting(info) => info.rpe,
+            ActivityInfo::Distance(info) => info.rpe,
+            ActivityInfo::Cycling(info) => info.rpe,
+            ActivityInfo::Other(info) => info
+                .get("rpe")
+                .and_then(serde_json::Value::as_f64)
+                .map(|r| r as f32),
+        }?;
+
+        (1.0..=MAX_RPE).contains(&rpe).then_some(rpe)
+    }
+
+    /// Converts back into JSON for storage.
+    pub fn into_value(self) -> serde_json::Value {
+        serde_json::to_value(self).unwrap_or_default()
+    }
+}
+
+/// Compiles a custom type's JSON Schema, failing if it isn't a valid schema.
+pub fn compile_schema(
+    schema: &serde_json::Value,
+) -> Result<jsonschema::Validator, ActivityInfoError> {
+    jsonschema::validator_for(schema)
+        .map_err(|e| ActivityInfoError::new(format!("invalid activity_info_schema - {}", e)))
+}
+
+impl CustomActivityType {
+    /// Checks `activity_info` against this type's JSON Schema, if it has one.
+    pub fn validate_activity_info(
+        &self,
+        info: &serde_json::Value,
+    ) -> Result<(), ActivityInfoError> {
+        let Some(schema) = &self.activity_info_schema else {
+            return Ok(());
+        };
+
+        let errors: Vec<String> = compile_schema(schema)?
+            .iter_errors(info)
+            .map(|e| e.to_string())
+            .collect();
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ActivityInfoError::new(format!(
+                "activity_info does not match {} - {}",
+                self.name,
+                errors.join(", ")
+            )))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn weight_lifting_validated() {
+        let info = ActivityInfo::parse(
+            &ActivityType::WeightLifting,
+            json!({
+                "exercises": [
+                    { "name": "squat", "sets": [{ "reps": 5, "weight_kg": 100.0 }] }
+                ]
+            }),
+        )
+        .expect("valid weight lifting info");
+
+        assert!(matches!(info, ActivityInfo::WeightLifting(_)));
+
+        let no_reps = ActivityInfo::parse(
+            &ActivityType::WeightLifting,
+            json!({ "exercises": [{ "name": "squat", "sets": [{ "reps": 0, "weight_kg": 100.0 }] }] }),
+        );
+        assert!(no_reps.is_err());
+    }
+
+    #[test]
+    fn pace_derived_from_distance_and_duration() {
+        let info = ActivityInfo::parse(
+            &ActivityType::Running,
+            json!({ "distance_km": 5.0, "duration_seconds": 1500 }),
+        )
+        .expect("valid running info");
+
+        assert_eq!(
+            info,
+            ActivityInfo::Distance(DistanceInfo {
+                distance_km: Some(5.0),
+                duration_seconds: Some(1500),
+                pace_seconds_per_km: Some(300.0),
+                rpe: None,
+            })
+        );
+        assert!(ActivityInfo::parse(&ActivityType::Walking, json!({})).is_err());
+        assert!(
+            ActivityInfo::parse(
+                &ActivityType::Walking,
+                json!({ "distance_km": 1.0, "rpe": 11 })
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn cycling_rejects_unknown_fields_and_bad_assist() {
+        assert!(
+            ActivityInfo::parse(
+                &ActivityType::Ebiking,
+                json!({ "distance_km": 20.0, "assist_level": 3, "elevation_gain_m": 150.0 })
+            )
+            .is_ok()
+        );
+        assert!(
+            ActivityInfo::parse(
+                &ActivityType::Ebiking,
+                json!({ "distance_km": 20.0, "assist_level": 9 })
+            )
+            .is_err()
+        );
+        assert!(
+            ActivityInfo::parse(&ActivityType::Cycling, json!({ "distanse_km": 20.0 })).is_err()
+        );
+    }
+
+    #[test]
+    fn other_is_free_form() {
+        let info = ActivityInfo::parse(&ActivityType::Other, json!({ "anything": [1, 2, 3] }))
+            .expect("free-form object");
+
+        assert_eq!(info.into_value(), json!({ "anything": [1, 2, 3] }));
+        assert!(ActivityInfo::parse(&ActivityType::Other, json!("string")).is_err());
+    }
+
+    #[test]
+    fn custom_types_checked_against_schema() {
+        let swimming = CustomActivityType {
+            activity_type_id: uuid::Uuid::nil(),
+            user_id: None,
+            name: "Swimming".to_string(),
+            activity_info_schema: Some(json!({
+                "type": "object",
+                "properties": { "laps": { "type": "integer", "minimum": 1 } },
+                "required": ["laps"]
+            })),
+            default_met: Some(7.0),
+        };
+
+        assert!(
+            swimming
+                .validate_activity_info(&json!({ "laps": 40 }))
+                .is_ok()
+        );
+        assert!(
+            swimming
+                .validate_activity_info(&json!({ "laps": 0 }))
+                .is_err()
+        );
+        assert!(swimming.validate_activity_info(&json!({})).is_err());
+        assert!(compile_schema(&json!({ "type": "not a type" })).is_err());
+    }
+}
@@ -0,0 +1,415 @@
+//! activity samples HTTP handlers
+//!
+//! This module provides HTTP endpoints for the heart rate, power, cadence and
+//! speed samples recorded during an activity, and the time spent in each
+//! heart rate zone.
+
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, error, instrument};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::{
+    activity::{
+        samples::{
+            ActivitySample, HeartRateZones, SampleSummary, heart_rate_zones, summarise,
+            validate_samples,
+        },
+        state::ActivityState,
+    },
+    auth::model::Caller,
+    error::YuhuhError,
+    user::{model::User, state::UserState},
+};
+
+// ============================================================================
+// HTTP Request types
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ReplaceActivitySamplesRequest {
+    pub user_id: Uuid,
+    /// Replaces any samples already stored for the activity.
+    pub samples: Vec<ActivitySample>,
+}
+
+/// Request parameters for finding an activity's samples.
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ReadActivitySamplesRequest {
+    /// user ID the activity belongs to.
+    pub user_id: Uuid,
+}
+
+// ============================================================================
+// HTTP Responsed types
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ReadActivitySamplesResponse {
+    /// Samples by offset.
+    pub samples: Vec<ActivitySample>,
+    /// Summary using the user's current heart rate settings.
+    pub summary: SampleSummary,
+}
+
+// ============================================================================
+// Implementations
+// ============================================================================
+
+/// Finds the user, making sure the activity is theirs.
+async fn find_owner(
+    activity_state: &ActivityState,
+    user_state: &UserState,
+    activity_record_id: &Uuid,
+    user_id: &Uuid,
+) -> Result<User, YuhuhError> {
+    let user = user_state.require_user(user_id).await?;
+
+    let owner = activity_state
+        .activity_samples_repo
+        .find_activity_owner(activity_record_id)
+        .await?;
+
+    if owner.as_ref() != Some(user_id) {
+        error!(activity_record_id = ?activity_record_id, user_id = ?user_id, "failed to find activity for user");
+        return Err(YuhuhError::NotFound("activity not found".to_string()));
+    }
+
+    Ok(user)
+}
+
+// =============================================================================
+// HTTP Handlers
+// =============================================================================
+
+/// Store the samples recorded during an activity
+#[utoipa::path(
+    put,
+    path = "activity/{activity_record_id}/samples",
+    tag = "activity",
+    params(("activity_record_id" = Uuid, Path, description = "Activity the samples were recorded during")),
+    responses(
+        (status = 200, description = "Samples stored", body = SampleSummary),
+        (status = 400, description = "Invalid samples"),
+        (status = 404, description = "User or activity not found"),
+))]
+#[instrument(skip(request))]
+pub async fn replace_activity_samples(
+    State(activity_state): State<Arc<ActivityState>>,
+    State(user_state): State<Arc<UserState>>,
+    caller: Caller,
+    Path(activity_record_id): Path<Uuid>,
+    Json(mut request): Json<ReplaceActivitySamplesRequest>,
+) -> Result<(StatusCode, Json<SampleSummary>), YuhuhError> {
+    debug!("entering replace_activity_samples");
+
+    caller.authorise_user(&request.user_id)?;
+
+    validate_samples(&mut request.samples).map_err(YuhuhError::BadRequest)?;
+
+    let user = find_owner(
+        &activity_state,
+        &user_state,
+        &activity_record_id,
+        &request.user_id,
+    )
+    .await?;
+
+    let summary = summarise(
+        &request.samples,
+        user.max_heart_rate,
+        user.resting_heart_rate,
+    );
+
+    activity_state
+        .activity_samples_repo
+        .replace_activity_samples(&activity_record_id, &request.samples, &summary)
+        .await?;
+
+    Ok((StatusCode::OK, Json(summary)))
+}
+
+/// Find the samples recorded during an activity
+#[utoipa::path(
+    get,
+    path = "activity/{activity_record_id}/samples",
+    tag = "activity",
+    params(
+        ("activity_record_id" = Uuid, Path, description = "Activity the samples were recorded during"),
+        ReadActivitySamplesRequest
+    ),
+    responses(
+        (status = 200, description = "Found samples", body = ReadActivitySamplesResponse),
+        (status = 404, description = "User or activity not found"),
+))]
+#[instrument]
+pub async fn read_activity_samples(
+    State(activity_state): State<Arc<ActivityState>>,
+    State(user_state): State<Arc<UserState>>,
+    caller: Caller,
+    Path(activity_record_id): Path<Uuid>,
+    Query(request): Query<ReadActivitySamplesRequest>,
+) -> Result<(StatusCode, Json<ReadActivitySamplesResponse>), YuhuhError> {
+    debug!("entering read_activity_samples");
+
+    caller.authorise_user(&request.user_id)?;
+
+    let user = find_owner(
+        &activity_state,
+        &user_state,
+        &activity_record_id,
+        &request.user_id,
+    )
+    .await?;
+
+    let samples = activity_state
+        .activity_samples_repo
+        .read_activity_samples(&activity_record_id)
+        .await?;
+
+    let summary = summarise(&samples, user.max_heart_rate, user.resting_heart_rate);
+
+    Ok((
+        StatusCode::OK,
+        Json(ReadActivitySamplesResponse { samples, summary }),
+    ))
+}
+
+/// Find the time spent in each heart rate zone during an activity
+#[utoipa::path(
+    get,
+    path = "activity/{activity_record_id}/zones",
+    tag = "activity",
+    params(
+        ("activity_record_id" = Uuid, Path, description = "Activity the samples were recorded during"),
+        ReadActivitySamplesRequest
+    ),
+    responses(
+        (status = 200, description = "Found heart rate zones", body = HeartRateZones),
+        (status = 400, description = "User has no max heart rate set"),
+        (status = 404, description = "User or activity not found"),
+))]
+#[instrument]
+pub async fn read_heart_rate_zones(
+    State(activity_state): State<Arc<ActivityState>>,
+    State(user_state): State<Arc<UserState>>,
+    caller: Caller,
+    Path(activity_record_id): Path<Uuid>,
+    Query(request): Query<ReadActivitySamplesRequest>,
+) -> Result<(StatusCode, Json<HeartRateZones>), YuhuhError> {
+    debug!("entering read_heart_rate_zones");
+
+    caller.authorise_user(&request.user_id)?;
+
+    let user = find_owner(
+        &activity_state,
+        &user_state,
+        &activity_record_id,
+        &request.user_id,
+    )
+    .await?;
+
+    let max_heart_rate = user.max_heart_rate.ok_or_else(|| {
+        YuhuhError::BadRequest("max_heart_rate must be set to work out zones".to_string())
+    })?;
+
+    let samples = activity_state
+        .activity_samples_repo
+        .read_activity_samples(&activity_record_id)
+        .await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(heart_rate_zones(
+            &samples,
+            max_heart_rate,
+            user.resting_heart_rate,
+        )),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use axum::{
+        body::Body,
+        http::{self, Request, StatusCode},
+    };
+    use http_body_util::BodyExt;
+    use pretty_assertions::assert_eq;
+    use tower::ServiceExt;
+    use uuid::uuid;
+
+    use crate::activity::{
+        activity_samples::{ReadActivitySamplesResponse, ReplaceActivitySamplesRequest},
+        read_activity_entries::ReadActivityEntriesResponse,
+        samples::{ActivitySample, HeartRateZones, SampleSummary},
+    };
+
+    const ALICE_RUN: &str = "aaaaaaaa-aaaa-aaaa-aaaa-aaaaaaaaaaaa";
+
+    fn sample(offset_seconds: i32, heart_rate: i16) -> ActivitySample {
+        ActivitySample {
+            offset_seconds,
+            heart_rate: Some(heart_rate),
+            power_watts: None,
+            cadence: Some(170.0),
+            speed_kmh: Some(10.0),
+        }
+    }
+
+    fn replace_request(
+        activity_record_id: &str,
+        request: &ReplaceActivitySamplesRequest,
+    ) -> Request<Body> {
+        Request::builder()
+            .method("PUT")
+            .uri(format!("/activity/{}/samples", activity_record_id))
+            .header(http::header::CONTENT_TYPE, "application/json")
+            .body(Body::from(
+                serde_json::to_string(request).expect("request is valid body"),
+            ))
+            .unwrap()
+    }
+
+    async fn get(app: axum::Router, uri: String) -> (StatusCode, axum::body::Bytes) {
+        let response = app
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        let status = response.status();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+
+        (status, body)
+    }
+
+    #[tokio::test]
+    async fn samples_stored_and_summarised() {
+        let (app, db, _) = crate::test::common::setup().await;
+
+        sqlx::raw_sql(include_str!("../../migrations/test/activity_samples.sql"))
+            .execute(&db)
+            .await
+            .expect("setup test sql ran successfully");
+
+        let request = ReplaceActivitySamplesRequest {
+            user_id: uuid!("11111111-1111-1111-1111-111111111111"),
+            samples: vec![sample(20, 180), sample(0, 100), sample(10, 125)],
+        };
+
+        let response = app
+            .clone()
+            .oneshot(replace_request(ALICE_RUN, &request))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let summary: SampleSummary =
+            serde_json::from_slice(&body).expect("valid SampleSummary bytes");
+
+        assert_eq!(summary.sample_count, 3);
+        assert_eq!(summary.duration_seconds, 20);
+        assert_eq!(summary.max_heart_rate, Some(180));
+
+        let (status, body) = get(
+            app.clone(),
+            format!(
+                "/activity/{}/samples?user_id=11111111-1111-1111-1111-111111111111",
+                ALICE_RUN
+            ),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let dto: ReadActivitySamplesResponse =
+            serde_json::from_slice(&body).expect("valid ReadActivitySamplesResponse bytes");
+        assert_eq!(
+            dto.samples
+                .iter()
+                .map(|s| s.offset_seconds)
+                .collect::<Vec<_>>(),
+            vec![0, 10, 20]
+        );
+
+        let (status, body) = get(
+            app.clone(),
+            format!(
+                "/activity/{}/zones?user_id=11111111-1111-1111-1111-111111111111",
+                ALICE_RUN
+            ),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let zones: HeartRateZones =
+            serde_json::from_slice(&body).expect("valid HeartRateZones bytes");
+        assert_eq!(zones.below_zones_seconds, 10);
+        assert_eq!(zones.zones[0].seconds, 10);
+
+        // The summary is kept on the activity itself
+        let (status, body) = get(
+            app,
+            "/activity?user_id=11111111-1111-1111-1111-111111111111".to_string(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let dto: ReadActivityEntriesResponse =
+            serde_json::from_slice(&body).expect("valid ReadActivityEntriesResponse bytes");
+        assert_eq!(dto.activity_entries[0].sample_summary, Some(summary));
+    }
+
+    #[tokio::test]
+    async fn other_users_activities_and_missing_max_rejected() {
+        let (app, db, _) = crate::test::common::setup().await;
+
+        sqlx::raw_sql(include_str!("../../migrations/test/activity_samples.sql"))
+            .execute(&db)
+            .await
+            .expect("setup test sql ran successfully");
+
+        let request = ReplaceActivitySamplesRequest {
+            user_id: uuid!("22222222-2222-2222-2222-222222222222"),
+            samples: vec![sample(0, 100)],
+        };
+
+        let response = app
+            .clone()
+            .oneshot(replace_request(ALICE_RUN, &request))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let invalid = ReplaceActivitySamplesRequest {
+            user_id: uuid!("22222222-2222-2222-2222-222222222222"),
+            samples: vec![sample(0, 100), sample(0, 110)],
+        };
+
+        let response = app
+            .clone()
+            .oneshot(replace_request(
+                "bbbbbbbb-bbbb-bbbb-bbbb-bbbbbbbbbbbb",
+                &invalid,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let (status, _) = get(
+            app,
+            "/activity/bbbbbbbb-bbbb-bbbb-bbbb-bbbbbbbbbbbb/zones?user_id=22222222-2222-2222-2222-222222222222"
+                .to_string(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+}
@@ -0,0 +1,225 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use tracing::{debug, error};
+use uuid::Uuid;
+
+use crate::{
+    activity::samples::{ActivitySample, SampleSummary},
+    error::YuhuhError,
+    webhooks::{
+        model::{EventType, WebhookEvent},
+        outbox::enqueue_events,
+    },
+};
+
+// =============================================================================
+// Traits
+// =============================================================================
+
+#[async_trait]
+pub trait ActivitySamplesRepository: std::fmt::Debug + Send + Sync + 'static {
+    /// User the activity belongs to, or `None` if there's no such activity.
+    async fn find_activity_owner(
+        &self,
+        activity_record_id: &Uuid,
+    ) -> Result<Option<Uuid>, YuhuhError>;
+
+    /// Replaces every sample of an activity along with its summary.
+    async fn replace_activity_samples(
+        &self,
+        activity_record_id: &Uuid,
+        samples: &[ActivitySample],
+        summary: &SampleSummary,
+    ) -> Result<(), YuhuhError>;
+
+    /// Every sample of an activity, by offset.
+    async fn read_activity_samples(
+        &self,
+        activity_record_id: &Uuid,
+    ) -> Result<Vec<ActivitySample>, YuhuhError>;
+}
+
+// =============================================================================
+// Production Implementation
+// =============================================================================
+
+#[derive(Debug)]
+pub struct ActivitySamplesRepositoryImpl {
+    pub db: PgPool,
+}
+
+impl ActivitySamplesRepositoryImpl {
+    pub fn new(db: PgPool) -> Self {
+        ActivitySamplesRepositoryImpl { db }
+    }
+}
+
+#[async_trait]
+impl ActivitySamplesRepository for ActivitySamplesRepositoryImpl {
+    async fn find_activity_owner(
+        &self,
+        activity_record_id: &Uuid,
+    ) -> Result<Option<Uuid>, YuhuhError> {
+        debug!(activity_record_id=?activity_record_id, "received find activity owner");
+
+        let owner = sqlx::query_scalar!(
+            r#"
+            SELECT user_id
+            FROM activity_records
+            WHERE activity_record_id = $1::uuid;
+            "#,
+            activity_record_id
+        )
+        .fetch_optional(&self.db)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, "database error while finding activity owner");
+
+            YuhuhError::DatabaseError(e)
+        })?;
+
+        Ok(owner)
+    }
+
+    async fn replace_activity_samples(
+        &self,
+        activity_record_id: &Uuid,
+        samples: &[ActivitySample],
+        summary: &SampleSummary,
+    ) -> Result<(), YuhuhError> {
+        debug!(
+            activity_record_id=?activity_record_id,
+            samples=samples.len(),
+            "received replace activity samples"
+        );
+
+        let mut offset_seconds: Vec<i32> = vec![];
+        let mut heart_rate: Vec<Option<i16>> = vec![];
+        let mut power_watts: Vec<Option<f32>> = vec![];
+        let mut cadence: Vec<Option<f32>> = vec![];
+        let mut speed_kmh: Vec<Option<f32>> = vec![];
+
+        samples.iter().for_each(|s| {
+            offset_seconds.push(s.offset_seconds);
+            heart_rate.push(s.heart_rate);
+            power_watts.push(s.power_watts);
+            cadence.push(s.cadence);
+            speed_kmh.push(s.speed_kmh);
+        });
+
+        let summary_value = serde_json::to_value(summary).map_err(|e| {
+            error!(error = ?e, "failed to serialise sample summary");
+
+            YuhuhError::InternalServerError(e.to_string())
+        })?;
+
+        let mut transaction = self.db.begin().await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM activity_samples
+            WHERE activity_record_id = $1::uuid;
+            "#,
+            activity_record_id
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, "database error while deleting activity samples");
+
+            YuhuhError::DatabaseError(e)
+        })?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO activity_samples (
+                activity_record_id,
+                offset_seconds,
+                heart_rate,
+                power_watts,
+                cadence,
+                speed_kmh
+            )
+            SELECT $1::uuid, * FROM UNNEST(
+                $2::integer[],
+                $3::smallint[],
+                $4::real[],
+                $5::real[],
+                $6::real[]
+            );
+            "#,
+            activity_record_id,
+            &offset_seconds[..],
+            &heart_rate[..] as &[Option<i16>],
+            &power_watts[..] as &[Option<f32>],
+            &cadence[..] as &[Option<f32>],
+            &speed_kmh[..] as &[Option<f32>]
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, "database error while inserting activity samples");
+
+            YuhuhError::DatabaseError(e)
+        })?;
+
+        let user_id = sqlx::query_scalar!(
+            r#"
+            UPDATE activity_records
+            SET sample_summary = $2::jsonb
+            WHERE activity_record_id = $1::uuid
+            RETURNING user_id;
+            "#,
+            activity_record_id,
+            summary_value
+        )
+        .fetch_one(&mut *transaction)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, "database error while updating sample summary");
+
+            YuhuhError::DatabaseError(e)
+        })?;
+
+        let event = WebhookEvent::new(
+            EventType::ActivityUpdated,
+            user_id,
+            &serde_json::json!({
+                "activity_record_id": activity_record_id,
+                "sample_summary": summary,
+            }),
+        )?;
+        enqueue_events(&mut transaction, &[event]).await?;
+
+        transaction.commit().await?;
+
+        Ok(())
+    }
+
+    async fn read_activity_samples(
+        &self,
+        activity_record_id: &Uuid,
+    ) -> Result<Vec<ActivitySample>, YuhuhError> {
+        debug!(activity_record_id=?activity_record_id, "received read activity samples");
+
+        let samples = sqlx::query_as!(
+            ActivitySample,
+            r#"
+            SELECT offset_seconds, heart_rate, power_watts, cadence, speed_kmh
+            FROM activity_samples
+            WHERE activity_record_id = $1::uuid
+            ORDER BY offset_seconds;
+            "#,
+            activity_record_id
+        )
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, "database error while finding activity samples");
+
+            YuhuhError::DatabaseError(e)
+        })?;
+
+        Ok(samples)
+    }
+}
@@ -0,0 +1,299 @@
+//! activity types HTTP handlers
+//!
+//! This module provides HTTP endpoints for custom activity types, which extend
+//! the built-in [`ActivityType`] values without a code release.
+
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::StatusCode,
+};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, instrument};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::{
+    activity::{
+        activity_info::compile_schema,
+        model::{ActivityType, CustomActivityType},
+        state::ActivityState,
+    },
+    auth::model::Caller,
+    error::YuhuhError,
+    user::state::UserState,
+};
+
+// ============================================================================
+// HTTP Request types
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateActivityTypeRequest {
+    /// Owner of the type, or `None` to make it available to everyone.
+    pub user_id: Option<Uuid>,
+    /// Letters and numbers only, e.g. "Swimming" or "RockClimbing".
+    pub name: String,
+    /// JSON Schema that `activity_info` must match.
+    #[schema(value_type = Option<Object>)]
+    pub activity_info_schema: Option<serde_json::Value>,
+    /// MET used to estimate calories burned.
+    pub default_met: Option<f32>,
+}
+
+/// Request parameters for finding activity types.
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ReadActivityTypesRequest {
+    /// user ID to search by.
+    pub user_id: Uuid,
+}
+
+// ============================================================================
+// HTTP Responsed types
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ReadActivityTypesResponse {
+    pub built_in: Vec<ActivityType>,
+    /// Global types and the user's own.
+    pub custom: Vec<CustomActivityType>,
+}
+
+// ============================================================================
+// Implementations
+// ============================================================================
+
+/// Finds the custom type a name refers to, ignoring case and preferring the
+/// user's own types over global ones.
+pub fn resolve_custom_type<'a>(
+    custom_types: &'a [CustomActivityType],
+    name: &str,
+) -> Option<&'a CustomActivityType> {
+    custom_types
+        .iter()
+        .filter(|t| t.name.eq_ignore_ascii_case(name))
+        .min_by_key(|t| t.user_id.is_none())
+}
+
+// =============================================================================
+// HTTP Handlers
+// =============================================================================
+
+/// Create a custom activity type
+#[utoipa::path(
+    post,
+    path = "activity/types",
+    tag = "activity",
+    responses(
+        (status = 201, description = "Activity type created", body = CustomActivityType),
+        (status = 400, description = "Invalid name, schema or MET, or the type already exists"),
+        (status = 404, description = "User not found"),
+))]
+#[instrument]
+pub async fn create_activity_type(
+    State(activity_state): State<Arc<ActivityState>>,
+    State(user_state): State<Arc<UserState>>,
+    caller: Caller,
+    Json(request): Json<CreateActivityTypeRequest>,
+) -> Result<(StatusCode, Json<CustomActivityType>), YuhuhError> {
+    debug!("entering create_activity_type");
+
+    match &request.user_id {
+        Some(user_id) => caller.authorise_user(user_id)?,
+        // Global types are available to every user
+        None => caller.authorise_service()?,
+    }
+
+    ActivityType::validate_custom_name(&request.name)
+        .map_err(|e| YuhuhError::BadRequest(e.to_string()))?;
+
+    if let Some(schema) = &request.activity_info_schema {
+        compile_schema(schema)?;
+    }
+
+    if let Some(default_met) = request.default_met
+        && !(default_met.is_finite() && default_met > 0.0)
+    {
+        return Err(YuhuhError::BadRequest(format!(
+            "default_met must be positive, but got {} instead",
+            default_met
+        )));
+    }
+
+    // A user's own type can't hide a global one of the same name
+    if let Some(user_id) = &request.user_id {
+        user_state.require_user(user_id).await?;
+
+        let existing = activity_state
+            .activity_types_repo
+            .read_activity_types(user_id)
+            .await?;
+
+        if resolve_custom_type(&existing, &request.name).is_some() {
+            return Err(YuhuhError::Conflict(format!(
+                "activity type {} already exists",
+                request.name
+            )));
+        }
+    }
+
+    let activity_type = activity_state
+        .activity_types_repo
+        .create_activity_type(
+            request.user_id,
+            &request.name,
+            request.activity_info_schema,
+            request.default_met,
+        )
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(activity_type)))
+}
+
+/// Find the activity types available to a user
+#[utoipa::path(
+    get,
+    path = "activity/types",
+    tag = "activity",
+    params(ReadActivityTypesRequest),
+    responses(
+        (status = 200, description = "Found activity types", body = ReadActivityTypesResponse),
+        (status = 404, description = "User not found"),
+))]
+#[instrument]
+pub async fn read_activity_types(
+    State(activity_state): State<Arc<ActivityState>>,
+    State(user_state): State<Arc<UserState>>,
+    caller: Caller,
+    Query(request): Query<ReadActivityTypesRequest>,
+) -> Result<(StatusCode, Json<ReadActivityTypesResponse>), YuhuhError> {
+    debug!("entering read_activity_types");
+
+    caller.authorise_user(&request.user_id)?;
+
+    user_state.require_user(&request.user_id).await?;
+
+    let custom = activity_state
+        .activity_types_repo
+        .read_activity_types(&request.user_id)
+        .await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(ReadActivityTypesResponse {
+            built_in: ActivityType::BUILT_IN.to_vec(),
+            custom,
+        }),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use axum::{
+        body::Body,
+        http::{self, Request, StatusCode},
+    };
+    use http_body_util::BodyExt;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use tower::ServiceExt;
+    use uuid::uuid;
+
+    use crate::activity::activity_types::{CreateActivityTypeRequest, ReadActivityTypesResponse};
+
+    fn create_request(request: &CreateActivityTypeRequest) -> Request<Body> {
+        Request::builder()
+            .method("POST")
+            .uri("/activity/types")
+            .header(http::header::CONTENT_TYPE, "application/json")
+            .body(Body::from(
+                serde_json::to_string(request).expect("request is valid body"),
+            ))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn user_types_listed_with_global_types() {
+        let (app, db, _) = crate::test::common::setup().await;
+
+        sqlx::raw_sql(include_str!(
+            "../../migrations/test/create_activity_entries.sql"
+        ))
+        .execute(&db)
+        .await
+        .expect("setup test sql ran successfully");
+
+        let request = CreateActivityTypeRequest {
+            user_id: Some(uuid!("11111111-1111-1111-1111-111111111111")),
+            name: "Bouldering".to_string(),
+            activity_info_schema: Some(json!({
+                "type": "object",
+                "properties": { "problems": { "type": "integer" } }
+            })),
+            default_met: Some(5.8),
+        };
+
+        let response = app.clone().oneshot(create_request(&request)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/activity/types?user_id=11111111-1111-1111-1111-111111111111")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let dto: ReadActivityTypesResponse =
+            serde_json::from_slice(&body).expect("valid ReadActivityTypesResponse bytes");
+
+        assert_eq!(dto.built_in.len(), 8);
+        assert_eq!(
+            dto.custom
+                .iter()
+                .map(|t| t.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["Bouldering", "Swimming", "Yoga"]
+        );
+    }
+
+    #[tokio::test]
+    async fn invalid_and_clashing_types_rejected() {
+        let (app, db, _) = crate::test::common::setup().await;
+
+        sqlx::raw_sql(include_str!(
+            "../../migrations/test/create_activity_entries.sql"
+        ))
+        .execute(&db)
+        .await
+        .expect("setup test sql ran successfully");
+
+        let requests = [
+            // Built-in
+            ("running", None),
+            // Global
+            ("swimming", None),
+            ("Rock climbing", None),
+            ("Dance", Some(json!({ "type": 5 }))),
+        ];
+
+        for (name, activity_info_schema) in requests {
+            let request = CreateActivityTypeRequest {
+                user_id: Some(uuid!("11111111-1111-1111-1111-111111111111")),
+                name: name.to_string(),
+                activity_info_schema,
+                default_met: None,
+            };
+
+            let response = app.clone().oneshot(create_request(&request)).await.unwrap();
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{}", name);
+        }
+    }
+}
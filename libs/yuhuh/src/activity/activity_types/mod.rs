@@ -0,0 +1,5 @@
+mod handler;
+mod repository;
+
+pub use handler::*;
+pub use repository::*;
@@ -0,0 +1,128 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use tracing::{debug, error};
+use uuid::Uuid;
+
+use crate::{activity::model::CustomActivityType, error::YuhuhError};
+
+// =============================================================================
+// Traits
+// =============================================================================
+
+#[async_trait]
+pub trait ActivityTypesRepository: std::fmt::Debug + Send + Sync + 'static {
+    /// Creates a custom type for a user, or a global one when `user_id` is
+    /// `None`.
+    async fn create_activity_type(
+        &self,
+        user_id: Option<Uuid>,
+        name: &str,
+        activity_info_schema: Option<serde_json::Value>,
+        default_met: Option<f32>,
+    ) -> Result<CustomActivityType, YuhuhError>;
+
+    /// Global types along with the user's own, ordered by name.
+    async fn read_activity_types(
+        &self,
+        user_id: &Uuid,
+    ) -> Result<Vec<CustomActivityType>, YuhuhError>;
+}
+
+// =============================================================================
+// Production Implementation
+// =============================================================================
+
+#[derive(Debug)]
+pub struct ActivityTypesRepositoryImpl {
+    pub db: PgPool,
+}
+
+impl ActivityTypesRepositoryImpl {
+    pub fn new(db: PgPool) -> Self {
+        ActivityTypesRepositoryImpl { db }
+    }
+}
+
+#[async_trait]
+impl ActivityTypesRepository for ActivityTypesRepositoryImpl {
+    async fn create_activity_type(
+        &self,
+        user_id: Option<Uuid>,
+        name: &str,
+        activity_info_schema: Option<serde_json::Value>,
+        default_met: Option<f32>,
+    ) -> Result<CustomActivityType, YuhuhError> {
+        debug!(user_id=?user_id, name=?name, "received create activity type");
+
+        let activity_type = sqlx::query_as!(
+            CustomActivityType,
+            r#"
+            INSERT INTO activity_types (
+                user_id,
+                name,
+                activity_info_schema,
+                default_met
+            )
+            VALUES ($1::uuid, $2::text, $3::jsonb, $4::real)
+            RETURNING
+                activity_type_id,
+                user_id,
+                name,
+                activity_info_schema,
+                default_met;
+            "#,
+            user_id,
+            name,
+            activity_info_schema,
+            default_met
+        )
+        .fetch_one(&self.db)
+        .await
+        .map_err(|e| {
+            if e.as_database_error()
+                .is_some_and(|d| d.is_unique_violation())
+            {
+                return YuhuhError::Conflict(format!("activity type {} already exists", name));
+            }
+
+            error!(error = ?e, "database error while creating activity type");
+
+            YuhuhError::DatabaseError(e)
+        })?;
+
+        Ok(activity_type)
+    }
+
+    async fn read_activity_types(
+        &self,
+        user_id: &Uuid,
+    ) -> Result<Vec<CustomActivityType>, YuhuhError> {
+        debug!(user_id=?user_id, "received read activity types");
+
+        let activity_types = sqlx::query_as!(
+            CustomActivityType,
+            r#"
+            SELECT
+                activity_type_id,
+                user_id,
+                name,
+                activity_info_schema,
+                default_met
+            FROM activity_types
+            WHERE user_id IS NULL
+            OR user_id = $1::uuid
+            ORDER BY lower(name), user_id NULLS LAST;
+            "#,
+            user_id
+        )
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, "database error while finding activity types");
+
+            YuhuhError::DatabaseError(e)
+        })?;
+
+        Ok(activity_types)
+    }
+}
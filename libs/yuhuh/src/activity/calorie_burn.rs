@@ -0,0 +1,163 @@
+//! Estimates calories burned by activities from metabolic equivalents (METs).
+//!
+//! A MET is the energy cost of an activity relative to sitting still, where
+//! one MET burns roughly one kilocalorie per kilogram of body weight an hour.
+//! Values are taken from the Compendium of Physical Activities.
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::activity::{activity_info::ActivityInfo, model::ActivityType};
+
+/// How hard an activity was.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum Intensity {
+    Light,
+    Moderate,
+    Vigorous,
+}
+
+/// MET value for an activity at a given intensity.
+pub fn met(activity_type: &ActivityType, intensity: Intensity) -> f32 {
+    let [light, moderate, vigorous] = match activity_type {
+        ActivityType::WeightLifting => [3.5, 5.0, 6.0],
+        ActivityType::Walking => [2.8, 3.5, 5.0],
+        ActivityType::Jogging => [6.0, 7.0, 8.0],
+        ActivityType::Running => [8.3, 9.8, 11.5],
+        ActivityType::Cycling => [4.0, 6.8, 10.0],
+        ActivityType::Ebiking => [3.5, 4.5, 6.0],
+        ActivityType::MountainBiking => [6.0, 8.5, 14.0],
+        ActivityType::Other | ActivityType::Custom(_) => [2.5, 4.0, 6.0],
+    };
+
+    match intensity {
+        Intensity::Light => light,
+        Intensity::Moderate => moderate,
+        Intensity::Vigorous => vigorous,
+    }
+}
+
+/// Average speed in km/h, when both distance and duration are known.
+fn speed_kmh(distance_km: Option<f32>, duration_seconds: Option<u32>) -> Option<f32> {
+    Some(distance_km? / (duration_seconds? as f32 / 3600.0))
+}
+
+fn by_speed(speed_kmh: Option<f32>, light_below: f32, vigorous_from: f32) -> Intensity {
+    match speed_kmh {
+        Some(speed) if speed < light_below => Intensity::Light,
+        Some(speed) if speed >= vigorous_from => Intensity::Vigorous,
+        _ => Intensity::Moderate,
+    }
+}
+
+/// Best guess at how hard an activity was from its recorded info.
+pub fn estimate_intensity(activity_type: &ActivityType, info: &ActivityInfo) -> Intensity {
+    match (activity_type, info) {
+        (ActivityType::Walking, ActivityInfo::Distance(i)) => {
+            by_speed(speed_kmh(i.distance_km, i.duration_seconds), 4.5, 6.0)
+        }
+        (ActivityType::Running | ActivityType::Jogging, ActivityInfo::Distance(i)) => {
+            let speed = i
+                .pace_seconds_per_km
+                .map(|pace| 3600.0 / pace)
+                .or_else(|| speed_kmh(i.distance_km, i.duration_seconds));
+
+            by_speed(speed, 8.0, 11.0)
+        }
+        // Heavier assistance means less work from the rider
+        (ActivityType::Ebiking, ActivityInfo::Cycling(i)) if i.assist_level >= Some(3) => {
+            Intensity::Light
+        }
+        (_, ActivityInfo::Cycling(i)) => {
+            by_speed(speed_kmh(i.distance_km, i.duration_seconds), 16.0, 22.0)
+        }
+        _ => Intensity::Moderate,
+    }
+}
+
+/// Estimates kilocalories burned by an activity, or `None` when its duration
+/// can't be worked out.
+///
+/// When no intensity is given it's estimated from the activity info.
+pub fn estimate_calories_burned(
+    activity_type: &ActivityType,
+    info: &ActivityInfo,
+    intensity: Option<Intensity>,
+    body_weight_kg: f32,
+) -> Option<f32> {
+    let intensity = intensity.unwrap_or_else(|| estimate_intensity(activity_type, info));
+
+    calories_burned(met(activity_type, intensity), info, body_weight_kg)
+}
+
+/// Kilocalories burned at a known MET, such as a custom type's default.
+pub fn calories_burned(met: f32, info: &ActivityInfo, body_weight_kg: f32) -> Option<f32> {
+    info.duration_seconds()
+        .map(|seconds| met * body_weight_kg * (seconds / 3600.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use super::*;
+
+    fn info(activity_type: &ActivityType, value: serde_json::Value) -> ActivityInfo {
+        ActivityInfo::parse(activity_type, value).expect("valid activity info")
+    }
+
+    #[test]
+    fn burn_uses_met_weight_and_duration() {
+        let walk = info(
+            &ActivityType::Walking,
+            json!({ "distance_km": 5.0, "duration_seconds": 3600 }),
+        );
+
+        // 5 km/h is a moderate walk, 3.5 METs for an hour at 80kg
+        assert_eq!(
+            estimate_calories_burned(&ActivityType::Walking, &walk, None, 80.0),
+            Some(280.0)
+        );
+        assert_eq!(
+            estimate_calories_burned(
+                &ActivityType::Walking,
+                &walk,
+                Some(Intensity::Vigorous),
+                80.0
+            ),
+            Some(400.0)
+        );
+    }
+
+    #[test]
+    fn intensity_estimated_from_pace_and_assist() {
+        let fast_run = info(
+            &ActivityType::Running,
+            json!({ "distance_km": 12.0, "duration_seconds": 3600 }),
+        );
+        let assisted = info(
+            &ActivityType::Ebiking,
+            json!({ "distance_km": 30.0, "duration_seconds": 3600, "assist_level": 4 }),
+        );
+
+        assert_eq!(
+            estimate_intensity(&ActivityType::Running, &fast_run),
+            Intensity::Vigorous
+        );
+        assert_eq!(
+            estimate_intensity(&ActivityType::Ebiking, &assisted),
+            Intensity::Light
+        );
+    }
+
+    #[test]
+    fn no_estimate_without_duration() {
+        let ride = info(&ActivityType::Cycling, json!({ "distance_km": 20.0 }));
+
+        assert_eq!(
+            estimate_calories_burned(&ActivityType::Cycling, &ride, None, 80.0),
+            None
+        );
+    }
+}
@@ -7,15 +7,20 @@ use std::sync::Arc;
 use axum::{Json, extract::State, http::StatusCode};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use tracing::instrument;
+use tracing::{debug, instrument};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::{
     activity::{
-        model::{ActivityEntry, ActivityType},
+        activity_info::ActivityInfo,
+        activity_types::resolve_custom_type,
+        calorie_burn::{Intensity, calories_burned, estimate_calories_burned},
+        model::{ActivityEntry, ActivityType, PersonalRecord},
         state::ActivityState,
+        strength::find_new_personal_records,
     },
+    auth::model::Caller,
     error::YuhuhError,
     user::state::UserState,
 };
@@ -24,31 +29,50 @@ use crate::{
 pub struct CreateActivityEntryRequest {
     pub user_id: Uuid,
     pub mood_entries: Vec<NewActivityEntry>,
+    /// Body weight in kilograms for estimating calories burned, defaulting to
+    /// the weight stored on the user.
+    pub body_weight_kg: Option<f32>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct NewActivityEntry {
     pub activity: String,
     pub activity_type: ActivityType,
-    #[schema(value_type = Object)]
+    /// Must match the schema for `activity_type`, custom types are checked
+    /// against their own JSON Schema.
+    #[schema(value_type = ActivityInfo)]
     pub activity_info: serde_json::Value,
+    /// How hard the activity was, estimated from `activity_info` when missing.
+    pub intensity: Option<Intensity>,
     pub logged_at: Option<DateTime<Utc>>,
 }
 
+// ============================================================================
+// HTTP Responsed types
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateActivityEntriesResponse {
+    /// Strength personal records beaten by the new entries.
+    pub personal_records: Vec<PersonalRecord>,
+}
+
 // ============================================================================
 // Implementations
 // ============================================================================
 
 impl NewActivityEntry {
-    pub fn into(self, user_id: Uuid) -> ActivityEntry {
+    pub fn into(self, user_id: Uuid, calories_burned: Option<f32>) -> ActivityEntry {
         ActivityEntry {
-            activity_record_id: None,
+            activity_record_id: Some(Uuid::now_v7()),
             user_id,
             created_at: self.logged_at,
             updated_at: None,
             activity: self.activity,
             activity_type: self.activity_type,
             activity_info: self.activity_info,
+            calories_burned,
+            sample_summary: None,
             logged_at: self.logged_at.unwrap_or(Utc::now()),
         }
     }
@@ -64,34 +88,371 @@ impl NewActivityEntry {
         path = "activity",
         tag = "activity",
         responses(
-            (status = 201, description = "activity entries created successfully"),
+            (status = 201, description = "activity entries created successfully", body = CreateActivityEntriesResponse),
+            (status = 400, description = "Unknown activity type, or activity_info does not match it"),
         )
     )]
 #[instrument]
 pub async fn create_activity_entries(
     State(activity_state): State<Arc<ActivityState>>,
     State(user_state): State<Arc<UserState>>,
+    caller: Caller,
     Json(request): Json<CreateActivityEntryRequest>,
-) -> Result<StatusCode, YuhuhError> {
-    if (user_state
-        .find_user_repo
-        .find_user_by_id(&request.user_id)
-        .await?)
-        .is_none()
+) -> Result<(StatusCode, Json<CreateActivityEntriesResponse>), YuhuhError> {
+    debug!("entering create_activity_entries");
+
+    caller.authorise_user(&request.user_id)?;
+
+    let user = user_state.require_user(&request.user_id).await?;
+
+    if let Some(body_weight_kg) = request.body_weight_kg
+        && !(body_weight_kg.is_finite() && body_weight_kg > 0.0)
     {
-        return Err(YuhuhError::NotFound("user not found".to_string()));
+        return Err(YuhuhError::BadRequest(format!(
+            "body_weight_kg must be positive, but got {} instead",
+            body_weight_kg
+        )));
     }
 
+    let body_weight_kg = request.body_weight_kg.or(user.body_weight_kg);
+
+    let custom_types = if request
+        .mood_entries
+        .iter()
+        .any(|f| matches!(f.activity_type, ActivityType::Custom(_)))
+    {
+        activity_state
+            .activity_types_repo
+            .read_activity_types(&request.user_id)
+            .await?
+    } else {
+        vec![]
+    };
+
+    let activity_entries = request
+        .mood_entries
+        .into_iter()
+        .map(|mut f| {
+            let custom_type = match &f.activity_type {
+                ActivityType::Custom(name) => {
+                    let Some(custom_type) = resolve_custom_type(&custom_types, name) else {
+                        return Err(YuhuhError::BadRequest(format!(
+                            "unknown activity type {}",
+                            name
+                        )));
+                    };
+
+                    custom_type.validate_activity_info(&f.activity_info)?;
+                    f.activity_type = ActivityType::Custom(custom_type.name.clone());
+                    Some(custom_type)
+                }
+                _ => None,
+            };
+
+            let info = ActivityInfo::parse(&f.activity_type, f.activity_info)?;
+            let calories_burned =
+                body_weight_kg.and_then(|weight| match custom_type.and_then(|t| t.default_met) {
+                    Some(met) => calories_burned(met, &info, weight),
+                    None => estimate_calories_burned(&f.activity_type, &info, f.intensity, weight),
+                });
+
+            f.activity_info = info.into_value();
+            Ok(f.into(request.user_id, calories_burned))
+        })
+        .collect::<Result<Vec<ActivityEntry>, YuhuhError>>()?;
+
+    let personal_records = if activity_entries
+        .iter()
+        .any(|a| matches!(a.activity_type, ActivityType::WeightLifting))
+    {
+        let current = activity_state
+            .read_personal_records_repo
+            .read_current_personal_records(&request.user_id, None)
+            .await?;
+
+        find_new_personal_records(&current, &activity_entries)
+    } else {
+        vec![]
+    };
+
     activity_state
         .create_activity_entries_repo
-        .create_activity_entries(
-            request
-                .mood_entries
-                .into_iter()
-                .map(|f| f.into(request.user_id))
-                .collect(),
-        )
+        .create_activity_entries(activity_entries, personal_records.clone())
         .await?;
 
-    Ok(StatusCode::CREATED)
+    Ok((
+        StatusCode::CREATED,
+        Json(CreateActivityEntriesResponse { personal_records }),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use axum::{
+        body::Body,
+        http::{self, Request, StatusCode},
+    };
+    use http_body_util::BodyExt;
+    use tower::ServiceExt;
+    use uuid::uuid;
+
+    use crate::activity::{
+        create_activity_entries::{
+            CreateActivityEntriesResponse, CreateActivityEntryRequest, NewActivityEntry,
+        },
+        model::{ActivityType, PersonalRecordType},
+        read_activity_entries::ActivityFilter,
+    };
+
+    fn create_request(request: &CreateActivityEntryRequest) -> Request<Body> {
+        Request::builder()
+            .method("POST")
+            .uri("/activity")
+            .header(http::header::CONTENT_TYPE, "application/json")
+            .body(Body::from(
+                serde_json::to_string(request).expect("request is valid body"),
+            ))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn typed_activity_info_stored() {
+        let (app, db, state) = crate::test::common::setup().await;
+
+        sqlx::raw_sql(include_str!(
+            "../../migrations/test/create_activity_entries.sql"
+        ))
+        .execute(&db)
+        .await
+        .expect("setup test sql ran successfully");
+
+        let request = CreateActivityEntryRequest {
+            user_id: uuid!("11111111-1111-1111-1111-111111111111"),
+            mood_entries: vec![
+                NewActivityEntry {
+                    activity: "parkrun".to_string(),
+                    activity_type: ActivityType::Running,
+                    activity_info: json!({ "distance_km": 5.0, "duration_seconds": 1500 }),
+                    intensity: None,
+                    logged_at: None,
+                },
+                NewActivityEntry {
+                    activity: "trampolining".to_string(),
+                    activity_type: ActivityType::Other,
+                    activity_info: json!({ "bounces": 400 }),
+                    intensity: None,
+                    logged_at: None,
+                },
+            ],
+            body_weight_kg: Some(70.0),
+        };
+
+        let response = app.oneshot(create_request(&request)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        let created = state
+            .activity
+            .read_activity_entries_repo
+            .read_activity_entries(
+                &uuid!("11111111-1111-1111-1111-111111111111"),
+                None,
+                None,
+                &ActivityFilter::default(),
+                100,
+                0,
+            )
+            .await
+            .expect("no errors on reading newly created entries");
+
+        let parkrun = created
+            .iter()
+            .find(|a| a.activity == "parkrun")
+            .expect("parkrun created");
+
+        assert_eq!(
+            parkrun.activity_info,
+            json!({ "distance_km": 5.0, "duration_seconds": 1500, "pace_seconds_per_km": 300.0 })
+        );
+        // 12 km/h is a vigorous run, 11.5 METs for 25 minutes at 70kg
+        assert_eq!(
+            parkrun.calories_burned,
+            Some(11.5 * 70.0 * (1500.0 / 3600.0))
+        );
+
+        let trampolining = created
+            .iter()
+            .find(|a| a.activity == "trampolining")
+            .expect("trampolining created");
+
+        // No duration to go on
+        assert_eq!(trampolining.calories_burned, None);
+    }
+
+    #[tokio::test]
+    async fn new_personal_records_flagged() {
+        let (app, db, state) = crate::test::common::setup().await;
+
+        sqlx::raw_sql(include_str!("../../migrations/test/personal_records.sql"))
+            .execute(&db)
+            .await
+            .expect("setup test sql ran successfully");
+
+        let request = CreateActivityEntryRequest {
+            user_id: uuid!("11111111-1111-1111-1111-111111111111"),
+            mood_entries: vec![NewActivityEntry {
+                activity: "max out".to_string(),
+                activity_type: ActivityType::WeightLifting,
+                activity_info: json!({
+                    "exercises": [{ "name": "squat", "sets": [{ "reps": 1, "weight_kg": 120.0 }] }]
+                }),
+                intensity: None,
+                logged_at: None,
+            }],
+            body_weight_kg: None,
+        };
+
+        let response = app.oneshot(create_request(&request)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let dto: CreateActivityEntriesResponse =
+            serde_json::from_slice(&body).expect("valid CreateActivityEntriesResponse bytes");
+
+        let flagged: Vec<_> = dto
+            .personal_records
+            .iter()
+            .map(|r| (r.record_type, r.value, r.previous_value))
+            .collect();
+
+        // 120kg once doesn't beat 1500kg of volume
+        assert_eq!(
+            flagged,
+            vec![
+                (PersonalRecordType::BestWeight, 120.0, Some(110.0)),
+                (
+                    PersonalRecordType::EstimatedOneRepMax,
+                    120.0,
+                    Some(116.666664)
+                ),
+            ]
+        );
+
+        let current = state
+            .activity
+            .read_personal_records_repo
+            .read_current_personal_records(
+                &uuid!("11111111-1111-1111-1111-111111111111"),
+                Some("squat"),
+            )
+            .await
+            .expect("no errors on reading personal records");
+
+        assert_eq!(
+            current
+                .iter()
+                .find(|r| r.record_type == PersonalRecordType::BestWeight)
+                .map(|r| r.value),
+            Some(120.0)
+        );
+    }
+
+    #[tokio::test]
+    async fn custom_activity_types_validated() {
+        let (app, db, state) = crate::test::common::setup().await;
+
+        sqlx::raw_sql(include_str!(
+            "../../migrations/test/create_activity_entries.sql"
+        ))
+        .execute(&db)
+        .await
+        .expect("setup test sql ran successfully");
+
+        let swim =
+            |activity_type: &str, activity_info: serde_json::Value| CreateActivityEntryRequest {
+                user_id: uuid!("11111111-1111-1111-1111-111111111111"),
+                mood_entries: vec![NewActivityEntry {
+                    activity: "laps".to_string(),
+                    activity_type: activity_type.parse().expect("valid activity type"),
+                    activity_info,
+                    intensity: None,
+                    logged_at: None,
+                }],
+                body_weight_kg: Some(80.0),
+            };
+
+        let response = app
+            .clone()
+            .oneshot(create_request(&swim(
+                "swimming",
+                json!({ "distance_m": 1500.0, "duration_seconds": 1800 }),
+            )))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        let created = state
+            .activity
+            .read_activity_entries_repo
+            .read_activity_entries(
+                &uuid!("11111111-1111-1111-1111-111111111111"),
+                None,
+                None,
+                &ActivityFilter::default(),
+                100,
+                0,
+            )
+            .await
+            .expect("no errors on reading newly created entries");
+
+        // Stored under the type's own name, burning its default 7 METs
+        assert_eq!(
+            created[0].activity_type,
+            ActivityType::Custom("Swimming".to_string())
+        );
+        assert_eq!(created[0].calories_burned, Some(7.0 * 80.0 * 0.5));
+
+        let response = app
+            .clone()
+            .oneshot(create_request(&swim("Swimming", json!({ "laps": 40 }))))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = app
+            .oneshot(create_request(&swim("Skydiving", json!({}))))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn mismatched_activity_info_returns_bad_request() {
+        let (app, db, _) = crate::test::common::setup().await;
+
+        sqlx::raw_sql(include_str!(
+            "../../migrations/test/create_activity_entries.sql"
+        ))
+        .execute(&db)
+        .await
+        .expect("setup test sql ran successfully");
+
+        let request = CreateActivityEntryRequest {
+            user_id: uuid!("11111111-1111-1111-1111-111111111111"),
+            mood_entries: vec![NewActivityEntry {
+                activity: "leg day".to_string(),
+                activity_type: ActivityType::WeightLifting,
+                activity_info: json!({ "distance_km": 5.0 }),
+                intensity: None,
+                logged_at: None,
+            }],
+            body_weight_kg: None,
+        };
+
+        let response = app.oneshot(create_request(&request)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
 }
@@ -1,10 +1,17 @@
 use async_trait::async_trait;
-use chrono::NaiveDateTime;
+use chrono::{DateTime, NaiveDateTime, Utc};
 use sqlx::PgPool;
 use tracing::{error, info};
 use uuid::Uuid;
 
-use crate::{activity::model::ActivityEntry, error::YuhuhError};
+use crate::{
+    activity::model::{ActivityEntry, PersonalRecord},
+    error::YuhuhError,
+    webhooks::{
+        model::{EventType, WebhookEvent},
+        outbox::enqueue_events,
+    },
+};
 
 // =============================================================================
 // Traits
@@ -12,7 +19,12 @@ use crate::{activity::model::ActivityEntry, error::YuhuhError};
 
 #[async_trait]
 pub trait CreateActivityEntriesRepository: std::fmt::Debug + Send + Sync + 'static {
-    async fn create_activity_entries(&self, entries: Vec<ActivityEntry>) -> Result<(), YuhuhError>;
+    /// Creates activity entries along with any personal records they set.
+    async fn create_activity_entries(
+        &self,
+        entries: Vec<ActivityEntry>,
+        personal_records: Vec<PersonalRecord>,
+    ) -> Result<(), YuhuhError>;
 }
 
 // =============================================================================
@@ -32,7 +44,11 @@ impl CreateActivityEntriesRepositoryImpl {
 
 #[async_trait]
 impl CreateActivityEntriesRepository for CreateActivityEntriesRepositoryImpl {
-    async fn create_activity_entries(&self, entries: Vec<ActivityEntry>) -> Result<(), YuhuhError> {
+    async fn create_activity_entries(
+        &self,
+        entries: Vec<ActivityEntry>,
+        personal_records: Vec<PersonalRecord>,
+    ) -> Result<(), YuhuhError> {
         if entries.is_empty() {
             error!("create_activity_entries received an empty vec");
 
@@ -43,42 +59,59 @@ impl CreateActivityEntriesRepository for CreateActivityEntriesRepositoryImpl {
 
         let mut transaction = self.db.begin().await?;
 
+        let mut activity_record_id_vecs: Vec<Uuid> = vec![];
         let mut user_id_vecs: Vec<Uuid> = vec![];
         let mut activity_vecs: Vec<String> = vec![];
         let mut activity_type: Vec<String> = vec![];
         let mut activity_info: Vec<serde_json::Value> = vec![];
+        let mut calories_burned: Vec<Option<f32>> = vec![];
         let mut logged_at_vecs: Vec<NaiveDateTime> = vec![];
 
-        entries.into_iter().for_each(|f| {
+        let mut events: Vec<WebhookEvent> = vec![];
+
+        for mut f in entries {
             info!(activity_entry=?f, "added activity entry to creation query");
+            activity_record_id_vecs.push(*f.activity_record_id.get_or_insert_with(Uuid::now_v7));
+            events.push(WebhookEvent::new(
+                EventType::ActivityCreated,
+                f.user_id,
+                &f,
+            )?);
             activity_vecs.push(f.activity);
             activity_type.push(f.activity_type.to_string());
             activity_info.push(f.activity_info);
+            calories_burned.push(f.calories_burned);
             user_id_vecs.push(f.user_id);
             logged_at_vecs.push(f.logged_at.naive_utc());
-        });
+        }
 
         sqlx::query!(
             r#"
             INSERT INTO activity_records (
+                activity_record_id,
                 user_id,
                 activity,
                 activity_type,
                 activity_info,
+                calories_burned,
                 logged_at
             )
             SELECT * FROM UNNEST(
                 $1::uuid[],
-                $2::text[],
+                $2::uuid[],
                 $3::text[],
-                $4::jsonb[],
-                $5::timestamp[]
+                $4::text[],
+                $5::jsonb[],
+                $6::real[],
+                $7::timestamp[]
             )
             "#,
+            &activity_record_id_vecs[..],
             &user_id_vecs[..],
             &activity_vecs[..],
             &activity_type[..],
             &activity_info[..],
+            &calories_burned[..] as &[Option<f32>],
             &logged_at_vecs[..]
         )
         .execute(&mut *transaction)
@@ -89,6 +122,66 @@ impl CreateActivityEntriesRepository for CreateActivityEntriesRepositoryImpl {
             YuhuhError::DatabaseError(e)
         })?;
 
+        if !personal_records.is_empty() {
+            let mut user_id_vecs: Vec<Uuid> = vec![];
+            let mut activity_record_id_vecs: Vec<Uuid> = vec![];
+            let mut exercise_vecs: Vec<String> = vec![];
+            let mut record_type_vecs: Vec<String> = vec![];
+            let mut value_vecs: Vec<f32> = vec![];
+            let mut previous_value_vecs: Vec<Option<f32>> = vec![];
+            let mut achieved_at_vecs: Vec<DateTime<Utc>> = vec![];
+
+            personal_records.into_iter().for_each(|r| {
+                info!(personal_record=?r, "added personal record to creation query");
+                user_id_vecs.push(r.user_id);
+                activity_record_id_vecs.push(r.activity_record_id);
+                exercise_vecs.push(r.exercise);
+                record_type_vecs.push(r.record_type.to_string());
+                value_vecs.push(r.value);
+                previous_value_vecs.push(r.previous_value);
+                achieved_at_vecs.push(r.achieved_at);
+            });
+
+            sqlx::query!(
+                r#"
+                INSERT INTO personal_records (
+                    user_id,
+                    activity_record_id,
+                    exercise,
+                    record_type,
+                    value,
+                    previous_value,
+                    achieved_at
+                )
+                SELECT * FROM UNNEST(
+                    $1::uuid[],
+                    $2::uuid[],
+                    $3::text[],
+                    $4::text[],
+                    $5::real[],
+                    $6::real[],
+                    $7::timestamptz[]
+                )
+                "#,
+                &user_id_vecs[..],
+                &activity_record_id_vecs[..],
+                &exercise_vecs[..],
+                &record_type_vecs[..],
+                &value_vecs[..],
+                &previous_value_vecs[..] as &[Option<f32>],
+                &achieved_at_vecs[..]
+            )
+            .execute(&mut *transaction)
+            .await
+            .map_err(|e| {
+                error!(error = ?e, "database error while creating personal records");
+
+                YuhuhError::DatabaseError(e)
+            })?;
+        }
+
+        enqueue_events(&mut transaction, &events).await?;
+
         // Commit the transaction to persist all changes
         transaction.commit().await?;
 
@@ -1,5 +1,14 @@
+pub mod activity_info;
+pub mod activity_samples;
+pub mod activity_types;
+pub mod calorie_burn;
 pub mod create_activity_entries;
 pub mod model;
 pub mod read_activity_entries;
+pub mod read_personal_records;
+pub mod read_training_load;
 pub mod router;
+pub mod samples;
 pub mod state;
+pub mod strength;
+pub mod training_load;
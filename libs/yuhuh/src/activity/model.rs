@@ -1,11 +1,23 @@
 use core::fmt;
+use std::str::FromStr;
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use utoipa::ToSchema;
+use utoipa::{PartialSchema, ToSchema};
 use uuid::Uuid;
 
-#[derive(Debug, Serialize, Deserialize, ToSchema)]
+use crate::{activity::samples::SampleSummary, error::ConversionError};
+
+/// Longest name a custom activity type can have.
+const MAX_CUSTOM_NAME_LENGTH: usize = 50;
+
+/// Built-in activity types, plus any custom types from the `activity_types`
+/// table.
+///
+/// Stored and serialised as its name, so custom types sit alongside the
+/// built-in names already stored as text.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
 pub enum ActivityType {
     WeightLifting,
     Walking,
@@ -15,6 +27,53 @@ pub enum ActivityType {
     Ebiking,
     MountainBiking,
     Other,
+    /// A user-defined or global type, like "Swimming" or "Yoga".
+    Custom(String),
+}
+
+impl ActivityType {
+    pub const BUILT_IN: [ActivityType; 8] = [
+        ActivityType::WeightLifting,
+        ActivityType::Walking,
+        ActivityType::Jogging,
+        ActivityType::Running,
+        ActivityType::Cycling,
+        ActivityType::Ebiking,
+        ActivityType::MountainBiking,
+        ActivityType::Other,
+    ];
+
+    /// Checks a name can be used for a custom type, it must start with a
+    /// letter, be alphanumeric and not clash with a built-in type.
+    pub fn validate_custom_name(name: &str) -> Result<(), ConversionError> {
+        if name.is_empty() || name.len() > MAX_CUSTOM_NAME_LENGTH {
+            return Err(ConversionError::new(format!(
+                "activity type names must be between 1 and {} characters",
+                MAX_CUSTOM_NAME_LENGTH
+            )));
+        }
+
+        if !name.starts_with(|c: char| c.is_ascii_alphabetic())
+            || !name.chars().all(|c| c.is_ascii_alphanumeric())
+        {
+            return Err(ConversionError::new(format!(
+                "activity type {} must start with a letter and only contain letters and numbers",
+                name
+            )));
+        }
+
+        if let Some(built_in) = ActivityType::BUILT_IN
+            .iter()
+            .find(|t| t.to_string().eq_ignore_ascii_case(name))
+        {
+            return Err(ConversionError::new(format!(
+                "activity type {} clashes with the built-in type {}",
+                name, built_in
+            )));
+        }
+
+        Ok(())
+    }
 }
 
 impl fmt::Display for ActivityType {
@@ -28,10 +87,85 @@ impl fmt::Display for ActivityType {
             ActivityType::Ebiking => write!(f, "Ebiking"),
             ActivityType::MountainBiking => write!(f, "MountainBiking"),
             ActivityType::Other => write!(f, "Other"),
+            ActivityType::Custom(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+impl FromStr for ActivityType {
+    type Err = ConversionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "WeightLifting" => Ok(ActivityType::WeightLifting),
+            "Walking" => Ok(ActivityType::Walking),
+            "Jogging" => Ok(ActivityType::Jogging),
+            "Running" => Ok(ActivityType::Running),
+            "Cycling" => Ok(ActivityType::Cycling),
+            "Ebiking" => Ok(ActivityType::Ebiking),
+            "MountainBiking" => Ok(ActivityType::MountainBiking),
+            "Other" => Ok(ActivityType::Other),
+            _ => ActivityType::validate_custom_name(s)
+                .map(|_| ActivityType::Custom(s.to_string()))
+                .map_err(|_| ConversionError::new(format!("unknown activity type {}", s))),
         }
     }
 }
 
+impl TryFrom<String> for ActivityType {
+    type Error = ConversionError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<ActivityType> for String {
+    fn from(value: ActivityType) -> Self {
+        value.to_string()
+    }
+}
+
+// openapi schema
+impl PartialSchema for ActivityType {
+    fn schema() -> utoipa::openapi::RefOr<utoipa::openapi::schema::Schema> {
+        utoipa::openapi::ObjectBuilder::new()
+            .schema_type(utoipa::openapi::schema::SchemaType::Type(
+                utoipa::openapi::schema::Type::String,
+            ))
+            .description(Some(
+                "A built-in activity type, or the name of a custom activity type",
+            ))
+            .examples(
+                ActivityType::BUILT_IN
+                    .iter()
+                    .map(|t| t.to_string())
+                    .chain(["Swimming".to_string()]),
+            )
+            .into()
+    }
+}
+
+impl ToSchema for ActivityType {
+    fn name() -> std::borrow::Cow<'static, str> {
+        std::borrow::Cow::Borrowed("ActivityType")
+    }
+}
+
+/// A custom activity type, either global or belonging to a single user.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema, sqlx::FromRow)]
+pub struct CustomActivityType {
+    pub activity_type_id: Uuid,
+    /// Owner of the type, or `None` for types available to everyone.
+    pub user_id: Option<Uuid>,
+    pub name: String,
+    /// JSON Schema that `activity_info` must match.
+    #[schema(value_type = Option<Object>)]
+    pub activity_info_schema: Option<serde_json::Value>,
+    /// MET used to estimate calories burned, whatever the intensity.
+    pub default_met: Option<f32>,
+}
+
 #[derive(Debug, Serialize, Deserialize, ToSchema, sqlx::FromRow)]
 pub struct ActivityEntry {
     pub activity_record_id: Option<Uuid>,
@@ -41,5 +175,132 @@ pub struct ActivityEntry {
     pub activity: String,
     pub activity_type: ActivityType,
     pub activity_info: serde_json::Value,
+    /// Estimated kilocalories burned, if there was enough to go on.
+    pub calories_burned: Option<f32>,
+    /// Summary of the samples recorded during the activity, if any.
+    #[sqlx(json(nullable))]
+    pub sample_summary: Option<SampleSummary>,
+    pub logged_at: DateTime<Utc>,
+}
+
+// =============================================================================
+// Row Structs
+// =============================================================================
+#[derive(Debug, sqlx::FromRow)]
+pub struct ActivityEntryRow {
+    pub activity_record_id: Option<Uuid>,
+    pub user_id: Uuid,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub activity: String,
+    pub activity_type: String,
+    pub activity_info: serde_json::Value,
+    /// Estimated kilocalories burned, if there was enough to go on.
+    pub calories_burned: Option<f32>,
+    pub sample_summary: Option<serde_json::Value>,
     pub logged_at: DateTime<Utc>,
 }
+
+impl TryInto<ActivityEntry> for ActivityEntryRow {
+    type Error = ConversionError;
+
+    fn try_into(self) -> Result<ActivityEntry, Self::Error> {
+        Ok(ActivityEntry {
+            activity_record_id: self.activity_record_id,
+            user_id: self.user_id,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+            activity: self.activity,
+            activity_type: self.activity_type.parse()?,
+            activity_info: self.activity_info,
+            calories_burned: self.calories_burned,
+            sample_summary: self
+                .sample_summary
+                .map(serde_json::from_value)
+                .transpose()
+                .map_err(|e| ConversionError::new(format!("invalid sample_summary: {}", e)))?,
+            logged_at: self.logged_at,
+        })
+    }
+}
+
+/// What a personal record was set for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+pub enum PersonalRecordType {
+    /// Heaviest single set.
+    BestWeight,
+    /// Best one rep max estimated from any set.
+    EstimatedOneRepMax,
+    /// Most weight moved across all sets in one session.
+    BestVolume,
+}
+
+impl fmt::Display for PersonalRecordType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PersonalRecordType::BestWeight => write!(f, "BestWeight"),
+            PersonalRecordType::EstimatedOneRepMax => write!(f, "EstimatedOneRepMax"),
+            PersonalRecordType::BestVolume => write!(f, "BestVolume"),
+        }
+    }
+}
+
+impl FromStr for PersonalRecordType {
+    type Err = ConversionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "BestWeight" => Ok(PersonalRecordType::BestWeight),
+            "EstimatedOneRepMax" => Ok(PersonalRecordType::EstimatedOneRepMax),
+            "BestVolume" => Ok(PersonalRecordType::BestVolume),
+            _ => Err(ConversionError::new(format!(
+                "unknown personal record type {}",
+                s
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct PersonalRecord {
+    pub personal_record_id: Option<Uuid>,
+    pub user_id: Uuid,
+    pub activity_record_id: Uuid,
+    /// Normalised exercise name.
+    pub exercise: String,
+    pub record_type: PersonalRecordType,
+    /// New best in kilograms.
+    pub value: f32,
+    /// Best that was beaten, if the exercise had been done before.
+    pub previous_value: Option<f32>,
+    pub achieved_at: DateTime<Utc>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct PersonalRecordRow {
+    pub personal_record_id: Uuid,
+    pub user_id: Uuid,
+    pub activity_record_id: Uuid,
+    pub exercise: String,
+    pub record_type: String,
+    pub value: f32,
+    pub previous_value: Option<f32>,
+    pub achieved_at: DateTime<Utc>,
+}
+
+impl TryInto<PersonalRecord> for PersonalRecordRow {
+    type Error = ConversionError;
+
+    fn try_into(self) -> Result<PersonalRecord, Self::Error> {
+        Ok(PersonalRecord {
+            personal_record_id: Some(self.personal_record_id),
+            user_id: self.user_id,
+            activity_record_id: self.activity_record_id,
+            exercise: self.exercise,
+            record_type: self.record_type.parse()?,
+            value: self.value,
+            previous_value: self.previous_value,
+            achieved_at: self.achieved_at,
+        })
+    }
+}
@@ -0,0 +1,196 @@
+use crate::{
+    activity::model::ActivityType,
+    error::{ConversionError, YuhuhError},
+};
+
+/// Comparison operators usable in `activity_info` predicates, longest first
+/// so `>=` isn't mistaken for `>`.
+const OPERATORS: [(&str, &str); 6] = [
+    (">=", ">="),
+    ("<=", "<="),
+    ("!=", "!="),
+    (">", ">"),
+    ("<", "<"),
+    ("=", "=="),
+];
+
+/// Narrows down which activity entries are read.
+#[derive(Debug, Default)]
+pub struct ActivityFilter {
+    /// Only entries of these types, or any type when empty.
+    pub activity_types: Vec<ActivityType>,
+    /// Only entries whose name contains this, ignoring case.
+    pub activity_contains: Option<String>,
+    /// Only entries whose `activity_info` matches every one of these
+    /// JSON paths.
+    pub info_predicates: Vec<InfoPredicate>,
+}
+
+/// A single comparison against a value in `activity_info`, e.g.
+/// `distance_km>10`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InfoPredicate {
+    path: Vec<String>,
+    operator: &'static str,
+    value: serde_json::Value,
+}
+
+impl InfoPredicate {
+    /// Parses `path<op>value`, where path is a dot separated list of keys and
+    /// op is one of `=`, `!=`, `>`, `>=`, `<` or `<=`.
+    pub fn parse(predicate: &str) -> Result<Self, YuhuhError> {
+        let invalid = |reason: &str| {
+            YuhuhError::BadRequest(format!(
+                "invalid activity_info predicate {} - {}",
+                predicate, reason
+            ))
+        };
+
+        let (index, symbol, operator) = predicate
+            .char_indices()
+            .find_map(|(i, _)| {
+                OPERATORS
+                    .iter()
+                    .find(|(symbol, _)| predicate[i..].starts_with(symbol))
+                    .map(|(symbol, operator)| (i, *symbol, *operator))
+            })
+            .ok_or_else(|| invalid("missing a comparison operator"))?;
+
+        let path = predicate[..index]
+            .trim()
+            .split('.')
+            .map(|key| {
+                if !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                    Ok(key.to_string())
+                } else {
+                    Err(invalid("keys may only contain letters, numbers and _"))
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let raw = predicate[index + symbol.len()..].trim();
+        let value = if let Ok(number) = raw.parse::<f64>() {
+            serde_json::Number::from_f64(number)
+                .map(serde_json::Value::Number)
+                .ok_or_else(|| invalid("numbers must be finite"))?
+        } else if let Ok(boolean) = raw.parse::<bool>() {
+            serde_json::Value::Bool(boolean)
+        } else if raw.is_empty() {
+            return Err(invalid("missing a value"));
+        } else {
+            serde_json::Value::String(raw.trim_matches('"').to_string())
+        };
+
+        Ok(InfoPredicate {
+            path,
+            operator,
+            value,
+        })
+    }
+
+    /// Renders the predicate as a SQL/JSON path for the `@?` operator.
+    ///
+    /// Keys are restricted when parsing and values are JSON encoded, so
+    /// nothing from the request can escape the path.
+    pub fn json_path(&self) -> String {
+        let keys: String = self
+            .path
+            .iter()
+            .map(|key| format!(".\"{}\"", key))
+            .collect();
+
+        format!("${} ? (@ {} {})", keys, self.operator, self.value)
+    }
+}
+
+impl ActivityFilter {
+    /// Builds a filter from the comma separated query parameters.
+    pub fn parse(
+        activity_types: Option<&str>,
+        activity_contains: Option<&str>,
+        info_predicates: Option<&str>,
+    ) -> Result<Self, YuhuhError> {
+        let split = |s: Option<&str>| {
+            s.unwrap_or_default()
+                .split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        };
+
+        Ok(ActivityFilter {
+            activity_types: split(activity_types)
+                .iter()
+                .map(|t| {
+                    t.parse()
+                        .map_err(|e: ConversionError| YuhuhError::BadRequest(e.to_string()))
+                })
+                .collect::<Result<_, _>>()?,
+            activity_contains: activity_contains
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::to_string),
+            info_predicates: split(info_predicates)
+                .iter()
+                .map(|p| InfoPredicate::parse(p))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// `activity_contains` as an `ILIKE` pattern, with wildcards in the
+    /// search itself escaped.
+    pub fn activity_pattern(&self) -> Option<String> {
+        self.activity_contains.as_ref().map(|s| {
+            let escaped = s
+                .replace('\\', "\\\\")
+                .replace('%', "\\%")
+                .replace('_', "\\_");
+
+            format!("%{}%", escaped)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn predicates_render_as_json_paths() {
+        assert_eq!(
+            InfoPredicate::parse("distance_km>=10").unwrap().json_path(),
+            r#"$."distance_km" ? (@ >= 10.0)"#
+        );
+        assert_eq!(
+            InfoPredicate::parse("exercises.name = \"squat\"")
+                .unwrap()
+                .json_path(),
+            r#"$."exercises"."name" ? (@ == "squat")"#
+        );
+        assert_eq!(
+            InfoPredicate::parse("indoor!=true").unwrap().json_path(),
+            r#"$."indoor" ? (@ != true)"#
+        );
+    }
+
+    #[test]
+    fn unsafe_predicates_rejected() {
+        assert!(InfoPredicate::parse("distance_km").is_err());
+        assert!(InfoPredicate::parse("distance_km>").is_err());
+        assert!(InfoPredicate::parse("$.a ? (@ > 1) || $.b>1").is_err());
+        assert!(InfoPredicate::parse("a.>1").is_err());
+    }
+
+    #[test]
+    fn filter_parses_query_parameters() {
+        let filter = ActivityFilter::parse(Some("Running, Walking"), Some("50%_off"), None)
+            .expect("valid filter");
+
+        assert_eq!(filter.activity_types.len(), 2);
+        assert_eq!(filter.activity_pattern(), Some("%50\\%\\_off%".to_string()));
+        assert!(ActivityFilter::parse(Some("Not a type!"), None, None).is_err());
+    }
+}
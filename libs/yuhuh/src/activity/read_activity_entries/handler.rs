@@ -12,18 +12,20 @@ use axum::{
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use tracing::{debug, error, instrument};
+use tracing::{debug, instrument};
 use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
 use crate::{
     activity::{
-        self,
+        activity_info::ActivityInfo,
         model::{ActivityEntry, ActivityType},
+        read_activity_entries::ActivityFilter,
+        samples::SampleSummary,
         state::ActivityState,
     },
+    auth::model::Caller,
     error::YuhuhError,
-    food::{model::FoodEntry, state::FoodState},
     user::state::UserState,
 };
 
@@ -40,6 +42,14 @@ pub struct ReadActivityEntriesRequest {
     pub offset: Option<u32>,
     pub logged_before_date: Option<DateTime<Utc>>,
     pub logged_after_date: Option<DateTime<Utc>>,
+    /// Comma separated activity types to include, e.g. `Running,Walking`.
+    pub activity_types: Option<String>,
+    /// Only include activities whose name contains this, ignoring case.
+    pub activity: Option<String>,
+    /// Comma separated predicates on `activity_info`, e.g.
+    /// `distance_km>10,duration_seconds<=3600`. Nested keys are separated by
+    /// dots, and `=`, `!=`, `>`, `>=`, `<` and `<=` are supported.
+    pub activity_info: Option<String>,
 }
 
 // ============================================================================
@@ -54,9 +64,15 @@ pub struct ReadActivityEntriesResponse {
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct FoundActivityRecord {
+    pub activity_record_id: Option<Uuid>,
     pub activity: String,
     pub activity_type: ActivityType,
+    #[schema(value_type = ActivityInfo)]
     pub activity_info: Value,
+    /// Estimated kilocalories burned.
+    pub calories_burned: Option<f32>,
+    /// Summary of the samples recorded during the activity, if any.
+    pub sample_summary: Option<SampleSummary>,
     pub logged_at: DateTime<Utc>,
 }
 
@@ -67,9 +83,12 @@ pub struct FoundActivityRecord {
 impl From<ActivityEntry> for FoundActivityRecord {
     fn from(record: ActivityEntry) -> Self {
         Self {
+            activity_record_id: record.activity_record_id,
             activity: record.activity,
             activity_type: record.activity_type,
             activity_info: record.activity_info,
+            calories_burned: record.calories_burned,
+            sample_summary: record.sample_summary,
             logged_at: record.logged_at,
         }
     }
@@ -86,25 +105,28 @@ impl From<ActivityEntry> for FoundActivityRecord {
     tag = "activity",
     params(ReadActivityEntriesRequest),
     responses(
-        (status = 200, description = "Found activity entries", body = ReadActivityEntriesResponse)
+        (status = 200, description = "Found activity entries", body = ReadActivityEntriesResponse),
+        (status = 400, description = "Invalid activity type or activity_info predicate"),
 ))]
 #[instrument]
 pub async fn read_activity_entries(
     State(activity_state): State<Arc<ActivityState>>,
     State(user_state): State<Arc<UserState>>,
 
+    caller: Caller,
     Query(request): Query<ReadActivityEntriesRequest>,
 ) -> Result<(StatusCode, Json<ReadActivityEntriesResponse>), YuhuhError> {
     debug!("entering read_activity_entries");
 
-    if (user_state
-        .find_user_repo
-        .find_user_by_id(&request.user_id)
-        .await?)
-        .is_none()
-    {
-        return Err(YuhuhError::NotFound("user not found".to_string()));
-    }
+    caller.authorise_user(&request.user_id)?;
+
+    user_state.require_user(&request.user_id).await?;
+
+    let filter = ActivityFilter::parse(
+        request.activity_types.as_deref(),
+        request.activity.as_deref(),
+        request.activity_info.as_deref(),
+    )?;
 
     let offset = request.offset.unwrap_or(0);
     let limit = request.limit.unwrap_or(10000);
@@ -116,6 +138,7 @@ pub async fn read_activity_entries(
             &request.user_id,
             request.logged_before_date,
             request.logged_after_date,
+            &filter,
             limit.into(),
             offset.into(),
         )
@@ -131,3 +154,121 @@ pub async fn read_activity_entries(
         }),
     ))
 }
+
+#[cfg(test)]
+mod tests {
+
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+    };
+    use http_body_util::BodyExt;
+    use pretty_assertions::assert_eq;
+    use tower::ServiceExt;
+
+    use crate::activity::read_activity_entries::ReadActivityEntriesResponse;
+
+    const ALICE: &str = "11111111-1111-1111-1111-111111111111";
+
+    /// Reads Alice's activities with extra query parameters, returning their
+    /// names newest first.
+    async fn read(app: axum::Router, query: &str) -> (StatusCode, Vec<String>) {
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/activity?user_id={}&{}", ALICE, query))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let status = response.status();
+        if status != StatusCode::OK {
+            return (status, vec![]);
+        }
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let dto: ReadActivityEntriesResponse =
+            serde_json::from_slice(&body).expect("valid ReadActivityEntriesResponse bytes");
+
+        (
+            status,
+            dto.activity_entries
+                .into_iter()
+                .map(|a| a.activity)
+                .collect(),
+        )
+    }
+
+    #[tokio::test]
+    async fn filters_by_type_and_name() {
+        let (app, db, _) = crate::test::common::setup().await;
+
+        sqlx::raw_sql(include_str!(
+            "../../migrations/test/read_activity_entries.sql"
+        ))
+        .execute(&db)
+        .await
+        .expect("setup test sql ran successfully");
+
+        assert_eq!(
+            read(app.clone(), "activity_types=Running,Walking").await,
+            (
+                StatusCode::OK,
+                vec![
+                    "walk to work".to_string(),
+                    "recovery run".to_string(),
+                    "Long run".to_string()
+                ]
+            )
+        );
+        assert_eq!(
+            read(app.clone(), "activity=RUN").await,
+            (
+                StatusCode::OK,
+                vec!["recovery run".to_string(), "Long run".to_string()]
+            )
+        );
+        assert_eq!(
+            read(app, "activity_types=Not%20a%20type").await.0,
+            StatusCode::BAD_REQUEST
+        );
+    }
+
+    #[tokio::test]
+    async fn filters_by_activity_info() {
+        let (app, db, _) = crate::test::common::setup().await;
+
+        sqlx::raw_sql(include_str!(
+            "../../migrations/test/read_activity_entries.sql"
+        ))
+        .execute(&db)
+        .await
+        .expect("setup test sql ran successfully");
+
+        assert_eq!(
+            read(app.clone(), "activity_info=distance_km%3E10").await,
+            (
+                StatusCode::OK,
+                vec!["commute".to_string(), "Long run".to_string()]
+            )
+        );
+        assert_eq!(
+            read(
+                app.clone(),
+                "activity_types=Running&activity_info=distance_km%3E10,duration_seconds%3C%3D7200"
+            )
+            .await,
+            (StatusCode::OK, vec!["Long run".to_string()])
+        );
+        assert_eq!(
+            read(app.clone(), "activity_info=exercises.name%3Dsquat").await,
+            (StatusCode::OK, vec!["gym".to_string()])
+        );
+        assert_eq!(
+            read(app, "activity_info=distance_km").await.0,
+            StatusCode::BAD_REQUEST
+        );
+    }
+}
@@ -1,5 +1,7 @@
+mod filter;
 mod handler;
 mod repository;
 
+pub use filter::*;
 pub use handler::*;
 pub use repository::*;
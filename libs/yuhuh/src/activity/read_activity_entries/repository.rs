@@ -1,10 +1,27 @@
 use async_trait::async_trait;
-use chrono::{DateTime, NaiveDateTime, Utc};
-use sqlx::PgPool;
-use tracing::{debug, error, info};
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Postgres, QueryBuilder};
+use tracing::{debug, error};
 use uuid::Uuid;
 
-use crate::{activity::model::ActivityEntry, error::YuhuhError};
+use crate::{
+    activity::{
+        model::{ActivityEntry, ActivityEntryRow},
+        read_activity_entries::ActivityFilter,
+    },
+    error::YuhuhError,
+};
+
+// =============================================================================
+// Public Types and Structs
+// =============================================================================
+
+/// Energy burned over every activity in a period, in kilocalories.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CaloriesBurnedTotal {
+    pub total_calories_burned: f32,
+    pub entries_without_calories_burned: i64,
+}
 
 // =============================================================================
 // Traits
@@ -17,9 +34,18 @@ pub trait ReadActivityEntriesRepository: std::fmt::Debug + Send + Sync + 'static
         user_id: &Uuid,
         before: Option<DateTime<Utc>>,
         after: Option<DateTime<Utc>>,
+        filter: &ActivityFilter,
         limit: i64,
         offset: i64,
     ) -> Result<Vec<ActivityEntry>, YuhuhError>;
+
+    /// Sums the calories burned by every activity in the period, unpaged.
+    async fn read_calories_burned_total(
+        &self,
+        user_id: &Uuid,
+        before: Option<DateTime<Utc>>,
+        after: Option<DateTime<Utc>>,
+    ) -> Result<CaloriesBurnedTotal, YuhuhError>;
 }
 
 // =============================================================================
@@ -44,6 +70,7 @@ impl ReadActivityEntriesRepository for ReadActivityEntriesRepositoryImpl {
         user_id: &Uuid,
         before: Option<DateTime<Utc>>,
         after: Option<DateTime<Utc>>,
+        filter: &ActivityFilter,
         limit: i64,
         offset: i64,
     ) -> Result<Vec<ActivityEntry>, YuhuhError> {
@@ -51,41 +78,132 @@ impl ReadActivityEntriesRepository for ReadActivityEntriesRepositoryImpl {
             user_id=?user_id,
             before=?before,
             after=?after,
+            filter=?filter,
             limit=?limit,
             offset=?offset,
             "received read activity entries"
         );
 
-        let activity_entries: Vec<ActivityEntry> = sqlx::query_as!(
-            FoodEntry,
+        let activity_types: Vec<String> = filter
+            .activity_types
+            .iter()
+            .map(|t| t.to_string())
+            .collect();
+
+        // Conditions are only added for filters that were given, as an
+        // `$n IS NULL OR ...` condition keeps the trigram and JSON path
+        // indexes from being used once a generic plan is cached
+        let mut query = QueryBuilder::<Postgres>::new(
+            r#"
+            SELECT
+                activity_record_id,
+                user_id,
+                created_at,
+                updated_at,
+                activity,
+                activity_type,
+                activity_info,
+                calories_burned,
+                sample_summary,
+                logged_at
+            FROM activity_records
+            WHERE user_id = "#,
+        );
+        query.push_bind(user_id);
+
+        if let Some(before) = before {
+            query.push(" AND logged_at <= ").push_bind(before);
+        }
+
+        if let Some(after) = after {
+            query.push(" AND logged_at >= ").push_bind(after);
+        }
+
+        if !activity_types.is_empty() {
+            query
+                .push(" AND activity_type = ANY(")
+                .push_bind(activity_types)
+                .push(")");
+        }
+
+        if let Some(pattern) = filter.activity_pattern() {
+            query.push(" AND activity ILIKE ").push_bind(pattern);
+        }
+
+        // The jsonb_path_ops index narrows down equality predicates, range
+        // predicates are checked against the user's rows
+        for predicate in &filter.info_predicates {
+            query
+                .push(" AND activity_info @? ")
+                .push_bind(predicate.json_path())
+                .push("::jsonpath");
+        }
+
+        query
+            .push(" ORDER BY logged_at DESC LIMIT ")
+            .push_bind(limit)
+            .push(" OFFSET ")
+            .push_bind(offset);
+
+        let records: Vec<ActivityEntryRow> = query
+            .build_query_as()
+            .fetch_all(&self.db)
+            .await
+            .map_err(|e| {
+                error!(error = ?e, "database error while finding activity records");
+
+                YuhuhError::DatabaseError(e)
+            })?;
+
+        debug!(activity_records=?records, "found activity records");
+
+        let activity_entries = records
+            .into_iter()
+            .map(|row| row.try_into())
+            .collect::<Result<Vec<ActivityEntry>, _>>()
+            .inspect_err(|e| error!(error=?e, "encountered parsing error for activity entry"))?;
+
+        Ok(activity_entries)
+    }
+
+    async fn read_calories_burned_total(
+        &self,
+        user_id: &Uuid,
+        before: Option<DateTime<Utc>>,
+        after: Option<DateTime<Utc>>,
+    ) -> Result<CaloriesBurnedTotal, YuhuhError> {
+        debug!(
+            user_id=?user_id,
+            before=?before,
+            after=?after,
+            "received calories burned total request for activity entries"
+        );
+
+        let total = sqlx::query_as!(
+            CaloriesBurnedTotal,
             r#"
-            SELECT *
+            SELECT
+                coalesce(sum(calories_burned), 0)::real AS "total_calories_burned!",
+                count(*) FILTER (WHERE calories_burned IS NULL) AS "entries_without_calories_burned!"
             FROM activity_records
             WHERE user_id = $1::uuid
             AND ($2::timestamptz IS NULL
                 OR logged_at <= $2::timestamptz)
             AND ($3::timestamptz IS NULL
-                OR logged_at >= $3::timestamptz)
-            ORDER BY logged_at DESC
-            LIMIT $4
-            OFFSET $5;
+                OR logged_at >= $3::timestamptz);
             "#,
             user_id,
             before,
-            after,
-            limit,
-            offset
+            after
         )
-        .fetch_all(&self.db)
+        .fetch_one(&self.db)
         .await
         .map_err(|e| {
-            error!(error = ?e, "database error while finding food records");
+            error!(error = ?e, "database error while totalling calories burned");
 
             YuhuhError::DatabaseError(e)
         })?;
 
-        debug!(activity_entries=?activity_entries, "found activity records");
-
-        Ok(activity_entries)
+        Ok(total)
     }
 }
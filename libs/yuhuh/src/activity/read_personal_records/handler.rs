@@ -0,0 +1,343 @@
+//! personal records HTTP handlers
+//!
+//! This module provides HTTP endpoints for a user's strength personal records
+//! and how each exercise has progressed over time.
+
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::StatusCode,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, instrument};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::{
+    activity::{
+        model::{PersonalRecord, PersonalRecordType},
+        state::ActivityState,
+        strength::{ExerciseMetrics, entry_metrics, normalise_exercise},
+    },
+    auth::model::Caller,
+    error::YuhuhError,
+    user::state::UserState,
+};
+
+// ============================================================================
+// HTTP Request types
+// ============================================================================
+
+/// Request parameters for finding personal records.
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ReadPersonalRecordsRequest {
+    /// user ID to search by.
+    pub user_id: Uuid,
+    /// Only include records for this exercise.
+    pub exercise: Option<String>,
+}
+
+/// Request parameters for an exercise's progression.
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ReadProgressionRequest {
+    /// user ID to search by.
+    pub user_id: Uuid,
+    pub exercise: String,
+}
+
+// ============================================================================
+// HTTP Responsed types
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ReadPersonalRecordsResponse {
+    pub personal_records: Vec<PersonalRecord>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ReadProgressionResponse {
+    pub exercise: String,
+    /// Every session the exercise was done in, oldest first.
+    pub sessions: Vec<ProgressionPoint>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ProgressionPoint {
+    pub activity_record_id: Option<Uuid>,
+    pub logged_at: DateTime<Utc>,
+    pub sets: u32,
+    pub reps: u32,
+    pub best_weight_kg: f32,
+    pub estimated_one_rep_max_kg: f32,
+    pub volume_kg: f32,
+    /// Records set in this session.
+    pub personal_records: Vec<PersonalRecordType>,
+}
+
+// ============================================================================
+// Implementations
+// ============================================================================
+
+impl ProgressionPoint {
+    fn new(
+        activity_record_id: Option<Uuid>,
+        logged_at: DateTime<Utc>,
+        metrics: ExerciseMetrics,
+        history: &[PersonalRecord],
+    ) -> Self {
+        Self {
+            activity_record_id,
+            logged_at,
+            sets: metrics.sets,
+            reps: metrics.reps,
+            best_weight_kg: metrics.best_weight_kg,
+            estimated_one_rep_max_kg: metrics.estimated_one_rep_max_kg,
+            volume_kg: metrics.volume_kg,
+            personal_records: history
+                .iter()
+                .filter(|r| Some(r.activity_record_id) == activity_record_id)
+                .map(|r| r.record_type)
+                .collect(),
+        }
+    }
+}
+
+// =============================================================================
+// HTTP Handlers
+// =============================================================================
+
+/// Find the current personal records for each of a user's exercises
+#[utoipa::path(
+    get,
+    path = "activity/records",
+    tag = "activity",
+    params(ReadPersonalRecordsRequest),
+    responses(
+        (status = 200, description = "Found personal records", body = ReadPersonalRecordsResponse),
+        (status = 404, description = "User not found"),
+))]
+#[instrument]
+pub async fn read_personal_records(
+    State(activity_state): State<Arc<ActivityState>>,
+    State(user_state): State<Arc<UserState>>,
+    caller: Caller,
+    Query(request): Query<ReadPersonalRecordsRequest>,
+) -> Result<(StatusCode, Json<ReadPersonalRecordsResponse>), YuhuhError> {
+    debug!("entering read_personal_records");
+
+    caller.authorise_user(&request.user_id)?;
+
+    user_state.require_user(&request.user_id).await?;
+
+    let exercise = request.exercise.as_deref().map(normalise_exercise);
+
+    let personal_records = activity_state
+        .read_personal_records_repo
+        .read_current_personal_records(&request.user_id, exercise.as_deref())
+        .await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(ReadPersonalRecordsResponse { personal_records }),
+    ))
+}
+
+/// Find every personal record a user has set, oldest first
+#[utoipa::path(
+    get,
+    path = "activity/records/history",
+    tag = "activity",
+    params(ReadPersonalRecordsRequest),
+    responses(
+        (status = 200, description = "Found personal record history", body = ReadPersonalRecordsResponse),
+        (status = 404, description = "User not found"),
+))]
+#[instrument]
+pub async fn read_personal_record_history(
+    State(activity_state): State<Arc<ActivityState>>,
+    State(user_state): State<Arc<UserState>>,
+    caller: Caller,
+    Query(request): Query<ReadPersonalRecordsRequest>,
+) -> Result<(StatusCode, Json<ReadPersonalRecordsResponse>), YuhuhError> {
+    debug!("entering read_personal_record_history");
+
+    caller.authorise_user(&request.user_id)?;
+
+    user_state.require_user(&request.user_id).await?;
+
+    let exercise = request.exercise.as_deref().map(normalise_exercise);
+
+    let personal_records = activity_state
+        .read_personal_records_repo
+        .read_personal_record_history(&request.user_id, exercise.as_deref())
+        .await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(ReadPersonalRecordsResponse { personal_records }),
+    ))
+}
+
+/// Find how an exercise has progressed across every session it was done in
+#[utoipa::path(
+    get,
+    path = "activity/records/progression",
+    tag = "activity",
+    params(ReadProgressionRequest),
+    responses(
+        (status = 200, description = "Found exercise progression", body = ReadProgressionResponse),
+        (status = 404, description = "User not found"),
+))]
+#[instrument]
+pub async fn read_progression(
+    State(activity_state): State<Arc<ActivityState>>,
+    State(user_state): State<Arc<UserState>>,
+    caller: Caller,
+    Query(request): Query<ReadProgressionRequest>,
+) -> Result<(StatusCode, Json<ReadProgressionResponse>), YuhuhError> {
+    debug!("entering read_progression");
+
+    caller.authorise_user(&request.user_id)?;
+
+    user_state.require_user(&request.user_id).await?;
+
+    let exercise = normalise_exercise(&request.exercise);
+
+    let history = activity_state
+        .read_personal_records_repo
+        .read_personal_record_history(&request.user_id, Some(&exercise))
+        .await?;
+
+    let entries = activity_state
+        .read_personal_records_repo
+        .read_weight_lifting_entries(&request.user_id)
+        .await?;
+
+    let sessions = entries
+        .iter()
+        .filter_map(|entry| {
+            entry_metrics(entry)?
+                .into_iter()
+                .find(|m| m.exercise == exercise)
+                .map(|m| {
+                    ProgressionPoint::new(entry.activity_record_id, entry.logged_at, m, &history)
+                })
+        })
+        .collect();
+
+    Ok((
+        StatusCode::OK,
+        Json(ReadProgressionResponse { exercise, sessions }),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+    };
+    use http_body_util::BodyExt;
+    use pretty_assertions::assert_eq;
+    use tower::ServiceExt;
+
+    use crate::activity::{
+        model::PersonalRecordType,
+        read_personal_records::{ReadPersonalRecordsResponse, ReadProgressionResponse},
+    };
+
+    async fn get(app: axum::Router, uri: &str) -> (StatusCode, axum::body::Bytes) {
+        let response = app
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        let status = response.status();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+
+        (status, body)
+    }
+
+    #[tokio::test]
+    async fn current_records_are_the_best_of_history() {
+        let (app, db, _) = crate::test::common::setup().await;
+
+        sqlx::raw_sql(include_str!("../../migrations/test/personal_records.sql"))
+            .execute(&db)
+            .await
+            .expect("setup test sql ran successfully");
+
+        let (status, body) = get(
+            app.clone(),
+            "/activity/records?user_id=11111111-1111-1111-1111-111111111111&exercise=Squat",
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let dto: ReadPersonalRecordsResponse =
+            serde_json::from_slice(&body).expect("valid ReadPersonalRecordsResponse bytes");
+        let current: Vec<_> = dto
+            .personal_records
+            .iter()
+            .map(|r| (r.record_type, r.value))
+            .collect();
+
+        assert_eq!(
+            current,
+            vec![
+                (PersonalRecordType::BestVolume, 1500.0),
+                (PersonalRecordType::BestWeight, 110.0),
+                (PersonalRecordType::EstimatedOneRepMax, 116.666664),
+            ]
+        );
+
+        let (status, body) = get(
+            app,
+            "/activity/records/history?user_id=11111111-1111-1111-1111-111111111111&exercise=squat",
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let dto: ReadPersonalRecordsResponse =
+            serde_json::from_slice(&body).expect("valid ReadPersonalRecordsResponse bytes");
+        assert_eq!(dto.personal_records.len(), 4);
+    }
+
+    #[tokio::test]
+    async fn progression_lists_each_session() {
+        let (app, db, _) = crate::test::common::setup().await;
+
+        sqlx::raw_sql(include_str!("../../migrations/test/personal_records.sql"))
+            .execute(&db)
+            .await
+            .expect("setup test sql ran successfully");
+
+        let (status, body) = get(
+            app.clone(),
+            "/activity/records/progression?user_id=11111111-1111-1111-1111-111111111111&exercise=squat",
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let dto: ReadProgressionResponse =
+            serde_json::from_slice(&body).expect("valid ReadProgressionResponse bytes");
+        let sessions: Vec<_> = dto
+            .sessions
+            .iter()
+            .map(|s| (s.best_weight_kg, s.volume_kg, s.personal_records.len()))
+            .collect();
+
+        assert_eq!(sessions, vec![(100.0, 1500.0, 3), (110.0, 330.0, 1)]);
+
+        let (status, _) = get(
+            app,
+            "/activity/records/progression?user_id=99999999-9999-9999-9999-999999999999&exercise=squat",
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,184 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use tracing::{debug, error};
+use uuid::Uuid;
+
+use crate::{
+    activity::model::{ActivityEntry, ActivityEntryRow, PersonalRecord, PersonalRecordRow},
+    error::YuhuhError,
+};
+
+// =============================================================================
+// Traits
+// =============================================================================
+
+#[async_trait]
+pub trait ReadPersonalRecordsRepository: std::fmt::Debug + Send + Sync + 'static {
+    /// Current best for each exercise and record type.
+    async fn read_current_personal_records(
+        &self,
+        user_id: &Uuid,
+        exercise: Option<&str>,
+    ) -> Result<Vec<PersonalRecord>, YuhuhError>;
+
+    /// Every record set, oldest first.
+    async fn read_personal_record_history(
+        &self,
+        user_id: &Uuid,
+        exercise: Option<&str>,
+    ) -> Result<Vec<PersonalRecord>, YuhuhError>;
+
+    /// Every weight lifting activity, oldest first.
+    async fn read_weight_lifting_entries(
+        &self,
+        user_id: &Uuid,
+    ) -> Result<Vec<ActivityEntry>, YuhuhError>;
+}
+
+// =============================================================================
+// Production Implementation
+// =============================================================================
+
+#[derive(Debug)]
+pub struct ReadPersonalRecordsRepositoryImpl {
+    pub db: PgPool,
+}
+
+impl ReadPersonalRecordsRepositoryImpl {
+    pub fn new(db: PgPool) -> Self {
+        ReadPersonalRecordsRepositoryImpl { db }
+    }
+}
+
+fn into_personal_records(rows: Vec<PersonalRecordRow>) -> Result<Vec<PersonalRecord>, YuhuhError> {
+    let records = rows
+        .into_iter()
+        .map(|row| row.try_into())
+        .collect::<Result<Vec<PersonalRecord>, _>>()
+        .inspect_err(|e| error!(error=?e, "encountered parsing error for personal record"))?;
+
+    Ok(records)
+}
+
+#[async_trait]
+impl ReadPersonalRecordsRepository for ReadPersonalRecordsRepositoryImpl {
+    async fn read_current_personal_records(
+        &self,
+        user_id: &Uuid,
+        exercise: Option<&str>,
+    ) -> Result<Vec<PersonalRecord>, YuhuhError> {
+        debug!(user_id=?user_id, exercise=?exercise, "received read current personal records");
+
+        let rows: Vec<PersonalRecordRow> = sqlx::query_as!(
+            PersonalRecordRow,
+            r#"
+            SELECT DISTINCT ON (exercise, record_type)
+                personal_record_id,
+                user_id,
+                activity_record_id,
+                exercise,
+                record_type,
+                value,
+                previous_value,
+                achieved_at
+            FROM personal_records
+            WHERE user_id = $1::uuid
+            AND ($2::text IS NULL OR exercise = $2::text)
+            ORDER BY exercise, record_type, value DESC, achieved_at;
+            "#,
+            user_id,
+            exercise
+        )
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, "database error while finding current personal records");
+
+            YuhuhError::DatabaseError(e)
+        })?;
+
+        into_personal_records(rows)
+    }
+
+    async fn read_personal_record_history(
+        &self,
+        user_id: &Uuid,
+        exercise: Option<&str>,
+    ) -> Result<Vec<PersonalRecord>, YuhuhError> {
+        debug!(user_id=?user_id, exercise=?exercise, "received read personal record history");
+
+        let rows: Vec<PersonalRecordRow> = sqlx::query_as!(
+            PersonalRecordRow,
+            r#"
+            SELECT
+                personal_record_id,
+                user_id,
+                activity_record_id,
+                exercise,
+                record_type,
+                value,
+                previous_value,
+                achieved_at
+            FROM personal_records
+            WHERE user_id = $1::uuid
+            AND ($2::text IS NULL OR exercise = $2::text)
+            ORDER BY achieved_at, exercise, record_type;
+            "#,
+            user_id,
+            exercise
+        )
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, "database error while finding personal record history");
+
+            YuhuhError::DatabaseError(e)
+        })?;
+
+        into_personal_records(rows)
+    }
+
+    async fn read_weight_lifting_entries(
+        &self,
+        user_id: &Uuid,
+    ) -> Result<Vec<ActivityEntry>, YuhuhError> {
+        debug!(user_id=?user_id, "received read weight lifting entries");
+
+        let records: Vec<ActivityEntryRow> = sqlx::query_as!(
+            ActivityEntryRow,
+            r#"
+            SELECT
+                activity_record_id,
+                user_id,
+                created_at,
+                updated_at,
+                activity,
+                activity_type,
+                activity_info,
+                calories_burned,
+                sample_summary,
+                logged_at
+            FROM activity_records
+            WHERE user_id = $1::uuid
+            AND activity_type = 'WeightLifting'
+            ORDER BY logged_at;
+            "#,
+            user_id
+        )
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, "database error while finding weight lifting records");
+
+            YuhuhError::DatabaseError(e)
+        })?;
+
+        let activity_entries = records
+            .into_iter()
+            .map(|row| row.try_into())
+            .collect::<Result<Vec<ActivityEntry>, _>>()
+            .inspect_err(|e| error!(error=?e, "encountered parsing error for activity entry"))?;
+
+        Ok(activity_entries)
+    }
+}
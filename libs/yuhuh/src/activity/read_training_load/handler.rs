@@ -0,0 +1,237 @@
+//! training load HTTP handlers
+//!
+//! This module provides an HTTP endpoint for a user's daily training load,
+//! acute:chronic ratio and fitness/fatigue/form series.
+
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::StatusCode,
+};
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, instrument, warn};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::{
+    activity::{
+        activity_info::ActivityInfo,
+        read_activity_entries::ActivityFilter,
+        state::ActivityState,
+        training_load::{
+            TrainingLoadDay, activity_load, overtraining_warnings, training_load_series,
+        },
+    },
+    auth::model::Caller,
+    date_range::DateRange,
+    error::YuhuhError,
+    user::state::UserState,
+};
+
+/// Days of history read before the range to warm up the averages.
+const WARM_UP_DAYS: u64 = 90;
+
+/// Range shown when no start date is given.
+const DEFAULT_RANGE_DAYS: u64 = 27;
+
+// ============================================================================
+// HTTP Request types
+// ============================================================================
+
+/// Request parameters for finding training load.
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ReadTrainingLoadRequest {
+    /// user ID to search by.
+    pub user_id: Uuid,
+    /// First day in the user's timezone, defaulting to four weeks before
+    /// `end_date`.
+    pub start_date: Option<NaiveDate>,
+    /// Last day in the user's timezone, defaulting to today.
+    pub end_date: Option<NaiveDate>,
+}
+
+// ============================================================================
+// HTTP Responsed types
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ReadTrainingLoadResponse {
+    /// Every day in the range, oldest first.
+    pub days: Vec<TrainingLoadDay>,
+    /// Early warnings of overtraining as of the last day.
+    pub warnings: Vec<String>,
+    /// Activities in the range without a duration to work load out from.
+    pub activities_without_load: u32,
+}
+
+// =============================================================================
+// HTTP Handlers
+// =============================================================================
+
+/// Find a user's daily training load, acute:chronic ratio and fitness, fatigue
+/// and form
+#[utoipa::path(
+    get,
+    path = "activity/load",
+    tag = "activity",
+    params(ReadTrainingLoadRequest),
+    responses(
+        (status = 200, description = "Found training load", body = ReadTrainingLoadResponse),
+        (status = 400, description = "Invalid date range"),
+        (status = 404, description = "User not found"),
+))]
+#[instrument]
+pub async fn read_training_load(
+    State(activity_state): State<Arc<ActivityState>>,
+    State(user_state): State<Arc<UserState>>,
+    caller: Caller,
+    Query(request): Query<ReadTrainingLoadRequest>,
+) -> Result<(StatusCode, Json<ReadTrainingLoadResponse>), YuhuhError> {
+    debug!("entering read_training_load");
+
+    caller.authorise_user(&request.user_id)?;
+
+    let user = user_state.require_user(&request.user_id).await?;
+
+    let tz = user.tz();
+    let range = DateRange::from_request(
+        request.start_date,
+        request.end_date,
+        Utc::now().with_timezone(&tz).date_naive(),
+        DEFAULT_RANGE_DAYS,
+    )?;
+    let (start, end) = (range.start, range.end);
+
+    let read = range.extended_back(WARM_UP_DAYS)?;
+    let from = read.start;
+    let (before, after) = read.utc_bounds()?;
+
+    // Read a day either side in UTC, then place each activity on its local day
+    let activity_records = activity_state
+        .read_activity_entries_repo
+        .read_activity_entries(
+            &request.user_id,
+            Some(before),
+            Some(after),
+            &ActivityFilter::default(),
+            i64::MAX,
+            0,
+        )
+        .await?;
+
+    let mut loads = vec![0.0; ((end - from).num_days() + 1) as usize];
+    let mut activities_without_load = 0;
+
+    for record in activity_records {
+        let date = record.logged_at.with_timezone(&tz).date_naive();
+        if date < from || date > end {
+            continue;
+        }
+
+        let load = ActivityInfo::parse(&record.activity_type, record.activity_info)
+            .inspect_err(|e| warn!(error = ?e, "skipping activity with invalid activity_info"))
+            .ok()
+            .and_then(|info| activity_load(&record.activity_type, &info));
+
+        match load {
+            Some(load) => loads[(date - from).num_days() as usize] += load,
+            None if date >= start => activities_without_load += 1,
+            None => {}
+        }
+    }
+
+    let days: Vec<TrainingLoadDay> = training_load_series(from, &loads)
+        .into_iter()
+        .filter(|d| d.date >= start)
+        .collect();
+
+    let warnings = days.last().map(overtraining_warnings).unwrap_or_default();
+
+    Ok((
+        StatusCode::OK,
+        Json(ReadTrainingLoadResponse {
+            days,
+            warnings,
+            activities_without_load,
+        }),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+    };
+    use http_body_util::BodyExt;
+    use pretty_assertions::assert_eq;
+    use tower::ServiceExt;
+
+    use crate::activity::read_training_load::ReadTrainingLoadResponse;
+
+    async fn read(
+        app: axum::Router,
+        query: &str,
+    ) -> (StatusCode, Option<ReadTrainingLoadResponse>) {
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/activity/load?{}", query))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let status = response.status();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+
+        (status, serde_json::from_slice(&body).ok())
+    }
+
+    #[tokio::test]
+    async fn ramped_up_load_warns() {
+        let (app, db, _) = crate::test::common::setup().await;
+
+        sqlx::raw_sql(include_str!("../../migrations/test/training_load.sql"))
+            .execute(&db)
+            .await
+            .expect("setup test sql ran successfully");
+
+        let (status, dto) = read(app.clone(), "user_id=11111111-1111-1111-1111-111111111111").await;
+        assert_eq!(status, StatusCode::OK);
+
+        let dto = dto.expect("valid ReadTrainingLoadResponse bytes");
+        assert_eq!(dto.days.len(), 28);
+        assert_eq!(dto.activities_without_load, 1);
+
+        // Three weeks of easy 30 minute runs, then a week of hard hours
+        let latest = dto.days.last().unwrap();
+        assert_eq!(latest.load, 480.0);
+        assert_eq!(latest.acute_load, 480.0);
+        assert_eq!(latest.chronic_load, (7.0 * 480.0 + 21.0 * 150.0) / 28.0);
+        assert!(
+            dto.warnings
+                .iter()
+                .any(|w| w.starts_with("acute:chronic ratio"))
+        );
+
+        let (status, dto) = read(app.clone(), "user_id=22222222-2222-2222-2222-222222222222").await;
+        assert_eq!(status, StatusCode::OK);
+
+        let dto = dto.expect("valid ReadTrainingLoadResponse bytes");
+        assert!(dto.days.iter().all(|d| d.load == 0.0));
+        assert!(dto.warnings.is_empty());
+
+        let (status, _) = read(
+            app,
+            "user_id=11111111-1111-1111-1111-111111111111&start_date=2025-02-01&end_date=2025-01-01",
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+}
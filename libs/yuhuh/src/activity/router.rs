@@ -1,11 +1,14 @@
 use axum::{
     Router,
-    routing::{get, post},
+    routing::{get, post, put},
 };
 use utoipa::OpenApi;
 
 use crate::{
-    activity::{create_activity_entries, read_activity_entries},
+    activity::{
+        activity_samples, activity_types, create_activity_entries, read_activity_entries,
+        read_personal_records, read_training_load,
+    },
     state::AppState,
 };
 
@@ -16,7 +19,16 @@ use crate::{
 #[derive(OpenApi)]
 #[openapi(paths(
     create_activity_entries::create_activity_entries,
-    read_activity_entries::read_activity_entries
+    read_activity_entries::read_activity_entries,
+    read_personal_records::read_personal_records,
+    read_personal_records::read_personal_record_history,
+    read_personal_records::read_progression,
+    activity_types::create_activity_type,
+    activity_types::read_activity_types,
+    read_training_load::read_training_load,
+    activity_samples::replace_activity_samples,
+    activity_samples::read_activity_samples,
+    activity_samples::read_heart_rate_zones
 ))]
 pub struct ActivityApi;
 
@@ -34,4 +46,37 @@ pub fn activity_router() -> Router<AppState> {
             "/activity",
             get(read_activity_entries::read_activity_entries),
         )
+        .route(
+            "/activity/records",
+            get(read_personal_records::read_personal_records),
+        )
+        .route(
+            "/activity/records/history",
+            get(read_personal_records::read_personal_record_history),
+        )
+        .route(
+            "/activity/records/progression",
+            get(read_personal_records::read_progression),
+        )
+        .route(
+            "/activity/types",
+            post(activity_types::create_activity_type),
+        )
+        .route("/activity/types", get(activity_types::read_activity_types))
+        .route(
+            "/activity/load",
+            get(read_training_load::read_training_load),
+        )
+        .route(
+            "/activity/{activity_record_id}/samples",
+            put(activity_samples::replace_activity_samples),
+        )
+        .route(
+            "/activity/{activity_record_id}/samples",
+            get(activity_samples::read_activity_samples),
+        )
+        .route(
+            "/activity/{activity_record_id}/zones",
+            get(activity_samples::read_heart_rate_zones),
+        )
 }
@@ -0,0 +1,287 @@
+//! Time series samples recorded during an activity, summarised and split into
+//! heart rate zones.
+//!
+//! Zones use the heart rate reserve (Karvonen) method, where zone 1 starts at
+//! 50% of the gap between resting and max heart rate and each zone after
+//! spans another 10%. Without a resting heart rate they fall back to
+//! percentages of max heart rate.
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Most samples an activity can have, a day at one sample a second.
+pub const MAX_SAMPLES: usize = 86_400;
+
+/// Longest gap a sample is counted for, so pauses in recording don't end up
+/// as time in a zone.
+const MAX_SAMPLE_GAP_SECONDS: i32 = 30;
+
+/// Highest heart rate that's accepted as a real reading.
+const MAX_VALID_HEART_RATE: i16 = 300;
+
+const ZONES: u8 = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema, sqlx::FromRow)]
+pub struct ActivitySample {
+    /// Seconds since the start of the activity.
+    pub offset_seconds: i32,
+    /// Beats per minute.
+    pub heart_rate: Option<i16>,
+    pub power_watts: Option<f32>,
+    /// Revolutions or steps per minute.
+    pub cadence: Option<f32>,
+    pub speed_kmh: Option<f32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct SampleSummary {
+    pub sample_count: u32,
+    /// Time between the first and last sample.
+    pub duration_seconds: i32,
+    pub average_heart_rate: Option<f32>,
+    pub max_heart_rate: Option<i16>,
+    pub average_power_watts: Option<f32>,
+    pub max_power_watts: Option<f32>,
+    pub average_cadence: Option<f32>,
+    pub average_speed_kmh: Option<f32>,
+    pub max_speed_kmh: Option<f32>,
+    /// Time in each zone, using the user's heart rates when the samples were
+    /// stored.
+    pub heart_rate_zones: Option<HeartRateZones>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct HeartRateZones {
+    pub max_heart_rate: i16,
+    pub resting_heart_rate: Option<i16>,
+    /// Zones 1 to 5, easiest first.
+    pub zones: Vec<HeartRateZone>,
+    /// Time spent below zone 1.
+    pub below_zones_seconds: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct HeartRateZone {
+    pub zone: u8,
+    pub min_heart_rate: i16,
+    /// Zone 5 also counts anything above max heart rate.
+    pub max_heart_rate: i16,
+    pub seconds: i32,
+}
+
+/// Sorts samples by offset and checks they're all plausible.
+pub fn validate_samples(samples: &mut [ActivitySample]) -> Result<(), String> {
+    if samples.is_empty() || samples.len() > MAX_SAMPLES {
+        return Err(format!(
+            "between 1 and {} samples are required",
+            MAX_SAMPLES
+        ));
+    }
+
+    samples.sort_by_key(|s| s.offset_seconds);
+
+    if samples[0].offset_seconds < 0 {
+        return Err("offset_seconds must not be negative".to_string());
+    }
+
+    if samples
+        .windows(2)
+        .any(|w| w[0].offset_seconds == w[1].offset_seconds)
+    {
+        return Err("each sample needs a different offset_seconds".to_string());
+    }
+
+    for sample in samples.iter() {
+        if let Some(heart_rate) = sample.heart_rate
+            && !(1..=MAX_VALID_HEART_RATE).contains(&heart_rate)
+        {
+            return Err(format!(
+                "heart_rate must be between 1 and {}, but got {} at {}s",
+                MAX_VALID_HEART_RATE, heart_rate, sample.offset_seconds
+            ));
+        }
+
+        for (name, value) in [
+            ("power_watts", sample.power_watts),
+            ("cadence", sample.cadence),
+            ("speed_kmh", sample.speed_kmh),
+        ] {
+            if let Some(value) = value
+                && !(value.is_finite() && value >= 0.0)
+            {
+                return Err(format!(
+                    "{} must not be negative, but got {} at {}s",
+                    name, value, sample.offset_seconds
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Seconds each sample counts for, the gap until the next sample.
+fn sample_seconds(samples: &[ActivitySample]) -> impl Iterator<Item = (&ActivitySample, i32)> {
+    samples.iter().enumerate().map(|(i, sample)| {
+        let seconds = samples
+            .get(i + 1)
+            .map(|next| (next.offset_seconds - sample.offset_seconds).min(MAX_SAMPLE_GAP_SECONDS))
+            .unwrap_or(0);
+
+        (sample, seconds)
+    })
+}
+
+/// Time spent in each heart rate zone, for samples sorted by offset.
+pub fn heart_rate_zones(
+    samples: &[ActivitySample],
+    max_heart_rate: i16,
+    resting_heart_rate: Option<i16>,
+) -> HeartRateZones {
+    let resting = resting_heart_rate.unwrap_or(0) as f32;
+    let reserve = max_heart_rate as f32 - resting;
+    let bound = |fraction: f32| (resting + fraction * reserve).round() as i16;
+
+    let mut zones: Vec<HeartRateZone> = (1..=ZONES)
+        .map(|zone| HeartRateZone {
+            zone,
+            min_heart_rate: bound(0.4 + 0.1 * zone as f32),
+            max_heart_rate: bound(0.5 + 0.1 * zone as f32),
+            seconds: 0,
+        })
+        .collect();
+    let mut below_zones_seconds = 0;
+
+    for (sample, seconds) in sample_seconds(samples) {
+        let Some(heart_rate) = sample.heart_rate else {
+            continue;
+        };
+
+        match zones
+            .iter_mut()
+            .rev()
+            .find(|z| heart_rate >= z.min_heart_rate)
+        {
+            Some(zone) => zone.seconds += seconds,
+            None => below_zones_seconds += seconds,
+        }
+    }
+
+    HeartRateZones {
+        max_heart_rate,
+        resting_heart_rate,
+        zones,
+        below_zones_seconds,
+    }
+}
+
+fn average(values: impl Iterator<Item = f32>) -> Option<f32> {
+    let (sum, count) = values.fold((0.0, 0), |(sum, count), v| (sum + v, count + 1));
+
+    (count > 0).then(|| sum / count as f32)
+}
+
+fn max(values: impl Iterator<Item = f32>) -> Option<f32> {
+    values.reduce(f32::max)
+}
+
+/// Summarises samples sorted by offset, including heart rate zones when the
+/// user's max heart rate is known.
+pub fn summarise(
+    samples: &[ActivitySample],
+    max_heart_rate: Option<i16>,
+    resting_heart_rate: Option<i16>,
+) -> SampleSummary {
+    let heart_rates = || samples.iter().filter_map(|s| s.heart_rate);
+    let power = || samples.iter().filter_map(|s| s.power_watts);
+    let speed = || samples.iter().filter_map(|s| s.speed_kmh);
+
+    let has_heart_rate = heart_rates().next().is_some();
+
+    SampleSummary {
+        sample_count: samples.len() as u32,
+        duration_seconds: match (samples.first(), samples.last()) {
+            (Some(first), Some(last)) => last.offset_seconds - first.offset_seconds,
+            _ => 0,
+        },
+        average_heart_rate: average(heart_rates().map(f32::from)),
+        max_heart_rate: heart_rates().max(),
+        average_power_watts: average(power()),
+        max_power_watts: max(power()),
+        average_cadence: average(samples.iter().filter_map(|s| s.cadence)),
+        average_speed_kmh: average(speed()),
+        max_speed_kmh: max(speed()),
+        heart_rate_zones: max_heart_rate
+            .filter(|_| has_heart_rate)
+            .map(|max| heart_rate_zones(samples, max, resting_heart_rate)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn heart_rate(offset_seconds: i32, heart_rate: i16) -> ActivitySample {
+        ActivitySample {
+            offset_seconds,
+            heart_rate: Some(heart_rate),
+            power_watts: None,
+            cadence: None,
+            speed_kmh: None,
+        }
+    }
+
+    #[test]
+    fn zones_use_heart_rate_reserve() {
+        // Reserve of 140, so zone 1 starts at 120 and zone 5 at 176
+        let samples = vec![
+            heart_rate(0, 100),
+            heart_rate(10, 125),
+            heart_rate(20, 180),
+            // Recording paused for a while
+            heart_rate(25, 200),
+            heart_rate(1000, 150),
+        ];
+
+        let zones = heart_rate_zones(&samples, 190, Some(50));
+
+        assert_eq!(
+            zones
+                .zones
+                .iter()
+                .map(|z| (z.min_heart_rate, z.seconds))
+                .collect::<Vec<_>>(),
+            vec![(120, 10), (134, 0), (148, 0), (162, 0), (176, 35)]
+        );
+        assert_eq!(zones.below_zones_seconds, 10);
+    }
+
+    #[test]
+    fn summary_skips_missing_values() {
+        let mut samples = vec![
+            ActivitySample {
+                offset_seconds: 10,
+                heart_rate: None,
+                power_watts: Some(250.0),
+                cadence: Some(90.0),
+                speed_kmh: Some(30.0),
+            },
+            heart_rate(0, 150),
+        ];
+
+        validate_samples(&mut samples).expect("valid samples");
+        let summary = summarise(&samples, None, None);
+
+        assert_eq!(summary.sample_count, 2);
+        assert_eq!(summary.duration_seconds, 10);
+        assert_eq!(summary.average_heart_rate, Some(150.0));
+        assert_eq!(summary.max_power_watts, Some(250.0));
+        assert_eq!(summary.heart_rate_zones, None);
+
+        let mut duplicate = vec![heart_rate(0, 150), heart_rate(0, 151)];
+        assert!(validate_samples(&mut duplicate).is_err());
+        assert!(validate_samples(&mut [heart_rate(0, 0)]).is_err());
+    }
+}
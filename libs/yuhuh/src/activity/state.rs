@@ -3,16 +3,22 @@ use std::sync::Arc;
 use sqlx::PgPool;
 
 use crate::activity::{
+    activity_samples::{ActivitySamplesRepository, ActivitySamplesRepositoryImpl},
+    activity_types::{ActivityTypesRepository, ActivityTypesRepositoryImpl},
     create_activity_entries::{
         CreateActivityEntriesRepository, CreateActivityEntriesRepositoryImpl,
     },
     read_activity_entries::{ReadActivityEntriesRepository, ReadActivityEntriesRepositoryImpl},
+    read_personal_records::{ReadPersonalRecordsRepository, ReadPersonalRecordsRepositoryImpl},
 };
 
 #[derive(Debug)]
 pub struct ActivityState {
     pub create_activity_entries_repo: Arc<dyn CreateActivityEntriesRepository>,
     pub read_activity_entries_repo: Arc<dyn ReadActivityEntriesRepository>,
+    pub read_personal_records_repo: Arc<dyn ReadPersonalRecordsRepository>,
+    pub activity_types_repo: Arc<dyn ActivityTypesRepository>,
+    pub activity_samples_repo: Arc<dyn ActivitySamplesRepository>,
 }
 
 impl ActivityState {
@@ -24,6 +30,11 @@ impl ActivityState {
             read_activity_entries_repo: Arc::new(ReadActivityEntriesRepositoryImpl::new(
                 db.clone(),
             )),
+            read_personal_records_repo: Arc::new(ReadPersonalRecordsRepositoryImpl::new(
+                db.clone(),
+            )),
+            activity_types_repo: Arc::new(ActivityTypesRepositoryImpl::new(db.clone())),
+            activity_samples_repo: Arc::new(ActivitySamplesRepositoryImpl::new(db.clone())),
         }
     }
 }
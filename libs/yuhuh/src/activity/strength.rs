@@ -0,0 +1,260 @@
+//! Strength training metrics and personal records for weight lifting.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+use utoipa::ToSchema;
+
+use crate::activity::{
+    activity_info::{ActivityInfo, WeightLiftingInfo},
+    model::{ActivityEntry, ActivityType, PersonalRecord, PersonalRecordType},
+};
+
+/// How a single exercise went in one session.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ExerciseMetrics {
+    /// Normalised exercise name.
+    pub exercise: String,
+    pub sets: u32,
+    pub reps: u32,
+    pub best_weight_kg: f32,
+    pub estimated_one_rep_max_kg: f32,
+    /// Reps multiplied by weight, summed across every set.
+    pub volume_kg: f32,
+}
+
+impl ExerciseMetrics {
+    pub fn value(&self, record_type: PersonalRecordType) -> f32 {
+        match record_type {
+            PersonalRecordType::BestWeight => self.best_weight_kg,
+            PersonalRecordType::EstimatedOneRepMax => self.estimated_one_rep_max_kg,
+            PersonalRecordType::BestVolume => self.volume_kg,
+        }
+    }
+}
+
+const RECORD_TYPES: [PersonalRecordType; 3] = [
+    PersonalRecordType::BestWeight,
+    PersonalRecordType::EstimatedOneRepMax,
+    PersonalRecordType::BestVolume,
+];
+
+/// Normalises exercise names so "Bench  Press" and "bench press" are the same
+/// exercise.
+pub fn normalise_exercise(name: &str) -> String {
+    name.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// One rep max estimated with the Epley formula.
+pub fn estimated_one_rep_max(reps: u32, weight_kg: f32) -> f32 {
+    if reps <= 1 {
+        weight_kg
+    } else {
+        weight_kg * (1.0 + reps as f32 / 30.0)
+    }
+}
+
+/// Metrics for each exercise in a session, in the order they were first done.
+///
+/// An exercise listed more than once is treated as a single exercise.
+pub fn exercise_metrics(info: &WeightLiftingInfo) -> Vec<ExerciseMetrics> {
+    let mut metrics: Vec<ExerciseMetrics> = vec![];
+
+    for exercise in &info.exercises {
+        let name = normalise_exercise(&exercise.name);
+
+        let index = match metrics.iter().position(|m| m.exercise == name) {
+            Some(index) => index,
+            None => {
+                metrics.push(ExerciseMetrics {
+                    exercise: name,
+                    sets: 0,
+                    reps: 0,
+                    best_weight_kg: 0.0,
+                    estimated_one_rep_max_kg: 0.0,
+                    volume_kg: 0.0,
+                });
+                metrics.len() - 1
+            }
+        };
+
+        let m = &mut metrics[index];
+        for set in &exercise.sets {
+            m.sets += 1;
+            m.reps += set.reps;
+            m.best_weight_kg = m.best_weight_kg.max(set.weight_kg);
+            m.estimated_one_rep_max_kg = m
+                .estimated_one_rep_max_kg
+                .max(estimated_one_rep_max(set.reps, set.weight_kg));
+            m.volume_kg += set.reps as f32 * set.weight_kg;
+        }
+    }
+
+    metrics
+}
+
+/// Metrics for a stored activity, or `None` if it isn't weight lifting.
+pub fn entry_metrics(entry: &ActivityEntry) -> Option<Vec<ExerciseMetrics>> {
+    if !matches!(entry.activity_type, ActivityType::WeightLifting) {
+        return None;
+    }
+
+    match ActivityInfo::parse(&entry.activity_type, entry.activity_info.clone()) {
+        Ok(ActivityInfo::WeightLifting(info)) => Some(exercise_metrics(&info)),
+        Ok(_) => None,
+        Err(e) => {
+            warn!(error = ?e, activity_record_id = ?entry.activity_record_id, "skipping weight lifting entry with invalid activity_info");
+            None
+        }
+    }
+}
+
+/// Finds the personal records set by new entries, compared against the
+/// user's current bests and each other in the order they were logged.
+///
+/// Bodyweight exercises never set weight based records.
+pub fn find_new_personal_records(
+    current: &[PersonalRecord],
+    entries: &[ActivityEntry],
+) -> Vec<PersonalRecord> {
+    let mut bests: HashMap<(String, PersonalRecordType), f32> = HashMap::new();
+    for record in current {
+        let best = bests
+            .entry((record.exercise.clone(), record.record_type))
+            .or_insert(record.value);
+        *best = best.max(record.value);
+    }
+
+    let mut ordered: Vec<&ActivityEntry> = entries.iter().collect();
+    ordered.sort_by_key(|e| e.logged_at);
+
+    let mut records = vec![];
+
+    for entry in ordered {
+        let Some(activity_record_id) = entry.activity_record_id else {
+            continue;
+        };
+
+        for metrics in entry_metrics(entry).unwrap_or_default() {
+            for record_type in RECORD_TYPES {
+                let value = metrics.value(record_type);
+                if value <= 0.0 {
+                    continue;
+                }
+
+                let key = (metrics.exercise.clone(), record_type);
+                let previous_value = bests.get(&key).copied();
+                if previous_value.is_some_and(|best| value <= best) {
+                    continue;
+                }
+
+                bests.insert(key, value);
+                records.push(PersonalRecord {
+                    personal_record_id: None,
+                    user_id: entry.user_id,
+                    activity_record_id,
+                    exercise: metrics.exercise.clone(),
+                    record_type,
+                    value,
+                    previous_value,
+                    achieved_at: entry.logged_at,
+                });
+            }
+        }
+    }
+
+    records
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn session(exercises: serde_json::Value, days_ago: i64) -> ActivityEntry {
+        ActivityEntry {
+            activity_record_id: Some(Uuid::now_v7()),
+            user_id: Uuid::nil(),
+            created_at: None,
+            updated_at: None,
+            activity: "gym".to_string(),
+            activity_type: ActivityType::WeightLifting,
+            activity_info: json!({ "exercises": exercises }),
+            calories_burned: None,
+            sample_summary: None,
+            logged_at: Utc::now() - Duration::days(days_ago),
+        }
+    }
+
+    #[test]
+    fn metrics_merge_repeated_exercises() {
+        let entry = session(
+            json!([
+                { "name": "Bench  Press", "sets": [{ "reps": 5, "weight_kg": 60.0 }] },
+                { "name": "bench press", "sets": [{ "reps": 1, "weight_kg": 70.0 }] }
+            ]),
+            0,
+        );
+
+        assert_eq!(
+            entry_metrics(&entry),
+            Some(vec![ExerciseMetrics {
+                exercise: "bench press".to_string(),
+                sets: 2,
+                reps: 6,
+                best_weight_kg: 70.0,
+                estimated_one_rep_max_kg: 70.0,
+                volume_kg: 370.0,
+            }])
+        );
+    }
+
+    #[test]
+    fn only_beaten_bests_are_records() {
+        let current = vec![PersonalRecord {
+            personal_record_id: None,
+            user_id: Uuid::nil(),
+            activity_record_id: Uuid::nil(),
+            exercise: "squat".to_string(),
+            record_type: PersonalRecordType::BestWeight,
+            value: 100.0,
+            previous_value: None,
+            achieved_at: Utc::now() - Duration::days(30),
+        }];
+
+        let older = session(
+            json!([{ "name": "squat", "sets": [{ "reps": 3, "weight_kg": 100.0 }] }]),
+            2,
+        );
+        let newer = session(
+            json!([{ "name": "squat", "sets": [{ "reps": 1, "weight_kg": 105.0 }] }]),
+            1,
+        );
+
+        let records = find_new_personal_records(&current, &[newer, older]);
+        let best_weights: Vec<_> = records
+            .iter()
+            .filter(|r| r.record_type == PersonalRecordType::BestWeight)
+            .map(|r| (r.value, r.previous_value))
+            .collect();
+
+        // Equalling the old best isn't a record
+        assert_eq!(best_weights, vec![(105.0, Some(100.0))]);
+
+        let volumes: Vec<_> = records
+            .iter()
+            .filter(|r| r.record_type == PersonalRecordType::BestVolume)
+            .map(|r| (r.value, r.previous_value))
+            .collect();
+
+        assert_eq!(volumes, vec![(300.0, None)]);
+    }
+}
@@ -0,0 +1,178 @@
+//! Training load from activity history.
+//!
+//! Each activity's load is its session RPE, the minutes spent multiplied by
+//! how hard it felt from 1 to 10. Daily loads then feed two models:
+//!
+//! - The acute:chronic workload ratio, comparing the last week's average load
+//!   against the last four weeks'. Ratios above 1.5 are linked with a higher
+//!   risk of injury.
+//! - The fitness/fatigue model, where fitness and fatigue are exponentially
+//!   weighted averages of load over 42 and 7 days, and form is the difference.
+
+use chrono::{Days, NaiveDate};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::activity::{
+    activity_info::ActivityInfo,
+    calorie_burn::{Intensity, estimate_intensity},
+    model::ActivityType,
+};
+
+/// Days averaged for acute load.
+const ACUTE_DAYS: usize = 7;
+
+/// Days averaged for chronic load.
+const CHRONIC_DAYS: usize = 28;
+
+/// Time constants for the fitness and fatigue averages.
+const FITNESS_DAYS: f32 = 42.0;
+const FATIGUE_DAYS: f32 = 7.0;
+
+/// Acute:chronic ratio above which the risk of injury climbs.
+const HIGH_RATIO: f32 = 1.5;
+
+/// Form below which fatigue is well ahead of fitness.
+const LOW_FORM: f32 = -30.0;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct TrainingLoadDay {
+    pub date: NaiveDate,
+    /// Total load of the day's activities.
+    pub load: f32,
+    /// Average daily load over the last 7 days.
+    pub acute_load: f32,
+    /// Average daily load over the last 28 days.
+    pub chronic_load: f32,
+    /// Acute load divided by chronic load, when there's any chronic load.
+    pub acute_chronic_ratio: Option<f32>,
+    pub fitness: f32,
+    pub fatigue: f32,
+    /// Fitness minus fatigue, negative when carrying more fatigue than usual.
+    pub form: f32,
+}
+
+/// RPE assumed from an estimated intensity when none was recorded.
+fn intensity_rpe(intensity: Intensity) -> f32 {
+    match intensity {
+        Intensity::Light => 3.0,
+        Intensity::Moderate => 5.0,
+        Intensity::Vigorous => 8.0,
+    }
+}
+
+/// Session RPE load of an activity, or `None` when its duration can't be
+/// worked out.
+pub fn activity_load(activity_type: &ActivityType, info: &ActivityInfo) -> Option<f32> {
+    let minutes = info.duration_seconds()? / 60.0;
+    let rpe = info
+        .rpe()
+        .unwrap_or_else(|| intensity_rpe(estimate_intensity(activity_type, info)));
+
+    Some(minutes * rpe)
+}
+
+fn average(loads: &[f32], index: usize, days: usize) -> f32 {
+    let start = (index + 1).saturating_sub(days);
+
+    loads[start..=index].iter().sum::<f32>() / days as f32
+}
+
+/// Builds the load series for consecutive days of `loads` starting at `from`.
+///
+/// Earlier days only warm up the averages, so callers should pass in more
+/// history than they intend to show.
+pub fn training_load_series(from: NaiveDate, loads: &[f32]) -> Vec<TrainingLoadDay> {
+    let mut fitness = 0.0;
+    let mut fatigue = 0.0;
+
+    loads
+        .iter()
+        .enumerate()
+        .map(|(index, &load)| {
+            fitness += (load - fitness) / FITNESS_DAYS;
+            fatigue += (load - fatigue) / FATIGUE_DAYS;
+
+            let acute_load = average(loads, index, ACUTE_DAYS);
+            let chronic_load = average(loads, index, CHRONIC_DAYS);
+
+            TrainingLoadDay {
+                date: from + Days::new(index as u64),
+                load,
+                acute_load,
+                chronic_load,
+                acute_chronic_ratio: (chronic_load > 0.0).then(|| acute_load / chronic_load),
+                fitness,
+                fatigue,
+                form: fitness - fatigue,
+            }
+        })
+        .collect()
+}
+
+/// Early warnings of overtraining from the most recent day.
+pub fn overtraining_warnings(latest: &TrainingLoadDay) -> Vec<String> {
+    let mut warnings = vec![];
+
+    if let Some(ratio) = latest.acute_chronic_ratio
+        && ratio > HIGH_RATIO
+    {
+        warnings.push(format!(
+            "acute:chronic ratio of {:.2} is above {}, load has ramped up faster than usual",
+            ratio, HIGH_RATIO
+        ));
+    }
+
+    if latest.form < LOW_FORM {
+        warnings.push(format!(
+            "form of {:.0} is below {}, fatigue is well ahead of fitness",
+            latest.form, LOW_FORM
+        ));
+    }
+
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn load_prefers_recorded_rpe() {
+        let easy = ActivityInfo::parse(
+            &ActivityType::Running,
+            json!({ "distance_km": 5.0, "duration_seconds": 1800, "rpe": 4 }),
+        )
+        .expect("valid running info");
+        let unrated = ActivityInfo::parse(
+            &ActivityType::Running,
+            json!({ "distance_km": 12.0, "duration_seconds": 3600 }),
+        )
+        .expect("valid running info");
+
+        assert_eq!(activity_load(&ActivityType::Running, &easy), Some(120.0));
+        // 12 km/h is vigorous
+        assert_eq!(activity_load(&ActivityType::Running, &unrated), Some(480.0));
+    }
+
+    #[test]
+    fn spike_in_load_warns() {
+        let from = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let mut loads = vec![100.0; 21];
+        loads.extend([400.0; 7]);
+
+        let series = training_load_series(from, &loads);
+        let latest = series.last().unwrap();
+
+        assert_eq!(latest.date, NaiveDate::from_ymd_opt(2025, 1, 28).unwrap());
+        assert_eq!(latest.acute_load, 400.0);
+        assert_eq!(latest.chronic_load, 175.0);
+        assert_eq!(overtraining_warnings(latest).len(), 2);
+
+        let steady = training_load_series(from, &[100.0; 200]);
+        assert!(overtraining_warnings(steady.last().unwrap()).is_empty());
+    }
+}
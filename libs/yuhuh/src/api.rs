@@ -1,56 +1,132 @@
 use std::sync::Arc;
 
 use anyhow::{Context, Ok, Result};
-use axum::{Router, http::HeaderName};
+use axum::{
+    Router,
+    http::{HeaderName, header::AUTHORIZATION},
+};
 use sqlx::PgPool;
 use tower_http::trace::{DefaultOnFailure, DefaultOnResponse};
 use tracing::info;
 use tracing::{Level, Span};
 
 use middleware::*;
-use utoipa::OpenApi;
+use utoipa::openapi::security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi};
 use utoipa_axum::router::OpenApiRouter;
 use utoipa_scalar::{Scalar, Servable};
 
 use crate::activity::router::activity_router;
+use crate::auth::key::API_KEY_HEADER;
+use crate::auth::layer::AuthLayer;
+use crate::auth::router::auth_router;
+use crate::auth::state::AuthState;
 use crate::config::Config;
+use crate::discord::router::discord_router;
 use crate::error::*;
+use crate::fasting::router::fasting_router;
 use crate::food::router::food_router;
 use crate::health::*;
+use crate::insights::router::insights_router;
+use crate::live::router::live_router;
 use crate::mood::router::mood_router;
-use crate::state::create_app_state;
+use crate::reminders::router::reminder_router;
+use crate::search::router::search_router;
+use crate::state::{AppState, create_app_state};
 use crate::user::router::user_router;
+use crate::webhooks::router::webhook_router;
 
 #[derive(OpenApi)]
 #[openapi(
     tags(
         (name = "yuhuh", description = "API")
     ),
+    modifiers(&SecurityAddon),
+    security(("api_key" = []), ("bearer" = [])),
     nest(
         (path="/api/v1/", api = crate::user::router::UserApi),
         (path="/api/v1/", api = crate::food::router::FoodApi),
         (path="/api/v1/", api = crate::activity::router::ActivityApi),
         (path="/api/v1/", api = crate::mood::router::MoodApi),
+        (path="/api/v1/", api = crate::fasting::router::FastingApi),
+        (path="/api/v1/", api = crate::insights::router::InsightsApi),
+        (path="/api/v1/", api = crate::search::router::SearchApi),
+        (path="/api/v1/", api = crate::reminders::router::ReminderApi),
+        (path="/api/v1/", api = crate::webhooks::router::WebhookApi),
+        (path="/api/v1/", api = crate::live::router::LiveApi),
+        (path="/api/v1/", api = crate::discord::router::DiscordApi),
+        (path="/api/v1/", api = crate::auth::router::AuthApi),
         (path="/api/v1/", api = crate::health::HealthApi)
     )
 )]
 struct ApiDoc;
 
+/// Documents the API keys and user tokens requests are authenticated with.
+struct SecurityAddon;
+
+impl Modify for SecurityAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+
+        components.add_security_scheme(
+            "api_key",
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new(API_KEY_HEADER))),
+        );
+        components.add_security_scheme(
+            "bearer",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .bearer_format("JWT")
+                    .build(),
+            ),
+        );
+    }
+}
+
+/// Layer authenticating requests with API keys and user tokens.
+fn auth_layer(config: &Config, auth_state: &AuthState) -> AuthLayer {
+    // Discord signs the interactions it sends instead
+    let mut public_paths = vec!["/discord/interactions".to_string()];
+    if config.public_health {
+        public_paths.push("/health".to_string());
+    }
+
+    AuthLayer::new(
+        auth_state.api_keys_repo.clone(),
+        auth_state.token_keys.clone(),
+        config.require_api_key,
+        config.admin_api_key.as_deref(),
+        public_paths,
+    )
+}
+
 pub fn new_app(config: &Config, db: PgPool, global_span: Arc<Span>) -> Router {
-    let app_state = create_app_state(config, db);
+    app_with_state(config, create_app_state(config, db), global_span)
+}
 
+fn app_with_state(config: &Config, app_state: AppState, global_span: Arc<Span>) -> Router {
     Router::new()
         .merge(health_router())
         .merge(user_router())
         .merge(food_router())
         .merge(activity_router())
         .merge(mood_router())
+        .merge(fasting_router())
+        .merge(insights_router())
+        .merge(search_router())
+        .merge(reminder_router())
+        .merge(webhook_router())
+        .merge(live_router())
+        .merge(discord_router())
+        .merge(auth_router())
         //.merge(user::user_router())
         .with_state(app_state.clone())
         .fallback(|| async {
             // Return the core not found error with a nice message for our caller
             YuhuhError::NotFound("no matching route found".to_string())
         })
+        .layer(auth_layer(config, &app_state.auth))
         .layer(
             tower_http::trace::TraceLayer::new_for_http()
                 .on_failure(DefaultOnFailure::new().level(Level::ERROR))
@@ -68,6 +144,13 @@ pub fn new_app(config: &Config, db: PgPool, global_span: Arc<Span>) -> Router {
             HeaderName::from_static(requestid::REQUEST_ID_HEADER),
             tower_http::request_id::MakeRequestUuid,
         ))
+        // Keep credentials out of the logs
+        .layer(
+            tower_http::sensitive_headers::SetSensitiveRequestHeadersLayer::new([
+                AUTHORIZATION,
+                HeaderName::from_static(API_KEY_HEADER),
+            ]),
+        )
 }
 
 pub async fn serve(config: &Config, db: PgPool) -> Result<()> {
@@ -79,13 +162,23 @@ pub async fn serve(config: &Config, db: PgPool) -> Result<()> {
     // tracing
     let global_span = Arc::new(Span::current());
 
-    let app: Router = new_app(config, db, global_span);
+    // The docs are authenticated the same way as the app, so share its keys
+    let app_state = create_app_state(config, db);
+    let docs_auth_layer = auth_layer(config, &app_state.auth);
+    let app: Router = app_with_state(config, app_state, global_span);
 
     let (app_openapi, api) = OpenApiRouter::with_openapi(ApiDoc::openapi())
         .nest("/api/v1", app.into())
         .split_for_parts();
 
-    let app_openapi = app_openapi.merge(Scalar::with_url("/scalar", api));
+    let docs = Router::new().merge(Scalar::with_url("/scalar", api));
+    let docs = if config.public_docs {
+        docs
+    } else {
+        docs.layer(docs_auth_layer)
+    };
+
+    let app_openapi = app_openapi.merge(docs);
 
     let listener = tokio::net::TcpListener::bind(format!("127.0.0.1:{}", config.port))
         .await
@@ -0,0 +1,356 @@
+//! API key HTTP handlers
+//!
+//! This module provides HTTP endpoints for admins to create the keys service
+//! clients authenticate with, see when they were last used and revoke them.
+
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, instrument};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    auth::{
+        key::{display_prefix, generate_key, hash_key},
+        model::{ApiKey, Scope},
+        state::AuthState,
+    },
+    error::YuhuhError,
+};
+
+/// Longest name an API key can have.
+const MAX_NAME_LENGTH: usize = 100;
+
+// ============================================================================
+// HTTP Request types
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateApiKeyRequest {
+    /// What the key is for, e.g. `discord bot`.
+    pub name: String,
+    /// What the key can do, at least one of `read`, `write` and `admin`.
+    pub scopes: Vec<Scope>,
+    /// When the key stops working, never when not set.
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+// ============================================================================
+// HTTP Responsed types
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateApiKeyResponse {
+    pub api_key: ApiKey,
+    /// The key to send in the `X-API-Key` header. Only ever returned here.
+    pub key: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ReadApiKeysResponse {
+    pub api_keys: Vec<ApiKey>,
+}
+
+// =============================================================================
+// HTTP Handlers
+// =============================================================================
+
+/// Create an API key for a service client
+#[utoipa::path(
+    post,
+    path = "api-keys",
+    tag = "api keys",
+    responses(
+        (status = 201, description = "api key created", body = CreateApiKeyResponse),
+        (status = 400, description = "invalid name, scopes or expiry"),
+        (status = 401, description = "missing or invalid api key"),
+        (status = 403, description = "api key without the admin scope")
+))]
+#[instrument]
+pub async fn create_api_key(
+    State(auth_state): State<Arc<AuthState>>,
+    Json(request): Json<CreateApiKeyRequest>,
+) -> Result<(StatusCode, Json<CreateApiKeyResponse>), YuhuhError> {
+    debug!("entering create_api_key");
+
+    let name = request.name.trim();
+    if name.is_empty() || name.chars().count() > MAX_NAME_LENGTH {
+        return Err(YuhuhError::BadRequest(format!(
+            "name must be between 1 and {} characters",
+            MAX_NAME_LENGTH
+        )));
+    }
+
+    let mut scopes = request.scopes;
+    scopes.sort();
+    scopes.dedup();
+    if scopes.is_empty() {
+        return Err(YuhuhError::BadRequest(
+            "an api key needs at least one scope".to_string(),
+        ));
+    }
+
+    let now = Utc::now();
+    if request
+        .expires_at
+        .is_some_and(|expires_at| expires_at <= now)
+    {
+        return Err(YuhuhError::BadRequest(
+            "expires_at must be in the future".to_string(),
+        ));
+    }
+
+    let key = generate_key()?;
+    let api_key = ApiKey {
+        api_key_id: Uuid::now_v7(),
+        name: name.to_string(),
+        prefix: display_prefix(&key),
+        scopes,
+        expires_at: request.expires_at,
+        last_used_at: None,
+        created_at: now,
+    };
+
+    auth_state
+        .api_keys_repo
+        .create_api_key(&api_key, &hash_key(&key))
+        .await?;
+
+    info!(api_key_id = ?api_key.api_key_id, prefix = api_key.prefix, "created api key");
+
+    Ok((
+        StatusCode::CREATED,
+        Json(CreateApiKeyResponse { api_key, key }),
+    ))
+}
+
+/// Find every API key, without the keys themselves
+#[utoipa::path(
+    get,
+    path = "api-keys",
+    tag = "api keys",
+    responses(
+        (status = 200, description = "found api keys", body = ReadApiKeysResponse),
+        (status = 401, description = "missing or invalid api key"),
+        (status = 403, description = "api key without the admin scope")
+))]
+#[instrument]
+pub async fn read_api_keys(
+    State(auth_state): State<Arc<AuthState>>,
+) -> Result<(StatusCode, Json<ReadApiKeysResponse>), YuhuhError> {
+    debug!("entering read_api_keys");
+
+    let api_keys = auth_state.api_keys_repo.read_api_keys().await?;
+
+    Ok((StatusCode::OK, Json(ReadApiKeysResponse { api_keys })))
+}
+
+/// Revoke an API key so it stops working straight away
+#[utoipa::path(
+    delete,
+    path = "api-keys/{api_key_id}",
+    tag = "api keys",
+    params(("api_key_id" = Uuid, Path, description = "API key to revoke")),
+    responses(
+        (status = 204, description = "api key revoked"),
+        (status = 401, description = "missing or invalid api key"),
+        (status = 403, description = "api key without the admin scope"),
+        (status = 404, description = "no such api key")
+))]
+#[instrument]
+pub async fn delete_api_key(
+    State(auth_state): State<Arc<AuthState>>,
+    Path(api_key_id): Path<Uuid>,
+) -> Result<StatusCode, YuhuhError> {
+    debug!("entering delete_api_key");
+
+    if !auth_state.api_keys_repo.delete_api_key(&api_key_id).await? {
+        return Err(YuhuhError::NotFound("no such api key".to_string()));
+    }
+
+    info!(api_key_id = ?api_key_id, "revoked api key");
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        Router,
+        body::Body,
+        http::{self, Request, StatusCode},
+    };
+    use chrono::{DateTime, TimeDelta, Utc};
+    use http_body_util::BodyExt;
+    use pretty_assertions::assert_eq;
+    use tower::ServiceExt;
+    use tracing::Span;
+
+    use crate::{
+        auth::{
+            api_keys::{CreateApiKeyRequest, CreateApiKeyResponse, ReadApiKeysResponse},
+            key::API_KEY_HEADER,
+            model::Scope,
+        },
+        config::Config,
+    };
+
+    const ADMIN_KEY: &str = "configured-admin-key";
+
+    fn request(method: &str, uri: &str, key: Option<&str>, body: Body) -> Request<Body> {
+        let mut builder = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(http::header::CONTENT_TYPE, "application/json");
+        if let Some(key) = key {
+            builder = builder.header(API_KEY_HEADER, key);
+        }
+
+        builder.body(body).unwrap()
+    }
+
+    async fn status(app: &Router, method: &str, uri: &str, key: Option<&str>) -> StatusCode {
+        app.clone()
+            .oneshot(request(method, uri, key, Body::from("{}")))
+            .await
+            .unwrap()
+            .status()
+    }
+
+    async fn create(app: &Router, key: &str, scopes: Vec<Scope>) -> (StatusCode, Vec<u8>) {
+        let body = serde_json::to_string(&CreateApiKeyRequest {
+            name: format!("{:?} client", scopes),
+            scopes,
+            expires_at: None,
+        })
+        .expect("request is valid body");
+
+        let response = app
+            .clone()
+            .oneshot(request("POST", "/api-keys", Some(key), Body::from(body)))
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+
+        (status, body.to_vec())
+    }
+
+    #[tokio::test]
+    async fn api_keys_required_with_enough_scope() {
+        let (_, db, _) = crate::test::common::setup().await;
+
+        let config = Config {
+            require_api_key: true,
+            public_health: true,
+            admin_api_key: Some(ADMIN_KEY.to_string()),
+            ..Default::default()
+        };
+        let app = crate::api::new_app(&config, db.clone(), std::sync::Arc::new(Span::current()));
+
+        assert_eq!(status(&app, "GET", "/health", None).await, StatusCode::OK);
+        assert_eq!(
+            status(&app, "GET", "/webhooks", None).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            status(&app, "GET", "/webhooks", Some("yuhuh_guessed")).await,
+            StatusCode::UNAUTHORIZED
+        );
+
+        let (code, body) = create(&app, ADMIN_KEY, vec![Scope::Read, Scope::Read]).await;
+        assert_eq!(code, StatusCode::CREATED);
+        let read: CreateApiKeyResponse =
+            serde_json::from_slice(&body).expect("valid CreateApiKeyResponse bytes");
+        assert_eq!(read.api_key.scopes, vec![Scope::Read]);
+        assert!(read.key.starts_with(&read.api_key.prefix));
+
+        let (_, body) = create(&app, ADMIN_KEY, vec![Scope::Write]).await;
+        let write: CreateApiKeyResponse =
+            serde_json::from_slice(&body).expect("valid CreateApiKeyResponse bytes");
+
+        let (code, _) = create(&app, ADMIN_KEY, vec![]).await;
+        assert_eq!(code, StatusCode::BAD_REQUEST);
+
+        // Reading needs read, changing needs write, and keys need admin
+        assert_eq!(
+            status(&app, "GET", "/webhooks", Some(&read.key)).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(&app, "POST", "/webhooks", Some(&read.key)).await,
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            status(&app, "POST", "/webhooks", Some(&write.key)).await,
+            StatusCode::UNPROCESSABLE_ENTITY
+        );
+        assert_eq!(
+            status(&app, "GET", "/api-keys", Some(&write.key)).await,
+            StatusCode::FORBIDDEN
+        );
+        let (code, _) = create(&app, &write.key, vec![Scope::Admin]).await;
+        assert_eq!(code, StatusCode::FORBIDDEN);
+
+        let response = app
+            .clone()
+            .oneshot(request("GET", "/api-keys", Some(ADMIN_KEY), Body::empty()))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let keys: ReadApiKeysResponse =
+            serde_json::from_slice(&body).expect("valid ReadApiKeysResponse bytes");
+        assert_eq!(keys.api_keys.len(), 2);
+        assert_eq!(keys.api_keys[0].api_key_id, read.api_key.api_key_id);
+        let last_used_at = keys.api_keys[0].last_used_at.expect("read key used");
+
+        // Using a key again straight away doesn't write to it
+        assert_eq!(
+            status(&app, "GET", "/webhooks", Some(&read.key)).await,
+            StatusCode::OK
+        );
+        let still_used_at: Option<DateTime<Utc>> =
+            sqlx::query_scalar("SELECT last_used_at FROM api_keys WHERE api_key_id = $1")
+                .bind(read.api_key.api_key_id)
+                .fetch_one(&db)
+                .await
+                .unwrap();
+        assert_eq!(still_used_at, Some(last_used_at));
+        assert!(!String::from_utf8_lossy(&body).contains(&read.key));
+
+        // Expired keys stop working
+        sqlx::query("UPDATE api_keys SET expires_at = $1 WHERE api_key_id = $2")
+            .bind(Utc::now() - TimeDelta::minutes(1))
+            .bind(write.api_key.api_key_id)
+            .execute(&db)
+            .await
+            .unwrap();
+        assert_eq!(
+            status(&app, "GET", "/webhooks", Some(&write.key)).await,
+            StatusCode::UNAUTHORIZED
+        );
+
+        // As do revoked ones
+        let uri = format!("/api-keys/{}", read.api_key.api_key_id);
+        assert_eq!(
+            status(&app, "DELETE", &uri, Some(ADMIN_KEY)).await,
+            StatusCode::NO_CONTENT
+        );
+        assert_eq!(
+            status(&app, "DELETE", &uri, Some(ADMIN_KEY)).await,
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            status(&app, "GET", "/webhooks", Some(&read.key)).await,
+            StatusCode::UNAUTHORIZED
+        );
+    }
+}
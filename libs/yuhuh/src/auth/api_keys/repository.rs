@@ -0,0 +1,208 @@
+use async_trait::async_trait;
+use chrono::{DateTime, TimeDelta, Utc};
+use sqlx::PgPool;
+use tracing::{debug, error};
+use uuid::Uuid;
+
+use crate::{
+    auth::model::{ApiKey, ApiKeyRow},
+    error::YuhuhError,
+};
+
+/// How out of date a key's `last_used_at` can get, so a busy key isn't
+/// written to on every request.
+const LAST_USED_RESOLUTION: TimeDelta = TimeDelta::minutes(1);
+
+// =============================================================================
+// Traits
+// =============================================================================
+
+#[async_trait]
+pub trait ApiKeysRepository: std::fmt::Debug + Send + Sync + 'static {
+    async fn create_api_key(&self, api_key: &ApiKey, key_hash: &str) -> Result<(), YuhuhError>;
+
+    /// Every key, oldest first.
+    async fn read_api_keys(&self) -> Result<Vec<ApiKey>, YuhuhError>;
+
+    /// Removes a key so it stops working, returning whether there was one.
+    async fn delete_api_key(&self, api_key_id: &Uuid) -> Result<bool, YuhuhError>;
+
+    /// The unexpired key with a hash, recording that it was used at `now`
+    /// unless it was already used within the last minute.
+    async fn use_api_key(
+        &self,
+        key_hash: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Option<ApiKey>, YuhuhError>;
+}
+
+// =============================================================================
+// Production Implementation
+// =============================================================================
+
+#[derive(Debug)]
+pub struct ApiKeysRepositoryImpl {
+    pub db: PgPool,
+}
+
+impl ApiKeysRepositoryImpl {
+    pub fn new(db: PgPool) -> Self {
+        ApiKeysRepositoryImpl { db }
+    }
+}
+
+#[async_trait]
+impl ApiKeysRepository for ApiKeysRepositoryImpl {
+    async fn create_api_key(&self, api_key: &ApiKey, key_hash: &str) -> Result<(), YuhuhError> {
+        debug!(api_key=?api_key, "creating api key");
+
+        let scopes: Vec<String> = api_key.scopes.iter().map(|s| s.to_string()).collect();
+
+        sqlx::query!(
+            r#"
+            INSERT INTO api_keys (
+                api_key_id,
+                created_at,
+                name,
+                prefix,
+                key_hash,
+                scopes,
+                expires_at
+            )
+            VALUES ($1::uuid, $2::timestamptz, $3::text, $4::text, $5::text, $6::text[], $7::timestamptz);
+            "#,
+            api_key.api_key_id,
+            api_key.created_at,
+            api_key.name,
+            api_key.prefix,
+            key_hash,
+            &scopes,
+            api_key.expires_at
+        )
+        .execute(&self.db)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, "database error while creating api key");
+
+            YuhuhError::DatabaseError(e)
+        })?;
+
+        Ok(())
+    }
+
+    async fn read_api_keys(&self) -> Result<Vec<ApiKey>, YuhuhError> {
+        let records: Vec<ApiKeyRow> = sqlx::query_as!(
+            ApiKeyRow,
+            r#"
+            SELECT
+                api_key_id,
+                name,
+                prefix,
+                scopes,
+                expires_at,
+                last_used_at,
+                created_at
+            FROM api_keys
+            ORDER BY api_key_id;
+            "#
+        )
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, "database error while reading api keys");
+
+            YuhuhError::DatabaseError(e)
+        })?;
+
+        let api_keys = records
+            .into_iter()
+            .map(ApiKey::try_from)
+            .collect::<Result<Vec<ApiKey>, _>>()
+            .inspect_err(|e| error!(error=?e, "encountered parsing error for api key"))?;
+
+        Ok(api_keys)
+    }
+
+    async fn delete_api_key(&self, api_key_id: &Uuid) -> Result<bool, YuhuhError> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM api_keys
+            WHERE api_key_id = $1::uuid;
+            "#,
+            api_key_id
+        )
+        .execute(&self.db)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, "database error while deleting api key");
+
+            YuhuhError::DatabaseError(e)
+        })?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn use_api_key(
+        &self,
+        key_hash: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Option<ApiKey>, YuhuhError> {
+        let record = sqlx::query_as!(
+            ApiKeyRow,
+            r#"
+            SELECT
+                api_key_id,
+                name,
+                prefix,
+                scopes,
+                expires_at,
+                last_used_at,
+                created_at
+            FROM api_keys
+            WHERE key_hash = $1::text
+            AND (expires_at IS NULL OR expires_at > $2::timestamptz);
+            "#,
+            key_hash,
+            now
+        )
+        .fetch_optional(&self.db)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, "database error while finding api key");
+
+            YuhuhError::DatabaseError(e)
+        })?;
+
+        let Some(mut record) = record else {
+            return Ok(None);
+        };
+
+        // Only written to when it's out of date, and conditionally, so
+        // requests at the same time don't all queue on the key's row
+        let used_before = now - LAST_USED_RESOLUTION;
+        if record.last_used_at.is_none_or(|used| used < used_before) {
+            sqlx::query!(
+                r#"
+                UPDATE api_keys
+                SET last_used_at = $2::timestamptz
+                WHERE api_key_id = $1::uuid
+                AND (last_used_at IS NULL OR last_used_at < $3::timestamptz);
+                "#,
+                record.api_key_id,
+                now,
+                used_before
+            )
+            .execute(&self.db)
+            .await
+            .map_err(|e| {
+                error!(error = ?e, "database error while using api key");
+
+                YuhuhError::DatabaseError(e)
+            })?;
+
+            record.last_used_at = Some(now);
+        }
+
+        Ok(Some(ApiKey::try_from(record)?))
+    }
+}
@@ -0,0 +1,370 @@
+//! token exchange HTTP handler
+//!
+//! This module provides the endpoint service clients exchange their API key
+//! for a token for one user, which clients acting for that user can then use
+//! without being able to reach anyone else's records.
+
+use std::sync::Arc;
+
+use axum::{Json, extract::State, http::StatusCode};
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, instrument};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    auth::{
+        model::{Caller, Scope},
+        state::AuthState,
+    },
+    config::Config,
+    error::YuhuhError,
+    user::state::UserState,
+};
+
+// ============================================================================
+// HTTP Request types
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ExchangeTokenRequest {
+    /// User the token is for.
+    pub user_id: Uuid,
+    /// What the token can do, `read` and `write` when not set. User tokens
+    /// can't have the `admin` scope.
+    pub scopes: Option<Vec<Scope>>,
+}
+
+// ============================================================================
+// HTTP Responsed types
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ExchangeTokenResponse {
+    /// Token to send as `Authorization: Bearer <token>`.
+    pub access_token: String,
+    /// Always `Bearer`.
+    pub token_type: String,
+    /// Seconds until the token expires.
+    pub expires_in: u64,
+    pub expires_at: DateTime<Utc>,
+    pub scopes: Vec<Scope>,
+}
+
+// =============================================================================
+// HTTP Handlers
+// =============================================================================
+
+/// Exchange an API key for a token for one user
+#[utoipa::path(
+    post,
+    path = "auth/token",
+    tag = "auth",
+    responses(
+        (status = 200, description = "token issued", body = ExchangeTokenResponse),
+        (status = 400, description = "invalid scopes"),
+        (status = 401, description = "missing or invalid api key"),
+        (status = 403, description = "not a service client, or scopes it doesn't have"),
+        (status = 404, description = "user not found"),
+        (status = 501, description = "user tokens aren't configured")
+))]
+#[instrument(skip(config))]
+pub async fn exchange_token(
+    State(config): State<Arc<Config>>,
+    State(auth_state): State<Arc<AuthState>>,
+    State(user_state): State<Arc<UserState>>,
+    caller: Caller,
+    Json(request): Json<ExchangeTokenRequest>,
+) -> Result<(StatusCode, Json<ExchangeTokenResponse>), YuhuhError> {
+    debug!("entering exchange_token");
+
+    caller.authorise_service()?;
+
+    let Some(token_keys) = &auth_state.token_keys else {
+        return Err(YuhuhError::NotImplemented);
+    };
+
+    let mut scopes = request.scopes.unwrap_or(vec![Scope::Read, Scope::Write]);
+    scopes.sort();
+    scopes.dedup();
+    if scopes.is_empty() || scopes.contains(&Scope::Admin) {
+        return Err(YuhuhError::BadRequest(
+            "user tokens need the read or write scope, and can't have the admin scope".to_string(),
+        ));
+    }
+
+    // A token can't do more than the key it was exchanged for
+    if let Some(scope) = scopes.iter().find(|s| !caller.grants(**s)) {
+        return Err(YuhuhError::Forbidden(format!(
+            "api key can't issue tokens with the {} scope",
+            scope
+        )));
+    }
+
+    user_state.require_user(&request.user_id).await?;
+
+    let now = Utc::now();
+    let expires_at = now
+        + TimeDelta::try_seconds(config.token_ttl_seconds as i64)
+            .ok_or_else(|| YuhuhError::InternalServerError("token ttl is too long".to_string()))?;
+
+    let access_token = token_keys.issue(request.user_id, scopes.clone(), now, expires_at)?;
+
+    info!(user_id = ?request.user_id, caller = ?caller, "issued user token");
+
+    Ok((
+        StatusCode::OK,
+        Json(ExchangeTokenResponse {
+            access_token,
+            token_type: "Bearer".to_string(),
+            expires_in: config.token_ttl_seconds,
+            expires_at,
+            scopes,
+        }),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        Router,
+        body::Body,
+        http::{self, Request, StatusCode},
+    };
+    use http_body_util::BodyExt;
+    use pretty_assertions::assert_eq;
+    use serde_json::{Value, json};
+    use tower::ServiceExt;
+    use tracing::Span;
+    use uuid::{Uuid, uuid};
+
+    use crate::{
+        auth::{exchange_token::ExchangeTokenResponse, key::API_KEY_HEADER, model::Scope},
+        config::Config,
+    };
+
+    const ADMIN_KEY: &str = "configured-admin-key";
+    const ALICE: Uuid = uuid!("11111111-1111-1111-1111-111111111111");
+    const BOBAT: Uuid = uuid!("22222222-2222-2222-2222-222222222222");
+
+    enum Credential<'a> {
+        ApiKey(&'a str),
+        Token(&'a str),
+    }
+
+    async fn send(
+        app: &Router,
+        method: &str,
+        uri: &str,
+        credential: Credential<'_>,
+        body: Value,
+    ) -> (StatusCode, Vec<u8>) {
+        let builder = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(http::header::CONTENT_TYPE, "application/json");
+        let builder = match credential {
+            Credential::ApiKey(key) => builder.header(API_KEY_HEADER, key),
+            Credential::Token(token) => {
+                builder.header(http::header::AUTHORIZATION, format!("Bearer {}", token))
+            }
+        };
+
+        let response = app
+            .clone()
+            .oneshot(builder.body(Body::from(body.to_string())).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+
+        (status, body.to_vec())
+    }
+
+    async fn token_for(app: &Router, user_id: Uuid, scopes: Option<Vec<Scope>>) -> String {
+        let (status, body) = send(
+            app,
+            "POST",
+            "/auth/token",
+            Credential::ApiKey(ADMIN_KEY),
+            json!({ "user_id": user_id, "scopes": scopes }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let response: ExchangeTokenResponse =
+            serde_json::from_slice(&body).expect("valid ExchangeTokenResponse bytes");
+        assert_eq!(response.token_type, "Bearer");
+        assert_eq!(response.expires_in, 3600);
+
+        response.access_token
+    }
+
+    #[tokio::test]
+    async fn user_tokens_only_reach_their_own_records() {
+        let (_, db, _) = crate::test::common::setup().await;
+
+        sqlx::raw_sql(include_str!("../../migrations/test/find_user.sql"))
+            .execute(&db)
+            .await
+            .expect("setup test sql ran successfully");
+
+        let config = Config {
+            require_api_key: true,
+            admin_api_key: Some(ADMIN_KEY.to_string()),
+            token_signing_key: Some("a shared secret that's long enough".to_string()),
+            token_ttl_seconds: 3600,
+            ..Default::default()
+        };
+        let app = crate::api::new_app(&config, db.clone(), std::sync::Arc::new(Span::current()));
+
+        let alice = token_for(&app, ALICE, None).await;
+        let as_alice = |method: &'static str, uri: String, body: Value| {
+            let app = app.clone();
+            let alice = alice.clone();
+            async move {
+                send(&app, method, &uri, Credential::Token(&alice), body)
+                    .await
+                    .0
+            }
+        };
+
+        assert_eq!(
+            as_alice("GET", format!("/food?user_id={}", ALICE), json!({})).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            as_alice("GET", format!("/users?id={}", ALICE), json!({})).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            as_alice(
+                "GET",
+                "/users?provider=discord&external_id=100".to_string(),
+                json!({})
+            )
+            .await,
+            StatusCode::OK
+        );
+
+        // Bobat's records are off limits
+        assert_eq!(
+            as_alice("GET", format!("/food?user_id={}", BOBAT), json!({})).await,
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            as_alice("GET", format!("/users?id={}", BOBAT), json!({})).await,
+            StatusCode::FORBIDDEN
+        );
+
+        // Nor can Bobat tell Alice's account is registered
+        let bobat = token_for(&app, BOBAT, None).await;
+        for external_id in ["100", "999"] {
+            let (status, _) = send(
+                &app,
+                "GET",
+                &format!("/users?provider=discord&external_id={}", external_id),
+                Credential::Token(&bobat),
+                json!({}),
+            )
+            .await;
+            assert_eq!(status, StatusCode::NOT_FOUND);
+        }
+        assert_eq!(
+            as_alice(
+                "POST",
+                "/mood".to_string(),
+                json!({ "user_id": BOBAT, "mood_entries": [] })
+            )
+            .await,
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            as_alice(
+                "POST",
+                "/webhooks".to_string(),
+                json!({ "url": "https://example.com/hook" })
+            )
+            .await,
+            StatusCode::FORBIDDEN
+        );
+
+        // As is anything only services can do
+        assert_eq!(
+            as_alice(
+                "POST",
+                "/auth/token".to_string(),
+                json!({ "user_id": ALICE })
+            )
+            .await,
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            as_alice(
+                "POST",
+                "/users/create/email".to_string(),
+                json!({ "external_id": "new@example.com" })
+            )
+            .await,
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            as_alice("GET", "/api-keys".to_string(), json!({})).await,
+            StatusCode::FORBIDDEN
+        );
+
+        // Tokens only get the scopes they're issued with
+        let read_only = token_for(&app, ALICE, Some(vec![Scope::Read])).await;
+        let (status, _) = send(
+            &app,
+            "POST",
+            "/mood",
+            Credential::Token(&read_only),
+            json!({ "user_id": ALICE, "mood_entries": [] }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let (status, _) = send(
+            &app,
+            "POST",
+            "/auth/token",
+            Credential::ApiKey(ADMIN_KEY),
+            json!({ "user_id": ALICE, "scopes": ["admin"] }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, _) = send(
+            &app,
+            "POST",
+            "/auth/token",
+            Credential::ApiKey(ADMIN_KEY),
+            json!({ "user_id": uuid!("33333333-3333-3333-3333-333333333333") }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (status, _) = send(
+            &app,
+            "GET",
+            &format!("/food?user_id={}", ALICE),
+            Credential::Token(&format!("{}x", alice)),
+            json!({}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        // Services act on behalf of any user
+        let (status, _) = send(
+            &app,
+            "GET",
+            &format!("/food?user_id={}", BOBAT),
+            Credential::ApiKey(ADMIN_KEY),
+            json!({}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+    }
+}
@@ -0,0 +1,68 @@
+//! Generation and hashing of API keys.
+//!
+//! Keys are 256 random bits, so a single SHA-256 is enough to store them
+//! safely: unlike passwords there's nothing to guess, and a slow hash would
+//! only slow down every request.
+
+use sha2::{Digest, Sha256};
+use tracing::error;
+
+use crate::error::YuhuhError;
+
+/// Header service clients send their API key in.
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Start of every key, so they're easy to spot, e.g. by secret scanners.
+const KEY_PREFIX: &str = "yuhuh_";
+
+/// Characters of a key kept in the clear to tell keys apart.
+const DISPLAY_PREFIX_LENGTH: usize = KEY_PREFIX.len() + 6;
+
+/// Random bytes in a key, hex encoded after the prefix.
+const KEY_BYTES: usize = 32;
+
+/// A new random API key.
+pub fn generate_key() -> Result<String, YuhuhError> {
+    let mut bytes = [0u8; KEY_BYTES];
+    getrandom::fill(&mut bytes).map_err(|e| {
+        error!(error = ?e, "failed to generate random bytes for api key");
+
+        YuhuhError::InternalServerError("failed to generate api key".to_string())
+    })?;
+
+    Ok(format!("{}{}", KEY_PREFIX, hex::encode(bytes)))
+}
+
+/// Hex encoded SHA-256 of a key, which is what's stored.
+pub fn hash_key(key: &str) -> String {
+    hex::encode(Sha256::digest(key.as_bytes()))
+}
+
+/// Start of a key that's safe to show and store.
+pub fn display_prefix(key: &str) -> String {
+    key.chars().take(DISPLAY_PREFIX_LENGTH).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_are_random_and_hashed() {
+        let key = generate_key().unwrap();
+        assert!(key.starts_with("yuhuh_"));
+        assert_eq!(key.len(), 6 + 64);
+        assert!(key[6..].chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(key, generate_key().unwrap());
+
+        assert_eq!(display_prefix(&key).len(), 12);
+        assert!(key.starts_with(&display_prefix(&key)));
+
+        assert_eq!(hash_key(&key), hash_key(&key));
+        assert_ne!(hash_key(&key), hash_key(&generate_key().unwrap()));
+        assert_eq!(
+            hash_key("abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+}
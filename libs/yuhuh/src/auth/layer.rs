@@ -0,0 +1,275 @@
+//! Tower layer authenticating every request.
+//!
+//! Service clients send an API key in the `X-API-Key` header, and users a
+//! token in `Authorization: Bearer <token>`. Reading needs the `read` scope,
+//! anything else the `write` scope, and managing API keys the `admin` scope.
+//! Who made the request is added to it as a [`Caller`] for handlers to check
+//! ownership against. Paths can be left public, like the health check.
+
+use std::{
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use axum::{
+    extract::Request,
+    http::{HeaderMap, Method, header::AUTHORIZATION},
+    response::{IntoResponse, Response},
+};
+use chrono::Utc;
+use tower::{Layer, Service};
+use tracing::{debug, warn};
+
+use crate::{
+    auth::{
+        api_keys::ApiKeysRepository,
+        key::{API_KEY_HEADER, display_prefix, hash_key},
+        model::{Caller, Scope},
+        token::TokenKeys,
+    },
+    error::YuhuhError,
+};
+
+/// Scope needed for a request.
+fn required_scope(method: &Method, path: &str) -> Scope {
+    if path == "/api-keys" || path.starts_with("/api-keys/") {
+        Scope::Admin
+    } else if matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS) {
+        Scope::Read
+    } else {
+        Scope::Write
+    }
+}
+
+/// Credentials sent with a request.
+#[derive(Debug)]
+struct Credentials {
+    api_key: Option<String>,
+    bearer_token: Option<String>,
+}
+
+impl Credentials {
+    fn from_headers(headers: &HeaderMap) -> Self {
+        let header = |name| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        };
+
+        Credentials {
+            api_key: header(API_KEY_HEADER),
+            bearer_token: header(AUTHORIZATION.as_str()).and_then(|v| {
+                v.strip_prefix("Bearer ")
+                    .map(|token| token.trim().to_string())
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AuthLayer {
+    api_keys_repo: Arc<dyn ApiKeysRepository>,
+    token_keys: Option<Arc<TokenKeys>>,
+    /// Whether requests without credentials are turned away, rather than
+    /// treated like ones with an admin key.
+    require_credentials: bool,
+    /// Hash of the admin key from the config, if one is set.
+    admin_key_hash: Option<String>,
+    /// Paths reachable without credentials.
+    public_paths: Arc<Vec<String>>,
+}
+
+impl AuthLayer {
+    pub fn new(
+        api_keys_repo: Arc<dyn ApiKeysRepository>,
+        token_keys: Option<Arc<TokenKeys>>,
+        require_credentials: bool,
+        admin_key: Option<&str>,
+        public_paths: Vec<String>,
+    ) -> Self {
+        AuthLayer {
+            api_keys_repo,
+            token_keys,
+            require_credentials,
+            admin_key_hash: admin_key.map(hash_key),
+            public_paths: Arc::new(public_paths),
+        }
+    }
+
+    fn admin() -> Caller {
+        Caller::Service {
+            api_key_id: None,
+            scopes: vec![Scope::Admin],
+        }
+    }
+
+    async fn authenticate(&self, credentials: &Credentials) -> Result<Caller, YuhuhError> {
+        if let Some(token) = &credentials.bearer_token {
+            let Some(token_keys) = &self.token_keys else {
+                debug!("rejected user token without a token signing key configured");
+
+                return Err(YuhuhError::Unauthorized);
+            };
+
+            let claims = token_keys.verify(token).inspect_err(|_| {
+                warn!("rejected invalid or expired user token");
+            })?;
+
+            return Ok(Caller::User {
+                user_id: claims.sub,
+                scopes: claims.scopes,
+            });
+        }
+
+        let Some(key) = &credentials.api_key else {
+            if self.require_credentials {
+                debug!("rejected request without credentials");
+
+                return Err(YuhuhError::Unauthorized);
+            }
+
+            return Ok(Self::admin());
+        };
+
+        let key_hash = hash_key(key);
+
+        if self.admin_key_hash.as_ref() == Some(&key_hash) {
+            return Ok(Self::admin());
+        }
+
+        let Some(api_key) = self
+            .api_keys_repo
+            .use_api_key(&key_hash, Utc::now())
+            .await?
+        else {
+            warn!(
+                prefix = display_prefix(key),
+                "rejected unknown or expired api key"
+            );
+
+            return Err(YuhuhError::Unauthorized);
+        };
+
+        Ok(Caller::Service {
+            api_key_id: Some(api_key.api_key_id),
+            scopes: api_key.scopes,
+        })
+    }
+
+    async fn authorise(
+        &self,
+        method: &Method,
+        path: &str,
+        credentials: &Credentials,
+    ) -> Result<Option<Caller>, YuhuhError> {
+        if self.public_paths.iter().any(|p| p == path) {
+            return Ok(None);
+        }
+
+        let caller = self.authenticate(credentials).await?;
+
+        let required = required_scope(method, path);
+        if !caller.grants(required) {
+            return Err(YuhuhError::Forbidden(format!(
+                "this needs the {} scope",
+                required
+            )));
+        }
+
+        Ok(Some(caller))
+    }
+}
+
+impl<S> Layer<S> for AuthLayer {
+    type Service = RequireAuth<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RequireAuth {
+            inner,
+            layer: self.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RequireAuth<S> {
+    inner: S,
+    layer: AuthLayer,
+}
+
+impl<S> Service<Request> for RequireAuth<S>
+where
+    S: Service<Request, Response = Response> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request) -> Self::Future {
+        // Keep the service that was polled ready for this request, leaving a
+        // clone for the next
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let layer = self.layer.clone();
+
+        let method = request.method().clone();
+        let path = request.uri().path().to_string();
+        let credentials = Credentials::from_headers(request.headers());
+
+        Box::pin(async move {
+            match layer.authorise(&method, &path, &credentials).await {
+                Ok(caller) => {
+                    if let Some(caller) = caller {
+                        request.extensions_mut().insert(caller);
+                    }
+
+                    inner.call(request).await
+                }
+                Err(e) => Ok(e.into_response()),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+
+    use super::*;
+
+    #[test]
+    fn scopes_required_by_method_and_path() {
+        assert_eq!(required_scope(&Method::GET, "/users/find"), Scope::Read);
+        assert_eq!(required_scope(&Method::POST, "/food"), Scope::Write);
+        assert_eq!(required_scope(&Method::DELETE, "/webhooks/1"), Scope::Write);
+        assert_eq!(required_scope(&Method::GET, "/api-keys"), Scope::Admin);
+        assert_eq!(required_scope(&Method::DELETE, "/api-keys/1"), Scope::Admin);
+        assert_eq!(required_scope(&Method::GET, "/api-keysmith"), Scope::Read);
+
+        assert!(Scope::Admin.grants(Scope::Write));
+        assert!(Scope::Write.grants(Scope::Read));
+        assert!(!Scope::Read.grants(Scope::Write));
+        assert!(!Scope::Write.grants(Scope::Admin));
+    }
+
+    #[test]
+    fn credentials_read_from_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert(API_KEY_HEADER, HeaderValue::from_static("yuhuh_abc"));
+        headers.insert(AUTHORIZATION, HeaderValue::from_static("Bearer a.b.c"));
+
+        let credentials = Credentials::from_headers(&headers);
+        assert_eq!(credentials.api_key.as_deref(), Some("yuhuh_abc"));
+        assert_eq!(credentials.bearer_token.as_deref(), Some("a.b.c"));
+
+        headers.insert(AUTHORIZATION, HeaderValue::from_static("Basic YWxpY2U6"));
+        assert_eq!(Credentials::from_headers(&headers).bearer_token, None);
+    }
+}
@@ -0,0 +1,8 @@
+pub mod api_keys;
+pub mod exchange_token;
+pub mod key;
+pub mod layer;
+pub mod model;
+pub mod router;
+pub mod state;
+pub mod token;
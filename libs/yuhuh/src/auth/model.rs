@@ -0,0 +1,168 @@
+use std::{fmt, str::FromStr};
+
+use axum::{extract::FromRequestParts, http::request::Parts};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{ConversionError, YuhuhError};
+
+/// What an API key is allowed to do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Scope {
+    /// Reading records, users and everything else.
+    Read,
+    /// Creating, changing and removing them, as well as reading.
+    Write,
+    /// Everything, including managing API keys.
+    Admin,
+}
+
+impl Scope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Scope::Read => "read",
+            Scope::Write => "write",
+            Scope::Admin => "admin",
+        }
+    }
+
+    /// Whether a key with this scope can do what needs `required`.
+    pub fn grants(&self, required: Scope) -> bool {
+        *self >= required
+    }
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Scope {
+    type Err = ConversionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "read" => Ok(Scope::Read),
+            "write" => Ok(Scope::Write),
+            "admin" => Ok(Scope::Admin),
+            _ => Err(ConversionError::new(format!("unknown scope {}", s))),
+        }
+    }
+}
+
+/// A key a service client authenticates with, without the key itself.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ApiKey {
+    pub api_key_id: Uuid,
+    /// What the key is for.
+    pub name: String,
+    /// First characters of the key, to tell keys apart.
+    pub prefix: String,
+    pub scopes: Vec<Scope>,
+    /// When the key stops working, never if not set.
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Who a request was made by, as authenticated by the auth layer.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Caller {
+    /// A service client with an API key, which can act on behalf of any user.
+    Service {
+        /// Key the client used, `None` for the admin key from the config or
+        /// when keys aren't required.
+        api_key_id: Option<Uuid>,
+        scopes: Vec<Scope>,
+    },
+    /// A user with a token, who can only access their own records.
+    User { user_id: Uuid, scopes: Vec<Scope> },
+}
+
+impl Caller {
+    pub fn scopes(&self) -> &[Scope] {
+        match self {
+            Caller::Service { scopes, .. } | Caller::User { scopes, .. } => scopes,
+        }
+    }
+
+    /// Whether the caller can do what needs `required`.
+    pub fn grants(&self, required: Scope) -> bool {
+        self.scopes().iter().any(|s| s.grants(required))
+    }
+
+    /// Checks the caller can access a user's records, which services can
+    /// for any user and users only for themselves.
+    pub fn authorise_user(&self, user_id: &Uuid) -> Result<(), YuhuhError> {
+        match self {
+            Caller::Service { .. } => Ok(()),
+            Caller::User {
+                user_id: caller_id, ..
+            } if caller_id == user_id => Ok(()),
+            Caller::User { .. } => Err(YuhuhError::Forbidden(
+                "user tokens can only access their own user's records".to_string(),
+            )),
+        }
+    }
+
+    /// Checks the caller is a service, for what users can't do for
+    /// themselves, like creating users or linking accounts to them.
+    pub fn authorise_service(&self) -> Result<(), YuhuhError> {
+        match self {
+            Caller::Service { .. } => Ok(()),
+            Caller::User { .. } => Err(YuhuhError::Forbidden(
+                "only service clients with an api key can do this".to_string(),
+            )),
+        }
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for Caller {
+    type Rejection = YuhuhError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<Caller>()
+            .cloned()
+            .ok_or(YuhuhError::Unauthorized)
+    }
+}
+
+// =============================================================================
+// Row Structs
+// =============================================================================
+#[derive(Debug, sqlx::FromRow)]
+pub struct ApiKeyRow {
+    pub api_key_id: Uuid,
+    pub name: String,
+    pub prefix: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TryFrom<ApiKeyRow> for ApiKey {
+    type Error = ConversionError;
+
+    fn try_from(row: ApiKeyRow) -> Result<Self, Self::Error> {
+        Ok(ApiKey {
+            api_key_id: row.api_key_id,
+            name: row.name,
+            prefix: row.prefix,
+            scopes: row
+                .scopes
+                .iter()
+                .map(|s| s.parse())
+                .collect::<Result<_, _>>()?,
+            expires_at: row.expires_at,
+            last_used_at: row.last_used_at,
+            created_at: row.created_at,
+        })
+    }
+}
@@ -0,0 +1,37 @@
+use axum::{
+    Router,
+    routing::{delete, get, post},
+};
+use utoipa::OpenApi;
+
+use crate::{
+    auth::{api_keys, exchange_token},
+    state::AppState,
+};
+
+// =============================================================================
+// API Docs
+// =============================================================================
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    api_keys::create_api_key,
+    api_keys::read_api_keys,
+    api_keys::delete_api_key,
+    exchange_token::exchange_token
+))]
+pub struct AuthApi;
+
+// =============================================================================
+// Router
+// =============================================================================
+
+pub fn auth_router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/api-keys",
+            get(api_keys::read_api_keys).post(api_keys::create_api_key),
+        )
+        .route("/api-keys/{api_key_id}", delete(api_keys::delete_api_key))
+        .route("/auth/token", post(exchange_token::exchange_token))
+}
@@ -0,0 +1,29 @@
+use std::sync::Arc;
+
+use sqlx::PgPool;
+
+use crate::{
+    auth::{
+        api_keys::{ApiKeysRepository, ApiKeysRepositoryImpl},
+        token::TokenKeys,
+    },
+    config::Config,
+};
+
+#[derive(Debug)]
+pub struct AuthState {
+    pub api_keys_repo: Arc<dyn ApiKeysRepository>,
+    /// Keys user tokens are signed with, `None` when they aren't configured.
+    pub token_keys: Option<Arc<TokenKeys>>,
+}
+
+impl AuthState {
+    pub fn new(db: PgPool, config: &Config) -> Self {
+        AuthState {
+            api_keys_repo: Arc::new(ApiKeysRepositoryImpl::new(db.clone())),
+            token_keys: TokenKeys::from_config(config)
+                .expect("token signing key is invalid")
+                .map(Arc::new),
+        }
+    }
+}
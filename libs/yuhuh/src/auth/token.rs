@@ -0,0 +1,217 @@
+//! Signed user tokens.
+//!
+//! Tokens are JWTs naming the user as their subject, signed with either a
+//! shared secret (HS256) or an Ed25519 key (EdDSA). They're sent as
+//! `Authorization: Bearer <token>` and only give access to that user's
+//! records, within the scopes they were issued with.
+
+use chrono::{DateTime, Utc};
+use ed25519_dalek::{SigningKey, pkcs8::EncodePrivateKey};
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{auth::model::Scope, config::Config, error::YuhuhError};
+
+/// Issuer of every token, checked when they're verified.
+const ISSUER: &str = "yuhuh";
+
+/// Shortest secret accepted for HS256, as anything shorter can be guessed.
+const MIN_SECRET_LENGTH: usize = 32;
+
+/// Algorithm user tokens are signed with.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq)]
+pub enum TokenAlgorithm {
+    /// HMAC-SHA256 with a shared secret.
+    #[default]
+    Hs256,
+    /// Ed25519 signatures, so tokens can be checked with just the public key.
+    Eddsa,
+}
+
+/// What a user token says about who it's for.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Claims {
+    /// User the token is for.
+    pub sub: Uuid,
+    pub iss: String,
+    /// Unix seconds the token was issued at.
+    pub iat: i64,
+    /// Unix seconds the token expires at.
+    pub exp: i64,
+    pub scopes: Vec<Scope>,
+}
+
+/// Keys user tokens are signed and verified with.
+pub struct TokenKeys {
+    algorithm: Algorithm,
+    encoding_key: EncodingKey,
+    decoding_key: DecodingKey,
+}
+
+impl std::fmt::Debug for TokenKeys {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TokenKeys")
+            .field("algorithm", &self.algorithm)
+            .finish_non_exhaustive()
+    }
+}
+
+impl TokenKeys {
+    /// Keys for a shared secret or a hex encoded Ed25519 private key.
+    pub fn new(algorithm: TokenAlgorithm, key: &str) -> Result<Self, YuhuhError> {
+        match algorithm {
+            TokenAlgorithm::Hs256 => {
+                if key.len() < MIN_SECRET_LENGTH {
+                    return Err(YuhuhError::InternalServerError(format!(
+                        "token signing secrets must be at least {} bytes",
+                        MIN_SECRET_LENGTH
+                    )));
+                }
+
+                Ok(TokenKeys {
+                    algorithm: Algorithm::HS256,
+                    encoding_key: EncodingKey::from_secret(key.as_bytes()),
+                    decoding_key: DecodingKey::from_secret(key.as_bytes()),
+                })
+            }
+            TokenAlgorithm::Eddsa => {
+                let bytes: [u8; 32] = hex::decode(key.trim())
+                    .ok()
+                    .and_then(|b| b.try_into().ok())
+                    .ok_or_else(|| {
+                        YuhuhError::InternalServerError(
+                            "eddsa token signing keys must be 32 hex encoded bytes".to_string(),
+                        )
+                    })?;
+                let signing_key = SigningKey::from_bytes(&bytes);
+
+                let der = signing_key.to_pkcs8_der().map_err(|e| {
+                    YuhuhError::InternalServerError(format!(
+                        "invalid eddsa token signing key - {}",
+                        e
+                    ))
+                })?;
+
+                Ok(TokenKeys {
+                    algorithm: Algorithm::EdDSA,
+                    encoding_key: EncodingKey::from_ed_der(der.as_bytes()),
+                    decoding_key: DecodingKey::from_ed_der(signing_key.verifying_key().as_bytes()),
+                })
+            }
+        }
+    }
+
+    /// Keys from the config, or `None` when no signing key is set.
+    pub fn from_config(config: &Config) -> Result<Option<Self>, YuhuhError> {
+        config
+            .token_signing_key
+            .as_deref()
+            .map(|key| TokenKeys::new(config.token_algorithm, key))
+            .transpose()
+    }
+
+    /// A token for a user, valid until `expires_at`.
+    pub fn issue(
+        &self,
+        user_id: Uuid,
+        scopes: Vec<Scope>,
+        now: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<String, YuhuhError> {
+        let claims = Claims {
+            sub: user_id,
+            iss: ISSUER.to_string(),
+            iat: now.timestamp(),
+            exp: expires_at.timestamp(),
+            scopes,
+        };
+
+        jsonwebtoken::encode(&Header::new(self.algorithm), &claims, &self.encoding_key)
+            .map_err(|e| YuhuhError::InternalServerError(format!("failed to sign token - {}", e)))
+    }
+
+    /// The claims of a token signed with these keys that hasn't expired.
+    ///
+    /// Only the configured algorithm is accepted, so a token can't choose
+    /// how it's checked.
+    pub fn verify(&self, token: &str) -> Result<Claims, YuhuhError> {
+        let mut validation = Validation::new(self.algorithm);
+        validation.set_issuer(&[ISSUER]);
+        validation.set_required_spec_claims(&["exp", "iss", "sub"]);
+        validation.leeway = 0;
+
+        jsonwebtoken::decode::<Claims>(token, &self.decoding_key, &validation)
+            .map(|data| data.claims)
+            .map_err(|_| YuhuhError::Unauthorized)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeDelta;
+    use pretty_assertions::assert_eq;
+    use uuid::uuid;
+
+    use super::*;
+
+    const SECRET: &str = "a shared secret that's long enough";
+    const ED25519_KEY: &str = "2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a";
+
+    #[test]
+    fn tokens_verify_only_with_the_keys_that_signed_them() {
+        let alice = uuid!("11111111-1111-1111-1111-111111111111");
+        let now = Utc::now();
+
+        for (algorithm, key, other_key) in [
+            (
+                TokenAlgorithm::Hs256,
+                SECRET,
+                "another shared secret that's long enough",
+            ),
+            (
+                TokenAlgorithm::Eddsa,
+                ED25519_KEY,
+                "0707070707070707070707070707070707070707070707070707070707070707",
+            ),
+        ] {
+            let keys = TokenKeys::new(algorithm, key).unwrap();
+
+            let token = keys
+                .issue(alice, vec![Scope::Read], now, now + TimeDelta::hours(1))
+                .unwrap();
+            let claims = keys.verify(&token).unwrap();
+            assert_eq!(claims.sub, alice);
+            assert_eq!(claims.scopes, vec![Scope::Read]);
+
+            let other = TokenKeys::new(algorithm, other_key).unwrap();
+            assert!(other.verify(&token).is_err(), "{:?}", algorithm);
+
+            // Tampered with after signing
+            let (signed, signature) = token.rsplit_once('.').unwrap();
+            let forged = format!("{}x.{}", signed, signature);
+            assert!(keys.verify(&forged).is_err(), "{:?}", algorithm);
+
+            let expired = keys
+                .issue(
+                    alice,
+                    vec![Scope::Read],
+                    now - TimeDelta::hours(2),
+                    now - TimeDelta::hours(1),
+                )
+                .unwrap();
+            assert!(keys.verify(&expired).is_err(), "{:?}", algorithm);
+        }
+
+        // Signed with the secret but checked as EdDSA
+        let hs256 = TokenKeys::new(TokenAlgorithm::Hs256, SECRET).unwrap();
+        let token = hs256
+            .issue(alice, vec![Scope::Read], now, now + TimeDelta::hours(1))
+            .unwrap();
+        let eddsa = TokenKeys::new(TokenAlgorithm::Eddsa, ED25519_KEY).unwrap();
+        assert!(eddsa.verify(&token).is_err());
+
+        assert!(TokenKeys::new(TokenAlgorithm::Hs256, "short").is_err());
+        assert!(TokenKeys::new(TokenAlgorithm::Eddsa, "not hex").is_err());
+    }
+}
@@ -1,3 +1,5 @@
+use crate::auth::token::TokenAlgorithm;
+
 #[derive(clap::Parser, Debug, Clone, Default)]
 pub struct Config {
     /// Port to serve core on.
@@ -12,4 +14,79 @@ pub struct Config {
 
     #[clap(long, env)]
     pub database_url: String,
+
+    /// Seconds between checks for due reminders, or 0 to not run the
+    /// reminder scheduler.
+    ///
+    /// Defaults to 60
+    #[clap(long, env)]
+    #[arg(default_value_t = 60)]
+    pub reminder_interval_seconds: u64,
+
+    /// Seconds between webhook deliveries, or 0 to not run the webhook
+    /// dispatcher. Events are still written to the outbox, and sent once it
+    /// runs.
+    ///
+    /// Defaults to 5
+    #[clap(long, env)]
+    #[arg(default_value_t = 5)]
+    pub webhook_interval_seconds: u64,
+
+    /// Whether webhooks can send to loopback, private network and link-local
+    /// addresses. Only for local development, as it lets anyone who can
+    /// register a webhook make requests into the server's network.
+    ///
+    /// Defaults to false
+    #[clap(long, env)]
+    pub webhook_allow_private_urls: bool,
+
+    /// Hex encoded public key of the Discord application, used to verify
+    /// interactions Discord sends. Interactions are rejected when not set.
+    #[clap(long, env)]
+    pub discord_public_key: Option<String>,
+
+    /// Whether requests to the API need an API key or user token. Turning
+    /// this off treats requests without either like ones with an admin key,
+    /// leaving every endpoint open to anyone who can reach the port.
+    ///
+    /// Defaults to true
+    #[clap(long, env, action = clap::ArgAction::Set)]
+    #[arg(default_value_t = true)]
+    pub require_api_key: bool,
+
+    /// Whether the health check can be reached without an API key.
+    ///
+    /// Defaults to true
+    #[clap(long, env, action = clap::ArgAction::Set)]
+    #[arg(default_value_t = true)]
+    pub public_health: bool,
+
+    /// Whether the `/scalar` API docs can be reached without an API key.
+    ///
+    /// Defaults to true
+    #[clap(long, env, action = clap::ArgAction::Set)]
+    #[arg(default_value_t = true)]
+    pub public_docs: bool,
+
+    /// API key with the admin scope, for creating the first stored keys. It's
+    /// kept out of the database, so it's best unset once they exist.
+    #[clap(long, env)]
+    pub admin_api_key: Option<String>,
+
+    /// Algorithm user tokens are signed with.
+    #[clap(long, env, default_value_t, value_enum)]
+    pub token_algorithm: TokenAlgorithm,
+
+    /// Key user tokens are signed with: a secret of at least 32 bytes for
+    /// `hs256`, or a hex encoded Ed25519 private key for `eddsa`. User tokens
+    /// can't be issued or used when not set.
+    #[clap(long, env)]
+    pub token_signing_key: Option<String>,
+
+    /// Seconds user tokens are valid for once issued.
+    ///
+    /// Defaults to 3600
+    #[clap(long, env)]
+    #[arg(default_value_t = 3600)]
+    pub token_ttl_seconds: u64,
 }
@@ -0,0 +1,126 @@
+//! Ranges of days asked for by reports over a user's history.
+//!
+//! Dates come straight from requests, so everything here is checked and
+//! dates too close to the limits of [`NaiveDate`] are a `BadRequest` rather
+//! than a panic.
+
+use chrono::{DateTime, Days, NaiveDate, NaiveTime, Utc};
+
+use crate::error::YuhuhError;
+
+/// Longest range that can be asked for at once.
+pub const MAX_RANGE_DAYS: i64 = 366;
+
+/// Days from `start` to `end` inclusive, in the user's timezone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateRange {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+fn out_of_range() -> YuhuhError {
+    YuhuhError::BadRequest("start_date and end_date are too far in the past or future".to_string())
+}
+
+impl DateRange {
+    /// Builds the range asked for, where `end_date` defaults to `today` and
+    /// `start_date` to `default_days` before the end.
+    pub fn from_request(
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+        today: NaiveDate,
+        default_days: u64,
+    ) -> Result<Self, YuhuhError> {
+        let end = end_date.unwrap_or(today);
+        let start = match start_date {
+            Some(start) => start,
+            None => end
+                .checked_sub_days(Days::new(default_days))
+                .ok_or_else(out_of_range)?,
+        };
+
+        let range_days = (end - start).num_days() + 1;
+        if !(1..=MAX_RANGE_DAYS).contains(&range_days) {
+            return Err(YuhuhError::BadRequest(format!(
+                "start_date must be on or before end_date and at most {} days apart",
+                MAX_RANGE_DAYS
+            )));
+        }
+
+        Ok(DateRange { start, end })
+    }
+
+    /// The range starting `days` earlier, for history read ahead of it.
+    pub fn extended_back(&self, days: u64) -> Result<Self, YuhuhError> {
+        Ok(DateRange {
+            start: self
+                .start
+                .checked_sub_days(Days::new(days))
+                .ok_or_else(out_of_range)?,
+            end: self.end,
+        })
+    }
+
+    /// Bounds in UTC to read records between, as `(before, after)`.
+    ///
+    /// A day either side is included, so whatever the user's timezone every
+    /// record on a day in the range is read and can then be placed on its
+    /// local day.
+    pub fn utc_bounds(&self) -> Result<(DateTime<Utc>, DateTime<Utc>), YuhuhError> {
+        let before = self
+            .end
+            .checked_add_days(Days::new(2))
+            .ok_or_else(out_of_range)?;
+        let after = self
+            .start
+            .checked_sub_days(Days::new(1))
+            .ok_or_else(out_of_range)?;
+
+        Ok((
+            before.and_time(NaiveTime::MIN).and_utc(),
+            after.and_time(NaiveTime::MIN).and_utc(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn date(date: &str) -> NaiveDate {
+        date.parse().unwrap()
+    }
+
+    #[test]
+    fn ranges_checked_without_overflowing() {
+        let today = date("2025-06-30");
+
+        let range = DateRange::from_request(None, None, today, 29).unwrap();
+        assert_eq!(range.start, date("2025-06-01"));
+        assert_eq!(range.end, today);
+        assert_eq!(
+            range.utc_bounds().unwrap(),
+            (
+                "2025-07-02T00:00:00Z".parse().unwrap(),
+                "2025-05-31T00:00:00Z".parse().unwrap()
+            )
+        );
+        assert_eq!(range.extended_back(6).unwrap().start, date("2025-05-26"));
+
+        assert!(DateRange::from_request(Some(date("2025-07-01")), None, today, 29).is_err());
+        assert!(DateRange::from_request(Some(date("2024-06-29")), None, today, 29).is_err());
+
+        // Valid dates, but reading around them would overflow
+        let latest = DateRange::from_request(None, Some(date("+262142-12-30")), today, 29)
+            .expect("range itself is valid");
+        assert!(latest.utc_bounds().is_err());
+
+        let earliest = NaiveDate::MIN;
+        assert!(DateRange::from_request(None, Some(earliest), today, 29).is_err());
+        let range = DateRange::from_request(Some(earliest), Some(earliest), today, 29).unwrap();
+        assert!(range.extended_back(1).is_err());
+        assert!(range.utc_bounds().is_err());
+    }
+}
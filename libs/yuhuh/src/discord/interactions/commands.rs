@@ -0,0 +1,305 @@
+//! Slash commands run through Discord interactions.
+//!
+//! Each command works on behalf of the yuhuh user linked to the Discord
+//! account that ran it, and returns the message to reply with.
+
+use chrono::{DateTime, NaiveTime, TimeZone, Utc};
+use tracing::{debug, info, warn};
+
+use crate::{
+    activity::{read_activity_entries::ActivityFilter, state::ActivityState},
+    discord::model::{CommandData, DiscordAccount},
+    error::YuhuhError,
+    fasting::{read_fasting::read_fasting_schedule, state::FastingState},
+    food::{create_food_entries::NewFoodEntry, state::FoodState},
+    mood::{
+        create_mood_entries::NewMoodEntry, rating::Rating, read_mood_entries::MoodFilter,
+        state::MoodState,
+    },
+    user::{create_user::CreateDBUserRequest, identity::Provider, model::User, state::UserState},
+};
+
+/// Most of each kind of record read when summarising a day.
+const MAX_DAILY_ENTRIES: i64 = 200;
+
+/// Longest food description that can be logged.
+const MAX_DESCRIPTION_LENGTH: usize = 500;
+
+/// Finds the user linked to a Discord account, registering a new one on the
+/// account's first command.
+pub async fn resolve_user(
+    user_state: &UserState,
+    account: &DiscordAccount,
+) -> Result<User, YuhuhError> {
+    let discord_id = Provider::Discord.normalise_external_id(&account.id)?;
+
+    if let Some(user) = user_state
+        .find_user_repo
+        .find_user_by_identity(Provider::Discord, &discord_id)
+        .await?
+    {
+        return Ok(user);
+    }
+
+    let created = user_state
+        .create_user_repo
+        .create_user(CreateDBUserRequest {
+            provider: Provider::Discord,
+            external_id: discord_id.clone(),
+            username: Some(account.username.clone()),
+            personalisation: None,
+            contact_name: account.global_name.clone(),
+            contact_email: None,
+            timezone: None,
+            energy_unit: Default::default(),
+        })
+        .await;
+
+    let user = match created {
+        Ok(user_id) => {
+            info!(user_id = %user_id, discord_id, "registered user from discord");
+
+            user_state.find_user_repo.find_user_by_id(&user_id).await?
+        }
+        Err(e) => {
+            // Another command from the same account may have registered it
+            warn!(error = ?e, discord_id, "failed to register user from discord");
+
+            user_state
+                .find_user_repo
+                .find_user_by_identity(Provider::Discord, &discord_id)
+                .await?
+        }
+    };
+
+    user.ok_or_else(|| {
+        YuhuhError::InternalServerError(format!(
+            "user for discord id {} not found after registering",
+            discord_id
+        ))
+    })
+}
+
+/// `/mood rating [notes]`
+pub async fn log_mood(
+    mood_state: &MoodState,
+    user: &User,
+    data: &CommandData,
+) -> Result<String, YuhuhError> {
+    let rating = data
+        .option("rating")
+        .and_then(|v| v.as_i64())
+        .ok_or_else(|| YuhuhError::BadRequest("a whole number rating is needed".to_string()))?;
+    let rating = Rating::try_from(rating)?;
+
+    let scales = mood_state
+        .rating_scales_repo
+        .read_rating_scales(&user.user_id)
+        .await?;
+
+    let entry = NewMoodEntry {
+        notes: data
+            .option("notes")
+            .and_then(|v| v.as_str())
+            .map(String::from),
+        mood: Some(rating),
+        energy: None,
+        sleep: None,
+        emotions: vec![],
+        context_tags: vec![],
+        logged_at: None,
+    };
+    let row = entry.into(user.user_id)?.into_row(&scales)?;
+
+    mood_state
+        .create_mood_entries_repo
+        .create_mood_entries(vec![row], &scales)
+        .await?;
+
+    Ok(format!(
+        "Logged a mood of {} ({} to {}).",
+        rating.get(),
+        scales.mood.min,
+        scales.mood.max
+    ))
+}
+
+/// `/food description [calories]`, with calories in the user's energy unit.
+pub async fn log_food(
+    food_state: &FoodState,
+    fasting_state: &FastingState,
+    user: &User,
+    data: &CommandData,
+) -> Result<String, YuhuhError> {
+    let description = data
+        .option("description")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .ok_or_else(|| YuhuhError::BadRequest("a description is needed".to_string()))?;
+
+    if description.len() > MAX_DESCRIPTION_LENGTH {
+        return Err(YuhuhError::BadRequest(format!(
+            "descriptions can be at most {} characters",
+            MAX_DESCRIPTION_LENGTH
+        )));
+    }
+
+    let calories = data
+        .option("calories")
+        .map(|c| {
+            c.as_f64()
+                .filter(|c| *c >= 0.0)
+                .map(|c| c as f32)
+                .ok_or_else(|| YuhuhError::BadRequest("calories can't be negative".to_string()))
+        })
+        .transpose()?;
+
+    let new_entry = NewFoodEntry {
+        description: description.to_string(),
+        calories,
+        energy_unit: Some(user.energy_unit),
+        carbs: None,
+        protein: None,
+        fats: None,
+        micronutrients: None,
+        logged_at: None,
+    };
+    let entry = NewFoodEntry::into(&new_entry, user.user_id);
+    let logged_at = entry.logged_at;
+
+    let fasting_schedule =
+        read_fasting_schedule(fasting_state, user, Some(logged_at), Some(logged_at)).await?;
+
+    food_state
+        .create_food_entries_repo
+        .create_food_entries(vec![entry])
+        .await?;
+
+    let mut reply = match calories {
+        Some(calories) => format!(
+            "Logged {}, {} {}.",
+            description,
+            calories.round(),
+            user.energy_unit
+        ),
+        None => format!("Logged {}.", description),
+    };
+
+    if let Some(warning) = fasting_schedule.warning(logged_at) {
+        reply.push_str(&format!("\nHeads up, this was {}.", warning));
+    }
+
+    Ok(reply)
+}
+
+/// `/today`, summarising what's been logged since midnight in the user's
+/// timezone.
+pub async fn summarise_today(
+    food_state: &FoodState,
+    mood_state: &MoodState,
+    activity_state: &ActivityState,
+    user: &User,
+    now: DateTime<Utc>,
+) -> Result<String, YuhuhError> {
+    let tz = user.tz();
+    let midnight = now.with_timezone(&tz).date_naive().and_time(NaiveTime::MIN);
+    // Midnight is skipped on some daylight saving changes
+    let start = tz
+        .from_local_datetime(&midnight)
+        .earliest()
+        .or_else(|| {
+            tz.from_local_datetime(&(midnight + chrono::Duration::hours(1)))
+                .earliest()
+        })
+        .map(|t| t.with_timezone(&Utc))
+        .unwrap_or(now);
+
+    debug!(user_id = ?user.user_id, start = ?start, "summarising day");
+
+    let foods = food_state
+        .read_food_entries_repo
+        .read_food_entries(&user.user_id, Some(now), Some(start), MAX_DAILY_ENTRIES, 0)
+        .await?;
+
+    let scales = mood_state
+        .rating_scales_repo
+        .read_rating_scales(&user.user_id)
+        .await?;
+    let moods = mood_state
+        .read_mood_entries_repo
+        .find_mood_entries(
+            &user.user_id,
+            &MoodFilter {
+                before: Some(now),
+                after: Some(start),
+                ..Default::default()
+            },
+            &scales,
+            MAX_DAILY_ENTRIES,
+            0,
+        )
+        .await?;
+
+    let activities = activity_state
+        .read_activity_entries_repo
+        .read_activity_entries(
+            &user.user_id,
+            Some(now),
+            Some(start),
+            &ActivityFilter::default(),
+            MAX_DAILY_ENTRIES,
+            0,
+        )
+        .await?;
+
+    if foods.is_empty() && moods.is_empty() && activities.is_empty() {
+        return Ok("Nothing logged today yet.".to_string());
+    }
+
+    let energy = |kcal: f32| {
+        format!(
+            "{} {}",
+            user.energy_unit.from_kcal(kcal).round(),
+            user.energy_unit
+        )
+    };
+
+    let mut lines = vec!["Today so far:".to_string()];
+
+    if !foods.is_empty() {
+        let kcal: f32 = foods.iter().filter_map(|f| f.calories).sum();
+        lines.push(format!("Food: {}, {}", entries(foods.len()), energy(kcal)));
+    }
+
+    // Entries are newest first
+    let ratings: Vec<i32> = moods
+        .iter()
+        .filter_map(|m| m.mood.map(|r| r.get()))
+        .collect();
+    if let Some(latest) = ratings.first() {
+        let average = ratings.iter().sum::<i32>() as f32 / ratings.len() as f32;
+        lines.push(format!(
+            "Mood: {} latest, {:.1} average ({} to {})",
+            latest, average, scales.mood.min, scales.mood.max
+        ));
+    }
+
+    if !activities.is_empty() {
+        let kcal: f32 = activities.iter().filter_map(|a| a.calories_burned).sum();
+        lines.push(format!(
+            "Activity: {}, {} burned",
+            entries(activities.len()),
+            energy(kcal)
+        ));
+    }
+
+    Ok(lines.join("\n"))
+}
+
+fn entries(count: usize) -> String {
+    match count {
+        1 => "1 entry".to_string(),
+        n => format!("{} entries", n),
+    }
+}
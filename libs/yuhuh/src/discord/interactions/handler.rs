@@ -0,0 +1,325 @@
+//! Discord interactions HTTP handler
+//!
+//! This module provides the endpoint Discord sends slash commands to, so
+//! users can log food and mood straight from Discord without a separate bot
+//! calling the API on their behalf.
+
+use std::sync::Arc;
+
+use axum::{Json, body::Bytes, extract::State, http::HeaderMap};
+use chrono::{TimeDelta, Utc};
+use tracing::{debug, error, instrument, warn};
+
+use crate::{
+    activity::state::ActivityState,
+    config::Config,
+    discord::{
+        interactions::commands::{log_food, log_mood, resolve_user, summarise_today},
+        model::{APPLICATION_COMMAND, Interaction, InteractionResponse, PING},
+        signature::{SIGNATURE_HEADER, TIMESTAMP_HEADER, parse_public_key, verify},
+    },
+    error::YuhuhError,
+    fasting::state::FastingState,
+    food::state::FoodState,
+    mood::state::MoodState,
+    user::state::UserState,
+};
+
+/// How long after being sent an interaction is still accepted.
+const SIGNATURE_TOLERANCE: TimeDelta = TimeDelta::minutes(5);
+
+// =============================================================================
+// Implementations
+// =============================================================================
+
+fn verify_request(config: &Config, headers: &HeaderMap, body: &[u8]) -> Result<(), YuhuhError> {
+    let Some(public_key) = &config.discord_public_key else {
+        warn!("received discord interaction without a discord public key configured");
+
+        return Err(YuhuhError::Unauthorized);
+    };
+    let public_key = parse_public_key(public_key)?;
+
+    let header = |name| headers.get(name).and_then(|v| v.to_str().ok());
+    let (Some(signature), Some(timestamp)) = (header(SIGNATURE_HEADER), header(TIMESTAMP_HEADER))
+    else {
+        return Err(YuhuhError::Unauthorized);
+    };
+
+    if !verify(
+        &public_key,
+        signature,
+        timestamp,
+        body,
+        Utc::now(),
+        SIGNATURE_TOLERANCE,
+    ) {
+        warn!("rejected discord interaction with an invalid signature");
+
+        return Err(YuhuhError::Unauthorized);
+    }
+
+    Ok(())
+}
+
+// =============================================================================
+// HTTP Handlers
+// =============================================================================
+
+/// Receive an interaction from Discord
+///
+/// Handles the `/mood`, `/food` and `/today` slash commands for the user
+/// linked to the Discord account running them, registering a user on the
+/// account's first command. Replies are only shown to that account.
+#[utoipa::path(
+    post,
+    path = "discord/interactions",
+    tag = "discord",
+    request_body = Interaction,
+    params(
+        ("X-Signature-Ed25519" = String, Header, description = "Signature of the timestamp and body"),
+        ("X-Signature-Timestamp" = String, Header, description = "When the interaction was sent")
+    ),
+    responses(
+        (status = 200, description = "Interaction handled", body = InteractionResponse),
+        (status = 400, description = "Malformed or unsupported interaction"),
+        (status = 401, description = "Invalid signature"),
+))]
+#[allow(clippy::too_many_arguments)]
+#[instrument(skip_all)]
+pub async fn handle_interaction(
+    State(config): State<Arc<Config>>,
+    State(user_state): State<Arc<UserState>>,
+    State(food_state): State<Arc<FoodState>>,
+    State(mood_state): State<Arc<MoodState>>,
+    State(activity_state): State<Arc<ActivityState>>,
+    State(fasting_state): State<Arc<FastingState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Json<InteractionResponse>, YuhuhError> {
+    debug!("entering handle_interaction");
+
+    verify_request(&config, &headers, &body)?;
+
+    let interaction: Interaction = serde_json::from_slice(&body)
+        .map_err(|e| YuhuhError::BadRequest(format!("invalid interaction - {}", e)))?;
+
+    match interaction.interaction_type {
+        PING => return Ok(Json(InteractionResponse::pong())),
+        APPLICATION_COMMAND => {}
+        other => {
+            return Err(YuhuhError::BadRequest(format!(
+                "unsupported interaction type {}",
+                other
+            )));
+        }
+    }
+
+    let (Some(account), Some(data)) = (interaction.account(), &interaction.data) else {
+        return Err(YuhuhError::BadRequest(
+            "commands need a user and command data".to_string(),
+        ));
+    };
+
+    let user = resolve_user(&user_state, account).await?;
+
+    debug!(user_id = ?user.user_id, command = data.name, "running discord command");
+
+    let reply = match data.name.as_str() {
+        "mood" => log_mood(&mood_state, &user, data).await,
+        "food" => log_food(&food_state, &fasting_state, &user, data).await,
+        "today" => {
+            summarise_today(&food_state, &mood_state, &activity_state, &user, Utc::now()).await
+        }
+        other => Ok(format!("Unknown command /{}.", other)),
+    };
+
+    // Mistakes in what was typed are explained in the reply, as Discord only
+    // shows that the interaction failed otherwise
+    let content = match reply {
+        Ok(content) => content,
+        Err(YuhuhError::BadRequest(reason)) => format!("Couldn't do that, {}.", reason),
+        Err(YuhuhError::RatingError(e)) => format!("Couldn't do that. {}.", e),
+        Err(e) => {
+            error!(error = ?e, command = data.name, "failed to run discord command");
+
+            return Err(e);
+        }
+    };
+
+    Ok(Json(InteractionResponse::reply(content)))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        Router,
+        body::Body,
+        http::{self, Request, StatusCode},
+    };
+    use chrono::Utc;
+    use ed25519_dalek::{Signer, SigningKey};
+    use http_body_util::BodyExt;
+    use pretty_assertions::assert_eq;
+    use serde_json::{Value, json};
+    use tower::ServiceExt;
+    use tracing::Span;
+    use uuid::uuid;
+
+    use crate::{
+        config::Config,
+        discord::signature::{SIGNATURE_HEADER, TIMESTAMP_HEADER},
+        mood::read_mood_entries::MoodFilter,
+        user::identity::Provider,
+    };
+
+    fn signing_key() -> SigningKey {
+        SigningKey::from_bytes(&[42; 32])
+    }
+
+    fn command(discord_id: &str, name: &str, options: Value) -> Value {
+        json!({
+            "type": 2,
+            "data": { "name": name, "options": options },
+            "member": {
+                "user": { "id": discord_id, "username": format!("user{}", discord_id), "global_name": null }
+            }
+        })
+    }
+
+    fn signed(key: &SigningKey, interaction: &Value) -> Request<Body> {
+        let body = serde_json::to_vec(interaction).unwrap();
+        let timestamp = Utc::now().timestamp().to_string();
+        let signature = key.sign(&[timestamp.as_bytes(), &body].concat());
+
+        Request::builder()
+            .method("POST")
+            .uri("/discord/interactions")
+            .header(http::header::CONTENT_TYPE, "application/json")
+            .header(SIGNATURE_HEADER, hex::encode(signature.to_bytes()))
+            .header(TIMESTAMP_HEADER, timestamp)
+            .body(Body::from(body))
+            .unwrap()
+    }
+
+    async fn reply(app: Router, interaction: &Value) -> Value {
+        let response = app
+            .oneshot(signed(&signing_key(), interaction))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    fn content(reply: &Value) -> &str {
+        assert_eq!(reply["type"], 4);
+        assert_eq!(reply["data"]["flags"], 64);
+
+        reply["data"]["content"].as_str().unwrap()
+    }
+
+    #[tokio::test]
+    async fn slash_commands_log_for_the_discord_user() {
+        let (_, db, state) = crate::test::common::setup().await;
+
+        sqlx::raw_sql(include_str!(
+            "../../migrations/test/discord_interactions.sql"
+        ))
+        .execute(&db)
+        .await
+        .expect("setup test sql ran successfully");
+
+        let config = Config {
+            discord_public_key: Some(hex::encode(signing_key().verifying_key().as_bytes())),
+            ..Default::default()
+        };
+        let app = crate::api::new_app(&config, db.clone(), std::sync::Arc::new(Span::current()));
+
+        let reply_to = |interaction: Value| {
+            let app = app.clone();
+            async move { reply(app, &interaction).await }
+        };
+
+        assert_eq!(reply_to(json!({ "type": 1 })).await, json!({ "type": 1 }));
+
+        // Signed by someone else
+        let response = app
+            .clone()
+            .oneshot(signed(
+                &SigningKey::from_bytes(&[1; 32]),
+                &json!({ "type": 1 }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        // Alice is already linked to discord user 100
+        let alice = uuid!("11111111-1111-1111-1111-111111111111");
+        let reply = reply_to(command(
+            "100",
+            "mood",
+            json!([{ "name": "rating", "value": 7 }]),
+        ))
+        .await;
+        assert_eq!(content(&reply), "Logged a mood of 7 (0 to 10).");
+
+        let moods = state
+            .mood
+            .read_mood_entries_repo
+            .find_mood_entries(&alice, &MoodFilter::default(), &Default::default(), 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(moods.len(), 1);
+        assert_eq!(moods[0].mood.map(|r| r.get()), Some(7));
+
+        let reply = reply_to(command(
+            "100",
+            "mood",
+            json!([{ "name": "rating", "value": 11 }]),
+        ))
+        .await;
+        assert!(
+            content(&reply).starts_with("Couldn't do that."),
+            "{:?}",
+            reply
+        );
+
+        // Unknown accounts are registered on their first command
+        let reply = reply_to(command(
+            "200",
+            "food",
+            json!([
+                { "name": "description", "value": "burger" },
+                { "name": "calories", "value": 600 }
+            ]),
+        ))
+        .await;
+        assert_eq!(content(&reply), "Logged burger, 600 kcal.");
+
+        let user = state
+            .user
+            .find_user_repo
+            .find_user_by_identity(Provider::Discord, "200")
+            .await
+            .unwrap()
+            .expect("user registered");
+        assert_eq!(
+            user.identity(Provider::Discord)
+                .unwrap()
+                .username
+                .as_deref(),
+            Some("user200")
+        );
+
+        let reply = reply_to(command("200", "today", json!([]))).await;
+        assert_eq!(content(&reply), "Today so far:\nFood: 1 entry, 600 kcal");
+
+        let reply = reply_to(command("300", "today", json!([]))).await;
+        assert_eq!(content(&reply), "Nothing logged today yet.");
+
+        let reply = reply_to(command("100", "dance", json!([]))).await;
+        assert_eq!(content(&reply), "Unknown command /dance.");
+    }
+}
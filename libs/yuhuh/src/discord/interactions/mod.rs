@@ -0,0 +1,4 @@
+mod commands;
+mod handler;
+
+pub use handler::*;
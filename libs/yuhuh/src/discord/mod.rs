@@ -0,0 +1,4 @@
+pub mod interactions;
+pub mod model;
+pub mod router;
+pub mod signature;
@@ -0,0 +1,113 @@
+//! The parts of Discord's interaction payloads that yuhuh uses.
+//!
+//! See <https://discord.com/developers/docs/interactions/receiving-and-responding>.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+
+/// Discord's `PING` interaction, sent to check the endpoint.
+pub const PING: u8 = 1;
+
+/// Discord's `APPLICATION_COMMAND` interaction, a slash command.
+pub const APPLICATION_COMMAND: u8 = 2;
+
+/// Response acknowledging a `PING`.
+pub const PONG: u8 = 1;
+
+/// Response replying to a command with a message.
+pub const CHANNEL_MESSAGE_WITH_SOURCE: u8 = 4;
+
+/// Message flag making a reply visible only to whoever ran the command.
+pub const EPHEMERAL: u32 = 1 << 6;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct Interaction {
+    #[serde(rename = "type")]
+    pub interaction_type: u8,
+    pub data: Option<CommandData>,
+    /// Set when run in a server.
+    pub member: Option<GuildMember>,
+    /// Set when run in a direct message.
+    pub user: Option<DiscordAccount>,
+}
+
+impl Interaction {
+    /// Discord account that ran the interaction.
+    pub fn account(&self) -> Option<&DiscordAccount> {
+        self.member.as_ref().map(|m| &m.user).or(self.user.as_ref())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct GuildMember {
+    pub user: DiscordAccount,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct DiscordAccount {
+    /// Snowflake, sent as a string.
+    pub id: String,
+    pub username: String,
+    /// Display name, if it's been set.
+    pub global_name: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CommandData {
+    /// Command name, without the leading slash.
+    pub name: String,
+    #[serde(default)]
+    pub options: Vec<CommandOption>,
+}
+
+impl CommandData {
+    /// Value of the option called `name`, if it was given.
+    pub fn option(&self, name: &str) -> Option<&Value> {
+        self.options
+            .iter()
+            .find(|o| o.name == name)
+            .and_then(|o| o.value.as_ref())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CommandOption {
+    pub name: String,
+    pub value: Option<Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct InteractionResponse {
+    #[serde(rename = "type")]
+    pub response_type: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<MessageData>,
+}
+
+impl InteractionResponse {
+    pub fn pong() -> Self {
+        InteractionResponse {
+            response_type: PONG,
+            data: None,
+        }
+    }
+
+    /// A reply only visible to whoever ran the command, as what's logged is
+    /// personal.
+    pub fn reply(content: impl Into<String>) -> Self {
+        InteractionResponse {
+            response_type: CHANNEL_MESSAGE_WITH_SOURCE,
+            data: Some(MessageData {
+                content: content.into(),
+                flags: EPHEMERAL,
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct MessageData {
+    pub content: String,
+    pub flags: u32,
+}
@@ -0,0 +1,23 @@
+use axum::{Router, routing::post};
+use utoipa::OpenApi;
+
+use crate::{discord::interactions, state::AppState};
+
+// =============================================================================
+// API Docs
+// =============================================================================
+
+#[derive(OpenApi)]
+#[openapi(paths(interactions::handle_interaction))]
+pub struct DiscordApi;
+
+// =============================================================================
+// Router
+// =============================================================================
+
+pub fn discord_router() -> Router<AppState> {
+    Router::new().route(
+        "/discord/interactions",
+        post(interactions::handle_interaction),
+    )
+}
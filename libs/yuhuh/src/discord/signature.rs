@@ -0,0 +1,123 @@
+//! Verification of interactions sent by Discord.
+//!
+//! Discord signs every interaction with the application's Ed25519 key, over
+//! the `X-Signature-Timestamp` header followed by the raw request body, and
+//! sends the hex encoded signature in `X-Signature-Ed25519`. Requests that
+//! fail verification must be answered with a 401.
+
+use chrono::{DateTime, TimeDelta, Utc};
+use ed25519_dalek::{Signature, VerifyingKey};
+
+use crate::error::YuhuhError;
+
+/// Header carrying the signature of an interaction.
+pub const SIGNATURE_HEADER: &str = "x-signature-ed25519";
+
+/// Header carrying when an interaction was sent, in unix seconds.
+pub const TIMESTAMP_HEADER: &str = "x-signature-timestamp";
+
+/// Parses the application's hex encoded public key.
+pub fn parse_public_key(public_key: &str) -> Result<VerifyingKey, YuhuhError> {
+    let bytes: [u8; 32] = hex::decode(public_key.trim())
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .ok_or_else(|| {
+            YuhuhError::InternalServerError(
+                "discord public key must be 32 hex encoded bytes".to_string(),
+            )
+        })?;
+
+    VerifyingKey::from_bytes(&bytes)
+        .map_err(|e| YuhuhError::InternalServerError(format!("invalid discord public key - {}", e)))
+}
+
+/// Whether a signature is valid for an interaction, and it was sent no more
+/// than `tolerance` before `now`.
+pub fn verify(
+    public_key: &VerifyingKey,
+    signature: &str,
+    timestamp: &str,
+    body: &[u8],
+    now: DateTime<Utc>,
+    tolerance: TimeDelta,
+) -> bool {
+    let Ok(sent_at) = timestamp.parse::<i64>() else {
+        return false;
+    };
+
+    // The header isn't authenticated yet, so any i64 has to be handled
+    if now.timestamp().abs_diff(sent_at) > tolerance.num_seconds().unsigned_abs() {
+        return false;
+    }
+
+    let Some(signature) = hex::decode(signature)
+        .ok()
+        .and_then(|s| Signature::from_slice(&s).ok())
+    else {
+        return false;
+    };
+
+    let mut message = Vec::with_capacity(timestamp.len() + body.len());
+    message.extend_from_slice(timestamp.as_bytes());
+    message.extend_from_slice(body);
+
+    public_key.verify_strict(&message, &signature).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use ed25519_dalek::{Signer, SigningKey};
+
+    use super::*;
+
+    fn at(time: &str) -> DateTime<Utc> {
+        time.parse().unwrap()
+    }
+
+    #[test]
+    fn signatures_verify_only_for_the_signed_interaction() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let public_key = parse_public_key(&hex::encode(key.verifying_key().as_bytes())).unwrap();
+
+        let sent_at = at("2025-06-02T09:30:00Z");
+        let timestamp = sent_at.timestamp().to_string();
+        let body = br#"{"type":1}"#;
+
+        let signature = hex::encode(
+            key.sign(&[timestamp.as_bytes(), body.as_slice()].concat())
+                .to_bytes(),
+        );
+
+        let tolerance = TimeDelta::minutes(5);
+        let check = |signature: &str, timestamp: &str, body: &[u8], now| {
+            verify(&public_key, signature, timestamp, body, now, tolerance)
+        };
+
+        assert!(check(&signature, &timestamp, body, sent_at));
+        assert!(!check(&signature, &timestamp, b"{}", sent_at));
+        assert!(!check(&signature, "1748856601", body, sent_at));
+        assert!(!check("abc", &timestamp, body, sent_at));
+        assert!(!check(&signature, "-9223372036854775808", body, sent_at));
+        assert!(!check(&signature, "9223372036854775807", body, sent_at));
+
+        // Replayed too late
+        assert!(!check(
+            &signature,
+            &timestamp,
+            body,
+            at("2025-06-02T09:36:00Z")
+        ));
+
+        let other = SigningKey::from_bytes(&[8; 32]);
+        assert!(!verify(
+            &other.verifying_key(),
+            &signature,
+            &timestamp,
+            body,
+            sent_at,
+            tolerance
+        ));
+
+        assert!(parse_public_key("not hex").is_err());
+    }
+}
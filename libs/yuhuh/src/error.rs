@@ -34,6 +34,34 @@ impl RatingError {
     }
 }
 
+#[derive(Error, Debug)]
+#[error("Invalid micronutrients: {message}")]
+pub struct MicronutrientError {
+    message: String,
+}
+
+impl MicronutrientError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+#[error("Invalid activity info: {message}")]
+pub struct ActivityInfoError {
+    message: String,
+}
+
+impl ActivityInfoError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+}
+
 /// A common error type that can be used throughout the API.
 ///
 /// Can be returned in a `Result` from an API handler function.
@@ -49,6 +77,9 @@ pub enum YuhuhError {
     #[error("Unauthorized")]
     Unauthorized,
 
+    #[error("Forbidden")]
+    Forbidden(String),
+
     #[error("Resource not found")]
     NotFound(String),
 
@@ -78,6 +109,12 @@ pub enum YuhuhError {
 
     #[error(transparent)]
     ConversionError(#[from] ConversionError),
+
+    #[error(transparent)]
+    MicronutrientError(#[from] MicronutrientError),
+
+    #[error(transparent)]
+    ActivityInfoError(#[from] ActivityInfoError),
 }
 
 #[derive(serde::Serialize, Deserialize, Debug)]
@@ -114,6 +151,11 @@ impl YuhuhError {
 
                 err.to_string()
             }
+            YuhuhError::Forbidden(err) => {
+                tracing::error!(error=?err, "encountered forbidden error - message: {}", err);
+
+                err.to_string()
+            }
             YuhuhError::NotFound(err) => {
                 tracing::error!(error=?err, "encountered not found error - message: {}", err);
 
@@ -155,6 +197,16 @@ impl YuhuhError {
             YuhuhError::ConversionError(error) => {
                 tracing::error!(error=?error, "encountered conversion error");
 
+                error.message.to_owned()
+            }
+            YuhuhError::MicronutrientError(error) => {
+                tracing::error!(error=?error, "encountered micronutrient error");
+
+                error.message.to_owned()
+            }
+            YuhuhError::ActivityInfoError(error) => {
+                tracing::error!(error=?error, "encountered activity info error");
+
                 error.message.to_owned()
             }
         }
@@ -168,10 +220,13 @@ impl YuhuhError {
             | YuhuhError::ConversionError(_)
             | YuhuhError::ContextError { .. } => StatusCode::INTERNAL_SERVER_ERROR,
             YuhuhError::Unauthorized => StatusCode::UNAUTHORIZED,
+            YuhuhError::Forbidden(_) => StatusCode::FORBIDDEN,
             YuhuhError::NotFound(_) => StatusCode::NOT_FOUND,
             YuhuhError::Conflict(_)
             | YuhuhError::BadRequest(_)
             | YuhuhError::RatingError(_)
+            | YuhuhError::MicronutrientError(_)
+            | YuhuhError::ActivityInfoError(_)
             | YuhuhError::ValidationError(_) => StatusCode::BAD_REQUEST,
         }
     }
@@ -0,0 +1,214 @@
+//! eating window HTTP handlers
+//!
+//! This module provides HTTP endpoints for managing a user's recurring eating
+//! window.
+
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::StatusCode,
+};
+use chrono::NaiveTime;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, instrument};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::{
+    auth::model::Caller,
+    error::YuhuhError,
+    fasting::{model::EatingWindow, state::FastingState},
+    user::state::UserState,
+};
+
+// ============================================================================
+// HTTP Request types
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SetEatingWindowRequest {
+    pub user_id: Uuid,
+    /// Local time the window opens, in the user's timezone.
+    pub opens_at: NaiveTime,
+    /// Local time the window closes, in the user's timezone.
+    pub closes_at: NaiveTime,
+}
+
+/// Request parameters identifying whose eating window to remove.
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct DeleteEatingWindowRequest {
+    /// user ID the window belongs to.
+    pub user_id: Uuid,
+}
+
+// =============================================================================
+// HTTP Handlers
+// =============================================================================
+
+/// Set a user's recurring eating window
+#[utoipa::path(
+    put,
+    path = "fasting/window",
+    tag = "fasting",
+    responses(
+        (status = 200, description = "eating window set", body = EatingWindow),
+        (status = 400, description = "eating window is empty")
+))]
+#[instrument]
+pub async fn set_eating_window(
+    State(fasting_state): State<Arc<FastingState>>,
+    State(user_state): State<Arc<UserState>>,
+    caller: Caller,
+    Json(request): Json<SetEatingWindowRequest>,
+) -> Result<(StatusCode, Json<EatingWindow>), YuhuhError> {
+    debug!("entering set_eating_window");
+
+    caller.authorise_user(&request.user_id)?;
+
+    user_state.require_user(&request.user_id).await?;
+
+    if request.opens_at == request.closes_at {
+        return Err(YuhuhError::BadRequest(
+            "eating window must open and close at different times".to_string(),
+        ));
+    }
+
+    let window = EatingWindow {
+        user_id: request.user_id,
+        opens_at: request.opens_at,
+        closes_at: request.closes_at,
+    };
+
+    fasting_state
+        .eating_window_repo
+        .set_eating_window(window.clone())
+        .await?;
+
+    Ok((StatusCode::OK, Json(window)))
+}
+
+/// Remove a user's recurring eating window
+#[utoipa::path(
+    delete,
+    path = "fasting/window",
+    tag = "fasting",
+    params(DeleteEatingWindowRequest),
+    responses(
+        (status = 204, description = "eating window removed"),
+        (status = 404, description = "no eating window set")
+))]
+#[instrument]
+pub async fn delete_eating_window(
+    State(fasting_state): State<Arc<FastingState>>,
+    caller: Caller,
+    Query(request): Query<DeleteEatingWindowRequest>,
+) -> Result<StatusCode, YuhuhError> {
+    debug!("entering delete_eating_window");
+
+    caller.authorise_user(&request.user_id)?;
+
+    if !fasting_state
+        .eating_window_repo
+        .delete_eating_window(&request.user_id)
+        .await?
+    {
+        return Err(YuhuhError::NotFound("no eating window set".to_string()));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use pretty_assertions::assert_eq;
+
+    use axum::{
+        body::Body,
+        http::{self, Request, StatusCode},
+    };
+    use tower::ServiceExt;
+    use uuid::uuid;
+
+    use crate::fasting::eating_window::SetEatingWindowRequest;
+
+    fn put(request: &SetEatingWindowRequest) -> Request<Body> {
+        Request::builder()
+            .method("PUT")
+            .uri("/fasting/window")
+            .header(http::header::CONTENT_TYPE, "application/json")
+            .body(Body::from(
+                serde_json::to_string(request).expect("request is valid body"),
+            ))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn eating_window_set_and_removed() {
+        let (app, db, state) = crate::test::common::setup().await;
+
+        sqlx::raw_sql(include_str!("../../migrations/test/fasting.sql"))
+            .execute(&db)
+            .await
+            .expect("setup test sql ran successfully");
+
+        let user_id = uuid!("22222222-2222-2222-2222-222222222222");
+
+        let response = app
+            .clone()
+            .oneshot(put(&SetEatingWindowRequest {
+                user_id,
+                opens_at: "20:00:00".parse().unwrap(),
+                closes_at: "02:00:00".parse().unwrap(),
+            }))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let window = state
+            .fasting
+            .read_fasting_repo
+            .read_eating_window(&user_id)
+            .await
+            .expect("read eating window")
+            .expect("eating window was set");
+        assert_eq!(window.closes_at, "02:00:00".parse().unwrap());
+
+        let delete = || {
+            Request::builder()
+                .method("DELETE")
+                .uri("/fasting/window?user_id=22222222-2222-2222-2222-222222222222")
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let response = app.clone().oneshot(delete()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+        let response = app.oneshot(delete()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn empty_eating_window_rejected() {
+        let (app, db, _) = crate::test::common::setup().await;
+
+        sqlx::raw_sql(include_str!("../../migrations/test/fasting.sql"))
+            .execute(&db)
+            .await
+            .expect("setup test sql ran successfully");
+
+        let response = app
+            .oneshot(put(&SetEatingWindowRequest {
+                user_id: uuid!("22222222-2222-2222-2222-222222222222"),
+                opens_at: "12:00:00".parse().unwrap(),
+                closes_at: "12:00:00".parse().unwrap(),
+            }))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+}
@@ -0,0 +1,86 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use tracing::{debug, error};
+use uuid::Uuid;
+
+use crate::{error::YuhuhError, fasting::model::EatingWindow};
+
+// =============================================================================
+// Traits
+// =============================================================================
+
+#[async_trait]
+pub trait EatingWindowRepository: std::fmt::Debug + Send + Sync + 'static {
+    /// Sets the user's eating window, replacing any existing one.
+    async fn set_eating_window(&self, window: EatingWindow) -> Result<(), YuhuhError>;
+
+    /// Removes the user's eating window, returning whether there was one.
+    async fn delete_eating_window(&self, user_id: &Uuid) -> Result<bool, YuhuhError>;
+}
+
+// =============================================================================
+// Production Implementation
+// =============================================================================
+
+#[derive(Debug)]
+pub struct EatingWindowRepositoryImpl {
+    pub db: PgPool,
+}
+
+impl EatingWindowRepositoryImpl {
+    pub fn new(db: PgPool) -> Self {
+        EatingWindowRepositoryImpl { db }
+    }
+}
+
+#[async_trait]
+impl EatingWindowRepository for EatingWindowRepositoryImpl {
+    async fn set_eating_window(&self, window: EatingWindow) -> Result<(), YuhuhError> {
+        debug!(window=?window, "setting eating window");
+
+        sqlx::query!(
+            r#"
+            INSERT INTO eating_windows (
+                user_id,
+                opens_at,
+                closes_at
+            )
+            VALUES ($1::uuid, $2::time, $3::time)
+            ON CONFLICT (user_id) DO UPDATE
+            SET opens_at = EXCLUDED.opens_at,
+                closes_at = EXCLUDED.closes_at;
+            "#,
+            window.user_id,
+            window.opens_at,
+            window.closes_at
+        )
+        .execute(&self.db)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, "database error while setting eating window");
+
+            YuhuhError::DatabaseError(e)
+        })?;
+
+        Ok(())
+    }
+
+    async fn delete_eating_window(&self, user_id: &Uuid) -> Result<bool, YuhuhError> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM eating_windows
+            WHERE user_id = $1::uuid;
+            "#,
+            user_id
+        )
+        .execute(&self.db)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, "database error while deleting eating window");
+
+            YuhuhError::DatabaseError(e)
+        })?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,6 @@
+pub mod eating_window;
+pub mod model;
+pub mod read_fasting;
+pub mod record_fasts;
+pub mod router;
+pub mod state;
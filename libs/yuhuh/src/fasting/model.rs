@@ -0,0 +1,217 @@
+use chrono::{DateTime, Days, NaiveDate, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, sqlx::FromRow)]
+pub struct Fast {
+    pub fast_id: Uuid,
+    pub user_id: Uuid,
+    pub started_at: DateTime<Utc>,
+    /// Time the fast was broken, missing while it's still going.
+    pub ended_at: Option<DateTime<Utc>>,
+    pub target_hours: Option<f32>,
+}
+
+/// A recurring daily eating window, in the user's local time.
+///
+/// Windows may wrap past midnight, e.g. opening at 20:00 and closing at
+/// 02:00. Any time outside of the window is treated as a fast.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema, sqlx::FromRow)]
+pub struct EatingWindow {
+    pub user_id: Uuid,
+    pub opens_at: NaiveTime,
+    pub closes_at: NaiveTime,
+}
+
+// =============================================================================
+// Implementations
+// =============================================================================
+
+impl Fast {
+    /// Whether `at` falls within this fast.
+    pub fn covers(&self, at: DateTime<Utc>) -> bool {
+        self.started_at <= at && self.ended_at.is_none_or(|ended_at| at < ended_at)
+    }
+
+    /// How long the fast lasted, or has lasted so far if it's still going.
+    pub fn duration(&self, now: DateTime<Utc>) -> chrono::Duration {
+        self.ended_at.unwrap_or(now) - self.started_at
+    }
+
+    /// Whether the fast has ended, having met its target if it had one.
+    pub fn completed(&self) -> bool {
+        match (self.ended_at, self.target_hours) {
+            (None, _) => false,
+            (Some(_), None) => true,
+            (Some(ended_at), Some(target_hours)) => {
+                (ended_at - self.started_at).num_seconds() as f32 >= target_hours * 3600.0
+            }
+        }
+    }
+}
+
+impl EatingWindow {
+    /// Whether the local time `at` falls outside of the eating window.
+    pub fn is_fasting_at(&self, at: NaiveTime) -> bool {
+        if self.opens_at < self.closes_at {
+            !(self.opens_at <= at && at < self.closes_at)
+        } else {
+            !(at >= self.opens_at || at < self.closes_at)
+        }
+    }
+
+    /// Start of the fast that's in progress at `now`, being the last time the
+    /// window closed, or `None` if the window is currently open.
+    pub fn current_fast_started_at(&self, now: DateTime<Utc>, tz: Tz) -> Option<DateTime<Utc>> {
+        let local = now.with_timezone(&tz);
+
+        if !self.is_fasting_at(local.time()) {
+            return None;
+        }
+
+        let today = local.date_naive();
+        let closed_on = if local.time() >= self.closes_at {
+            today
+        } else {
+            today.checked_sub_days(Days::new(1))?
+        };
+
+        local_to_utc(closed_on, self.closes_at, tz)
+    }
+}
+
+/// Resolves a local date and time into UTC, taking the earlier time when
+/// daylight saving makes it ambiguous and skipping ahead when it doesn't exist.
+fn local_to_utc(date: NaiveDate, time: NaiveTime, tz: Tz) -> Option<DateTime<Utc>> {
+    let local = date.and_time(time);
+
+    tz.from_local_datetime(&local)
+        .earliest()
+        .or_else(|| {
+            tz.from_local_datetime(&(local + chrono::Duration::hours(1)))
+                .earliest()
+        })
+        .map(|t| t.with_timezone(&Utc))
+}
+
+/// Everything needed to decide whether a point in time was during a fast.
+#[derive(Debug)]
+pub struct FastingSchedule {
+    pub fasts: Vec<Fast>,
+    pub eating_window: Option<EatingWindow>,
+    pub tz: Tz,
+}
+
+impl FastingSchedule {
+    /// A warning for food logged at `logged_at`, if it was during a fast.
+    ///
+    /// Explicit fasts are checked first, then the recurring eating window using
+    /// the local time in the user's timezone.
+    pub fn warning(&self, logged_at: DateTime<Utc>) -> Option<String> {
+        let local = logged_at.with_timezone(&self.tz);
+
+        if let Some(fast) = self.fasts.iter().find(|f| f.covers(logged_at)) {
+            return Some(format!(
+                "logged at {} during a fast started at {}",
+                local.format("%Y-%m-%d %H:%M"),
+                fast.started_at
+                    .with_timezone(&self.tz)
+                    .format("%Y-%m-%d %H:%M")
+            ));
+        }
+
+        self.eating_window
+            .as_ref()
+            .filter(|w| w.is_fasting_at(local.time()))
+            .map(|w| {
+                format!(
+                    "logged at {} outside of the eating window {} to {}",
+                    local.format("%Y-%m-%d %H:%M"),
+                    w.opens_at.format("%H:%M"),
+                    w.closes_at.format("%H:%M")
+                )
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+    use pretty_assertions::assert_eq;
+    use uuid::uuid;
+
+    use super::*;
+
+    fn window(opens_at: &str, closes_at: &str) -> EatingWindow {
+        EatingWindow {
+            user_id: uuid!("11111111-1111-1111-1111-111111111111"),
+            opens_at: opens_at.parse().unwrap(),
+            closes_at: closes_at.parse().unwrap(),
+        }
+    }
+
+    fn utc(at: &str) -> DateTime<Utc> {
+        at.parse().unwrap()
+    }
+
+    #[test]
+    fn eating_windows_can_wrap_midnight() {
+        let day = window("12:00:00", "20:00:00");
+        let night = window("20:00:00", "02:00:00");
+
+        assert!(day.is_fasting_at("08:00:00".parse().unwrap()));
+        assert!(!day.is_fasting_at("12:00:00".parse().unwrap()));
+        assert!(day.is_fasting_at("20:00:00".parse().unwrap()));
+
+        assert!(!night.is_fasting_at("23:00:00".parse().unwrap()));
+        assert!(!night.is_fasting_at("01:59:00".parse().unwrap()));
+        assert!(night.is_fasting_at("12:00:00".parse().unwrap()));
+    }
+
+    #[test]
+    fn window_fasts_start_at_last_close_in_local_time() {
+        let w = window("12:00:00", "20:00:00");
+        let tz: Tz = "Australia/Brisbane".parse().unwrap();
+
+        // 08:00 local, so the fast started 20:00 local the day before
+        assert_eq!(
+            w.current_fast_started_at(utc("2025-10-27T22:00:00Z"), tz),
+            Some(utc("2025-10-27T10:00:00Z"))
+        );
+        // 13:00 local, the window is open
+        assert_eq!(
+            w.current_fast_started_at(utc("2025-10-28T03:00:00Z"), tz),
+            None
+        );
+    }
+
+    #[test]
+    fn warnings_use_fasts_then_local_window() {
+        let started_at = utc("2025-10-27T00:00:00Z");
+        let schedule = FastingSchedule {
+            fasts: vec![Fast {
+                fast_id: uuid!("33333333-3333-3333-3333-333333333333"),
+                user_id: uuid!("11111111-1111-1111-1111-111111111111"),
+                started_at,
+                ended_at: Some(started_at + Duration::hours(16)),
+                target_hours: Some(16.0),
+            }],
+            eating_window: Some(window("12:00:00", "20:00:00")),
+            tz: "Australia/Brisbane".parse().unwrap(),
+        };
+
+        assert_eq!(
+            schedule.warning(utc("2025-10-27T01:00:00Z")),
+            Some(
+                "logged at 2025-10-27 11:00 during a fast started at 2025-10-27 10:00".to_string()
+            )
+        );
+        // 14:00 local is inside the eating window
+        assert_eq!(schedule.warning(utc("2025-10-28T04:00:00Z")), None);
+        // 21:00 local is not
+        assert!(schedule.warning(utc("2025-10-28T11:00:00Z")).is_some());
+        assert!(schedule.fasts[0].completed());
+    }
+}
@@ -0,0 +1,329 @@
+//! read fasting HTTP handler
+//!
+//! This module provides an HTTP endpoint reporting a user's current fast,
+//! history and streaks.
+
+use std::{collections::BTreeSet, sync::Arc};
+
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::StatusCode,
+};
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, instrument};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::{
+    auth::model::Caller,
+    error::YuhuhError,
+    fasting::{
+        model::{EatingWindow, Fast, FastingSchedule},
+        state::FastingState,
+    },
+    user::{model::User, state::UserState},
+};
+
+// ============================================================================
+// HTTP Request types
+// ============================================================================
+
+/// Request parameters for reading a user's fasting.
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ReadFastingRequest {
+    /// user ID to search by.
+    pub user_id: Uuid,
+    /// Maximum number of past fasts to return, defaults to 30.
+    pub limit: Option<u32>,
+}
+
+// ============================================================================
+// HTTP Responsed types
+// ============================================================================
+
+/// What the current fast comes from.
+#[derive(Debug, Serialize, Deserialize, PartialEq, ToSchema)]
+pub enum FastSource {
+    /// A fast explicitly started by the user.
+    Fast,
+    /// Time outside of the user's recurring eating window.
+    EatingWindow,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CurrentFast {
+    pub source: FastSource,
+    pub started_at: DateTime<Utc>,
+    pub duration_seconds: i64,
+    pub target_hours: Option<f32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct FoundFast {
+    pub fast_id: Uuid,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: Option<DateTime<Utc>>,
+    pub target_hours: Option<f32>,
+    pub duration_seconds: i64,
+    /// Whether the fast ended having met its target, if it had one.
+    pub completed: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ReadFastingResponse {
+    /// Fast in progress right now, if any.
+    pub current: Option<CurrentFast>,
+    pub eating_window: Option<EatingWindow>,
+    /// Past and current fasts, most recent first.
+    pub history: Vec<FoundFast>,
+    /// Consecutive days, in the user's timezone, up to today or yesterday
+    /// with at least one completed fast.
+    pub current_streak_days: u32,
+    pub longest_streak_days: u32,
+}
+
+// ============================================================================
+// Implementations
+// ============================================================================
+
+impl FoundFast {
+    fn new(fast: &Fast, now: DateTime<Utc>) -> Self {
+        FoundFast {
+            fast_id: fast.fast_id,
+            started_at: fast.started_at,
+            ended_at: fast.ended_at,
+            target_hours: fast.target_hours,
+            duration_seconds: fast.duration(now).num_seconds(),
+            completed: fast.completed(),
+        }
+    }
+}
+
+/// Finds the current fast, preferring one explicitly started over the eating
+/// window.
+fn current_fast(schedule: &FastingSchedule, now: DateTime<Utc>) -> Option<CurrentFast> {
+    if let Some(fast) = schedule.fasts.iter().find(|f| f.ended_at.is_none()) {
+        return Some(CurrentFast {
+            source: FastSource::Fast,
+            started_at: fast.started_at,
+            duration_seconds: fast.duration(now).num_seconds(),
+            target_hours: fast.target_hours,
+        });
+    }
+
+    let window = schedule.eating_window.as_ref()?;
+    let started_at = window.current_fast_started_at(now, schedule.tz)?;
+
+    Some(CurrentFast {
+        source: FastSource::EatingWindow,
+        started_at,
+        duration_seconds: (now - started_at).num_seconds(),
+        target_hours: None,
+    })
+}
+
+/// Works out the current and longest runs of consecutive local days with a
+/// completed fast, where the current run may end yesterday as today isn't
+/// over yet.
+fn streaks(fasts: &[Fast], tz: Tz, today: NaiveDate) -> (u32, u32) {
+    let days: BTreeSet<NaiveDate> = fasts
+        .iter()
+        .filter(|f| f.completed())
+        .filter_map(|f| f.ended_at)
+        .map(|ended_at| ended_at.with_timezone(&tz).date_naive())
+        .collect();
+
+    let mut longest = 0;
+    let mut run = 0;
+    let mut previous: Option<NaiveDate> = None;
+
+    for day in &days {
+        run = match previous {
+            Some(p) if p.succ_opt() == Some(*day) => run + 1,
+            _ => 1,
+        };
+        longest = longest.max(run);
+        previous = Some(*day);
+    }
+
+    let yesterday = today.checked_sub_days(Days::new(1));
+    let current = match previous {
+        Some(last) if last == today || Some(last) == yesterday => run,
+        _ => 0,
+    };
+
+    (current, longest)
+}
+
+/// Reads everything needed to check whether times between `after` and
+/// `before` were during one of the user's fasts.
+pub async fn read_fasting_schedule(
+    fasting_state: &FastingState,
+    user: &User,
+    before: Option<DateTime<Utc>>,
+    after: Option<DateTime<Utc>>,
+) -> Result<FastingSchedule, YuhuhError> {
+    let fasts = fasting_state
+        .read_fasting_repo
+        .read_fasts(&user.user_id, before, after)
+        .await?;
+
+    let eating_window = fasting_state
+        .read_fasting_repo
+        .read_eating_window(&user.user_id)
+        .await?;
+
+    Ok(FastingSchedule {
+        fasts,
+        eating_window,
+        tz: user.tz(),
+    })
+}
+
+// =============================================================================
+// HTTP Handlers
+// =============================================================================
+
+/// Find a user's current fast, fasting history and streaks
+#[utoipa::path(
+    get,
+    path = "fasting",
+    tag = "fasting",
+    params(ReadFastingRequest),
+    responses(
+        (status = 200, description = "Found fasting", body = ReadFastingResponse)
+))]
+#[instrument]
+pub async fn read_fasting(
+    State(fasting_state): State<Arc<FastingState>>,
+    State(user_state): State<Arc<UserState>>,
+    caller: Caller,
+    Query(request): Query<ReadFastingRequest>,
+) -> Result<(StatusCode, Json<ReadFastingResponse>), YuhuhError> {
+    debug!("entering read_fasting");
+
+    caller.authorise_user(&request.user_id)?;
+
+    let user = user_state.require_user(&request.user_id).await?;
+
+    let now = Utc::now();
+    let schedule = read_fasting_schedule(&fasting_state, &user, None, None).await?;
+    let (current_streak_days, longest_streak_days) = streaks(
+        &schedule.fasts,
+        schedule.tz,
+        now.with_timezone(&schedule.tz).date_naive(),
+    );
+
+    Ok((
+        StatusCode::OK,
+        Json(ReadFastingResponse {
+            current: current_fast(&schedule, now),
+            history: schedule
+                .fasts
+                .iter()
+                .take(request.limit.unwrap_or(30) as usize)
+                .map(|f| FoundFast::new(f, now))
+                .collect(),
+            eating_window: schedule.eating_window,
+            current_streak_days,
+            longest_streak_days,
+        }),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use chrono::{Duration, Utc};
+    use http_body_util::BodyExt;
+    use pretty_assertions::assert_eq;
+
+    use axum::{
+        body::Body,
+        http::{self, Request, StatusCode},
+    };
+    use tower::ServiceExt;
+    use uuid::uuid;
+
+    use crate::fasting::{
+        read_fasting::{FastSource, ReadFastingResponse},
+        record_fasts::StartFastRequest,
+    };
+
+    async fn read_fasting(app: axum::Router, user_id: &str) -> ReadFastingResponse {
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri(format!("/fasting?user_id={}", user_id))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        serde_json::from_slice(&body).expect("valid ReadFastingResponse bytes")
+    }
+
+    #[tokio::test]
+    async fn history_and_streaks_reported() {
+        let (app, db, _) = crate::test::common::setup().await;
+
+        sqlx::raw_sql(include_str!("../../migrations/test/fasting.sql"))
+            .execute(&db)
+            .await
+            .expect("setup test sql ran successfully");
+
+        let dto = read_fasting(app, "11111111-1111-1111-1111-111111111111").await;
+
+        assert_eq!(dto.history.len(), 6);
+        assert_eq!(dto.history.iter().filter(|f| f.completed).count(), 5);
+        assert_eq!(dto.current_streak_days, 3);
+        assert_eq!(dto.longest_streak_days, 3);
+        assert!(dto.eating_window.is_some());
+    }
+
+    #[tokio::test]
+    async fn current_fast_reported() {
+        let (app, db, _) = crate::test::common::setup().await;
+
+        sqlx::raw_sql(include_str!("../../migrations/test/fasting.sql"))
+            .execute(&db)
+            .await
+            .expect("setup test sql ran successfully");
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/fasting/start")
+                    .header(http::header::CONTENT_TYPE, "application/json")
+                    .body(Body::from(
+                        serde_json::to_string(&StartFastRequest {
+                            user_id: uuid!("22222222-2222-2222-2222-222222222222"),
+                            started_at: Some(Utc::now() - Duration::hours(2)),
+                            target_hours: None,
+                        })
+                        .expect("request is valid body"),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        let dto = read_fasting(app, "22222222-2222-2222-2222-222222222222").await;
+        let current = dto.current.expect("fast in progress");
+
+        assert_eq!(current.source, FastSource::Fast);
+        assert!(current.duration_seconds >= 2 * 60 * 60);
+        assert_eq!(dto.current_streak_days, 0);
+    }
+}
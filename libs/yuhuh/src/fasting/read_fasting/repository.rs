@@ -0,0 +1,110 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use tracing::{debug, error};
+use uuid::Uuid;
+
+use crate::{
+    error::YuhuhError,
+    fasting::model::{EatingWindow, Fast},
+};
+
+// =============================================================================
+// Traits
+// =============================================================================
+
+#[async_trait]
+pub trait ReadFastingRepository: std::fmt::Debug + Send + Sync + 'static {
+    /// Finds a user's fasts overlapping the given range, most recent first.
+    async fn read_fasts(
+        &self,
+        user_id: &Uuid,
+        before: Option<DateTime<Utc>>,
+        after: Option<DateTime<Utc>>,
+    ) -> Result<Vec<Fast>, YuhuhError>;
+
+    async fn read_eating_window(&self, user_id: &Uuid) -> Result<Option<EatingWindow>, YuhuhError>;
+}
+
+// =============================================================================
+// Production Implementation
+// =============================================================================
+
+#[derive(Debug)]
+pub struct ReadFastingRepositoryImpl {
+    pub db: PgPool,
+}
+
+impl ReadFastingRepositoryImpl {
+    pub fn new(db: PgPool) -> Self {
+        ReadFastingRepositoryImpl { db }
+    }
+}
+
+#[async_trait]
+impl ReadFastingRepository for ReadFastingRepositoryImpl {
+    async fn read_fasts(
+        &self,
+        user_id: &Uuid,
+        before: Option<DateTime<Utc>>,
+        after: Option<DateTime<Utc>>,
+    ) -> Result<Vec<Fast>, YuhuhError> {
+        debug!(user_id=?user_id, before=?before, after=?after, "received read request for fasts");
+
+        let fasts = sqlx::query_as!(
+            Fast,
+            r#"
+            SELECT
+                fast_id,
+                user_id,
+                started_at,
+                ended_at,
+                target_hours
+            FROM fasts
+            WHERE user_id = $1::uuid
+            AND ($2::timestamptz IS NULL
+                OR started_at <= $2::timestamptz)
+            AND ($3::timestamptz IS NULL
+                OR ended_at IS NULL
+                OR ended_at >= $3::timestamptz)
+            ORDER BY started_at DESC;
+            "#,
+            user_id,
+            before,
+            after
+        )
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, "database error while reading fasts");
+
+            YuhuhError::DatabaseError(e)
+        })?;
+
+        debug!(fasts=?fasts, "found fasts");
+
+        Ok(fasts)
+    }
+
+    async fn read_eating_window(&self, user_id: &Uuid) -> Result<Option<EatingWindow>, YuhuhError> {
+        sqlx::query_as!(
+            EatingWindow,
+            r#"
+            SELECT
+                user_id,
+                opens_at,
+                closes_at
+            FROM eating_windows
+            WHERE user_id = $1::uuid;
+            "#,
+            user_id
+        )
+        .fetch_optional(&self.db)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, "database error while reading eating window");
+
+            YuhuhError::DatabaseError(e)
+        })
+    }
+}
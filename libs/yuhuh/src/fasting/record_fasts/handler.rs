@@ -0,0 +1,217 @@
+//! fast recording HTTP handlers
+//!
+//! This module provides HTTP endpoints for starting and stopping fasts.
+
+use std::sync::Arc;
+
+use axum::{Json, extract::State, http::StatusCode};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, instrument};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    auth::model::Caller,
+    error::YuhuhError,
+    fasting::{model::Fast, state::FastingState},
+    user::state::UserState,
+};
+
+// ============================================================================
+// HTTP Request types
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct StartFastRequest {
+    pub user_id: Uuid,
+    /// Time the fast started, defaulting to now.
+    pub started_at: Option<DateTime<Utc>>,
+    /// Optional goal for how long the fast should last.
+    pub target_hours: Option<f32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct StopFastRequest {
+    pub user_id: Uuid,
+    /// Time the fast was broken, defaulting to now.
+    pub ended_at: Option<DateTime<Utc>>,
+}
+
+// =============================================================================
+// HTTP Handlers
+// =============================================================================
+
+/// Start a fast for a user
+#[utoipa::path(
+    post,
+    path = "fasting/start",
+    tag = "fasting",
+    responses(
+        (status = 201, description = "fast started", body = Fast),
+        (status = 400, description = "a fast is already in progress")
+))]
+#[instrument]
+pub async fn start_fast(
+    State(fasting_state): State<Arc<FastingState>>,
+    State(user_state): State<Arc<UserState>>,
+    caller: Caller,
+    Json(request): Json<StartFastRequest>,
+) -> Result<(StatusCode, Json<Fast>), YuhuhError> {
+    debug!("entering start_fast");
+
+    caller.authorise_user(&request.user_id)?;
+
+    user_state.require_user(&request.user_id).await?;
+
+    if let Some(target_hours) = request.target_hours
+        && !(target_hours.is_finite() && target_hours > 0.0)
+    {
+        return Err(YuhuhError::BadRequest(format!(
+            "target_hours must be positive, but got {} instead",
+            target_hours
+        )));
+    }
+
+    let fast = fasting_state
+        .record_fasts_repo
+        .start_fast(
+            &request.user_id,
+            request.started_at.unwrap_or(Utc::now()),
+            request.target_hours,
+        )
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(fast)))
+}
+
+/// Stop a user's fast in progress
+#[utoipa::path(
+    post,
+    path = "fasting/stop",
+    tag = "fasting",
+    responses(
+        (status = 200, description = "fast stopped", body = Fast),
+        (status = 404, description = "no fast in progress")
+))]
+#[instrument]
+pub async fn stop_fast(
+    State(fasting_state): State<Arc<FastingState>>,
+    caller: Caller,
+    Json(request): Json<StopFastRequest>,
+) -> Result<(StatusCode, Json<Fast>), YuhuhError> {
+    debug!("entering stop_fast");
+
+    caller.authorise_user(&request.user_id)?;
+
+    let fast = fasting_state
+        .record_fasts_repo
+        .stop_fast(&request.user_id, request.ended_at.unwrap_or(Utc::now()))
+        .await?
+        .ok_or_else(|| YuhuhError::NotFound("no fast in progress".to_string()))?;
+
+    Ok((StatusCode::OK, Json(fast)))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use chrono::{Duration, Utc};
+    use http_body_util::BodyExt;
+    use pretty_assertions::assert_eq;
+
+    use axum::{
+        body::Body,
+        http::{self, Request, StatusCode},
+    };
+    use tower::ServiceExt;
+    use uuid::uuid;
+
+    use crate::fasting::{
+        model::Fast,
+        record_fasts::{StartFastRequest, StopFastRequest},
+    };
+
+    fn post<T: serde::Serialize>(uri: &str, request: &T) -> Request<Body> {
+        Request::builder()
+            .method("POST")
+            .uri(uri)
+            .header(http::header::CONTENT_TYPE, "application/json")
+            .body(Body::from(
+                serde_json::to_string(request).expect("request is valid body"),
+            ))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn fasts_started_and_stopped() {
+        let (app, db, _) = crate::test::common::setup().await;
+
+        sqlx::raw_sql(include_str!("../../migrations/test/fasting.sql"))
+            .execute(&db)
+            .await
+            .expect("setup test sql ran successfully");
+
+        let start = StartFastRequest {
+            user_id: uuid!("22222222-2222-2222-2222-222222222222"),
+            started_at: Some(Utc::now() - Duration::hours(2)),
+            target_hours: Some(16.0),
+        };
+
+        let response = app
+            .clone()
+            .oneshot(post("/fasting/start", &start))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        // Only one fast can be in progress
+        let response = app
+            .clone()
+            .oneshot(post("/fasting/start", &start))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = app
+            .oneshot(post(
+                "/fasting/stop",
+                &StopFastRequest {
+                    user_id: uuid!("22222222-2222-2222-2222-222222222222"),
+                    ended_at: None,
+                },
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let fast: Fast = serde_json::from_slice(&body).expect("valid Fast bytes");
+
+        assert!(fast.ended_at.is_some());
+        assert!(!fast.completed());
+    }
+
+    #[tokio::test]
+    async fn stopping_without_a_fast_not_found() {
+        let (app, db, _) = crate::test::common::setup().await;
+
+        sqlx::raw_sql(include_str!("../../migrations/test/fasting.sql"))
+            .execute(&db)
+            .await
+            .expect("setup test sql ran successfully");
+
+        let response = app
+            .oneshot(post(
+                "/fasting/stop",
+                &StopFastRequest {
+                    user_id: uuid!("22222222-2222-2222-2222-222222222222"),
+                    ended_at: None,
+                },
+            ))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,129 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use tracing::{debug, error};
+use uuid::Uuid;
+
+use crate::{error::YuhuhError, fasting::model::Fast};
+
+// =============================================================================
+// Traits
+// =============================================================================
+
+#[async_trait]
+pub trait RecordFastsRepository: std::fmt::Debug + Send + Sync + 'static {
+    /// Starts a new fast, failing if the user already has one in progress.
+    async fn start_fast(
+        &self,
+        user_id: &Uuid,
+        started_at: DateTime<Utc>,
+        target_hours: Option<f32>,
+    ) -> Result<Fast, YuhuhError>;
+
+    /// Ends the user's fast in progress, returning `None` if there wasn't one.
+    async fn stop_fast(
+        &self,
+        user_id: &Uuid,
+        ended_at: DateTime<Utc>,
+    ) -> Result<Option<Fast>, YuhuhError>;
+}
+
+// =============================================================================
+// Production Implementation
+// =============================================================================
+
+#[derive(Debug)]
+pub struct RecordFastsRepositoryImpl {
+    pub db: PgPool,
+}
+
+impl RecordFastsRepositoryImpl {
+    pub fn new(db: PgPool) -> Self {
+        RecordFastsRepositoryImpl { db }
+    }
+}
+
+#[async_trait]
+impl RecordFastsRepository for RecordFastsRepositoryImpl {
+    async fn start_fast(
+        &self,
+        user_id: &Uuid,
+        started_at: DateTime<Utc>,
+        target_hours: Option<f32>,
+    ) -> Result<Fast, YuhuhError> {
+        debug!(user_id=?user_id, started_at=?started_at, "starting fast");
+
+        sqlx::query_as!(
+            Fast,
+            r#"
+            INSERT INTO fasts (
+                user_id,
+                started_at,
+                target_hours
+            )
+            VALUES ($1::uuid, $2::timestamptz, $3::real)
+            RETURNING
+                fast_id,
+                user_id,
+                started_at,
+                ended_at,
+                target_hours;
+            "#,
+            user_id,
+            started_at,
+            target_hours
+        )
+        .fetch_one(&self.db)
+        .await
+        .map_err(|e| {
+            if e.as_database_error()
+                .is_some_and(|d| d.is_unique_violation())
+            {
+                return YuhuhError::Conflict("a fast is already in progress".to_string());
+            }
+
+            error!(error = ?e, "database error while starting fast");
+
+            YuhuhError::DatabaseError(e)
+        })
+    }
+
+    async fn stop_fast(
+        &self,
+        user_id: &Uuid,
+        ended_at: DateTime<Utc>,
+    ) -> Result<Option<Fast>, YuhuhError> {
+        debug!(user_id=?user_id, ended_at=?ended_at, "stopping fast");
+
+        sqlx::query_as!(
+            Fast,
+            r#"
+            UPDATE fasts
+            SET ended_at = $2::timestamptz
+            WHERE user_id = $1::uuid
+            AND ended_at IS NULL
+            RETURNING
+                fast_id,
+                user_id,
+                started_at,
+                ended_at,
+                target_hours;
+            "#,
+            user_id,
+            ended_at
+        )
+        .fetch_optional(&self.db)
+        .await
+        .map_err(|e| {
+            if e.as_database_error()
+                .is_some_and(|d| d.is_check_violation())
+            {
+                return YuhuhError::BadRequest("a fast must end after it started".to_string());
+            }
+
+            error!(error = ?e, "database error while stopping fast");
+
+            YuhuhError::DatabaseError(e)
+        })
+    }
+}
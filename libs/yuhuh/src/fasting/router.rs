@@ -0,0 +1,39 @@
+use axum::{
+    Router,
+    routing::{get, post, put},
+};
+use utoipa::OpenApi;
+
+use crate::{
+    fasting::{eating_window, read_fasting, record_fasts},
+    state::AppState,
+};
+
+// =============================================================================
+// API Docs
+// =============================================================================
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    read_fasting::read_fasting,
+    record_fasts::start_fast,
+    record_fasts::stop_fast,
+    eating_window::set_eating_window,
+    eating_window::delete_eating_window
+))]
+pub struct FastingApi;
+
+// =============================================================================
+// Router
+// =============================================================================
+
+pub fn fasting_router() -> Router<AppState> {
+    Router::new()
+        .route("/fasting", get(read_fasting::read_fasting))
+        .route("/fasting/start", post(record_fasts::start_fast))
+        .route("/fasting/stop", post(record_fasts::stop_fast))
+        .route(
+            "/fasting/window",
+            put(eating_window::set_eating_window).delete(eating_window::delete_eating_window),
+        )
+}
@@ -0,0 +1,26 @@
+use std::sync::Arc;
+
+use sqlx::PgPool;
+
+use crate::fasting::{
+    eating_window::{EatingWindowRepository, EatingWindowRepositoryImpl},
+    read_fasting::{ReadFastingRepository, ReadFastingRepositoryImpl},
+    record_fasts::{RecordFastsRepository, RecordFastsRepositoryImpl},
+};
+
+#[derive(Debug)]
+pub struct FastingState {
+    pub record_fasts_repo: Arc<dyn RecordFastsRepository>,
+    pub eating_window_repo: Arc<dyn EatingWindowRepository>,
+    pub read_fasting_repo: Arc<dyn ReadFastingRepository>,
+}
+
+impl FastingState {
+    pub fn new(db: PgPool) -> Self {
+        FastingState {
+            record_fasts_repo: Arc::new(RecordFastsRepositoryImpl::new(db.clone())),
+            eating_window_repo: Arc::new(EatingWindowRepositoryImpl::new(db.clone())),
+            read_fasting_repo: Arc::new(ReadFastingRepositoryImpl::new(db.clone())),
+        }
+    }
+}
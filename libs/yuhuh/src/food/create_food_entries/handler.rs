@@ -12,8 +12,12 @@ use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::{
+    auth::model::Caller,
     error::YuhuhError,
-    food::{model::FoodEntry, state::FoodState},
+    fasting::{read_fasting::read_fasting_schedule, state::FastingState},
+    food::{
+        energy::EnergyUnit, micronutrients::Micronutrients, model::FoodEntry, state::FoodState,
+    },
     user::state::UserState,
 };
 
@@ -31,24 +35,39 @@ pub struct CreateFoodEntryRequest {
 pub struct NewFoodEntry {
     pub description: String,
     pub calories: Option<f32>,
+    /// Unit `calories` is given in, defaulting to kilocalories.
+    pub energy_unit: Option<EnergyUnit>,
     pub carbs: Option<f32>,
     pub protein: Option<f32>,
     pub fats: Option<f32>,
-    pub micronutrients: Option<serde_json::Value>,
+    pub micronutrients: Option<Micronutrients>,
     pub logged_at: Option<DateTime<Utc>>,
 }
 
+// ============================================================================
+// HTTP Responsed types
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateFoodEntriesResponse {
+    /// Entries were still created, but some were logged during a fast.
+    pub warnings: Vec<String>,
+}
+
 // ============================================================================
 // Implementations
 // ============================================================================
 
 impl NewFoodEntry {
     pub fn into(&self, user_id: Uuid) -> FoodEntry {
+        let energy_unit = self.energy_unit.unwrap_or_default();
+
         FoodEntry {
             food_record_id: None,
             user_id,
             description: self.description.clone(),
-            calories: self.calories,
+            calories: self.calories.map(|c| energy_unit.to_kcal(c)),
+            energy_unit,
             carbs: self.carbs,
             protein: self.protein,
             fats: self.fats,
@@ -69,25 +88,28 @@ impl NewFoodEntry {
         path = "food/create",
         tag = "food",
         responses(
-            (status = 201, description = "food entries created successfully"),
+            (status = 201, description = "food entries created successfully", body = CreateFoodEntriesResponse),
         )
     )]
 #[instrument]
 pub async fn create_food_entries(
     State(food_state): State<Arc<FoodState>>,
     State(user_state): State<Arc<UserState>>,
+    State(fasting_state): State<Arc<FastingState>>,
+    caller: Caller,
     Json(request): Json<CreateFoodEntryRequest>,
-) -> Result<StatusCode, YuhuhError> {
+) -> Result<(StatusCode, Json<CreateFoodEntriesResponse>), YuhuhError> {
     debug!("entering create_food_entries");
 
-    if (user_state
-        .find_user_repo
-        .find_user_by_id(&request.user_id)
-        .await?)
-        .is_none()
-    {
-        return Err(YuhuhError::NotFound("user not found".to_string()));
-    }
+    caller.authorise_user(&request.user_id)?;
+
+    let user = user_state.require_user(&request.user_id).await?;
+
+    request
+        .food_entries
+        .iter()
+        .filter_map(|f| f.micronutrients.as_ref())
+        .try_for_each(Micronutrients::validate)?;
 
     let food_entries: Vec<FoodEntry> = request
         .food_entries
@@ -97,29 +119,53 @@ pub async fn create_food_entries(
 
     debug!(food_entries=?food_entries, "food entries mapped");
 
+    let fasting_schedule = read_fasting_schedule(
+        &fasting_state,
+        &user,
+        food_entries.iter().map(|f| f.logged_at).max(),
+        food_entries.iter().map(|f| f.logged_at).min(),
+    )
+    .await?;
+
+    let warnings = food_entries
+        .iter()
+        .filter_map(|f| {
+            fasting_schedule
+                .warning(f.logged_at)
+                .map(|w| format!("{} {}", f.description, w))
+        })
+        .collect();
+
     food_state
         .create_food_entries_repo
         .create_food_entries(food_entries)
         .await?;
 
-    Ok(StatusCode::CREATED)
+    Ok((
+        StatusCode::CREATED,
+        Json(CreateFoodEntriesResponse { warnings }),
+    ))
 }
 
 #[cfg(test)]
 mod tests {
 
     use chrono::{Duration, Timelike, Utc};
+    use http_body_util::BodyExt;
     use pretty_assertions::assert_eq;
 
     use axum::{
         body::Body,
         http::{self, Request, StatusCode},
     };
-    use serde_json::json;
     use tower::ServiceExt;
     use uuid::uuid;
 
-    use crate::food::create_food_entries::{CreateFoodEntryRequest, NewFoodEntry};
+    use crate::food::{
+        create_food_entries::{CreateFoodEntriesResponse, CreateFoodEntryRequest, NewFoodEntry},
+        energy::EnergyUnit,
+        micronutrients::{Micronutrients, Nutrient, NutrientUnit},
+    };
 
     #[tokio::test]
     async fn create_food_entries_correctly() {
@@ -138,10 +184,17 @@ mod tests {
             food_entries: vec![NewFoodEntry {
                 description: "new food entry".to_string(),
                 calories: Some(5.0),
+                energy_unit: None,
                 carbs: Some(10.0),
                 protein: Some(15.0),
                 fats: Some(20.0),
-                micronutrients: Some(json!("{}")),
+                micronutrients: Some(Micronutrients {
+                    sodium: Some(Nutrient {
+                        amount: 120.0,
+                        unit: NutrientUnit::Milligrams,
+                    }),
+                    ..Default::default()
+                }),
                 logged_at: None,
             }],
         };
@@ -180,7 +233,16 @@ mod tests {
         assert_eq!(created[0].carbs, Some(10.0));
         assert_eq!(created[0].protein, Some(15.0));
         assert_eq!(created[0].fats, Some(20.0));
-        assert_eq!(created[0].micronutrients, Some(json!("{}")));
+        assert_eq!(
+            created[0].micronutrients,
+            Some(Micronutrients {
+                sodium: Some(Nutrient {
+                    amount: 120.0,
+                    unit: NutrientUnit::Milligrams,
+                }),
+                ..Default::default()
+            })
+        );
 
         let now = Utc::now();
         let one_day_ago = now - Duration::days(1);
@@ -217,10 +279,11 @@ mod tests {
             food_entries: vec![NewFoodEntry {
                 description: "new food entry".to_string(),
                 calories: Some(5.0),
+                energy_unit: None,
                 carbs: Some(10.0),
                 protein: Some(15.0),
                 fats: Some(20.0),
-                micronutrients: Some(json!("{}")),
+                micronutrients: None,
                 logged_at: Some(logged_at_time),
             }],
         };
@@ -261,6 +324,163 @@ mod tests {
         assert_eq!(expected_truncated, actual_truncated)
     }
 
+    #[tokio::test]
+    async fn kilojoules_are_stored_as_kilocalories() {
+        let (app, db, state) = crate::test::common::setup().await;
+
+        // Load test data into the database
+        sqlx::raw_sql(include_str!(
+            "../../migrations/test/create_food_entries.sql"
+        ))
+        .execute(&db)
+        .await
+        .expect("setup test sql ran successfully");
+
+        let request = CreateFoodEntryRequest {
+            user_id: uuid!("11111111-1111-1111-1111-111111111111"),
+            food_entries: vec![NewFoodEntry {
+                description: "meat pie".to_string(),
+                calories: Some(2092.0),
+                energy_unit: Some(EnergyUnit::Kilojoules),
+                carbs: None,
+                protein: None,
+                fats: None,
+                micronutrients: None,
+                logged_at: None,
+            }],
+        };
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/food/create")
+                    .header(http::header::CONTENT_TYPE, "application/json")
+                    .body(Body::from(
+                        serde_json::to_string(&request).expect("request is valid body"),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        let created = state
+            .food
+            .read_food_entries_repo
+            .read_food_entries(
+                &uuid!("11111111-1111-1111-1111-111111111111"),
+                None,
+                None,
+                100,
+                0,
+            )
+            .await
+            .expect("no errors on reading newly created entry");
+
+        assert_eq!(created[0].calories, Some(500.0));
+        assert_eq!(created[0].energy_unit, EnergyUnit::Kilojoules);
+    }
+
+    #[tokio::test]
+    async fn food_logged_during_fast_warned() {
+        let (app, db, _) = crate::test::common::setup().await;
+
+        // Alice has a fast that ended a day ago
+        sqlx::raw_sql(include_str!("../../migrations/test/fasting.sql"))
+            .execute(&db)
+            .await
+            .expect("setup test sql ran successfully");
+
+        let request = CreateFoodEntryRequest {
+            user_id: uuid!("11111111-1111-1111-1111-111111111111"),
+            food_entries: vec![NewFoodEntry {
+                description: "sneaky biscuit".to_string(),
+                calories: Some(75.0),
+                energy_unit: None,
+                carbs: None,
+                protein: None,
+                fats: None,
+                micronutrients: None,
+                logged_at: Some(Utc::now() - Duration::days(1) - Duration::hours(2)),
+            }],
+        };
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/food/create")
+                    .header(http::header::CONTENT_TYPE, "application/json")
+                    .body(Body::from(
+                        serde_json::to_string(&request).expect("request is valid body"),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let dto: CreateFoodEntriesResponse =
+            serde_json::from_slice(&body).expect("valid CreateFoodEntriesResponse bytes");
+
+        assert_eq!(dto.warnings.len(), 1);
+        assert!(dto.warnings[0].starts_with("sneaky biscuit logged at"));
+        assert!(dto.warnings[0].contains("during a fast"));
+    }
+
+    #[tokio::test]
+    async fn invalid_micronutrients_return_bad_request() {
+        let (app, db, _) = crate::test::common::setup().await;
+
+        // Load test data into the database
+        sqlx::raw_sql(include_str!(
+            "../../migrations/test/create_food_entries.sql"
+        ))
+        .execute(&db)
+        .await
+        .expect("setup test sql ran successfully");
+
+        let request = CreateFoodEntryRequest {
+            user_id: uuid!("11111111-1111-1111-1111-111111111111"),
+            food_entries: vec![NewFoodEntry {
+                description: "salty food entry".to_string(),
+                calories: None,
+                energy_unit: None,
+                carbs: None,
+                protein: None,
+                fats: None,
+                micronutrients: Some(Micronutrients {
+                    sodium: Some(Nutrient {
+                        amount: -5.0,
+                        unit: NutrientUnit::Milligrams,
+                    }),
+                    ..Default::default()
+                }),
+                logged_at: None,
+            }],
+        };
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/food/create")
+                    .header(http::header::CONTENT_TYPE, "application/json")
+                    .body(Body::from(
+                        serde_json::to_string(&request).expect("request is valid body"),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
     #[tokio::test]
     async fn invalid_user_returns_not_found() {
         let (app, db, _) = crate::test::common::setup().await;
@@ -4,7 +4,14 @@ use sqlx::PgPool;
 use tracing::{error, info};
 use uuid::Uuid;
 
-use crate::{error::YuhuhError, food::model::FoodEntry};
+use crate::{
+    error::YuhuhError,
+    food::model::FoodEntry,
+    webhooks::{
+        model::{EventType, WebhookEvent},
+        outbox::enqueue_events,
+    },
+};
 
 // =============================================================================
 // Traits
@@ -43,8 +50,10 @@ impl CreateFoodEntryRepository for CreateFoodEntryRepositoryImpl {
 
         let mut transaction = self.db.begin().await?;
 
+        let mut food_record_id_vecs: Vec<Uuid> = vec![];
         let mut description_vecs: Vec<String> = vec![];
         let mut calories_vecs: Vec<Option<f32>> = vec![];
+        let mut energy_unit_vecs: Vec<String> = vec![];
         let mut carbs_vecs: Vec<Option<f32>> = vec![];
         let mut protein_vecs: Vec<Option<f32>> = vec![];
         let mut fats_vecs: Vec<Option<f32>> = vec![];
@@ -53,22 +62,34 @@ impl CreateFoodEntryRepository for CreateFoodEntryRepositoryImpl {
         let mut created_at_vecs: Vec<NaiveDateTime> = vec![];
         let mut logged_at_vecs: Vec<NaiveDateTime> = vec![];
 
-        entries.iter().for_each(|f| {
+        let mut events: Vec<WebhookEvent> = vec![];
+
+        for mut f in entries {
             info!(food_entry=?f, "added food entry to creation query");
+            let food_record_id = *f.food_record_id.get_or_insert_with(Uuid::now_v7);
+            food_record_id_vecs.push(food_record_id);
             description_vecs.push(f.description.clone());
             calories_vecs.push(f.calories);
+            energy_unit_vecs.push(f.energy_unit.to_string());
             carbs_vecs.push(f.carbs);
             protein_vecs.push(f.protein);
             fats_vecs.push(f.fats);
-            micronutrients_vecs.push(f.micronutrients.clone());
+            micronutrients_vecs.push(
+                f.micronutrients
+                    .as_ref()
+                    .and_then(|m| serde_json::to_value(m).ok()),
+            );
             user_id_vecs.push(f.user_id);
             created_at_vecs.push(f.created_at.naive_utc());
             logged_at_vecs.push(f.logged_at.naive_utc());
-        });
+
+            events.push(WebhookEvent::new(EventType::FoodCreated, f.user_id, &f)?);
+        }
 
         sqlx::query!(
             r#"
             INSERT INTO food_records (
+                food_record_id,
                 user_id, 
                 created_at, 
                 description, 
@@ -77,20 +98,24 @@ impl CreateFoodEntryRepository for CreateFoodEntryRepositoryImpl {
                 protein, 
                 fats, 
                 micronutrients,
-                logged_at
+                logged_at,
+                energy_unit
             )
             SELECT * FROM UNNEST(
-                $1::uuid[], 
-                $2::timestamp[],
-                $3::text[],
-                $4::real[],
+                $1::uuid[],
+                $2::uuid[], 
+                $3::timestamp[],
+                $4::text[],
                 $5::real[],
                 $6::real[],
                 $7::real[],
-                $8::jsonb[],
-                $9::timestamp[]
+                $8::real[],
+                $9::jsonb[],
+                $10::timestamp[],
+                $11::text[]
             )
             "#,
+            &food_record_id_vecs[..],
             &user_id_vecs[..],
             &created_at_vecs[..],
             &description_vecs[..],
@@ -100,6 +125,7 @@ impl CreateFoodEntryRepository for CreateFoodEntryRepositoryImpl {
             &fats_vecs[..] as &[Option<f32>],
             &micronutrients_vecs[..] as &[Option<serde_json::Value>],
             &logged_at_vecs[..],
+            &energy_unit_vecs[..],
         )
         .execute(&mut *transaction)
         .await
@@ -109,6 +135,8 @@ impl CreateFoodEntryRepository for CreateFoodEntryRepositoryImpl {
             YuhuhError::DatabaseError(e)
         })?;
 
+        enqueue_events(&mut transaction, &events).await?;
+
         // Commit the transaction to persist all changes
         transaction.commit().await?;
 
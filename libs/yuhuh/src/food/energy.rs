@@ -0,0 +1,69 @@
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::error::ConversionError;
+
+/// Kilojoules in a single kilocalorie.
+const KJ_PER_KCAL: f32 = 4.184;
+
+/// Unit food energy is recorded or displayed in.
+///
+/// Calories are always stored as kilocalories, with the unit only deciding how
+/// values are converted on the way in and out.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum EnergyUnit {
+    #[default]
+    #[serde(rename = "kcal")]
+    Kilocalories,
+    #[serde(rename = "kJ")]
+    Kilojoules,
+}
+
+impl EnergyUnit {
+    /// Converts an amount in this unit into kilocalories.
+    pub fn to_kcal(&self, amount: f32) -> f32 {
+        match self {
+            EnergyUnit::Kilocalories => amount,
+            EnergyUnit::Kilojoules => amount / KJ_PER_KCAL,
+        }
+    }
+
+    /// Converts an amount in kilocalories into this unit.
+    pub fn from_kcal(&self, kcal: f32) -> f32 {
+        match self {
+            EnergyUnit::Kilocalories => kcal,
+            EnergyUnit::Kilojoules => kcal * KJ_PER_KCAL,
+        }
+    }
+}
+
+impl fmt::Display for EnergyUnit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EnergyUnit::Kilocalories => write!(f, "kcal"),
+            EnergyUnit::Kilojoules => write!(f, "kJ"),
+        }
+    }
+}
+
+impl FromStr for EnergyUnit {
+    type Err = ConversionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "kcal" => Ok(EnergyUnit::Kilocalories),
+            "kJ" => Ok(EnergyUnit::Kilojoules),
+            _ => Err(ConversionError::new(format!("unknown energy unit {}", s))),
+        }
+    }
+}
+
+impl TryFrom<String> for EnergyUnit {
+    type Error = ConversionError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
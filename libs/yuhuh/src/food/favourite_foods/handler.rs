@@ -0,0 +1,390 @@
+//! favourite foods HTTP handlers
+//!
+//! This module provides HTTP endpoints for curating a user's favourite foods.
+
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, instrument};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::{
+    auth::model::Caller,
+    error::YuhuhError,
+    food::{
+        energy::EnergyUnit, favourite_foods::FavouriteFood, micronutrients::Micronutrients,
+        state::FoodState,
+    },
+    user::state::UserState,
+};
+
+// ============================================================================
+// HTTP Request types
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateFavouriteFoodRequest {
+    pub user_id: Uuid,
+    pub description: String,
+    pub calories: Option<f32>,
+    /// Unit `calories` is given in, defaulting to kilocalories.
+    pub energy_unit: Option<EnergyUnit>,
+    pub carbs: Option<f32>,
+    pub protein: Option<f32>,
+    pub fats: Option<f32>,
+    pub micronutrients: Option<Micronutrients>,
+}
+
+/// Request parameters identifying whose favourites to act on.
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct FavouriteFoodsRequest {
+    /// user ID the favourites belong to.
+    pub user_id: Uuid,
+}
+
+// ============================================================================
+// HTTP Responsed types
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateFavouriteFoodResponse {
+    pub food_favourite_id: Uuid,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct FoundFavouriteFood {
+    pub food_favourite_id: Uuid,
+    pub description: String,
+    pub calories: Option<f32>,
+    pub energy_unit: EnergyUnit,
+    pub carbs: Option<f32>,
+    pub protein: Option<f32>,
+    pub fats: Option<f32>,
+    pub micronutrients: Option<Micronutrients>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ReadFavouriteFoodsResponse {
+    pub favourites: Vec<FoundFavouriteFood>,
+}
+
+// ============================================================================
+// Implementations
+// ============================================================================
+
+impl FoundFavouriteFood {
+    fn new(value: FavouriteFood, energy_unit: EnergyUnit) -> Self {
+        FoundFavouriteFood {
+            food_favourite_id: value.food_favourite_id.unwrap_or_default(),
+            description: value.description,
+            calories: value.calories.map(|c| energy_unit.from_kcal(c)),
+            energy_unit,
+            carbs: value.carbs,
+            protein: value.protein,
+            fats: value.fats,
+            micronutrients: value.micronutrients,
+            created_at: value.created_at.unwrap_or_default(),
+        }
+    }
+}
+
+// =============================================================================
+// HTTP Handlers
+// =============================================================================
+
+/// Save a food as a favourite for a user
+#[utoipa::path(
+    post,
+    path = "food/favourites",
+    tag = "food",
+    responses(
+        (status = 201, description = "favourite created successfully", body = CreateFavouriteFoodResponse),
+        (status = 400, description = "favourite already exists")
+))]
+#[instrument]
+pub async fn create_favourite_food(
+    State(food_state): State<Arc<FoodState>>,
+    State(user_state): State<Arc<UserState>>,
+    caller: Caller,
+    Json(request): Json<CreateFavouriteFoodRequest>,
+) -> Result<(StatusCode, Json<CreateFavouriteFoodResponse>), YuhuhError> {
+    debug!("entering create_favourite_food");
+
+    caller.authorise_user(&request.user_id)?;
+
+    user_state.require_user(&request.user_id).await?;
+
+    if let Some(micronutrients) = &request.micronutrients {
+        micronutrients.validate()?;
+    }
+
+    let energy_unit = request.energy_unit.unwrap_or_default();
+
+    let food_favourite_id = food_state
+        .favourite_foods_repo
+        .create_favourite_food(FavouriteFood {
+            food_favourite_id: None,
+            user_id: request.user_id,
+            description: request.description,
+            calories: request.calories.map(|c| energy_unit.to_kcal(c)),
+            carbs: request.carbs,
+            protein: request.protein,
+            fats: request.fats,
+            micronutrients: request.micronutrients,
+            created_at: None,
+        })
+        .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(CreateFavouriteFoodResponse { food_favourite_id }),
+    ))
+}
+
+/// Find a user's favourite foods
+#[utoipa::path(
+    get,
+    path = "food/favourites",
+    tag = "food",
+    params(FavouriteFoodsRequest),
+    responses(
+        (status = 200, description = "Found favourite foods", body = ReadFavouriteFoodsResponse)
+))]
+#[instrument]
+pub async fn read_favourite_foods(
+    State(food_state): State<Arc<FoodState>>,
+    State(user_state): State<Arc<UserState>>,
+    caller: Caller,
+    Query(request): Query<FavouriteFoodsRequest>,
+) -> Result<(StatusCode, Json<ReadFavouriteFoodsResponse>), YuhuhError> {
+    debug!("entering read_favourite_foods");
+
+    caller.authorise_user(&request.user_id)?;
+
+    let user = user_state.require_user(&request.user_id).await?;
+
+    let favourites = food_state
+        .favourite_foods_repo
+        .read_favourite_foods(&request.user_id)
+        .await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(ReadFavouriteFoodsResponse {
+            favourites: favourites
+                .into_iter()
+                .map(|f| FoundFavouriteFood::new(f, user.energy_unit))
+                .collect(),
+        }),
+    ))
+}
+
+/// Remove one of a user's favourite foods
+#[utoipa::path(
+    delete,
+    path = "food/favourites/{food_favourite_id}",
+    tag = "food",
+    params(
+        ("food_favourite_id" = Uuid, Path, description = "favourite to remove"),
+        FavouriteFoodsRequest
+    ),
+    responses(
+        (status = 204, description = "favourite removed"),
+        (status = 404, description = "favourite not found")
+))]
+#[instrument]
+pub async fn delete_favourite_food(
+    State(food_state): State<Arc<FoodState>>,
+    caller: Caller,
+    Path(food_favourite_id): Path<Uuid>,
+    Query(request): Query<FavouriteFoodsRequest>,
+) -> Result<StatusCode, YuhuhError> {
+    debug!("entering delete_favourite_food");
+
+    caller.authorise_user(&request.user_id)?;
+
+    let deleted = food_state
+        .favourite_foods_repo
+        .delete_favourite_food(&request.user_id, &food_favourite_id)
+        .await?;
+
+    if !deleted {
+        return Err(YuhuhError::NotFound("favourite not found".to_string()));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use http_body_util::BodyExt;
+    use pretty_assertions::assert_eq;
+
+    use axum::{
+        body::Body,
+        http::{self, Request, StatusCode},
+    };
+    use tower::ServiceExt;
+    use uuid::uuid;
+
+    use crate::food::{
+        energy::EnergyUnit,
+        favourite_foods::{
+            CreateFavouriteFoodRequest, CreateFavouriteFoodResponse, ReadFavouriteFoodsResponse,
+        },
+    };
+
+    fn burger() -> CreateFavouriteFoodRequest {
+        CreateFavouriteFoodRequest {
+            user_id: uuid!("11111111-1111-1111-1111-111111111111"),
+            description: "burger".to_string(),
+            calories: Some(2510.4),
+            energy_unit: Some(EnergyUnit::Kilojoules),
+            carbs: Some(40.0),
+            protein: Some(30.0),
+            fats: Some(30.0),
+            micronutrients: None,
+        }
+    }
+
+    fn create_request(request: &CreateFavouriteFoodRequest) -> Request<Body> {
+        Request::builder()
+            .method("POST")
+            .uri("/food/favourites")
+            .header(http::header::CONTENT_TYPE, "application/json")
+            .body(Body::from(
+                serde_json::to_string(request).expect("request is valid body"),
+            ))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn favourites_can_be_created_read_and_deleted() {
+        let (app, db, _) = crate::test::common::setup().await;
+
+        sqlx::raw_sql(include_str!("../../migrations/test/recent_foods.sql"))
+            .execute(&db)
+            .await
+            .expect("setup test sql ran successfully");
+
+        let response = app
+            .clone()
+            .oneshot(create_request(&burger()))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let created: CreateFavouriteFoodResponse =
+            serde_json::from_slice(&body).expect("valid CreateFavouriteFoodResponse bytes");
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri("/food/favourites?user_id=11111111-1111-1111-1111-111111111111")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let dto: ReadFavouriteFoodsResponse =
+            serde_json::from_slice(&body).expect("valid ReadFavouriteFoodsResponse bytes");
+
+        assert_eq!(dto.favourites.len(), 1);
+        assert_eq!(
+            dto.favourites[0].food_favourite_id,
+            created.food_favourite_id
+        );
+        // Stored as kilocalories and displayed in the user's default unit
+        assert_eq!(dto.favourites[0].calories, Some(600.0));
+        assert_eq!(dto.favourites[0].energy_unit, EnergyUnit::Kilocalories);
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("DELETE")
+                    .uri(format!(
+                        "/food/favourites/{}?user_id=11111111-1111-1111-1111-111111111111",
+                        created.food_favourite_id
+                    ))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    }
+
+    #[tokio::test]
+    async fn duplicate_favourites_rejected() {
+        let (app, db, _) = crate::test::common::setup().await;
+
+        sqlx::raw_sql(include_str!("../../migrations/test/recent_foods.sql"))
+            .execute(&db)
+            .await
+            .expect("setup test sql ran successfully");
+
+        let response = app
+            .clone()
+            .oneshot(create_request(&burger()))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        let mut duplicate = burger();
+        duplicate.description = "Burger".to_string();
+
+        let response = app.oneshot(create_request(&duplicate)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn deleting_another_users_favourite_not_found() {
+        let (app, db, _) = crate::test::common::setup().await;
+
+        sqlx::raw_sql(include_str!("../../migrations/test/recent_foods.sql"))
+            .execute(&db)
+            .await
+            .expect("setup test sql ran successfully");
+
+        let response = app
+            .clone()
+            .oneshot(create_request(&burger()))
+            .await
+            .unwrap();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let created: CreateFavouriteFoodResponse =
+            serde_json::from_slice(&body).expect("valid CreateFavouriteFoodResponse bytes");
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("DELETE")
+                    .uri(format!(
+                        "/food/favourites/{}?user_id=22222222-2222-2222-2222-222222222222",
+                        created.food_favourite_id
+                    ))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,217 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use tracing::{debug, error, info};
+use uuid::Uuid;
+
+use crate::{error::YuhuhError, food::micronutrients::Micronutrients};
+
+// =============================================================================
+// Public Types and Structs
+// =============================================================================
+
+/// A food the user has saved for quick re-logging.
+#[derive(Debug)]
+pub struct FavouriteFood {
+    // Ignored when new
+    pub food_favourite_id: Option<Uuid>,
+    pub user_id: Uuid,
+    pub description: String,
+    // Always kilocalories
+    pub calories: Option<f32>,
+    pub carbs: Option<f32>,
+    pub protein: Option<f32>,
+    pub fats: Option<f32>,
+    pub micronutrients: Option<Micronutrients>,
+    // Ignored when new
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+// =============================================================================
+// Row Structs
+// =============================================================================
+
+#[derive(Debug, sqlx::FromRow)]
+struct FavouriteFoodRow {
+    food_favourite_id: Uuid,
+    user_id: Uuid,
+    description: String,
+    calories: Option<f32>,
+    carbs: Option<f32>,
+    protein: Option<f32>,
+    fats: Option<f32>,
+    micronutrients: Option<serde_json::Value>,
+    created_at: DateTime<Utc>,
+}
+
+impl From<FavouriteFoodRow> for FavouriteFood {
+    fn from(value: FavouriteFoodRow) -> Self {
+        FavouriteFood {
+            food_favourite_id: Some(value.food_favourite_id),
+            user_id: value.user_id,
+            description: value.description,
+            calories: value.calories,
+            carbs: value.carbs,
+            protein: value.protein,
+            fats: value.fats,
+            micronutrients: value.micronutrients.and_then(Micronutrients::from_stored),
+            created_at: Some(value.created_at),
+        }
+    }
+}
+
+// =============================================================================
+// Traits
+// =============================================================================
+
+#[async_trait]
+pub trait FavouriteFoodsRepository: std::fmt::Debug + Send + Sync + 'static {
+    /// Saves a new favourite, returning its ID.
+    ///
+    /// Returns `YuhuhError::Conflict` if the user already has a favourite with
+    /// the same description.
+    async fn create_favourite_food(&self, favourite: FavouriteFood) -> Result<Uuid, YuhuhError>;
+
+    async fn read_favourite_foods(&self, user_id: &Uuid) -> Result<Vec<FavouriteFood>, YuhuhError>;
+
+    /// Removes a favourite, returning whether it existed for the user.
+    async fn delete_favourite_food(
+        &self,
+        user_id: &Uuid,
+        food_favourite_id: &Uuid,
+    ) -> Result<bool, YuhuhError>;
+}
+
+// =============================================================================
+// Production Implementation
+// =============================================================================
+
+#[derive(Debug)]
+pub struct FavouriteFoodsRepositoryImpl {
+    pub db: PgPool,
+}
+
+impl FavouriteFoodsRepositoryImpl {
+    pub fn new(db: PgPool) -> Self {
+        FavouriteFoodsRepositoryImpl { db }
+    }
+}
+
+#[async_trait]
+impl FavouriteFoodsRepository for FavouriteFoodsRepositoryImpl {
+    async fn create_favourite_food(&self, favourite: FavouriteFood) -> Result<Uuid, YuhuhError> {
+        info!(favourite=?favourite, "creating favourite food");
+
+        let food_favourite_id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO food_favourites (
+                user_id,
+                description,
+                calories,
+                carbs,
+                protein,
+                fats,
+                micronutrients
+            ) VALUES (
+                $1,
+                $2,
+                $3,
+                $4,
+                $5,
+                $6,
+                $7
+            ) RETURNING food_favourite_id
+            "#,
+            favourite.user_id,
+            favourite.description,
+            favourite.calories,
+            favourite.carbs,
+            favourite.protein,
+            favourite.fats,
+            favourite
+                .micronutrients
+                .as_ref()
+                .and_then(|m| serde_json::to_value(m).ok()),
+        )
+        .fetch_one(&self.db)
+        .await
+        .map_err(|e| {
+            if e.as_database_error()
+                .is_some_and(|d| d.is_unique_violation())
+            {
+                return YuhuhError::Conflict(format!(
+                    "favourite {} already exists",
+                    favourite.description
+                ));
+            }
+
+            error!(error = ?e, "database error while creating favourite food");
+
+            YuhuhError::DatabaseError(e)
+        })?;
+
+        Ok(food_favourite_id)
+    }
+
+    async fn read_favourite_foods(&self, user_id: &Uuid) -> Result<Vec<FavouriteFood>, YuhuhError> {
+        debug!(user_id=?user_id, "received read request for favourite foods");
+
+        let records: Vec<FavouriteFoodRow> = sqlx::query_as!(
+            FavouriteFoodRow,
+            r#"
+            SELECT
+                food_favourite_id,
+                user_id,
+                description,
+                calories,
+                carbs,
+                protein,
+                fats,
+                micronutrients,
+                created_at
+            FROM food_favourites
+            WHERE user_id = $1::uuid
+            ORDER BY lower(description);
+            "#,
+            user_id,
+        )
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, "database error while finding favourite foods");
+
+            YuhuhError::DatabaseError(e)
+        })?;
+
+        debug!(favourite_foods=?records, "found favourite foods");
+
+        Ok(records.into_iter().map(FavouriteFood::from).collect())
+    }
+
+    async fn delete_favourite_food(
+        &self,
+        user_id: &Uuid,
+        food_favourite_id: &Uuid,
+    ) -> Result<bool, YuhuhError> {
+        info!(user_id=?user_id, food_favourite_id=?food_favourite_id, "deleting favourite food");
+
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM food_favourites
+            WHERE user_id = $1::uuid
+            AND food_favourite_id = $2::uuid
+            "#,
+            user_id,
+            food_favourite_id
+        )
+        .execute(&self.db)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, "database error while deleting favourite food");
+
+            YuhuhError::DatabaseError(e)
+        })?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,341 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+use utoipa::ToSchema;
+
+use crate::error::MicronutrientError;
+
+// =============================================================================
+// Units
+// =============================================================================
+
+/// Unit a micronutrient amount is recorded in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum NutrientUnit {
+    #[serde(rename = "g")]
+    Grams,
+    #[serde(rename = "mg")]
+    Milligrams,
+    #[serde(rename = "mcg", alias = "µg", alias = "ug")]
+    Micrograms,
+    /// International units, only meaningful for some vitamins.
+    #[serde(rename = "IU")]
+    InternationalUnits,
+}
+
+impl NutrientUnit {
+    /// Micrograms per one of this unit, or `None` when the unit is not a mass.
+    fn micrograms(&self) -> Option<f32> {
+        match self {
+            NutrientUnit::Grams => Some(1_000_000.0),
+            NutrientUnit::Milligrams => Some(1_000.0),
+            NutrientUnit::Micrograms => Some(1.0),
+            NutrientUnit::InternationalUnits => None,
+        }
+    }
+}
+
+// =============================================================================
+// Nutrient
+// =============================================================================
+
+/// A single micronutrient amount along with the unit it was recorded in.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Nutrient {
+    pub amount: f32,
+    pub unit: NutrientUnit,
+}
+
+/// Describes how a known micronutrient is stored and aggregated.
+struct NutrientSpec {
+    name: &'static str,
+    /// Unit totals are reported in.
+    unit: NutrientUnit,
+    /// Amount of `unit` per IU, for the vitamins where IU are in common use.
+    per_iu: Option<f32>,
+}
+
+const SODIUM: NutrientSpec = mass("sodium", NutrientUnit::Milligrams);
+const POTASSIUM: NutrientSpec = mass("potassium", NutrientUnit::Milligrams);
+const FIBRE: NutrientSpec = mass("fibre", NutrientUnit::Grams);
+const SUGAR: NutrientSpec = mass("sugar", NutrientUnit::Grams);
+const SATURATED_FAT: NutrientSpec = mass("saturated_fat", NutrientUnit::Grams);
+const CHOLESTEROL: NutrientSpec = mass("cholesterol", NutrientUnit::Milligrams);
+const CALCIUM: NutrientSpec = mass("calcium", NutrientUnit::Milligrams);
+const IRON: NutrientSpec = mass("iron", NutrientUnit::Milligrams);
+const VITAMIN_A: NutrientSpec = NutrientSpec {
+    name: "vitamin_a",
+    unit: NutrientUnit::Micrograms,
+    // Retinol activity equivalents
+    per_iu: Some(0.3),
+};
+const VITAMIN_C: NutrientSpec = mass("vitamin_c", NutrientUnit::Milligrams);
+const VITAMIN_D: NutrientSpec = NutrientSpec {
+    name: "vitamin_d",
+    unit: NutrientUnit::Micrograms,
+    per_iu: Some(0.025),
+};
+const VITAMIN_B12: NutrientSpec = mass("vitamin_b12", NutrientUnit::Micrograms);
+
+const fn mass(name: &'static str, unit: NutrientUnit) -> NutrientSpec {
+    NutrientSpec {
+        name,
+        unit,
+        per_iu: None,
+    }
+}
+
+impl Nutrient {
+    /// Converts this amount into the unit the spec reports in.
+    fn to_canonical(self, spec: &NutrientSpec) -> Result<f32, MicronutrientError> {
+        if !self.amount.is_finite() || self.amount < 0.0 {
+            return Err(MicronutrientError::new(format!(
+                "{} must be a non-negative amount, but got {} instead",
+                spec.name, self.amount
+            )));
+        }
+
+        let canonical_micrograms = spec.unit.micrograms().unwrap_or(1.0);
+
+        match (self.unit.micrograms(), spec.per_iu) {
+            (Some(micrograms), _) => Ok(self.amount * micrograms / canonical_micrograms),
+            (None, Some(per_iu)) => Ok(self.amount * per_iu),
+            (None, None) => Err(MicronutrientError::new(format!(
+                "{} cannot be recorded in IU",
+                spec.name
+            ))),
+        }
+    }
+}
+
+// =============================================================================
+// Micronutrients
+// =============================================================================
+
+/// Micronutrients recorded against a food entry.
+///
+/// Well known nutrients are typed and validated, while anything else is kept
+/// verbatim under its own key so clients aren't blocked on us modelling it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Micronutrients {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sodium: Option<Nutrient>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub potassium: Option<Nutrient>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fibre: Option<Nutrient>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sugar: Option<Nutrient>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub saturated_fat: Option<Nutrient>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cholesterol: Option<Nutrient>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub calcium: Option<Nutrient>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iron: Option<Nutrient>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vitamin_a: Option<Nutrient>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vitamin_c: Option<Nutrient>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vitamin_d: Option<Nutrient>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vitamin_b12: Option<Nutrient>,
+    /// Unknown keys, stored as is and never aggregated.
+    #[serde(flatten)]
+    #[schema(value_type = Object)]
+    pub other: BTreeMap<String, serde_json::Value>,
+}
+
+impl Micronutrients {
+    fn known(&self) -> [(&'static NutrientSpec, Option<Nutrient>); 12] {
+        [
+            (&SODIUM, self.sodium),
+            (&POTASSIUM, self.potassium),
+            (&FIBRE, self.fibre),
+            (&SUGAR, self.sugar),
+            (&SATURATED_FAT, self.saturated_fat),
+            (&CHOLESTEROL, self.cholesterol),
+            (&CALCIUM, self.calcium),
+            (&IRON, self.iron),
+            (&VITAMIN_A, self.vitamin_a),
+            (&VITAMIN_C, self.vitamin_c),
+            (&VITAMIN_D, self.vitamin_d),
+            (&VITAMIN_B12, self.vitamin_b12),
+        ]
+    }
+
+    fn known_mut(&mut self) -> [(&'static NutrientSpec, &mut Option<Nutrient>); 12] {
+        [
+            (&SODIUM, &mut self.sodium),
+            (&POTASSIUM, &mut self.potassium),
+            (&FIBRE, &mut self.fibre),
+            (&SUGAR, &mut self.sugar),
+            (&SATURATED_FAT, &mut self.saturated_fat),
+            (&CHOLESTEROL, &mut self.cholesterol),
+            (&CALCIUM, &mut self.calcium),
+            (&IRON, &mut self.iron),
+            (&VITAMIN_A, &mut self.vitamin_a),
+            (&VITAMIN_C, &mut self.vitamin_c),
+            (&VITAMIN_D, &mut self.vitamin_d),
+            (&VITAMIN_B12, &mut self.vitamin_b12),
+        ]
+    }
+
+    /// Ensures every known nutrient has a sane amount and a unit it can be
+    /// converted from.
+    pub fn validate(&self) -> Result<(), MicronutrientError> {
+        self.known()
+            .into_iter()
+            .filter_map(|(spec, nutrient)| nutrient.map(|n| n.to_canonical(spec)))
+            .try_for_each(|r| r.map(|_| ()))
+    }
+
+    /// Adds the known nutrients of `other` onto `self`, in canonical units.
+    ///
+    /// Nutrients that fail conversion are skipped, as stored entries have
+    /// already been validated on the way in.
+    pub fn accumulate(&mut self, other: &Micronutrients) {
+        for ((spec, total), (_, nutrient)) in self.known_mut().into_iter().zip(other.known()) {
+            let Some(amount) = nutrient.and_then(|n| n.to_canonical(spec).ok()) else {
+                continue;
+            };
+
+            let total = total.get_or_insert(Nutrient {
+                amount: 0.0,
+                unit: spec.unit,
+            });
+            total.amount += amount;
+        }
+    }
+
+    /// Parses micronutrients as stored in the database.
+    ///
+    /// Older rows were written before the schema was typed, so anything that no
+    /// longer parses is surfaced under `other` rather than failing the read.
+    pub fn from_stored(value: serde_json::Value) -> Option<Micronutrients> {
+        match value {
+            serde_json::Value::Null => None,
+            serde_json::Value::Object(map) => Some(
+                serde_json::from_value(serde_json::Value::Object(map.clone())).unwrap_or_else(
+                    |e| {
+                        warn!(error=?e, "stored micronutrients do not match schema");
+
+                        Micronutrients {
+                            other: map.into_iter().collect(),
+                            ..Default::default()
+                        }
+                    },
+                ),
+            ),
+            other => {
+                warn!(micronutrients=?other, "stored micronutrients are not an object");
+
+                Some(Micronutrients {
+                    other: BTreeMap::from([("value".to_string(), other)]),
+                    ..Default::default()
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn unknown_keys_are_kept() {
+        let parsed: Micronutrients = serde_json::from_value(json!({
+            "sodium": { "amount": 120.0, "unit": "mg" },
+            "omega_3": "lots"
+        }))
+        .expect("valid micronutrients");
+
+        assert_eq!(
+            parsed.sodium,
+            Some(Nutrient {
+                amount: 120.0,
+                unit: NutrientUnit::Milligrams
+            })
+        );
+        assert_eq!(parsed.other.get("omega_3"), Some(&json!("lots")));
+    }
+
+    #[test]
+    fn rejects_invalid_amounts_and_units() {
+        let negative = Micronutrients {
+            sugar: Some(Nutrient {
+                amount: -1.0,
+                unit: NutrientUnit::Grams,
+            }),
+            ..Default::default()
+        };
+        let iu_sodium = Micronutrients {
+            sodium: Some(Nutrient {
+                amount: 10.0,
+                unit: NutrientUnit::InternationalUnits,
+            }),
+            ..Default::default()
+        };
+
+        assert!(negative.validate().is_err());
+        assert!(iu_sodium.validate().is_err());
+    }
+
+    #[test]
+    fn accumulates_in_canonical_units() {
+        let mut totals = Micronutrients::default();
+
+        totals.accumulate(&Micronutrients {
+            sodium: Some(Nutrient {
+                amount: 1.0,
+                unit: NutrientUnit::Grams,
+            }),
+            vitamin_d: Some(Nutrient {
+                amount: 400.0,
+                unit: NutrientUnit::InternationalUnits,
+            }),
+            ..Default::default()
+        });
+        totals.accumulate(&Micronutrients {
+            sodium: Some(Nutrient {
+                amount: 250.0,
+                unit: NutrientUnit::Milligrams,
+            }),
+            ..Default::default()
+        });
+
+        assert_eq!(
+            totals.sodium,
+            Some(Nutrient {
+                amount: 1250.0,
+                unit: NutrientUnit::Milligrams
+            })
+        );
+        assert_eq!(
+            totals.vitamin_d,
+            Some(Nutrient {
+                amount: 10.0,
+                unit: NutrientUnit::Micrograms
+            })
+        );
+    }
+
+    #[test]
+    fn legacy_values_fall_back_to_other() {
+        assert_eq!(Micronutrients::from_stored(serde_json::Value::Null), None);
+
+        let legacy = Micronutrients::from_stored(json!("{}")).expect("kept");
+        assert_eq!(legacy.other.get("value"), Some(&json!("{}")));
+
+        let mismatched = Micronutrients::from_stored(json!({ "sodium": 5 })).expect("kept");
+        assert_eq!(mismatched.sodium, None);
+        assert_eq!(mismatched.other.get("sodium"), Some(&json!(5)));
+    }
+}
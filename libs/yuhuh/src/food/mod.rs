@@ -1,5 +1,10 @@
 pub mod create_food_entries;
+pub mod energy;
+pub mod favourite_foods;
+pub mod micronutrients;
 pub mod model;
+pub mod parse_food_description;
 pub mod read_food_entries;
+pub mod recent_foods;
 pub mod router;
 pub mod state;
@@ -2,9 +2,34 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+use crate::{
+    error::ConversionError,
+    food::{energy::EnergyUnit, micronutrients::Micronutrients},
+};
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct FoodEntry {
     // Ignored when new
+    pub food_record_id: Option<Uuid>,
+    pub description: String,
+    // Always kilocalories
+    pub calories: Option<f32>,
+    // Unit the calories were originally logged in
+    pub energy_unit: EnergyUnit,
+    pub carbs: Option<f32>,
+    pub protein: Option<f32>,
+    pub fats: Option<f32>,
+    pub micronutrients: Option<Micronutrients>,
+    pub user_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub logged_at: DateTime<Utc>,
+}
+
+// =============================================================================
+// Row Structs
+// =============================================================================
+#[derive(Debug, sqlx::FromRow)]
+pub struct FoodEntryRow {
     pub food_record_id: Option<Uuid>,
     pub description: String,
     pub calories: Option<f32>,
@@ -15,4 +40,25 @@ pub struct FoodEntry {
     pub user_id: Uuid,
     pub created_at: DateTime<Utc>,
     pub logged_at: DateTime<Utc>,
+    pub energy_unit: String,
+}
+
+impl TryFrom<FoodEntryRow> for FoodEntry {
+    type Error = ConversionError;
+
+    fn try_from(value: FoodEntryRow) -> Result<Self, Self::Error> {
+        Ok(FoodEntry {
+            food_record_id: value.food_record_id,
+            description: value.description,
+            calories: value.calories,
+            energy_unit: value.energy_unit.parse()?,
+            carbs: value.carbs,
+            protein: value.protein,
+            fats: value.fats,
+            micronutrients: value.micronutrients.and_then(Micronutrients::from_stored),
+            user_id: value.user_id,
+            created_at: value.created_at,
+            logged_at: value.logged_at,
+        })
+    }
 }
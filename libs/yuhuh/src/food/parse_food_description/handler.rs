@@ -0,0 +1,214 @@
+//! food description parsing HTTP handler
+//!
+//! This module provides an HTTP endpoint for estimating food entries from a
+//! free-text description, without logging anything.
+
+use axum::{Json, http::StatusCode};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, instrument};
+use utoipa::ToSchema;
+
+use crate::{
+    error::YuhuhError,
+    food::{
+        create_food_entries::NewFoodEntry,
+        energy::EnergyUnit,
+        parse_food_description::parser::{ParsedFood, parse_description},
+    },
+};
+
+// ============================================================================
+// HTTP Request types
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ParseFoodDescriptionRequest {
+    /// Free-text description, e.g. "2 eggs, 1 slice toast and a flat white".
+    pub description: String,
+    /// Unit suggested calories are given in, defaulting to kilocalories.
+    pub energy_unit: Option<EnergyUnit>,
+}
+
+// ============================================================================
+// HTTP Responsed types
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct FoodSuggestion {
+    /// Name of the known food the item was matched against.
+    pub matched_food: String,
+    pub quantity: f32,
+    /// Unit as typed, if any.
+    pub unit: Option<String>,
+    /// Number of standard servings of the matched food.
+    pub servings: f32,
+    /// How sure the parser is of this suggestion, between 0 and 1.
+    pub confidence: f32,
+    /// Suggested entry, ready to be reviewed and sent to `food/create`.
+    pub food_entry: NewFoodEntry,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ParseFoodDescriptionResponse {
+    pub suggestions: Vec<FoodSuggestion>,
+    /// Parts of the description that could not be matched to a known food.
+    pub unmatched: Vec<String>,
+}
+
+// ============================================================================
+// Implementations
+// ============================================================================
+
+fn round(value: f32) -> f32 {
+    (value * 10.0).round() / 10.0
+}
+
+impl FoodSuggestion {
+    fn new(parsed: ParsedFood, energy_unit: EnergyUnit) -> Self {
+        let food = parsed.food;
+
+        FoodSuggestion {
+            matched_food: food.name.to_string(),
+            quantity: parsed.quantity,
+            unit: parsed.unit,
+            servings: round(parsed.servings),
+            confidence: parsed.confidence,
+            food_entry: NewFoodEntry {
+                description: parsed.text,
+                calories: Some(round(
+                    energy_unit.from_kcal(food.calories * parsed.servings),
+                )),
+                energy_unit: Some(energy_unit),
+                carbs: Some(round(food.carbs * parsed.servings)),
+                protein: Some(round(food.protein * parsed.servings)),
+                fats: Some(round(food.fats * parsed.servings)),
+                micronutrients: None,
+                logged_at: None,
+            },
+        }
+    }
+}
+
+// =============================================================================
+// HTTP Handlers
+// =============================================================================
+
+/// Estimate food entries from a free-text description
+///
+/// Suggestions are never logged, clients are expected to confirm them with
+/// the user and then create the entries themselves.
+#[utoipa::path(
+    post,
+    path = "food/parse",
+    tag = "food",
+    responses(
+        (status = 200, description = "Parsed food description", body = ParseFoodDescriptionResponse),
+        (status = 400, description = "Empty description")
+))]
+#[instrument]
+pub async fn parse_food_description(
+    Json(request): Json<ParseFoodDescriptionRequest>,
+) -> Result<(StatusCode, Json<ParseFoodDescriptionResponse>), YuhuhError> {
+    debug!("entering parse_food_description");
+
+    if request.description.trim().is_empty() {
+        return Err(YuhuhError::BadRequest(
+            "description must not be empty".to_string(),
+        ));
+    }
+
+    let energy_unit = request.energy_unit.unwrap_or_default();
+    let parsed = parse_description(&request.description);
+
+    debug!(parsed=?parsed, "parsed food description");
+
+    Ok((
+        StatusCode::OK,
+        Json(ParseFoodDescriptionResponse {
+            suggestions: parsed
+                .foods
+                .into_iter()
+                .map(|f| FoodSuggestion::new(f, energy_unit))
+                .collect(),
+            unmatched: parsed.unmatched,
+        }),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use http_body_util::BodyExt;
+    use pretty_assertions::assert_eq;
+
+    use axum::{
+        body::Body,
+        http::{self, Request, StatusCode},
+    };
+    use tower::ServiceExt;
+
+    use crate::food::{
+        energy::EnergyUnit,
+        parse_food_description::{ParseFoodDescriptionRequest, ParseFoodDescriptionResponse},
+    };
+
+    fn parse_request(request: &ParseFoodDescriptionRequest) -> Request<Body> {
+        Request::builder()
+            .method("POST")
+            .uri("/food/parse")
+            .header(http::header::CONTENT_TYPE, "application/json")
+            .body(Body::from(
+                serde_json::to_string(request).expect("request is valid body"),
+            ))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn description_parsed_into_suggestions() {
+        let (app, db, _) = crate::test::common::setup().await;
+
+        let response = app
+            .oneshot(parse_request(&ParseFoodDescriptionRequest {
+                description: "2 eggs, 1 slice toast and a mystery pie".to_string(),
+                energy_unit: Some(EnergyUnit::Kilojoules),
+            }))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let dto: ParseFoodDescriptionResponse =
+            serde_json::from_slice(&body).expect("valid ParseFoodDescriptionResponse bytes");
+
+        assert_eq!(dto.suggestions.len(), 2);
+        assert_eq!(dto.suggestions[0].matched_food, "egg");
+        assert_eq!(dto.suggestions[0].food_entry.description, "2 eggs");
+        assert_eq!(dto.suggestions[0].food_entry.calories, Some(602.5));
+        assert_eq!(dto.suggestions[0].food_entry.protein, Some(12.6));
+        assert_eq!(dto.suggestions[1].matched_food, "toast");
+        assert_eq!(dto.unmatched, vec!["a mystery pie".to_string()]);
+
+        // Nothing is ever logged from a parse
+        let logged: i64 = sqlx::query_scalar("SELECT count(*) FROM food_records")
+            .fetch_one(&db)
+            .await
+            .expect("counted food records");
+        assert_eq!(logged, 0);
+    }
+
+    #[tokio::test]
+    async fn empty_description_rejected() {
+        let (app, _, _) = crate::test::common::setup().await;
+
+        let response = app
+            .oneshot(parse_request(&ParseFoodDescriptionRequest {
+                description: "  ".to_string(),
+                energy_unit: None,
+            }))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+}
@@ -0,0 +1,5 @@
+mod handler;
+mod parser;
+mod table;
+
+pub use handler::*;
@@ -0,0 +1,371 @@
+//! Deterministic parser for free-text food descriptions.
+//!
+//! Descriptions are split into line items, each of which is broken into a
+//! quantity, an optional unit and a food name that is matched against the
+//! bundled [`FOODS`] table.
+
+use super::table::{FOODS, TableFood};
+
+/// Matches scoring below this are reported as unmatched rather than guessed.
+const MIN_MATCH_SCORE: f32 = 0.25;
+
+/// Phrases separating line items within a description.
+const SEPARATORS: &[&str] = &[";", "\n", "&", "+", " and ", " with ", " plus "];
+
+/// A line item parsed from a description and matched against the table.
+#[derive(Debug)]
+pub(super) struct ParsedFood {
+    /// Text the item was parsed from.
+    pub text: String,
+    pub food: &'static TableFood,
+    /// Quantity as typed, defaulting to one.
+    pub quantity: f32,
+    /// Unit as typed, if any.
+    pub unit: Option<String>,
+    /// Number of table servings the quantity and unit amount to.
+    pub servings: f32,
+    /// How sure we are of the match, between 0 and 1.
+    pub confidence: f32,
+}
+
+#[derive(Debug, Default)]
+pub(super) struct ParsedDescription {
+    pub foods: Vec<ParsedFood>,
+    /// Items that could not be matched against any known food.
+    pub unmatched: Vec<String>,
+}
+
+/// A unit a quantity may be given in.
+#[derive(Debug, Clone, PartialEq)]
+enum Unit {
+    /// Grams per one of the unit.
+    Mass(f32),
+    /// Millilitres per one of the unit.
+    Volume(f32),
+    /// A countable portion, optionally with a typical volume for when it
+    /// doesn't match the food's own portion.
+    Count(&'static str, Option<f32>),
+}
+
+fn parse_unit(word: &str) -> Option<Unit> {
+    let unit = match singular(word).as_str() {
+        "g" | "gr" | "gram" | "gramme" => Unit::Mass(1.0),
+        "kg" | "kilo" | "kilogram" => Unit::Mass(1000.0),
+        "oz" | "ounce" => Unit::Mass(28.35),
+        "lb" | "pound" => Unit::Mass(453.6),
+        "ml" | "millilitre" | "milliliter" => Unit::Volume(1.0),
+        "l" | "litre" | "liter" => Unit::Volume(1000.0),
+        "tbsp" | "tablespoon" => Unit::Volume(15.0),
+        "tsp" | "teaspoon" => Unit::Volume(5.0),
+        "pint" => Unit::Volume(568.0),
+        "cup" => Unit::Count("cup", Some(240.0)),
+        "mug" => Unit::Count("mug", Some(300.0)),
+        "glass" => Unit::Count("glass", Some(250.0)),
+        "can" => Unit::Count("can", Some(375.0)),
+        "bottle" => Unit::Count("bottle", Some(600.0)),
+        "slice" => Unit::Count("slice", None),
+        "piece" => Unit::Count("piece", None),
+        "serving" => Unit::Count("serving", None),
+        "portion" => Unit::Count("portion", None),
+        "bowl" => Unit::Count("bowl", None),
+        "plate" => Unit::Count("plate", None),
+        "bar" => Unit::Count("bar", None),
+        "scoop" => Unit::Count("scoop", None),
+        "handful" => Unit::Count("handful", None),
+        "rasher" => Unit::Count("rasher", None),
+        "fillet" => Unit::Count("fillet", None),
+        "tub" => Unit::Count("tub", None),
+        _ => return None,
+    };
+
+    Some(unit)
+}
+
+fn parse_number_word(word: &str) -> Option<f32> {
+    let number = match word {
+        "a" | "an" | "one" => 1.0,
+        "half" => 0.5,
+        "two" | "couple" => 2.0,
+        "three" => 3.0,
+        "four" => 4.0,
+        "five" => 5.0,
+        "six" => 6.0,
+        "seven" => 7.0,
+        "eight" => 8.0,
+        "nine" => 9.0,
+        "ten" => 10.0,
+        "dozen" => 12.0,
+        _ => return None,
+    };
+
+    Some(number)
+}
+
+/// Parses a number such as `2`, `1.5`, `1/2` or `½`.
+fn parse_number(number: &str) -> Option<f32> {
+    match number {
+        "½" => return Some(0.5),
+        "¼" => return Some(0.25),
+        "¾" => return Some(0.75),
+        _ => {}
+    }
+
+    match number.split_once('/') {
+        Some((numerator, denominator)) => {
+            let denominator: f32 = denominator.parse().ok()?;
+            (denominator != 0.0).then_some(numerator.parse::<f32>().ok()? / denominator)
+        }
+        None => number.parse().ok(),
+    }
+    .filter(|n| n.is_finite() && *n > 0.0)
+}
+
+/// Naive English singular form, good enough for food names and units.
+fn singular(word: &str) -> String {
+    if let Some(stem) = word.strip_suffix("oes") {
+        format!("{}o", stem)
+    } else if ["ches", "shes", "sses"].iter().any(|s| word.ends_with(s)) {
+        word[..word.len() - 2].to_string()
+    } else if word.ends_with('s') && !word.ends_with("ss") && word.len() > 2 {
+        word[..word.len() - 1].to_string()
+    } else {
+        word.to_string()
+    }
+}
+
+fn singular_tokens(phrase: &str) -> Vec<String> {
+    phrase.split_whitespace().map(singular).collect()
+}
+
+/// Scores how well a food name matches a typed phrase, between 0 and 1.
+fn match_score(phrase: &[String], name: &str) -> f32 {
+    let name = singular_tokens(name);
+
+    if phrase == name.as_slice() {
+        return 1.0;
+    }
+
+    let common = name.iter().filter(|t| phrase.contains(t)).count();
+
+    if common == name.len() {
+        // Every word of the name was typed, alongside some extra words such
+        // as "large" or "homemade"
+        return 0.6 + 0.3 * name.len() as f32 / phrase.len() as f32;
+    }
+
+    let union = phrase.len() + name.len() - common;
+    0.6 * common as f32 / union as f32
+}
+
+fn match_food(phrase: &str) -> Option<(&'static TableFood, f32)> {
+    let phrase = singular_tokens(phrase);
+
+    if phrase.is_empty() {
+        return None;
+    }
+
+    FOODS
+        .iter()
+        .map(|food| {
+            let score = std::iter::once(food.name)
+                .chain(food.aliases.iter().copied())
+                .map(|name| match_score(&phrase, name))
+                .fold(0.0, f32::max);
+
+            (food, score)
+        })
+        .fold(
+            None,
+            |best: Option<(&TableFood, f32)>, (food, score)| match best {
+                Some((_, best_score)) if best_score >= score => best,
+                _ => Some((food, score)),
+            },
+        )
+        .filter(|(_, score)| *score >= MIN_MATCH_SCORE)
+}
+
+/// Works out how many table servings an amount is, along with how much the
+/// conversion should be trusted.
+fn servings(food: &TableFood, quantity: f32, unit: Option<&Unit>) -> (f32, f32) {
+    match unit {
+        None => (quantity, 1.0),
+        Some(Unit::Mass(grams)) => (quantity * grams / food.grams, 1.0),
+        // Drinks are tabled in millilitres, anything else assumes water density
+        Some(Unit::Volume(millilitres)) => (quantity * millilitres / food.grams, 0.9),
+        Some(Unit::Count(portion, _))
+            if *portion == food.portion || ["serving", "portion"].contains(portion) =>
+        {
+            (quantity, 1.0)
+        }
+        Some(Unit::Count(_, Some(millilitres))) => (quantity * millilitres / food.grams, 0.8),
+        Some(Unit::Count(_, None)) => (quantity, 0.6),
+    }
+}
+
+/// Parses a single line item such as "2 slices of toast" or "200g rice".
+fn parse_item(text: &str) -> Option<ParsedFood> {
+    let tokens: Vec<&str> = text
+        .split_whitespace()
+        .map(|t| t.trim_matches(|c: char| !c.is_alphanumeric() && c != '/'))
+        .filter(|t| !t.is_empty())
+        .collect();
+
+    let mut rest = tokens.as_slice();
+    let mut quantity = None;
+    let mut unit = None;
+
+    // Quantity, either as a number with an optional unit attached such as
+    // "200g" or "2x", or as a word such as "a" or "half"
+    if let Some((first, tail)) = rest.split_first() {
+        let split = first
+            .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '/'))
+            .filter(|i| *i > 0)
+            .unwrap_or(first.len());
+        let (number, suffix) = first.split_at(split);
+
+        if let Some(number) = parse_number(number) {
+            quantity = Some(number);
+            rest = tail;
+
+            if !suffix.is_empty() && suffix != "x" {
+                unit = Some((suffix.to_string(), parse_unit(suffix)?));
+            }
+        } else if let Some(number) = parse_number_word(first) {
+            quantity = Some(number);
+            rest = tail;
+        } else if *first == "some" {
+            rest = tail;
+        }
+    }
+
+    // Fillers such as "x" in "2 x eggs" or "a" in "half a sandwich"
+    while let Some((first, tail)) = rest.split_first() {
+        if ["x", "a", "an", "of"].contains(first) {
+            rest = tail;
+        } else {
+            break;
+        }
+    }
+
+    // A unit is only taken when there is something left to be the food
+    if unit.is_none()
+        && let Some((first, tail)) = rest.split_first()
+        && let Some(parsed) = parse_unit(first)
+        && tail.iter().any(|t| *t != "of")
+    {
+        unit = Some((first.to_string(), parsed));
+        rest = tail;
+    }
+
+    if rest.first() == Some(&"of") {
+        rest = &rest[1..];
+    }
+
+    let (food, score) = match_food(&rest.join(" "))?;
+    let (servings, unit_confidence) =
+        servings(food, quantity.unwrap_or(1.0), unit.as_ref().map(|(_, u)| u));
+    let quantity_confidence = if quantity.is_some() { 1.0 } else { 0.9 };
+
+    Some(ParsedFood {
+        text: text.to_string(),
+        food,
+        quantity: quantity.unwrap_or(1.0),
+        unit: unit.map(|(typed, _)| typed),
+        servings,
+        confidence: (score * unit_confidence * quantity_confidence * 100.0).round() / 100.0,
+    })
+}
+
+/// Parses a free-text description into line items matched against the
+/// bundled food table.
+pub(super) fn parse_description(description: &str) -> ParsedDescription {
+    let mut normalised = format!(" {} ", description.to_lowercase());
+    for separator in SEPARATORS {
+        normalised = normalised.replace(separator, ",");
+    }
+
+    normalised
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .fold(ParsedDescription::default(), |mut parsed, item| {
+            match parse_item(item) {
+                Some(food) => parsed.foods.push(food),
+                None => parsed.unmatched.push(item.to_string()),
+            }
+            parsed
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn summary(parsed: &ParsedDescription) -> Vec<(&str, f32, f32)> {
+        parsed
+            .foods
+            .iter()
+            .map(|f| (f.food.name, f.servings, f.confidence))
+            .collect()
+    }
+
+    #[test]
+    fn parses_line_items() {
+        let parsed = parse_description("2 eggs, 1 slice toast and a flat white");
+
+        assert_eq!(
+            summary(&parsed),
+            vec![
+                ("egg", 2.0, 1.0),
+                ("toast", 1.0, 1.0),
+                ("flat white", 1.0, 1.0)
+            ]
+        );
+        assert_eq!(parsed.foods[1].unit.as_deref(), Some("slice"));
+        assert!(parsed.unmatched.is_empty());
+    }
+
+    #[test]
+    fn converts_mass_and_volume() {
+        let parsed = parse_description("316g rice; 500 ml milk, 2 cups of pasta");
+
+        assert_eq!(
+            summary(&parsed),
+            vec![("rice", 2.0, 1.0), ("milk", 2.0, 0.9), ("pasta", 2.0, 1.0)]
+        );
+    }
+
+    #[test]
+    fn understands_fractions_and_words() {
+        let parsed = parse_description("half an avocado + 1/2 cup rice + three biscuits");
+
+        assert_eq!(
+            summary(&parsed),
+            vec![
+                ("avocado", 0.5, 1.0),
+                ("rice", 0.5, 1.0),
+                ("biscuit", 3.0, 1.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn loose_matches_are_less_confident() {
+        let parsed = parse_description("a large banana, toast");
+
+        assert_eq!(parsed.foods[0].food.name, "banana");
+        assert_eq!(parsed.foods[0].confidence, 0.75);
+        // No quantity given, so one is assumed
+        assert_eq!(parsed.foods[1].confidence, 0.9);
+    }
+
+    #[test]
+    fn unknown_foods_are_unmatched() {
+        let parsed = parse_description("a bowl of xyzzy and 3 bananas");
+
+        assert_eq!(summary(&parsed), vec![("banana", 3.0, 1.0)]);
+        assert_eq!(parsed.unmatched, vec!["a bowl of xyzzy".to_string()]);
+    }
+}
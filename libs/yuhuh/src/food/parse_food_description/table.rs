@@ -0,0 +1,96 @@
+//! Bundled nutrition table used to estimate macros offline.
+//!
+//! Values are approximate, per single typical serving, and only intended to
+//! give users a sensible starting point they can correct before logging.
+
+/// A common food and its nutrition for one serving.
+#[derive(Debug, PartialEq)]
+pub(super) struct TableFood {
+    pub name: &'static str,
+    /// Other names the food is commonly typed as, in singular form.
+    pub aliases: &'static [&'static str],
+    /// What a single serving is counted in, e.g. "slice" or "cup".
+    pub portion: &'static str,
+    /// Grams, or millilitres for drinks, in one serving.
+    pub grams: f32,
+    /// Kilocalories in one serving.
+    pub calories: f32,
+    pub carbs: f32,
+    pub protein: f32,
+    pub fats: f32,
+}
+
+const fn food(
+    name: &'static str,
+    aliases: &'static [&'static str],
+    portion: &'static str,
+    grams: f32,
+    [calories, carbs, protein, fats]: [f32; 4],
+) -> TableFood {
+    TableFood {
+        name,
+        aliases,
+        portion,
+        grams,
+        calories,
+        carbs,
+        protein,
+        fats,
+    }
+}
+
+#[rustfmt::skip]
+pub(super) const FOODS: &[TableFood] = &[
+    // Breakfast
+    food("egg", &["boiled egg", "fried egg", "poached egg", "scrambled egg"], "egg", 50.0, [72.0, 0.4, 6.3, 4.8]),
+    food("toast", &["bread", "white bread", "wholemeal toast", "slice of bread"], "slice", 30.0, [80.0, 15.0, 3.0, 1.0]),
+    food("bacon", &["bacon rasher"], "rasher", 10.0, [43.0, 0.1, 3.0, 3.3]),
+    food("sausage", &["snag"], "sausage", 50.0, [150.0, 2.0, 7.0, 13.0]),
+    food("porridge", &["oats", "oatmeal"], "bowl", 250.0, [150.0, 27.0, 5.0, 3.0]),
+    food("cereal", &["muesli", "granola", "cornflakes"], "bowl", 45.0, [170.0, 35.0, 4.0, 2.0]),
+    food("yoghurt", &["yogurt", "greek yoghurt", "greek yogurt"], "tub", 150.0, [95.0, 7.0, 8.0, 5.0]),
+    food("croissant", &[], "croissant", 57.0, [230.0, 26.0, 4.7, 12.0]),
+    food("muffin", &[], "muffin", 113.0, [420.0, 55.0, 6.0, 20.0]),
+    food("avocado", &["avo"], "avocado", 200.0, [320.0, 17.0, 4.0, 29.0]),
+    food("butter", &[], "tbsp", 14.0, [100.0, 0.0, 0.1, 11.0]),
+    food("peanut butter", &[], "tbsp", 16.0, [95.0, 3.5, 3.5, 8.0]),
+    // Fruit and vegetables
+    food("banana", &[], "banana", 118.0, [105.0, 27.0, 1.3, 0.4]),
+    food("apple", &[], "apple", 182.0, [95.0, 25.0, 0.5, 0.3]),
+    food("orange", &["mandarin"], "orange", 131.0, [62.0, 15.0, 1.2, 0.2]),
+    food("potato", &["baked potato"], "potato", 173.0, [160.0, 37.0, 4.3, 0.2]),
+    food("salad", &["green salad", "side salad"], "bowl", 150.0, [30.0, 5.0, 2.0, 0.3]),
+    // Mains
+    food("rice", &["white rice", "brown rice"], "cup", 158.0, [205.0, 45.0, 4.3, 0.4]),
+    food("pasta", &["spaghetti", "penne"], "cup", 140.0, [220.0, 43.0, 8.0, 1.3]),
+    food("noodles", &["noodle"], "cup", 160.0, [220.0, 40.0, 7.0, 3.0]),
+    food("chicken breast", &["chicken"], "breast", 120.0, [198.0, 0.0, 37.0, 4.3]),
+    food("steak", &["beef steak"], "steak", 200.0, [500.0, 0.0, 50.0, 32.0]),
+    food("salmon", &["salmon fillet"], "fillet", 150.0, [310.0, 0.0, 33.0, 19.0]),
+    food("tuna", &["canned tuna"], "can", 95.0, [100.0, 0.0, 22.0, 1.0]),
+    food("burger", &["hamburger", "cheeseburger"], "burger", 220.0, [550.0, 40.0, 28.0, 30.0]),
+    food("pizza", &[], "slice", 107.0, [285.0, 36.0, 12.0, 10.0]),
+    food("chips", &["fries", "french fries", "hot chips"], "serving", 117.0, [365.0, 48.0, 4.0, 17.0]),
+    food("sandwich", &["sanga"], "sandwich", 150.0, [350.0, 40.0, 18.0, 12.0]),
+    food("burrito", &[], "burrito", 300.0, [600.0, 70.0, 25.0, 22.0]),
+    food("soup", &[], "bowl", 250.0, [150.0, 18.0, 6.0, 5.0]),
+    food("cheese", &["cheddar"], "slice", 20.0, [80.0, 0.3, 5.0, 6.6]),
+    // Snacks and sweets
+    food("chocolate", &["chocolate bar"], "bar", 45.0, [235.0, 26.0, 3.0, 13.0]),
+    food("biscuit", &["cookie"], "biscuit", 15.0, [75.0, 10.0, 1.0, 3.5]),
+    food("donut", &["doughnut"], "donut", 60.0, [250.0, 30.0, 3.0, 14.0]),
+    food("ice cream", &["gelato"], "scoop", 66.0, [137.0, 16.0, 2.3, 7.0]),
+    food("almonds", &["almond", "nuts", "mixed nuts"], "handful", 28.0, [165.0, 6.0, 6.0, 14.0]),
+    food("protein shake", &["protein powder", "whey"], "scoop", 30.0, [120.0, 3.0, 24.0, 1.5]),
+    // Drinks
+    food("flat white", &[], "cup", 240.0, [120.0, 10.0, 7.0, 6.0]),
+    food("latte", &["cafe latte"], "cup", 240.0, [150.0, 15.0, 10.0, 6.0]),
+    food("cappuccino", &[], "cup", 240.0, [110.0, 9.0, 6.0, 4.0]),
+    food("coffee", &["black coffee", "long black", "americano", "espresso"], "cup", 240.0, [2.0, 0.0, 0.3, 0.0]),
+    food("tea", &["black tea", "green tea", "cup of tea"], "cup", 240.0, [2.0, 0.5, 0.0, 0.0]),
+    food("milk", &["whole milk", "glass of milk"], "glass", 250.0, [155.0, 12.0, 8.0, 8.0]),
+    food("orange juice", &["oj", "juice"], "glass", 250.0, [112.0, 26.0, 1.7, 0.5]),
+    food("cola", &["coke", "soft drink", "soda"], "can", 375.0, [150.0, 39.0, 0.0, 0.0]),
+    food("beer", &["lager", "ale"], "can", 375.0, [150.0, 13.0, 1.6, 0.0]),
+    food("wine", &["red wine", "white wine"], "glass", 150.0, [125.0, 4.0, 0.1, 0.0]),
+];
@@ -15,13 +15,18 @@ use axum::{
 };
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use tracing::{debug, error, instrument};
+use tracing::{debug, instrument};
 use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
 use crate::{
+    activity::state::ActivityState,
+    auth::model::Caller,
     error::YuhuhError,
-    food::{model::FoodEntry, state::FoodState},
+    fasting::{read_fasting::read_fasting_schedule, state::FastingState},
+    food::{
+        energy::EnergyUnit, micronutrients::Micronutrients, model::FoodEntry, state::FoodState,
+    },
     user::state::UserState,
 };
 
@@ -38,6 +43,8 @@ pub struct ReadFoodEntriesRequest {
     pub offset: Option<u32>,
     pub logged_before_date: Option<DateTime<Utc>>,
     pub logged_after_date: Option<DateTime<Utc>>,
+    /// Unit to report energy in, defaulting to the user's preference.
+    pub energy_unit: Option<EnergyUnit>,
 }
 
 // ============================================================================
@@ -48,18 +55,35 @@ pub struct ReadFoodEntriesRequest {
 pub struct FoundFoodRecord {
     pub description: String,
     pub calories: Option<f32>,
+    pub energy_unit: EnergyUnit,
     pub carbs: Option<f32>,
     pub protein: Option<f32>,
     pub fats: Option<f32>,
+    pub micronutrients: Option<Micronutrients>,
     pub logged_at: DateTime<Utc>,
+    /// Present when the entry was logged during one of the user's fasts.
+    pub fasting_warning: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, ToSchema)]
 pub struct CaloriesResult {
     pub total_calories: f32,
+    pub energy_unit: EnergyUnit,
     pub food_entries_without_calories: u32,
 }
 
+/// Energy eaten minus energy burned by activities logged in the same period.
+///
+/// Both are totalled over every entry in the period, not just the page of
+/// food entries returned.
+#[derive(Debug, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct NetCaloriesResult {
+    pub total_calories_burned: f32,
+    pub net_calories: f32,
+    pub energy_unit: EnergyUnit,
+    pub activity_entries_without_calories_burned: u32,
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, ToSchema)]
 pub struct MacrosResult {
     pub total_carbs: f32,
@@ -70,27 +94,40 @@ pub struct MacrosResult {
     pub food_entries_without_fats: u32,
 }
 
+/// Known micronutrients summed across entries, in their canonical units.
+#[derive(Debug, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct MicronutrientsResult {
+    pub totals: Micronutrients,
+    pub food_entries_without_micronutrients: u32,
+}
+
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ReadFoodEntriesResponse {
     pub found_food_entries: u32,
     pub food_entries: Vec<FoundFoodRecord>,
     pub calories_result: CaloriesResult,
+    pub net_calories_result: NetCaloriesResult,
     pub macros_result: MacrosResult,
+    pub micronutrients_result: MicronutrientsResult,
 }
 
 // ============================================================================
 // Trait Implementations
 // ============================================================================
 
-impl From<&FoodEntry> for FoundFoodRecord {
-    fn from(value: &FoodEntry) -> Self {
+impl FoundFoodRecord {
+    /// Maps a food entry, reporting its energy in `energy_unit`.
+    fn new(value: &FoodEntry, energy_unit: EnergyUnit, fasting_warning: Option<String>) -> Self {
         FoundFoodRecord {
             description: value.description.clone(),
-            calories: value.calories,
+            calories: value.calories.map(|c| energy_unit.from_kcal(c)),
+            energy_unit,
             carbs: value.carbs,
             protein: value.protein,
             fats: value.fats,
+            micronutrients: value.micronutrients.clone(),
             logged_at: value.logged_at,
+            fasting_warning,
         }
     }
 }
@@ -112,20 +149,18 @@ impl From<&FoodEntry> for FoundFoodRecord {
 pub async fn read_food_entries(
     State(food_state): State<Arc<FoodState>>,
     State(user_state): State<Arc<UserState>>,
-
+    State(fasting_state): State<Arc<FastingState>>,
+    State(activity_state): State<Arc<ActivityState>>,
+    caller: Caller,
     Query(request): Query<ReadFoodEntriesRequest>,
 ) -> Result<(StatusCode, Json<ReadFoodEntriesResponse>), YuhuhError> {
     debug!("entering read_food_entries");
 
-    if (user_state
-        .find_user_repo
-        .find_user_by_id(&request.user_id)
-        .await?)
-        .is_none()
-    {
-        error!(user_id = ?request.user_id, "failed to find user");
-        return Err(YuhuhError::NotFound("user not found".to_string()));
-    }
+    caller.authorise_user(&request.user_id)?;
+
+    let user = user_state.require_user(&request.user_id).await?;
+
+    let energy_unit = request.energy_unit.unwrap_or(user.energy_unit);
 
     let offset = request.offset.unwrap_or(0);
     let limit = request.limit.unwrap_or(10000);
@@ -142,8 +177,17 @@ pub async fn read_food_entries(
         )
         .await?;
 
+    let fasting_schedule = read_fasting_schedule(
+        &fasting_state,
+        &user,
+        food_records.iter().map(|f| f.logged_at).max(),
+        food_records.iter().map(|f| f.logged_at).min(),
+    )
+    .await?;
+
     let mut calories_result = CaloriesResult {
         total_calories: 0.0,
+        energy_unit,
         food_entries_without_calories: 0,
     };
     let mut macros_result = MacrosResult {
@@ -154,6 +198,10 @@ pub async fn read_food_entries(
         food_entries_without_protein: 0,
         food_entries_without_fats: 0,
     };
+    let mut micronutrients_result = MicronutrientsResult {
+        totals: Micronutrients::default(),
+        food_entries_without_micronutrients: 0,
+    };
     let mut mapped_food_records: Vec<FoundFoodRecord> = Vec::with_capacity(food_records.len());
 
     food_records.iter().for_each(|fr| {
@@ -181,14 +229,55 @@ pub async fn read_food_entries(
             macros_result.food_entries_without_fats += 1;
         }
 
-        mapped_food_records.push(FoundFoodRecord::from(fr));
+        if let Some(micronutrients) = &fr.micronutrients {
+            micronutrients_result.totals.accumulate(micronutrients);
+        } else {
+            micronutrients_result.food_entries_without_micronutrients += 1;
+        }
+
+        mapped_food_records.push(FoundFoodRecord::new(
+            fr,
+            energy_unit,
+            fasting_schedule.warning(fr.logged_at),
+        ));
     });
 
+    // Totalled over the whole period, as food is paged but net calories
+    // aren't
+    let calories_total = food_state
+        .read_food_entries_repo
+        .read_calories_total(
+            &request.user_id,
+            request.logged_before_date,
+            request.logged_after_date,
+        )
+        .await?;
+    let burned_total = activity_state
+        .read_activity_entries_repo
+        .read_calories_burned_total(
+            &request.user_id,
+            request.logged_before_date,
+            request.logged_after_date,
+        )
+        .await?;
+
+    let net_calories_result = NetCaloriesResult {
+        total_calories_burned: energy_unit.from_kcal(burned_total.total_calories_burned),
+        net_calories: energy_unit
+            .from_kcal(calories_total.total_calories - burned_total.total_calories_burned),
+        energy_unit,
+        activity_entries_without_calories_burned: burned_total.entries_without_calories_burned
+            as u32,
+    };
+    calories_result.total_calories = energy_unit.from_kcal(calories_result.total_calories);
+
     let response = Json(ReadFoodEntriesResponse {
         found_food_entries: mapped_food_records.len() as u32,
         food_entries: mapped_food_records,
         calories_result,
+        net_calories_result,
         macros_result,
+        micronutrients_result,
     });
 
     debug!(response=?response, "found food records");
@@ -210,7 +299,14 @@ mod tests {
     use tower::ServiceExt;
     use url::form_urlencoded;
 
-    use crate::food::read_food_entries::{CaloriesResult, MacrosResult, ReadFoodEntriesResponse};
+    use crate::food::{
+        energy::EnergyUnit,
+        micronutrients::{Micronutrients, Nutrient, NutrientUnit},
+        read_food_entries::{
+            CaloriesResult, MacrosResult, MicronutrientsResult, NetCaloriesResult,
+            ReadFoodEntriesResponse,
+        },
+    };
 
     /// Tests that food entries are returned for a specific user in descending order by logged date
     #[tokio::test]
@@ -282,9 +378,141 @@ mod tests {
             dto.calories_result,
             CaloriesResult {
                 total_calories: 200.0,
+                energy_unit: EnergyUnit::Kilocalories,
+                food_entries_without_calories: 1
+            }
+        );
+    }
+
+    /// Tests that calories burned by activities are taken off what was eaten
+    #[tokio::test]
+    async fn calculates_net_calories_correctly() {
+        let (app, db, _) = crate::test::common::setup().await;
+
+        sqlx::raw_sql(include_str!("../../migrations/test/read_food_entries.sql"))
+            .execute(&db)
+            .await
+            .expect("setup test sql ran successfully");
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri("/food?user_id=11111111-1111-1111-1111-111111111111")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let dto: ReadFoodEntriesResponse =
+            serde_json::from_slice(&body).expect("valid FindFoodEntryResponse bytes");
+
+        assert_eq!(
+            dto.net_calories_result,
+            NetCaloriesResult {
+                total_calories_burned: 150.0,
+                net_calories: 50.0,
+                energy_unit: EnergyUnit::Kilocalories,
+                activity_entries_without_calories_burned: 1
+            }
+        );
+    }
+
+    /// Tests that net calories cover the whole period when food is paged
+    #[tokio::test]
+    async fn net_calories_not_paged() {
+        let (app, db, _) = crate::test::common::setup().await;
+
+        sqlx::raw_sql(include_str!("../../migrations/test/read_food_entries.sql"))
+            .execute(&db)
+            .await
+            .expect("setup test sql ran successfully");
+
+        sqlx::query(
+            "INSERT INTO food_records (user_id, description, calories, logged_at)
+            VALUES ('22222222-2222-2222-2222-222222222222', 'bobats chips', 250, now() - interval '1 hour')",
+        )
+        .execute(&db)
+        .await
+        .expect("second food entry created");
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri("/food?user_id=22222222-2222-2222-2222-222222222222&limit=1")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let dto: ReadFoodEntriesResponse =
+            serde_json::from_slice(&body).expect("valid FindFoodEntryResponse bytes");
+
+        // Only the burger is on the page, but the chips are still eaten
+        assert_eq!(dto.found_food_entries, 1);
+        assert_eq!(dto.calories_result.total_calories, 100.0);
+        assert_eq!(
+            dto.net_calories_result,
+            NetCaloriesResult {
+                total_calories_burned: 400.0,
+                net_calories: -50.0,
+                energy_unit: EnergyUnit::Kilocalories,
+                activity_entries_without_calories_burned: 0
+            }
+        );
+    }
+
+    /// Tests that energy is converted into the user's preferred unit
+    #[tokio::test]
+    async fn converts_calories_to_preferred_unit() {
+        let (app, db, _) = crate::test::common::setup().await;
+
+        sqlx::raw_sql(include_str!("../../migrations/test/read_food_entries.sql"))
+            .execute(&db)
+            .await
+            .expect("setup test sql ran successfully");
+
+        sqlx::query("UPDATE users SET energy_unit = 'kJ'")
+            .execute(&db)
+            .await
+            .expect("preferred unit updated");
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri("/food?user_id=11111111-1111-1111-1111-111111111111")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let dto: ReadFoodEntriesResponse =
+            serde_json::from_slice(&body).expect("valid FindFoodEntryResponse bytes");
+
+        assert_eq!(
+            dto.calories_result,
+            CaloriesResult {
+                total_calories: 836.8,
+                energy_unit: EnergyUnit::Kilojoules,
                 food_entries_without_calories: 1
             }
         );
+        assert_eq!(dto.food_entries[1].calories, Some(418.4));
+        assert_eq!(dto.food_entries[1].energy_unit, EnergyUnit::Kilojoules);
     }
 
     /// Tests that macronutrients (carbs, protein, fats) are calculated correctly
@@ -328,6 +556,54 @@ mod tests {
         );
     }
 
+    /// Tests that known micronutrients are summed in canonical units
+    #[tokio::test]
+    async fn calculates_micronutrients_correctly() {
+        let (app, db, _) = crate::test::common::setup().await;
+
+        sqlx::raw_sql(include_str!("../../migrations/test/read_food_entries.sql"))
+            .execute(&db)
+            .await
+            .expect("setup test sql ran successfully");
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri("/food?user_id=11111111-1111-1111-1111-111111111111")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let dto: ReadFoodEntriesResponse =
+            serde_json::from_slice(&body).expect("valid FindFoodEntryResponse bytes");
+
+        // Sodium is recorded in both grams and milligrams, fibre only once,
+        // and the untyped key is never summed
+        assert_eq!(
+            dto.micronutrients_result,
+            MicronutrientsResult {
+                totals: Micronutrients {
+                    sodium: Some(Nutrient {
+                        amount: 1500.0,
+                        unit: NutrientUnit::Milligrams
+                    }),
+                    fibre: Some(Nutrient {
+                        amount: 3.0,
+                        unit: NutrientUnit::Grams
+                    }),
+                    ..Default::default()
+                },
+                food_entries_without_micronutrients: 0
+            }
+        );
+    }
+
     /// Tests that the logged_before_date filter correctly excludes entries after the specified date
     #[tokio::test]
     async fn before_filters_correctly() {
@@ -4,7 +4,17 @@ use sqlx::PgPool;
 use tracing::{debug, error};
 use uuid::Uuid;
 
-use crate::{error::YuhuhError, food::model::FoodEntry};
+use crate::{
+    error::YuhuhError,
+    food::model::{FoodEntry, FoodEntryRow},
+};
+
+/// Energy eaten over every food entry in a period, in kilocalories.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CaloriesTotal {
+    pub total_calories: f32,
+    pub entries_without_calories: i64,
+}
 
 #[async_trait]
 pub trait ReadFoodEntriesRepository: std::fmt::Debug + Send + Sync + 'static {
@@ -16,6 +26,14 @@ pub trait ReadFoodEntriesRepository: std::fmt::Debug + Send + Sync + 'static {
         limit: i64,
         offset: i64,
     ) -> Result<Vec<FoodEntry>, YuhuhError>;
+
+    /// Sums the calories of every food entry in the period, unpaged.
+    async fn read_calories_total(
+        &self,
+        user_id: &Uuid,
+        before: Option<DateTime<Utc>>,
+        after: Option<DateTime<Utc>>,
+    ) -> Result<CaloriesTotal, YuhuhError>;
 }
 
 #[derive(Debug)]
@@ -48,10 +66,21 @@ impl ReadFoodEntriesRepository for ReadFoodEntriesRepositoryImpl {
             "received find request for food entries"
         );
 
-        let food_records: Vec<FoodEntry> = sqlx::query_as!(
-            FoodEntry,
+        let records: Vec<FoodEntryRow> = sqlx::query_as!(
+            FoodEntryRow,
             r#"
-            SELECT *
+            SELECT
+                food_record_id,
+                description,
+                calories,
+                carbs,
+                protein,
+                fats,
+                micronutrients,
+                user_id,
+                created_at,
+                logged_at,
+                energy_unit
             FROM food_records
             WHERE user_id = $1::uuid
             AND ($2::timestamptz IS NULL
@@ -76,8 +105,55 @@ impl ReadFoodEntriesRepository for ReadFoodEntriesRepositoryImpl {
             YuhuhError::DatabaseError(e)
         })?;
 
-        debug!(food_records=?food_records, "found food records");
+        debug!(food_records=?records, "found food records");
+
+        let food_entries = records
+            .into_iter()
+            .map(FoodEntry::try_from)
+            .collect::<Result<Vec<FoodEntry>, _>>()
+            .inspect_err(|e| error!(error=?e, "encountered parsing error for food entry"))?;
+
+        Ok(food_entries)
+    }
+
+    async fn read_calories_total(
+        &self,
+        user_id: &Uuid,
+        before: Option<DateTime<Utc>>,
+        after: Option<DateTime<Utc>>,
+    ) -> Result<CaloriesTotal, YuhuhError> {
+        debug!(
+            user_id=?user_id,
+            before=?before,
+            after=?after,
+            "received calories total request for food entries"
+        );
+
+        let total = sqlx::query_as!(
+            CaloriesTotal,
+            r#"
+            SELECT
+                coalesce(sum(calories), 0)::real AS "total_calories!",
+                count(*) FILTER (WHERE calories IS NULL) AS "entries_without_calories!"
+            FROM food_records
+            WHERE user_id = $1::uuid
+            AND ($2::timestamptz IS NULL
+                OR logged_at <= $2::timestamptz)
+            AND ($3::timestamptz IS NULL
+                OR logged_at >= $3::timestamptz);
+            "#,
+            user_id,
+            before,
+            after
+        )
+        .fetch_one(&self.db)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, "database error while totalling food calories");
+
+            YuhuhError::DatabaseError(e)
+        })?;
 
-        Ok(food_records)
+        Ok(total)
     }
 }
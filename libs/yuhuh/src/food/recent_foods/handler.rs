@@ -0,0 +1,264 @@
+//! recent and frequent foods HTTP handlers
+//!
+//! This module provides HTTP endpoints for finding the foods a user logs most,
+//! so clients can offer them for quick re-logging.
+
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::StatusCode,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, instrument};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::{
+    auth::model::Caller,
+    error::YuhuhError,
+    food::{
+        energy::EnergyUnit, micronutrients::Micronutrients, recent_foods::RecentFood,
+        state::FoodState,
+    },
+    user::state::UserState,
+};
+
+// ============================================================================
+// HTTP Request types
+// ============================================================================
+
+/// Request parameters for finding recent or frequent foods.
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ReadRecentFoodsRequest {
+    /// user ID to search by.
+    pub user_id: Uuid,
+    /// Maximum number of distinct foods to return, defaults to 10.
+    pub limit: Option<u32>,
+}
+
+// ============================================================================
+// HTTP Responsed types
+// ============================================================================
+
+/// A distinct food along with the values it was last logged with.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct QuickAddFood {
+    pub description: String,
+    pub calories: Option<f32>,
+    pub energy_unit: EnergyUnit,
+    pub carbs: Option<f32>,
+    pub protein: Option<f32>,
+    pub fats: Option<f32>,
+    pub micronutrients: Option<Micronutrients>,
+    pub times_logged: u32,
+    pub last_logged_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ReadRecentFoodsResponse {
+    pub foods: Vec<QuickAddFood>,
+}
+
+// ============================================================================
+// Implementations
+// ============================================================================
+
+impl QuickAddFood {
+    fn new(value: RecentFood, energy_unit: EnergyUnit) -> Self {
+        QuickAddFood {
+            description: value.description,
+            calories: value.calories.map(|c| energy_unit.from_kcal(c)),
+            energy_unit,
+            carbs: value.carbs,
+            protein: value.protein,
+            fats: value.fats,
+            micronutrients: value.micronutrients,
+            times_logged: value.times_logged as u32,
+            last_logged_at: value.last_logged_at,
+        }
+    }
+}
+
+// =============================================================================
+// HTTP Handlers
+// =============================================================================
+
+/// Find the foods a user has logged most recently
+#[utoipa::path(
+    get,
+    path = "food/recent",
+    tag = "food",
+    params(ReadRecentFoodsRequest),
+    responses(
+        (status = 200, description = "Found recent foods", body = ReadRecentFoodsResponse)
+))]
+#[instrument]
+pub async fn read_recent_foods(
+    State(food_state): State<Arc<FoodState>>,
+    State(user_state): State<Arc<UserState>>,
+    caller: Caller,
+    Query(request): Query<ReadRecentFoodsRequest>,
+) -> Result<(StatusCode, Json<ReadRecentFoodsResponse>), YuhuhError> {
+    debug!("entering read_recent_foods");
+
+    caller.authorise_user(&request.user_id)?;
+
+    let user = user_state.require_user(&request.user_id).await?;
+
+    let foods = food_state
+        .recent_foods_repo
+        .read_recent_foods(&request.user_id, request.limit.unwrap_or(10).into())
+        .await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(ReadRecentFoodsResponse {
+            foods: foods
+                .into_iter()
+                .map(|f| QuickAddFood::new(f, user.energy_unit))
+                .collect(),
+        }),
+    ))
+}
+
+/// Find the foods a user logs most often
+#[utoipa::path(
+    get,
+    path = "food/frequent",
+    tag = "food",
+    params(ReadRecentFoodsRequest),
+    responses(
+        (status = 200, description = "Found frequent foods", body = ReadRecentFoodsResponse)
+))]
+#[instrument]
+pub async fn read_frequent_foods(
+    State(food_state): State<Arc<FoodState>>,
+    State(user_state): State<Arc<UserState>>,
+    caller: Caller,
+    Query(request): Query<ReadRecentFoodsRequest>,
+) -> Result<(StatusCode, Json<ReadRecentFoodsResponse>), YuhuhError> {
+    debug!("entering read_frequent_foods");
+
+    caller.authorise_user(&request.user_id)?;
+
+    let user = user_state.require_user(&request.user_id).await?;
+
+    let foods = food_state
+        .recent_foods_repo
+        .read_frequent_foods(&request.user_id, request.limit.unwrap_or(10).into())
+        .await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(ReadRecentFoodsResponse {
+            foods: foods
+                .into_iter()
+                .map(|f| QuickAddFood::new(f, user.energy_unit))
+                .collect(),
+        }),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use http_body_util::BodyExt;
+    use pretty_assertions::assert_eq;
+
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+    };
+    use tower::ServiceExt;
+
+    use crate::food::recent_foods::ReadRecentFoodsResponse;
+
+    /// Tests that distinct foods are returned newest first with their latest values
+    #[tokio::test]
+    async fn recent_foods_returned() {
+        let (app, db, _) = crate::test::common::setup().await;
+
+        sqlx::raw_sql(include_str!("../../migrations/test/recent_foods.sql"))
+            .execute(&db)
+            .await
+            .expect("setup test sql ran successfully");
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri("/food/recent?user_id=11111111-1111-1111-1111-111111111111")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let dto: ReadRecentFoodsResponse =
+            serde_json::from_slice(&body).expect("valid ReadRecentFoodsResponse bytes");
+
+        assert_eq!(dto.foods.len(), 2);
+        assert_eq!(&dto.foods[0].description, "flat white");
+        assert_eq!(&dto.foods[1].description, "Burger");
+        // The latest burger wins, regardless of casing
+        assert_eq!(dto.foods[1].calories, Some(650.0));
+        assert_eq!(dto.foods[1].times_logged, 3);
+    }
+
+    /// Tests that distinct foods are returned most logged first
+    #[tokio::test]
+    async fn frequent_foods_returned() {
+        let (app, db, _) = crate::test::common::setup().await;
+
+        sqlx::raw_sql(include_str!("../../migrations/test/recent_foods.sql"))
+            .execute(&db)
+            .await
+            .expect("setup test sql ran successfully");
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri("/food/frequent?user_id=11111111-1111-1111-1111-111111111111&limit=1")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let dto: ReadRecentFoodsResponse =
+            serde_json::from_slice(&body).expect("valid ReadRecentFoodsResponse bytes");
+
+        assert_eq!(dto.foods.len(), 1);
+        assert_eq!(&dto.foods[0].description, "Burger");
+        assert_eq!(dto.foods[0].times_logged, 3);
+    }
+
+    /// Tests that querying for a non-existent user returns 404 Not Found
+    #[tokio::test]
+    async fn user_not_found_handled() {
+        let (app, _, _) = crate::test::common::setup().await;
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri("/food/recent?user_id=55555555-5555-5555-5555-555555555555")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
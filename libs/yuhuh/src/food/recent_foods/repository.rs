@@ -0,0 +1,201 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use tracing::{debug, error};
+use uuid::Uuid;
+
+use crate::{error::YuhuhError, food::micronutrients::Micronutrients};
+
+// =============================================================================
+// Public Types and Structs
+// =============================================================================
+
+/// A distinct food a user has logged, with the values from its latest entry.
+#[derive(Debug)]
+pub struct RecentFood {
+    pub description: String,
+    // Always kilocalories
+    pub calories: Option<f32>,
+    pub carbs: Option<f32>,
+    pub protein: Option<f32>,
+    pub fats: Option<f32>,
+    pub micronutrients: Option<Micronutrients>,
+    pub times_logged: i64,
+    pub last_logged_at: DateTime<Utc>,
+}
+
+// =============================================================================
+// Row Structs
+// =============================================================================
+
+#[derive(Debug, sqlx::FromRow)]
+struct RecentFoodRow {
+    description: String,
+    calories: Option<f32>,
+    carbs: Option<f32>,
+    protein: Option<f32>,
+    fats: Option<f32>,
+    micronutrients: Option<serde_json::Value>,
+    times_logged: i64,
+    last_logged_at: DateTime<Utc>,
+}
+
+impl From<RecentFoodRow> for RecentFood {
+    fn from(value: RecentFoodRow) -> Self {
+        RecentFood {
+            description: value.description,
+            calories: value.calories,
+            carbs: value.carbs,
+            protein: value.protein,
+            fats: value.fats,
+            micronutrients: value.micronutrients.and_then(Micronutrients::from_stored),
+            times_logged: value.times_logged,
+            last_logged_at: value.last_logged_at,
+        }
+    }
+}
+
+// =============================================================================
+// Traits
+// =============================================================================
+
+/// Foods are grouped case insensitively by description, so "Burger" and
+/// "burger" count as the same food.
+#[async_trait]
+pub trait RecentFoodsRepository: std::fmt::Debug + Send + Sync + 'static {
+    /// Distinct foods ordered by when they were last logged.
+    async fn read_recent_foods(
+        &self,
+        user_id: &Uuid,
+        limit: i64,
+    ) -> Result<Vec<RecentFood>, YuhuhError>;
+
+    /// Distinct foods ordered by how often they have been logged.
+    async fn read_frequent_foods(
+        &self,
+        user_id: &Uuid,
+        limit: i64,
+    ) -> Result<Vec<RecentFood>, YuhuhError>;
+}
+
+// =============================================================================
+// Production Implementation
+// =============================================================================
+
+#[derive(Debug)]
+pub struct RecentFoodsRepositoryImpl {
+    pub db: PgPool,
+}
+
+impl RecentFoodsRepositoryImpl {
+    pub fn new(db: PgPool) -> Self {
+        RecentFoodsRepositoryImpl { db }
+    }
+}
+
+#[async_trait]
+impl RecentFoodsRepository for RecentFoodsRepositoryImpl {
+    async fn read_recent_foods(
+        &self,
+        user_id: &Uuid,
+        limit: i64,
+    ) -> Result<Vec<RecentFood>, YuhuhError> {
+        debug!(user_id=?user_id, limit=?limit, "received read request for recent foods");
+
+        let records: Vec<RecentFoodRow> = sqlx::query_as!(
+            RecentFoodRow,
+            r#"
+            SELECT
+                description AS "description!",
+                calories,
+                carbs,
+                protein,
+                fats,
+                micronutrients,
+                times_logged AS "times_logged!",
+                logged_at AS "last_logged_at!"
+            FROM (
+                SELECT DISTINCT ON (lower(description))
+                    description,
+                    calories,
+                    carbs,
+                    protein,
+                    fats,
+                    micronutrients,
+                    logged_at,
+                    count(*) OVER (PARTITION BY lower(description)) AS times_logged
+                FROM food_records
+                WHERE user_id = $1::uuid
+                ORDER BY lower(description), logged_at DESC
+            ) latest
+            ORDER BY logged_at DESC
+            LIMIT $2;
+            "#,
+            user_id,
+            limit
+        )
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, "database error while finding recent foods");
+
+            YuhuhError::DatabaseError(e)
+        })?;
+
+        debug!(recent_foods=?records, "found recent foods");
+
+        Ok(records.into_iter().map(RecentFood::from).collect())
+    }
+
+    async fn read_frequent_foods(
+        &self,
+        user_id: &Uuid,
+        limit: i64,
+    ) -> Result<Vec<RecentFood>, YuhuhError> {
+        debug!(user_id=?user_id, limit=?limit, "received read request for frequent foods");
+
+        let records: Vec<RecentFoodRow> = sqlx::query_as!(
+            RecentFoodRow,
+            r#"
+            SELECT
+                description AS "description!",
+                calories,
+                carbs,
+                protein,
+                fats,
+                micronutrients,
+                times_logged AS "times_logged!",
+                logged_at AS "last_logged_at!"
+            FROM (
+                SELECT DISTINCT ON (lower(description))
+                    description,
+                    calories,
+                    carbs,
+                    protein,
+                    fats,
+                    micronutrients,
+                    logged_at,
+                    count(*) OVER (PARTITION BY lower(description)) AS times_logged
+                FROM food_records
+                WHERE user_id = $1::uuid
+                ORDER BY lower(description), logged_at DESC
+            ) latest
+            ORDER BY times_logged DESC, logged_at DESC
+            LIMIT $2;
+            "#,
+            user_id,
+            limit
+        )
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, "database error while finding frequent foods");
+
+            YuhuhError::DatabaseError(e)
+        })?;
+
+        debug!(frequent_foods=?records, "found frequent foods");
+
+        Ok(records.into_iter().map(RecentFood::from).collect())
+    }
+}
@@ -1,14 +1,16 @@
 use crate::{
     food::{
         create_food_entries::{self},
+        favourite_foods, parse_food_description,
         read_food_entries::{self},
+        recent_foods,
     },
     state::AppState,
 };
 
 use axum::{
     Router,
-    routing::{get, post},
+    routing::{delete, get, post},
 };
 use utoipa::OpenApi;
 
@@ -19,7 +21,13 @@ use utoipa::OpenApi;
 #[derive(OpenApi)]
 #[openapi(paths(
     read_food_entries::read_food_entries,
-    create_food_entries::create_food_entries
+    create_food_entries::create_food_entries,
+    recent_foods::read_recent_foods,
+    recent_foods::read_frequent_foods,
+    favourite_foods::create_favourite_food,
+    favourite_foods::read_favourite_foods,
+    favourite_foods::delete_favourite_food,
+    parse_food_description::parse_food_description
 ))]
 pub struct FoodApi;
 
@@ -34,4 +42,18 @@ pub fn food_router() -> Router<AppState> {
             "/food/create",
             post(create_food_entries::create_food_entries),
         )
+        .route("/food/recent", get(recent_foods::read_recent_foods))
+        .route("/food/frequent", get(recent_foods::read_frequent_foods))
+        .route(
+            "/food/favourites",
+            get(favourite_foods::read_favourite_foods).post(favourite_foods::create_favourite_food),
+        )
+        .route(
+            "/food/favourites/{food_favourite_id}",
+            delete(favourite_foods::delete_favourite_food),
+        )
+        .route(
+            "/food/parse",
+            post(parse_food_description::parse_food_description),
+        )
 }
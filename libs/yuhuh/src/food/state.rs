@@ -4,13 +4,17 @@ use sqlx::PgPool;
 
 use crate::food::{
     create_food_entries::{CreateFoodEntryRepository, CreateFoodEntryRepositoryImpl},
+    favourite_foods::{FavouriteFoodsRepository, FavouriteFoodsRepositoryImpl},
     read_food_entries::{ReadFoodEntriesRepository, ReadFoodEntriesRepositoryImpl},
+    recent_foods::{RecentFoodsRepository, RecentFoodsRepositoryImpl},
 };
 
 #[derive(Debug)]
 pub struct FoodState {
     pub create_food_entries_repo: Arc<dyn CreateFoodEntryRepository>,
     pub read_food_entries_repo: Arc<dyn ReadFoodEntriesRepository>,
+    pub recent_foods_repo: Arc<dyn RecentFoodsRepository>,
+    pub favourite_foods_repo: Arc<dyn FavouriteFoodsRepository>,
 }
 
 impl FoodState {
@@ -18,6 +22,8 @@ impl FoodState {
         FoodState {
             create_food_entries_repo: Arc::new(CreateFoodEntryRepositoryImpl::new(db.clone())),
             read_food_entries_repo: Arc::new(ReadFoodEntriesRepositoryImpl::new(db.clone())),
+            recent_foods_repo: Arc::new(RecentFoodsRepositoryImpl::new(db.clone())),
+            favourite_foods_repo: Arc::new(FavouriteFoodsRepositoryImpl::new(db.clone())),
         }
     }
 }
@@ -0,0 +1,281 @@
+//! Correlations between daily aggregates across food, activity and mood.
+//!
+//! Each pair of metrics is compared on the same day and with the outcome a
+//! day later, using Pearson's r as the effect size. Days missing either value
+//! are left out, and pairs with fewer days than the minimum are reported
+//! without a coefficient rather than as a noisy one.
+
+use std::fmt;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Days needed for a correlation when the caller doesn't ask for more.
+pub const DEFAULT_MIN_SAMPLES: u32 = 14;
+
+/// Fewest days a correlation can be worked out from at all.
+pub const MIN_SAMPLES_FLOOR: u32 = 3;
+
+/// Days between the predictor and the outcome that are compared.
+pub const LAGS: [u32; 2] = [0, 1];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DailyMetric {
+    /// Kilocalories eaten.
+    Calories,
+    /// Grams of protein eaten.
+    Protein,
+    /// Minutes of activity, zero on days without any.
+    ActivityMinutes,
+    /// Average sleep rating on the user's scale.
+    Sleep,
+    /// Average mood rating on the user's scale.
+    Mood,
+    /// Average energy rating on the user's scale.
+    Energy,
+}
+
+impl DailyMetric {
+    pub const PREDICTORS: [DailyMetric; 4] = [
+        DailyMetric::Calories,
+        DailyMetric::Protein,
+        DailyMetric::ActivityMinutes,
+        DailyMetric::Sleep,
+    ];
+
+    pub const OUTCOMES: [DailyMetric; 2] = [DailyMetric::Mood, DailyMetric::Energy];
+}
+
+impl fmt::Display for DailyMetric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DailyMetric::Calories => write!(f, "calories"),
+            DailyMetric::Protein => write!(f, "protein"),
+            DailyMetric::ActivityMinutes => write!(f, "activity_minutes"),
+            DailyMetric::Sleep => write!(f, "sleep"),
+            DailyMetric::Mood => write!(f, "mood"),
+            DailyMetric::Energy => write!(f, "energy"),
+        }
+    }
+}
+
+/// Everything logged on one day in the user's timezone.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct DailyAggregate {
+    pub date: NaiveDate,
+    /// `None` when no food with calories was logged.
+    pub calories: Option<f32>,
+    /// `None` when no food with protein was logged.
+    pub protein: Option<f32>,
+    pub activity_minutes: f32,
+    pub sleep: Option<f32>,
+    pub mood: Option<f32>,
+    pub energy: Option<f32>,
+}
+
+impl DailyAggregate {
+    pub fn new(date: NaiveDate) -> Self {
+        DailyAggregate {
+            date,
+            ..Default::default()
+        }
+    }
+
+    pub fn get(&self, metric: DailyMetric) -> Option<f32> {
+        match metric {
+            DailyMetric::Calories => self.calories,
+            DailyMetric::Protein => self.protein,
+            DailyMetric::ActivityMinutes => Some(self.activity_minutes),
+            DailyMetric::Sleep => self.sleep,
+            DailyMetric::Mood => self.mood,
+            DailyMetric::Energy => self.energy,
+        }
+    }
+}
+
+/// Rough size of an effect, using Cohen's thresholds for r.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum EffectStrength {
+    Negligible,
+    Small,
+    Medium,
+    Large,
+}
+
+impl EffectStrength {
+    pub fn from_coefficient(r: f32) -> Self {
+        match r.abs() {
+            r if r < 0.1 => EffectStrength::Negligible,
+            r if r < 0.3 => EffectStrength::Small,
+            r if r < 0.5 => EffectStrength::Medium,
+            _ => EffectStrength::Large,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Correlation {
+    pub predictor: DailyMetric,
+    pub outcome: DailyMetric,
+    /// Days after the predictor the outcome was taken from.
+    pub lag_days: u32,
+    /// Days with both values.
+    pub sample_count: u32,
+    /// Whether there were enough days to report an effect.
+    pub sufficient_data: bool,
+    /// Pearson's r, from -1 to 1. `None` without enough data or when either
+    /// metric never changed.
+    pub coefficient: Option<f32>,
+    /// Share of the outcome's variance explained by the predictor.
+    pub r_squared: Option<f32>,
+    /// Change in the outcome for each unit of the predictor.
+    pub slope: Option<f32>,
+    pub strength: Option<EffectStrength>,
+}
+
+/// Pearson's r and the least squares slope of `y` on `x`, or `None` when
+/// either never changes.
+fn pearson(pairs: &[(f64, f64)]) -> Option<(f64, f64)> {
+    let n = pairs.len() as f64;
+    let mean_x = pairs.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = pairs.iter().map(|(_, y)| y).sum::<f64>() / n;
+
+    let (covariance, variance_x, variance_y) =
+        pairs.iter().fold((0.0, 0.0, 0.0), |(c, vx, vy), (x, y)| {
+            let (dx, dy) = (x - mean_x, y - mean_y);
+            (c + dx * dy, vx + dx * dx, vy + dy * dy)
+        });
+
+    if variance_x <= f64::EPSILON || variance_y <= f64::EPSILON {
+        return None;
+    }
+
+    let r = (covariance / (variance_x * variance_y).sqrt()).clamp(-1.0, 1.0);
+
+    Some((r, covariance / variance_x))
+}
+
+/// Correlates `predictor` on each day with `outcome` `lag_days` later, for
+/// consecutive days oldest first.
+pub fn correlate(
+    days: &[DailyAggregate],
+    predictor: DailyMetric,
+    outcome: DailyMetric,
+    lag_days: u32,
+    min_samples: u32,
+) -> Correlation {
+    let pairs: Vec<(f64, f64)> = days
+        .iter()
+        .zip(days.iter().skip(lag_days as usize))
+        .filter_map(|(day, later)| {
+            Some((
+                f64::from(day.get(predictor)?),
+                f64::from(later.get(outcome)?),
+            ))
+        })
+        .collect();
+
+    let sample_count = pairs.len() as u32;
+    let sufficient_data = sample_count >= min_samples.max(MIN_SAMPLES_FLOOR);
+
+    let fit = sufficient_data.then(|| pearson(&pairs)).flatten();
+    let coefficient = fit.map(|(r, _)| r as f32);
+
+    Correlation {
+        predictor,
+        outcome,
+        lag_days,
+        sample_count,
+        sufficient_data,
+        coefficient,
+        r_squared: coefficient.map(|r| r * r),
+        slope: fit.map(|(_, slope)| slope as f32),
+        strength: coefficient.map(EffectStrength::from_coefficient),
+    }
+}
+
+/// Every predictor against every outcome, on the same day and the day after.
+pub fn correlations(days: &[DailyAggregate], min_samples: u32) -> Vec<Correlation> {
+    DailyMetric::PREDICTORS
+        .iter()
+        .flat_map(|&predictor| {
+            DailyMetric::OUTCOMES.iter().flat_map(move |&outcome| {
+                LAGS.iter()
+                    .map(move |&lag| correlate(days, predictor, outcome, lag, min_samples))
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Days;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn days(values: &[(Option<f32>, Option<f32>)]) -> Vec<DailyAggregate> {
+        let start = NaiveDate::from_ymd_opt(2025, 9, 1).unwrap();
+
+        values
+            .iter()
+            .enumerate()
+            .map(|(i, &(sleep, mood))| DailyAggregate {
+                sleep,
+                mood,
+                ..DailyAggregate::new(start + Days::new(i as u64))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn lagged_correlation_pairs_the_next_day() {
+        // Mood follows the previous night's sleep exactly, a day late
+        let days = days(&[
+            (Some(4.0), Some(5.0)),
+            (Some(8.0), Some(3.0)),
+            (Some(6.0), Some(7.0)),
+            (None, Some(5.0)),
+            (Some(2.0), Some(9.0)),
+            (Some(5.0), Some(1.0)),
+        ]);
+
+        let lagged = correlate(&days, DailyMetric::Sleep, DailyMetric::Mood, 1, 3);
+        assert_eq!(lagged.sample_count, 4);
+        assert!(lagged.sufficient_data);
+        assert_eq!(lagged.coefficient, Some(1.0));
+        assert_eq!(lagged.strength, Some(EffectStrength::Large));
+
+        let same_day = correlate(&days, DailyMetric::Sleep, DailyMetric::Mood, 0, 3);
+        assert_eq!(same_day.sample_count, 5);
+        assert!(same_day.coefficient.unwrap() < -0.3);
+    }
+
+    #[test]
+    fn too_few_or_constant_days_have_no_effect() {
+        let days = days(&[
+            (Some(4.0), Some(5.0)),
+            (Some(8.0), Some(9.0)),
+            (Some(6.0), Some(7.0)),
+        ]);
+
+        let sparse = correlate(&days, DailyMetric::Sleep, DailyMetric::Mood, 0, 4);
+        assert_eq!(sparse.sample_count, 3);
+        assert!(!sparse.sufficient_data);
+        assert_eq!(sparse.coefficient, None);
+
+        let perfect = correlate(&days, DailyMetric::Sleep, DailyMetric::Mood, 0, 3);
+        assert_eq!(perfect.coefficient, Some(1.0));
+        assert_eq!(perfect.slope, Some(1.0));
+
+        // No activity on any day, so nothing to correlate against
+        let flat = correlate(&days, DailyMetric::ActivityMinutes, DailyMetric::Mood, 0, 3);
+        assert!(flat.sufficient_data);
+        assert_eq!(flat.coefficient, None);
+
+        assert_eq!(correlations(&days, 3).len(), 16);
+    }
+}
@@ -0,0 +1,3 @@
+pub mod correlation;
+pub mod read_correlations;
+pub mod router;
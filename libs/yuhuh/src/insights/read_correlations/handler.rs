@@ -0,0 +1,374 @@
+//! correlation insights HTTP handler
+//!
+//! This module provides an HTTP endpoint correlating a user's daily food,
+//! activity and sleep against their mood and energy, on the same day and the
+//! day after.
+
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::StatusCode,
+};
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, instrument, warn};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::{
+    activity::{
+        activity_info::ActivityInfo, read_activity_entries::ActivityFilter, state::ActivityState,
+    },
+    auth::model::Caller,
+    date_range::{DateRange, MAX_RANGE_DAYS},
+    error::YuhuhError,
+    food::state::FoodState,
+    insights::correlation::{
+        Correlation, DEFAULT_MIN_SAMPLES, DailyAggregate, MIN_SAMPLES_FLOOR, correlations,
+    },
+    mood::{
+        scale::{RatingMetric, RatingScale},
+        state::MoodState,
+    },
+    user::state::UserState,
+};
+
+/// Range used when no start date is given.
+const DEFAULT_RANGE_DAYS: u64 = 89;
+
+// ============================================================================
+// HTTP Request types
+// ============================================================================
+
+/// Request parameters for finding correlation insights.
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ReadCorrelationsRequest {
+    /// user ID to search by.
+    pub user_id: Uuid,
+    /// First day in the user's timezone, defaulting to 90 days up to
+    /// `end_date`.
+    pub start_date: Option<NaiveDate>,
+    /// Last day in the user's timezone, defaulting to today.
+    pub end_date: Option<NaiveDate>,
+    /// Days with both values needed before an effect is reported, defaulting
+    /// to 14.
+    pub min_samples: Option<u32>,
+}
+
+// ============================================================================
+// HTTP Responsed types
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ReadCorrelationsResponse {
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub min_samples: u32,
+    /// Every day in the range, oldest first.
+    pub days: Vec<DailyAggregate>,
+    /// Each predictor against mood and energy, on the same day and the day
+    /// after.
+    pub correlations: Vec<Correlation>,
+}
+
+// =============================================================================
+// Implementations
+// =============================================================================
+
+/// Running total of normalised ratings for one day.
+#[derive(Debug, Default, Clone, Copy)]
+struct RatingTotal {
+    sum: f32,
+    count: u32,
+}
+
+impl RatingTotal {
+    fn add(&mut self, value: Option<f32>) {
+        if let Some(value) = value
+            && RatingScale::is_normalised(value)
+        {
+            self.sum += value;
+            self.count += 1;
+        }
+    }
+
+    fn average(&self, scale: &RatingScale) -> Option<f32> {
+        (self.count > 0).then(|| scale.scale(self.sum / self.count as f32))
+    }
+}
+
+fn add(total: &mut Option<f32>, value: Option<f32>) {
+    if let Some(value) = value {
+        *total = Some(total.unwrap_or(0.0) + value);
+    }
+}
+
+// =============================================================================
+// HTTP Handlers
+// =============================================================================
+
+/// Find how a user's food, activity and sleep relate to their mood and energy
+#[utoipa::path(
+    get,
+    path = "insights/correlations",
+    tag = "insights",
+    params(ReadCorrelationsRequest),
+    responses(
+        (status = 200, description = "Found correlations", body = ReadCorrelationsResponse),
+        (status = 400, description = "Invalid date range or minimum samples"),
+        (status = 404, description = "User not found"),
+))]
+#[instrument]
+pub async fn read_correlations(
+    State(food_state): State<Arc<FoodState>>,
+    State(activity_state): State<Arc<ActivityState>>,
+    State(mood_state): State<Arc<MoodState>>,
+    State(user_state): State<Arc<UserState>>,
+    caller: Caller,
+    Query(request): Query<ReadCorrelationsRequest>,
+) -> Result<(StatusCode, Json<ReadCorrelationsResponse>), YuhuhError> {
+    debug!("entering read_correlations");
+
+    caller.authorise_user(&request.user_id)?;
+
+    let user = user_state.require_user(&request.user_id).await?;
+
+    let tz = user.tz();
+    let range = DateRange::from_request(
+        request.start_date,
+        request.end_date,
+        Utc::now().with_timezone(&tz).date_naive(),
+        DEFAULT_RANGE_DAYS,
+    )?;
+    let (start, end) = (range.start, range.end);
+
+    let min_samples = request.min_samples.unwrap_or(DEFAULT_MIN_SAMPLES);
+    if !(MIN_SAMPLES_FLOOR..=MAX_RANGE_DAYS as u32).contains(&min_samples) {
+        return Err(YuhuhError::BadRequest(format!(
+            "min_samples must be between {} and {}",
+            MIN_SAMPLES_FLOOR, MAX_RANGE_DAYS
+        )));
+    }
+
+    // Read a day either side in UTC, then place each record on its local day
+    let (before, after) = range.utc_bounds()?;
+
+    let food_entries = food_state
+        .read_food_entries_repo
+        .read_food_entries(&request.user_id, Some(before), Some(after), i64::MAX, 0)
+        .await?;
+
+    let activity_records = activity_state
+        .read_activity_entries_repo
+        .read_activity_entries(
+            &request.user_id,
+            Some(before),
+            Some(after),
+            &ActivityFilter::default(),
+            i64::MAX,
+            0,
+        )
+        .await?;
+
+    let scales = mood_state
+        .rating_scales_repo
+        .read_rating_scales(&request.user_id)
+        .await?;
+
+    let mood_rows = mood_state
+        .read_mood_stats_repo
+        .read_mood_rows(&request.user_id, before, after)
+        .await?;
+
+    let mut days: Vec<DailyAggregate> = start
+        .iter_days()
+        .take_while(|date| *date <= end)
+        .map(DailyAggregate::new)
+        .collect();
+    let index = |date: NaiveDate| {
+        (start..=end)
+            .contains(&date)
+            .then(|| (date - start).num_days() as usize)
+    };
+
+    for entry in food_entries {
+        let Some(i) = index(entry.logged_at.with_timezone(&tz).date_naive()) else {
+            continue;
+        };
+
+        add(&mut days[i].calories, entry.calories);
+        add(&mut days[i].protein, entry.protein);
+    }
+
+    for record in activity_records {
+        let Some(i) = index(record.logged_at.with_timezone(&tz).date_naive()) else {
+            continue;
+        };
+
+        let seconds = ActivityInfo::parse(&record.activity_type, record.activity_info)
+            .inspect_err(|e| warn!(error = ?e, "skipping activity with invalid activity_info"))
+            .ok()
+            .and_then(|info| info.duration_seconds());
+
+        if let Some(seconds) = seconds {
+            days[i].activity_minutes += seconds / 60.0;
+        }
+    }
+
+    let mut ratings = vec![[RatingTotal::default(); 3]; days.len()];
+
+    for row in mood_rows {
+        let Some(i) = index(row.logged_at.with_timezone(&tz).date_naive()) else {
+            continue;
+        };
+
+        ratings[i][0].add(row.mood);
+        ratings[i][1].add(row.energy);
+        ratings[i][2].add(row.sleep);
+    }
+
+    for (day, [mood, energy, sleep]) in days.iter_mut().zip(ratings) {
+        day.mood = mood.average(scales.get(RatingMetric::Mood));
+        day.energy = energy.average(scales.get(RatingMetric::Energy));
+        day.sleep = sleep.average(scales.get(RatingMetric::Sleep));
+    }
+
+    let correlations = correlations(&days, min_samples);
+
+    Ok((
+        StatusCode::OK,
+        Json(ReadCorrelationsResponse {
+            start_date: start,
+            end_date: end,
+            min_samples,
+            days,
+            correlations,
+        }),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+    };
+    use http_body_util::BodyExt;
+    use pretty_assertions::assert_eq;
+    use tower::ServiceExt;
+
+    use crate::insights::{
+        correlation::{Correlation, DailyMetric, EffectStrength},
+        read_correlations::ReadCorrelationsResponse,
+    };
+
+    async fn read(
+        app: axum::Router,
+        query: &str,
+    ) -> (StatusCode, Option<ReadCorrelationsResponse>) {
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/insights/correlations?{}", query))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let status = response.status();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+
+        (status, serde_json::from_slice(&body).ok())
+    }
+
+    fn find(
+        dto: &ReadCorrelationsResponse,
+        predictor: DailyMetric,
+        outcome: DailyMetric,
+        lag_days: u32,
+    ) -> &Correlation {
+        dto.correlations
+            .iter()
+            .find(|c| c.predictor == predictor && c.outcome == outcome && c.lag_days == lag_days)
+            .expect("correlation for every pair")
+    }
+
+    #[tokio::test]
+    async fn correlations_pair_days_and_need_enough_data() {
+        let (app, db, _) = crate::test::common::setup().await;
+
+        sqlx::raw_sql(include_str!("../../migrations/test/correlations.sql"))
+            .execute(&db)
+            .await
+            .expect("setup test sql ran successfully");
+
+        let (status, dto) = read(
+            app.clone(),
+            "user_id=11111111-1111-1111-1111-111111111111&start_date=2025-03-01&end_date=2025-03-20",
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let dto = dto.expect("valid ReadCorrelationsResponse bytes");
+        assert_eq!(dto.days.len(), 20);
+        assert_eq!(dto.min_samples, 14);
+        assert_eq!(dto.correlations.len(), 16);
+
+        // Both meals add up, and ratings show on the default 0 to 10 scale
+        assert_eq!(dto.days[1].calories, Some(2100.0));
+        assert_eq!(dto.days[0].activity_minutes, 30.0);
+        assert_eq!(dto.days[1].activity_minutes, 0.0);
+        assert_eq!(dto.days[1].sleep, Some(3.0));
+        assert_eq!(dto.days[10].calories, None);
+
+        // Mood follows the night before's sleep
+        let sleep_mood = find(&dto, DailyMetric::Sleep, DailyMetric::Mood, 1);
+        assert_eq!(sleep_mood.sample_count, 19);
+        assert_eq!(sleep_mood.strength, Some(EffectStrength::Large));
+        assert!(sleep_mood.coefficient.unwrap() > 0.999);
+
+        // Energy never changes, so has nothing to correlate with
+        let sleep_energy = find(&dto, DailyMetric::Sleep, DailyMetric::Energy, 0);
+        assert!(sleep_energy.sufficient_data);
+        assert_eq!(sleep_energy.coefficient, None);
+
+        // Food was only logged for ten days
+        let calories_mood = find(&dto, DailyMetric::Calories, DailyMetric::Mood, 0);
+        assert_eq!(calories_mood.sample_count, 10);
+        assert!(!calories_mood.sufficient_data);
+        assert_eq!(calories_mood.coefficient, None);
+
+        let activity_mood = find(&dto, DailyMetric::ActivityMinutes, DailyMetric::Mood, 0);
+        assert_eq!(activity_mood.sample_count, 20);
+        assert!(activity_mood.coefficient.is_some());
+
+        // Asking for less data reports the food correlations
+        let (status, dto) = read(
+            app.clone(),
+            "user_id=11111111-1111-1111-1111-111111111111&start_date=2025-03-01&end_date=2025-03-20&min_samples=10",
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let dto = dto.expect("valid ReadCorrelationsResponse bytes");
+        assert!(
+            find(&dto, DailyMetric::Calories, DailyMetric::Mood, 0)
+                .coefficient
+                .is_some()
+        );
+
+        let (status, _) = read(
+            app.clone(),
+            "user_id=11111111-1111-1111-1111-111111111111&min_samples=2",
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, _) = read(app, "user_id=33333333-3333-3333-3333-333333333333").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,23 @@
+use axum::{Router, routing::get};
+use utoipa::OpenApi;
+
+use crate::{insights::read_correlations, state::AppState};
+
+// =============================================================================
+// API Docs
+// =============================================================================
+
+#[derive(OpenApi)]
+#[openapi(paths(read_correlations::read_correlations))]
+pub struct InsightsApi;
+
+// =============================================================================
+// Router
+// =============================================================================
+
+pub fn insights_router() -> Router<AppState> {
+    Router::new().route(
+        "/insights/correlations",
+        get(read_correlations::read_correlations),
+    )
+}
@@ -1,18 +1,37 @@
 pub mod activity;
 pub mod api;
+pub mod auth;
 pub mod config;
+pub mod date_range;
+pub mod discord;
 pub mod error;
+pub mod fasting;
 pub mod food;
 pub mod health;
+pub mod insights;
+pub mod live;
 pub mod migrations;
 pub mod mood;
+pub mod reminders;
+pub mod search;
 pub mod state;
 mod test;
 pub mod user;
+pub mod webhooks;
+
+use std::{sync::Arc, time::Duration};
 
 use anyhow::{Context, Ok, Result};
 use tracing::info;
 
+use crate::{
+    reminders::{
+        scheduler::{ReminderScheduler, ReminderSchedulerRepositoryImpl},
+        sink::LogSink,
+    },
+    webhooks::dispatcher::{WebhookDispatcher, WebhookDispatcherRepositoryImpl},
+};
+
 pub async fn main(config: &config::Config) -> Result<()> {
     let global_span = log::init(
         &log::CommonFields {
@@ -31,6 +50,24 @@ pub async fn main(config: &config::Config) -> Result<()> {
 
     info!("db connection and setup successful");
 
+    if config.reminder_interval_seconds > 0 {
+        let scheduler = ReminderScheduler::new(
+            Arc::new(ReminderSchedulerRepositoryImpl::new(db.clone())),
+            Arc::new(LogSink),
+        );
+
+        tokio::spawn(scheduler.run(Duration::from_secs(config.reminder_interval_seconds)));
+    }
+
+    if config.webhook_interval_seconds > 0 {
+        let dispatcher = WebhookDispatcher::new(
+            Arc::new(WebhookDispatcherRepositoryImpl::new(db.clone())),
+            config.webhook_allow_private_urls,
+        )?;
+
+        tokio::spawn(dispatcher.run(Duration::from_secs(config.webhook_interval_seconds)));
+    }
+
     // Spin up API
     api::serve(config, db)
         .await
@@ -0,0 +1,204 @@
+//! Fan-out of events to live feeds.
+//!
+//! Every event written to the outbox is announced with a Postgres `NOTIFY`
+//! once its transaction commits, so each instance hears about changes made
+//! by any other. An instance starts listening when its first feed opens, and
+//! passes each event on to the feeds open for that event's user.
+
+use std::{
+    collections::HashMap,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use serde::Deserialize;
+use sqlx::{PgPool, postgres::PgListener};
+use tokio::sync::{OnceCell, broadcast};
+use tokio_stream::{
+    Stream,
+    wrappers::{BroadcastStream, errors::BroadcastStreamRecvError},
+};
+use tracing::{debug, error, info, warn};
+use uuid::Uuid;
+
+use crate::{
+    error::YuhuhError, live::stream_events::LiveEventsRepository, webhooks::model::WebhookEvent,
+};
+
+/// Channel outbox events are announced on.
+pub const EVENTS_CHANNEL: &str = "yuhuh_events";
+
+/// Events held for a slow feed before it starts missing them.
+const FEED_CAPACITY: usize = 64;
+
+/// Wait before listening again after losing the connection.
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// What's announced for each event.
+#[derive(Debug, Deserialize)]
+struct EventNotification {
+    event_id: Uuid,
+    user_id: Uuid,
+}
+
+/// A user's live feed of events, closed when dropped.
+#[derive(Debug)]
+pub struct Feed {
+    /// Only `None` while the feed is being dropped.
+    events: Option<BroadcastStream<Arc<WebhookEvent>>>,
+    hub: Arc<EventHub>,
+    user_id: Uuid,
+}
+
+impl Stream for Feed {
+    type Item = Result<Arc<WebhookEvent>, BroadcastStreamRecvError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        match &mut self.events {
+            Some(events) => Pin::new(events).poll_next(cx),
+            None => Poll::Ready(None),
+        }
+    }
+}
+
+impl Drop for Feed {
+    fn drop(&mut self) {
+        // Closed first, so it's no longer counted as a receiver
+        self.events.take();
+        self.hub.close_feed(&self.user_id);
+    }
+}
+
+#[derive(Debug)]
+pub struct EventHub {
+    db: PgPool,
+    repo: Arc<dyn LiveEventsRepository>,
+    feeds: Mutex<HashMap<Uuid, broadcast::Sender<Arc<WebhookEvent>>>>,
+    listening: OnceCell<()>,
+}
+
+impl EventHub {
+    pub fn new(db: PgPool, repo: Arc<dyn LiveEventsRepository>) -> Self {
+        EventHub {
+            db,
+            repo,
+            feeds: Mutex::new(HashMap::new()),
+            listening: OnceCell::new(),
+        }
+    }
+
+    /// Opens a feed of a user's events, listening for events first if this
+    /// is the first feed on the instance.
+    pub async fn subscribe(self: &Arc<Self>, user_id: Uuid) -> Result<Feed, YuhuhError> {
+        let receiver = self
+            .feeds
+            .lock()
+            .expect("feeds lock poisoned")
+            .entry(user_id)
+            .or_insert_with(|| broadcast::channel(FEED_CAPACITY).0)
+            .subscribe();
+
+        self.listening
+            .get_or_try_init(|| async {
+                let mut listener = PgListener::connect_with(&self.db).await.map_err(|e| {
+                    error!(error = ?e, "failed to connect event listener");
+
+                    YuhuhError::DatabaseError(e)
+                })?;
+                listener.listen(EVENTS_CHANNEL).await.map_err(|e| {
+                    error!(error = ?e, "failed to listen for events");
+
+                    YuhuhError::DatabaseError(e)
+                })?;
+
+                tokio::spawn(self.clone().listen(listener));
+
+                Ok::<_, YuhuhError>(())
+            })
+            .await?;
+
+        Ok(Feed {
+            events: Some(BroadcastStream::new(receiver)),
+            hub: self.clone(),
+            user_id,
+        })
+    }
+
+    /// Users with a feed open on this instance.
+    pub fn open_feeds(&self) -> usize {
+        self.feeds.lock().expect("feeds lock poisoned").len()
+    }
+
+    /// Forgets a user's channel once their last feed has closed, so users
+    /// who stop streaming aren't held on to.
+    fn close_feed(&self, user_id: &Uuid) {
+        // Never panics, as it's called when feeds are dropped
+        let Ok(mut feeds) = self.feeds.lock() else {
+            return;
+        };
+
+        if feeds
+            .get(user_id)
+            .is_some_and(|feed| feed.receiver_count() == 0)
+        {
+            feeds.remove(user_id);
+        }
+    }
+
+    /// Passes on events until the process exits. Events announced while the
+    /// connection is lost are missed, feeds catch up by reconnecting with
+    /// the last event they saw.
+    async fn listen(self: Arc<Self>, mut listener: PgListener) {
+        info!(channel = EVENTS_CHANNEL, "listening for events");
+
+        loop {
+            match listener.recv().await {
+                Ok(notification) => self.publish(notification.payload()).await,
+                Err(e) => {
+                    error!(error = ?e, "lost event listener connection, reconnecting");
+
+                    tokio::time::sleep(RECONNECT_DELAY).await;
+                }
+            }
+        }
+    }
+
+    async fn publish(&self, payload: &str) {
+        let notification: EventNotification = match serde_json::from_str(payload) {
+            Ok(notification) => notification,
+            Err(e) => {
+                warn!(error = ?e, payload = payload, "ignoring malformed event notification");
+                return;
+            }
+        };
+
+        let feed = {
+            let mut feeds = self.feeds.lock().expect("feeds lock poisoned");
+
+            match feeds.get(&notification.user_id) {
+                Some(feed) if feed.receiver_count() > 0 => feed.clone(),
+                Some(_) => {
+                    // Every feed for the user has closed
+                    feeds.remove(&notification.user_id);
+                    return;
+                }
+                None => return,
+            }
+        };
+
+        match self.repo.find_event(&notification.event_id).await {
+            Ok(Some(event)) => {
+                debug!(event_id = ?event.event_id, "publishing event to live feeds");
+
+                // Feeds may have closed in the meantime
+                let _ = feed.send(Arc::new(event));
+            }
+            Ok(None) => warn!(event_id = ?notification.event_id, "announced event not found"),
+            Err(e) => {
+                error!(error = ?e, event_id = ?notification.event_id, "failed to read announced event")
+            }
+        }
+    }
+}
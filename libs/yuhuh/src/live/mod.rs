@@ -0,0 +1,4 @@
+pub mod hub;
+pub mod router;
+pub mod state;
+pub mod stream_events;
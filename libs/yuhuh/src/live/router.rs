@@ -0,0 +1,20 @@
+use axum::{Router, routing::get};
+use utoipa::OpenApi;
+
+use crate::{live::stream_events, state::AppState};
+
+// =============================================================================
+// API Docs
+// =============================================================================
+
+#[derive(OpenApi)]
+#[openapi(paths(stream_events::stream_events))]
+pub struct LiveApi;
+
+// =============================================================================
+// Router
+// =============================================================================
+
+pub fn live_router() -> Router<AppState> {
+    Router::new().route("/users/{user_id}/events", get(stream_events::stream_events))
+}
@@ -0,0 +1,26 @@
+use std::sync::Arc;
+
+use sqlx::PgPool;
+
+use crate::live::{
+    hub::EventHub,
+    stream_events::{LiveEventsRepository, LiveEventsRepositoryImpl},
+};
+
+#[derive(Debug)]
+pub struct LiveState {
+    pub live_events_repo: Arc<dyn LiveEventsRepository>,
+    pub hub: Arc<EventHub>,
+}
+
+impl LiveState {
+    pub fn new(db: PgPool) -> Self {
+        let live_events_repo: Arc<dyn LiveEventsRepository> =
+            Arc::new(LiveEventsRepositoryImpl::new(db.clone()));
+
+        LiveState {
+            hub: Arc::new(EventHub::new(db.clone(), live_events_repo.clone())),
+            live_events_repo,
+        }
+    }
+}
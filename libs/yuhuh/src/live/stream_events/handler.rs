@@ -0,0 +1,273 @@
+//! live event feed HTTP handler
+//!
+//! This module provides a Server-Sent Events endpoint pushing a user's
+//! newly created or changed food, mood and activity records as they happen,
+//! so dashboards don't need to poll.
+
+use std::{convert::Infallible, sync::Arc};
+
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    response::sse::{Event, KeepAlive, Sse},
+};
+use tokio_stream::{Stream, StreamExt, wrappers::errors::BroadcastStreamRecvError};
+use tracing::{debug, error, instrument, warn};
+use uuid::Uuid;
+
+use crate::{
+    auth::model::Caller, error::YuhuhError, live::state::LiveState, search::model::RecordKind,
+    user::state::UserState, webhooks::model::WebhookEvent,
+};
+
+/// Most missed events replayed when a feed reconnects.
+const MAX_REPLAYED_EVENTS: i64 = 100;
+
+/// Kinds of record whose events are sent.
+const FEED_KINDS: [RecordKind; 3] = [RecordKind::Food, RecordKind::Mood, RecordKind::Activity];
+
+// =============================================================================
+// Implementations
+// =============================================================================
+
+fn to_sse_event(event: &WebhookEvent) -> Event {
+    Event::default()
+        .id(event.event_id.to_string())
+        .event(event.event_type.to_string())
+        .json_data(event)
+        .unwrap_or_else(|e| {
+            error!(error = ?e, event_id = ?event.event_id, "failed to serialise live event");
+
+            Event::default().comment("failed to serialise event")
+        })
+}
+
+// =============================================================================
+// HTTP Handlers
+// =============================================================================
+
+/// Stream a user's new and changed food, mood and activity records
+///
+/// Each record event is sent with its type as the SSE event name, e.g.
+/// `food.created`, and the same body as webhooks receive. A `lagged` event
+/// with the number of events missed is sent when the client falls behind.
+#[utoipa::path(
+    get,
+    path = "users/{user_id}/events",
+    tag = "live",
+    params(
+        ("user_id" = Uuid, Path, description = "User to stream the events of"),
+        ("Last-Event-ID" = Option<Uuid>, Header, description = "Last event seen, to replay any missed since")
+    ),
+    responses(
+        (status = 200, description = "Stream of events", content_type = "text/event-stream", body = WebhookEvent),
+        (status = 404, description = "User not found"),
+))]
+#[instrument(skip(headers))]
+pub async fn stream_events(
+    State(live_state): State<Arc<LiveState>>,
+    State(user_state): State<Arc<UserState>>,
+    caller: Caller,
+    Path(user_id): Path<Uuid>,
+    headers: HeaderMap,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, YuhuhError> {
+    debug!("entering stream_events");
+
+    caller.authorise_user(&user_id)?;
+
+    user_state.require_user(&user_id).await?;
+
+    // Subscribed before replaying, so nothing is missed in between
+    let feed = live_state.hub.subscribe(user_id).await?;
+
+    let last_event_id = headers
+        .get("last-event-id")
+        .and_then(|id| id.to_str().ok())
+        .and_then(|id| id.trim().parse::<Uuid>().ok());
+
+    let replayed = match &last_event_id {
+        Some(last_event_id) => {
+            live_state
+                .live_events_repo
+                .read_events_after(&user_id, last_event_id, &FEED_KINDS, MAX_REPLAYED_EVENTS)
+                .await?
+        }
+        None => vec![],
+    };
+
+    // Event IDs are time ordered, so anything up to the last replayed event
+    // has already been sent
+    let sent_up_to = replayed.last().map(|e| e.event_id).or(last_event_id);
+
+    let replayed = tokio_stream::iter(replayed).map(|event| Ok(to_sse_event(&event)));
+
+    let live = feed.filter_map(move |event| match event {
+        Ok(event)
+            if event.event_type.record_kind().is_some()
+                && sent_up_to.is_none_or(|id| event.event_id > id) =>
+        {
+            Some(Ok(to_sse_event(&event)))
+        }
+        Ok(_) => None,
+        Err(BroadcastStreamRecvError::Lagged(missed)) => {
+            warn!(user_id = ?user_id, missed = missed, "live feed fell behind");
+
+            Some(Ok(Event::default()
+                .event("lagged")
+                .data(missed.to_string())))
+        }
+    });
+
+    Ok(Sse::new(replayed.chain(live)).keep_alive(KeepAlive::default()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use axum::{
+        Router,
+        body::Body,
+        http::{self, Request, StatusCode},
+    };
+    use http_body_util::BodyExt;
+    use pretty_assertions::assert_eq;
+    use tower::ServiceExt;
+    use uuid::{Uuid, uuid};
+
+    use crate::food::create_food_entries::{CreateFoodEntryRequest, NewFoodEntry};
+
+    const ALICE: Uuid = uuid!("11111111-1111-1111-1111-111111111111");
+    const BOBAT: Uuid = uuid!("22222222-2222-2222-2222-222222222222");
+
+    async fn log_food(app: Router, user_id: Uuid, description: &str) {
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/food/create")
+                    .header(http::header::CONTENT_TYPE, "application/json")
+                    .body(Body::from(
+                        serde_json::to_string(&CreateFoodEntryRequest {
+                            user_id,
+                            food_entries: vec![NewFoodEntry {
+                                description: description.to_string(),
+                                calories: Some(300.0),
+                                energy_unit: None,
+                                carbs: None,
+                                protein: None,
+                                fats: None,
+                                micronutrients: None,
+                                logged_at: None,
+                            }],
+                        })
+                        .unwrap(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+    }
+
+    async fn open_feed(app: Router, last_event_id: Option<&str>) -> Body {
+        let mut request = Request::builder().uri(format!("/users/{}/events", ALICE));
+        if let Some(last_event_id) = last_event_id {
+            request = request.header("last-event-id", last_event_id);
+        }
+
+        let response = app
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[http::header::CONTENT_TYPE],
+            "text/event-stream"
+        );
+
+        response.into_body()
+    }
+
+    /// Reads the next event sent, as its lines.
+    async fn next_event(body: &mut Body) -> Vec<String> {
+        let mut text = String::new();
+
+        while !text.contains("\n\n") {
+            let frame = tokio::time::timeout(Duration::from_secs(10), body.frame())
+                .await
+                .expect("event sent in time")
+                .expect("feed still open")
+                .expect("valid frame");
+
+            if let Ok(data) = frame.into_data() {
+                text.push_str(std::str::from_utf8(&data).unwrap());
+            }
+        }
+
+        text.lines().map(str::to_string).collect()
+    }
+
+    fn field<'a>(event: &'a [String], name: &str) -> &'a str {
+        event
+            .iter()
+            .find_map(|line| line.strip_prefix(&format!("{}: ", name)))
+            .unwrap_or_else(|| panic!("event has {} - {:?}", name, event))
+    }
+
+    #[tokio::test]
+    async fn new_records_are_pushed_and_replayed() {
+        let (app, db, state) = crate::test::common::setup().await;
+
+        sqlx::raw_sql(include_str!("../../migrations/test/live_events.sql"))
+            .execute(&db)
+            .await
+            .expect("setup test sql ran successfully");
+
+        let mut feed = open_feed(app.clone(), None).await;
+
+        // Bobat's food isn't sent to Alice's feed
+        log_food(app.clone(), BOBAT, "toast").await;
+        log_food(app.clone(), ALICE, "porridge").await;
+
+        let event = next_event(&mut feed).await;
+        assert_eq!(field(&event, "event"), "food.created");
+
+        let data: serde_json::Value = serde_json::from_str(field(&event, "data")).unwrap();
+        assert_eq!(data["user_id"], ALICE.to_string());
+        assert_eq!(data["data"]["description"], "porridge");
+
+        let porridge_id = field(&event, "id").to_string();
+        assert_eq!(data["event_id"], porridge_id);
+
+        // Reconnecting replays what was missed since the last event seen
+        log_food(app.clone(), ALICE, "sushi").await;
+
+        let mut reconnected = open_feed(app.clone(), Some(&porridge_id)).await;
+        let event = next_event(&mut reconnected).await;
+        let data: serde_json::Value = serde_json::from_str(field(&event, "data")).unwrap();
+        assert_eq!(data["data"]["description"], "sushi");
+
+        // Alice is forgotten once her last feed closes
+        let hub = &state.live.hub;
+        let first = hub.subscribe(ALICE).await.expect("feed opened");
+        let second = hub.subscribe(ALICE).await.expect("feed opened");
+        assert_eq!(hub.open_feeds(), 1);
+
+        drop(first);
+        assert_eq!(hub.open_feeds(), 1);
+        drop(second);
+        assert_eq!(hub.open_feeds(), 0);
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/users/33333333-3333-3333-3333-333333333333/events")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
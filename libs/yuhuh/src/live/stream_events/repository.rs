@@ -0,0 +1,114 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use tracing::{debug, error};
+use uuid::Uuid;
+
+use crate::{error::YuhuhError, search::model::RecordKind, webhooks::model::WebhookEvent};
+
+// =============================================================================
+// Traits
+// =============================================================================
+
+#[async_trait]
+pub trait LiveEventsRepository: std::fmt::Debug + Send + Sync + 'static {
+    async fn find_event(&self, event_id: &Uuid) -> Result<Option<WebhookEvent>, YuhuhError>;
+
+    /// Up to `limit` of a user's events about `kinds` of record that came
+    /// after `after_event_id`, oldest first.
+    async fn read_events_after(
+        &self,
+        user_id: &Uuid,
+        after_event_id: &Uuid,
+        kinds: &[RecordKind],
+        limit: i64,
+    ) -> Result<Vec<WebhookEvent>, YuhuhError>;
+}
+
+// =============================================================================
+// Production Implementation
+// =============================================================================
+
+#[derive(Debug)]
+pub struct LiveEventsRepositoryImpl {
+    pub db: PgPool,
+}
+
+impl LiveEventsRepositoryImpl {
+    pub fn new(db: PgPool) -> Self {
+        LiveEventsRepositoryImpl { db }
+    }
+}
+
+fn parse_event(payload: serde_json::Value) -> Result<WebhookEvent, YuhuhError> {
+    serde_json::from_value(payload).map_err(|e| {
+        error!(error = ?e, "encountered parsing error for event");
+
+        YuhuhError::InternalServerError(e.to_string())
+    })
+}
+
+#[async_trait]
+impl LiveEventsRepository for LiveEventsRepositoryImpl {
+    async fn find_event(&self, event_id: &Uuid) -> Result<Option<WebhookEvent>, YuhuhError> {
+        let payload = sqlx::query_scalar!(
+            r#"
+            SELECT payload
+            FROM webhook_outbox
+            WHERE event_id = $1::uuid;
+            "#,
+            event_id
+        )
+        .fetch_optional(&self.db)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, "database error while finding event");
+
+            YuhuhError::DatabaseError(e)
+        })?;
+
+        payload.map(parse_event).transpose()
+    }
+
+    async fn read_events_after(
+        &self,
+        user_id: &Uuid,
+        after_event_id: &Uuid,
+        kinds: &[RecordKind],
+        limit: i64,
+    ) -> Result<Vec<WebhookEvent>, YuhuhError> {
+        debug!(
+            user_id=?user_id,
+            after_event_id=?after_event_id,
+            kinds=?kinds,
+            limit=?limit,
+            "received read events after"
+        );
+
+        let kinds: Vec<String> = kinds.iter().map(|k| k.to_string()).collect();
+
+        let payloads = sqlx::query_scalar!(
+            r#"
+            SELECT payload
+            FROM webhook_outbox
+            WHERE user_id = $1::uuid
+            AND event_id > $2::uuid
+            AND split_part(event_type, '.', 1) = ANY($3::text[])
+            ORDER BY event_id
+            LIMIT $4;
+            "#,
+            user_id,
+            after_event_id,
+            &kinds,
+            limit
+        )
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, "database error while reading events");
+
+            YuhuhError::DatabaseError(e)
+        })?;
+
+        payloads.into_iter().map(parse_event).collect()
+    }
+}
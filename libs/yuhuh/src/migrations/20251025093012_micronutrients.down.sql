@@ -0,0 +1,2 @@
+-- Add down migration script here
+alter table food_records drop constraint if exists micronutrients_is_object;
@@ -0,0 +1,14 @@
+-- Micronutrients are now typed by the api, with unknown keys kept alongside
+-- the known ones. Anything stored before that which isn't an object can't be
+-- merged into that shape, so it's kept under a single "value" key instead.
+update food_records
+set micronutrients = null
+where jsonb_typeof(micronutrients) = 'null';
+
+update food_records
+set micronutrients = jsonb_build_object('value', micronutrients)
+where jsonb_typeof(micronutrients) <> 'object';
+
+alter table food_records
+    add constraint micronutrients_is_object
+    check (micronutrients is null or jsonb_typeof(micronutrients) = 'object');
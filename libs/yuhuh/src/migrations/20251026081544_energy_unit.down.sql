@@ -0,0 +1,3 @@
+-- Add down migration script here
+alter table food_records drop column if exists energy_unit;
+alter table users drop column if exists energy_unit;
@@ -0,0 +1,13 @@
+-- Calories are stored as kilocalories, regardless of what the user logged them
+-- in. We keep the unit they were entered with so the original value can be
+-- recovered, and any existing rows are assumed to have been kilocalories.
+alter table food_records
+    add column energy_unit text not null default 'kcal'
+    constraint food_records_energy_unit_check check (energy_unit in ('kcal', 'kJ'));
+
+comment on column food_records.calories is 'Energy in kilocalories';
+
+-- Unit the user would like energy to be displayed in
+alter table users
+    add column energy_unit text not null default 'kcal'
+    constraint users_energy_unit_check check (energy_unit in ('kcal', 'kJ'));
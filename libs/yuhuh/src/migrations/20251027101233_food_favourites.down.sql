@@ -0,0 +1,3 @@
+-- Add down migration script here
+drop index if exists food_records_user_description_idx;
+drop table if exists food_favourites;
@@ -0,0 +1,42 @@
+-- Foods a user has saved for quick re-logging
+create table food_favourites
+(
+    -- ID of the favourite
+    food_favourite_id   uuid    primary key default uuidv7(),
+
+    -- User this favourite belongs to
+    user_id             uuid    not null,
+
+    -- Time the favourite was created
+    created_at          timestamptz not null default now(),
+
+    -- Last time the favourite was updated, pretty self explanatory
+    updated_at          timestamptz,
+
+    -- Description logged when the favourite is used
+    description         text    not null,
+
+    -- Energy in kilocalories, same as food_records
+    calories            real,
+
+    -- Macronutrients
+    carbs               real,
+    protein             real,
+    fats                real,
+
+    -- Micronutrients, in the same shape as food_records
+    micronutrients      jsonb,
+
+    CONSTRAINT fk_food_favourites_user_id FOREIGN KEY(user_id) REFERENCES users(user_id) ON DELETE CASCADE
+);
+
+-- A user shouldn't end up with the same favourite twice
+create unique index food_favourites_user_description_idx
+    on food_favourites (user_id, lower(description));
+
+-- Apply our `updated_at` trigger from the first migration
+SELECT trigger_updated_at('"food_favourites"');
+
+-- Recent and frequent foods group a user's records by description
+create index food_records_user_description_idx
+    on food_records (user_id, lower(description), logged_at desc);
@@ -0,0 +1,2 @@
+drop table if exists eating_windows;
+drop table if exists fasts;
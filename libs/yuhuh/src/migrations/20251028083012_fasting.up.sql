@@ -0,0 +1,59 @@
+-- Fasts a user has started, and possibly stopped
+create table fasts
+(
+    -- ID of the fast
+    fast_id             uuid    primary key default uuidv7(),
+
+    -- User this fast belongs to
+    user_id             uuid    not null,
+
+    -- Time the fast was created
+    created_at          timestamptz not null default now(),
+
+    -- Last time the fast was updated, pretty self explanatory
+    updated_at          timestamptz,
+
+    -- Time the fast started
+    started_at          timestamptz not null,
+
+    -- Time the fast was broken, null while it's still going
+    ended_at            timestamptz,
+
+    -- Optional goal for how long the fast should last
+    target_hours        real,
+
+    CONSTRAINT fk_fasts_user_id FOREIGN KEY(user_id) REFERENCES users(user_id) ON DELETE CASCADE,
+    CONSTRAINT fasts_end_after_start CHECK (ended_at IS NULL OR ended_at > started_at),
+    CONSTRAINT fasts_target_positive CHECK (target_hours IS NULL OR target_hours > 0)
+);
+
+-- Only a single fast can be in progress at a time
+create unique index fasts_in_progress_idx on fasts (user_id) where ended_at is null;
+
+create index fasts_user_started_at_idx on fasts (user_id, started_at desc);
+
+-- Apply our `updated_at` trigger from the first migration
+SELECT trigger_updated_at('"fasts"');
+
+-- Recurring daily eating window, anything outside of it is a fast
+create table eating_windows
+(
+    -- User this window belongs to, a user only has the one window
+    user_id             uuid    primary key,
+
+    -- Time the window was created
+    created_at          timestamptz not null default now(),
+
+    -- Last time the window was updated, pretty self explanatory
+    updated_at          timestamptz,
+
+    -- Local times in the user's timezone, windows may wrap past midnight
+    opens_at            time    not null,
+    closes_at           time    not null,
+
+    CONSTRAINT fk_eating_windows_user_id FOREIGN KEY(user_id) REFERENCES users(user_id) ON DELETE CASCADE,
+    CONSTRAINT eating_windows_not_empty CHECK (opens_at <> closes_at)
+);
+
+-- Apply our `updated_at` trigger from the first migration
+SELECT trigger_updated_at('"eating_windows"');
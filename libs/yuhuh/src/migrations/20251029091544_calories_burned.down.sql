@@ -0,0 +1,2 @@
+alter table activity_records drop column if exists calories_burned;
+alter table users drop column if exists body_weight_kg;
@@ -0,0 +1,9 @@
+-- Body weight used to estimate calories burned by activities, in kilograms
+alter table users
+    add column body_weight_kg real
+    CONSTRAINT users_body_weight_positive CHECK (body_weight_kg IS NULL OR body_weight_kg > 0);
+
+-- Estimated energy burned by the activity in kilocalories, null when there
+-- wasn't enough information to estimate it
+alter table activity_records
+    add column calories_burned real;
@@ -0,0 +1,44 @@
+-- Strength personal records, a row is added each time a best is beaten so the
+-- full history is kept
+create table personal_records
+(
+    -- ID of the personal record
+    personal_record_id  uuid    primary key default uuidv7(),
+
+    -- User this record belongs to
+    user_id             uuid    not null,
+
+    -- Activity the record was set in
+    activity_record_id  uuid    not null,
+
+    -- Time the record was created
+    created_at          timestamptz not null default now(),
+
+    -- Last time the record was updated, pretty self explanatory
+    updated_at          timestamptz,
+
+    -- Normalised (trimmed and lowercased) exercise name
+    exercise            text    not null,
+
+    -- What was beaten, best weight, estimated one rep max or best volume
+    record_type         text    not null,
+
+    -- New best in kilograms
+    value               real    not null,
+
+    -- Best this record beat, null for the first time an exercise is done
+    previous_value      real,
+
+    -- Time the activity was logged at
+    achieved_at         timestamptz not null,
+
+    CONSTRAINT fk_personal_records_user_id FOREIGN KEY(user_id) REFERENCES users(user_id) ON DELETE CASCADE,
+    CONSTRAINT fk_personal_records_activity_record_id FOREIGN KEY(activity_record_id) REFERENCES activity_records(activity_record_id) ON DELETE CASCADE,
+    CONSTRAINT personal_records_value_positive CHECK (value > 0)
+);
+
+create index personal_records_user_exercise_idx
+    on personal_records (user_id, exercise, record_type, value desc);
+
+-- Apply our `updated_at` trigger from the first migration
+SELECT trigger_updated_at('"personal_records"');
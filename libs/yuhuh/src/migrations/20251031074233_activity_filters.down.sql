@@ -0,0 +1,3 @@
+drop index if exists activity_records_activity_info_idx;
+drop index if exists activity_records_activity_trgm_idx;
+drop index if exists activity_records_user_type_logged_at_idx;
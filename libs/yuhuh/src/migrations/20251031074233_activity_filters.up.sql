@@ -0,0 +1,14 @@
+-- Trigram matching for searching activity names
+create extension if not exists pg_trgm;
+
+-- Reads filtered down to a handful of activity types
+create index activity_records_user_type_logged_at_idx
+    on activity_records (user_id, activity_type, logged_at desc);
+
+-- Substring searches on activity names
+create index activity_records_activity_trgm_idx
+    on activity_records using gin (activity gin_trgm_ops);
+
+-- JSON path predicates on activity_info
+create index activity_records_activity_info_idx
+    on activity_records using gin (activity_info jsonb_path_ops);
@@ -0,0 +1,67 @@
+-- Custom activity types extending the built-in ones, which stay as plain text
+-- in activity_records.activity_type
+create table activity_types
+(
+    -- ID of the activity type
+    activity_type_id        uuid    primary key default uuidv7(),
+
+    -- User this type belongs to, null for types available to everyone
+    user_id                 uuid,
+
+    -- Time the type was created
+    created_at              timestamptz not null default now(),
+
+    -- Last time the type was updated, pretty self explanatory
+    updated_at              timestamptz,
+
+    -- Name stored in activity_records.activity_type
+    name                    text    not null,
+
+    -- Optional JSON Schema activity_info must match
+    activity_info_schema    jsonb,
+
+    -- Optional MET used to estimate calories burned
+    default_met             real,
+
+    CONSTRAINT fk_activity_types_user_id FOREIGN KEY(user_id) REFERENCES users(user_id) ON DELETE CASCADE,
+    CONSTRAINT activity_types_default_met_positive CHECK (default_met IS NULL OR default_met > 0)
+);
+
+-- Names are unique among global types, and among each user's own types
+create unique index activity_types_global_name_idx
+    on activity_types (lower(name)) where user_id is null;
+
+create unique index activity_types_user_name_idx
+    on activity_types (user_id, lower(name)) where user_id is not null;
+
+-- Apply our `updated_at` trigger from the first migration
+SELECT trigger_updated_at('"activity_types"');
+
+-- A couple of common types everyone gets
+insert into activity_types (name, activity_info_schema, default_met)
+values
+    (
+        'Swimming',
+        '{
+            "type": "object",
+            "properties": {
+                "distance_m": { "type": "number", "exclusiveMinimum": 0 },
+                "duration_seconds": { "type": "integer", "minimum": 1 },
+                "stroke": { "type": "string" }
+            },
+            "additionalProperties": false
+        }'::jsonb,
+        7.0
+    ),
+    (
+        'Yoga',
+        '{
+            "type": "object",
+            "properties": {
+                "duration_seconds": { "type": "integer", "minimum": 1 },
+                "style": { "type": "string" }
+            },
+            "additionalProperties": false
+        }'::jsonb,
+        2.5
+    );
@@ -0,0 +1,5 @@
+drop table if exists activity_samples;
+alter table activity_records drop column if exists sample_summary;
+alter table users drop constraint if exists users_resting_below_max_heart_rate;
+alter table users drop column if exists resting_heart_rate;
+alter table users drop column if exists max_heart_rate;
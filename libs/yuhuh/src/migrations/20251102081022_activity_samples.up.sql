@@ -0,0 +1,45 @@
+-- Heart rate settings used to work out heart rate zones, in beats per minute
+alter table users
+    add column max_heart_rate smallint
+    CONSTRAINT users_max_heart_rate_positive CHECK (max_heart_rate IS NULL OR max_heart_rate > 0);
+
+alter table users
+    add column resting_heart_rate smallint
+    CONSTRAINT users_resting_heart_rate_positive CHECK (resting_heart_rate IS NULL OR resting_heart_rate > 0);
+
+alter table users
+    add CONSTRAINT users_resting_below_max_heart_rate
+    CHECK (resting_heart_rate IS NULL OR max_heart_rate IS NULL OR resting_heart_rate < max_heart_rate);
+
+-- Summary of an activity's samples, kept on the activity so reads don't need
+-- to go through every sample
+alter table activity_records
+    add column sample_summary jsonb;
+
+-- Time series recorded during an activity, kept out of activity_info so it
+-- doesn't bloat every read
+create table activity_samples
+(
+    -- Activity the sample was recorded during
+    activity_record_id  uuid    not null,
+
+    -- Seconds since the start of the activity
+    offset_seconds      integer not null,
+
+    -- Beats per minute
+    heart_rate          smallint,
+
+    -- Watts
+    power_watts         real,
+
+    -- Revolutions or steps per minute
+    cadence             real,
+
+    -- Kilometres per hour
+    speed_kmh           real,
+
+    primary key (activity_record_id, offset_seconds),
+
+    CONSTRAINT fk_activity_samples_activity_record_id FOREIGN KEY(activity_record_id) REFERENCES activity_records(activity_record_id) ON DELETE CASCADE,
+    CONSTRAINT activity_samples_offset_not_negative CHECK (offset_seconds >= 0)
+);
@@ -0,0 +1,21 @@
+-- Emotions and context tags attached to mood entries
+create table mood_tags
+(
+    -- Entry the tag is attached to
+    mood_record_id      uuid    not null,
+
+    -- Either 'emotion', from a fixed vocabulary, or a free-form 'context' tag
+    kind                text    not null,
+
+    -- Lowercased name of the tag
+    tag                 text    not null,
+
+    primary key (mood_record_id, kind, tag),
+
+    CONSTRAINT fk_mood_tags_mood_record_id FOREIGN KEY(mood_record_id) REFERENCES mood_records(mood_record_id) ON DELETE CASCADE,
+    CONSTRAINT mood_tags_kind CHECK (kind IN ('emotion', 'context')),
+    CONSTRAINT mood_tags_tag_lowercase CHECK (tag = lower(tag) AND length(tag) > 0)
+);
+
+-- Find entries by tag
+create index mood_tags_tag_idx on mood_tags (tag, mood_record_id);
@@ -0,0 +1,4 @@
+drop table if exists rating_scales;
+alter table mood_records alter column sleep type smallint using round(sleep * 10);
+alter table mood_records alter column energy type smallint using round(energy * 10);
+alter table mood_records alter column mood type smallint using round(mood * 10);
@@ -0,0 +1,36 @@
+-- Ratings are stored normalised from 0 to 1, whatever scale the user gives
+-- them on. Existing ratings were all given out of 10.
+alter table mood_records alter column mood type real using mood / 10.0;
+alter table mood_records alter column energy type real using energy / 10.0;
+alter table mood_records alter column sleep type real using sleep / 10.0;
+
+-- Scales users rate on, with 0 to 10 used where there isn't one
+create table rating_scales
+(
+    -- User the scale belongs to
+    user_id             uuid    not null,
+
+    -- One of 'mood', 'energy' or 'sleep'
+    metric              text    not null,
+
+    -- Lowest rating on the scale
+    min_rating          smallint not null,
+
+    -- Highest rating on the scale
+    max_rating          smallint not null,
+
+    -- Time the scale was created
+    created_at          timestamptz not null default now(),
+
+    -- Last time the scale was changed
+    updated_at          timestamptz,
+
+    primary key (user_id, metric),
+
+    CONSTRAINT fk_rating_scales_user_id FOREIGN KEY(user_id) REFERENCES users(user_id) ON DELETE CASCADE,
+    CONSTRAINT rating_scales_metric CHECK (metric IN ('mood', 'energy', 'sleep')),
+    CONSTRAINT rating_scales_min_below_max CHECK (min_rating < max_rating)
+);
+
+-- Apply our `updated_at` trigger from the first migration
+SELECT trigger_updated_at('"rating_scales"');
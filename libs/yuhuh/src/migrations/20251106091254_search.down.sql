@@ -0,0 +1,6 @@
+drop index if exists activity_records_search_vector_idx;
+drop index if exists food_records_search_vector_idx;
+drop index if exists mood_records_search_vector_idx;
+alter table activity_records drop column if exists search_vector;
+alter table food_records drop column if exists search_vector;
+alter table mood_records drop column if exists search_vector;
@@ -0,0 +1,18 @@
+-- Text searched on each record kind, kept up to date by Postgres. The main
+-- text of every kind is weighted the same so ranks compare across kinds, with
+-- text inside activity_info weighted below an activity's name.
+alter table mood_records add column search_vector tsvector
+    generated always as (setweight(to_tsvector('english', coalesce(notes, '')), 'A')) stored;
+
+alter table food_records add column search_vector tsvector
+    generated always as (setweight(to_tsvector('english', description), 'A')) stored;
+
+alter table activity_records add column search_vector tsvector
+    generated always as (
+        setweight(to_tsvector('english', activity), 'A')
+        || setweight(jsonb_to_tsvector('english', activity_info, '["string"]'), 'B')
+    ) stored;
+
+create index mood_records_search_vector_idx on mood_records using gin (search_vector);
+create index food_records_search_vector_idx on food_records using gin (search_vector);
+create index activity_records_search_vector_idx on activity_records using gin (search_vector);
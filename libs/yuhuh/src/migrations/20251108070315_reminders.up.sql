@@ -0,0 +1,40 @@
+-- Daily check-in reminders a user has asked for
+create table reminder_rules
+(
+    -- ID of the rule
+    reminder_rule_id    uuid    primary key default uuidv7(),
+
+    -- User this rule belongs to
+    user_id             uuid    not null,
+
+    -- Time the rule was created
+    created_at          timestamptz not null default now(),
+
+    -- Last time the rule was updated, pretty self explanatory
+    updated_at          timestamptz,
+
+    -- Kind of entry the reminder asks for, one of 'mood', 'food' or 'activity'
+    entry_kind          text    not null,
+
+    -- Local time in the user's timezone the reminder is due
+    remind_at           time    not null,
+
+    -- Local time from which an entry counts as already logged, the start of
+    -- the day when null
+    logged_since        time,
+
+    -- Optional message sent with the reminder
+    message             text,
+
+    -- Local date the rule last fired or was skipped on
+    last_fired_on       date,
+
+    CONSTRAINT fk_reminder_rules_user_id FOREIGN KEY(user_id) REFERENCES users(user_id) ON DELETE CASCADE,
+    CONSTRAINT reminder_rules_entry_kind CHECK (entry_kind IN ('mood', 'food', 'activity')),
+    CONSTRAINT reminder_rules_logged_since_before CHECK (logged_since IS NULL OR logged_since < remind_at)
+);
+
+create index reminder_rules_user_id_idx on reminder_rules (user_id);
+
+-- Apply our `updated_at` trigger from the first migration
+SELECT trigger_updated_at('"reminder_rules"');
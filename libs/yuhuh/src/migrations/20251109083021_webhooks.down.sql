@@ -0,0 +1,4 @@
+drop table if exists webhook_delivery_attempts;
+drop table if exists webhook_deliveries;
+drop table if exists webhook_outbox;
+drop table if exists webhook_subscriptions;
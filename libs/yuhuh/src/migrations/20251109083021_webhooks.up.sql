@@ -0,0 +1,123 @@
+-- Webhook URLs integrators have registered to receive events on
+create table webhook_subscriptions
+(
+    -- ID of the webhook
+    webhook_id          uuid    primary key default uuidv7(),
+
+    -- User whose events are sent, every user's when null
+    user_id             uuid,
+
+    -- Time the webhook was registered
+    created_at          timestamptz not null default now(),
+
+    -- Last time the webhook was updated, pretty self explanatory
+    updated_at          timestamptz,
+
+    -- URL events are posted to
+    url                 text    not null,
+
+    -- Shared secret each delivery is signed with
+    secret              text    not null,
+
+    -- Event types sent to the webhook, every type when empty
+    event_types         text[]  not null default '{}',
+
+    CONSTRAINT fk_webhook_subscriptions_user_id FOREIGN KEY(user_id) REFERENCES users(user_id) ON DELETE CASCADE
+);
+
+create index webhook_subscriptions_user_id_idx on webhook_subscriptions (user_id);
+
+-- Apply our `updated_at` trigger from the first migration
+SELECT trigger_updated_at('"webhook_subscriptions"');
+
+-- Events waiting to be sent, written in the same transaction as the change
+-- they describe so none are lost or sent for changes that were rolled back
+create table webhook_outbox
+(
+    -- ID of the event, sent to receivers
+    event_id            uuid    primary key,
+
+    -- Type of event, e.g. 'food.created'
+    event_type          text    not null,
+
+    -- User the event is about
+    user_id             uuid    not null,
+
+    -- Time the event happened
+    created_at          timestamptz not null default now(),
+
+    -- JSON body sent to receivers
+    payload             jsonb   not null,
+
+    -- Time deliveries were queued for every matching webhook
+    processed_at        timestamptz
+);
+
+create index webhook_outbox_unprocessed_idx on webhook_outbox (event_id) where processed_at is null;
+
+-- An event to be sent to one webhook
+create table webhook_deliveries
+(
+    -- ID of the delivery, sent to receivers
+    delivery_id         uuid    primary key default uuidv7(),
+
+    -- Event being delivered
+    event_id            uuid    not null,
+
+    -- Webhook the event is delivered to
+    webhook_id          uuid    not null,
+
+    -- Time the delivery was queued
+    created_at          timestamptz not null default now(),
+
+    -- Last time the delivery was updated, pretty self explanatory
+    updated_at          timestamptz,
+
+    -- Number of attempts made so far
+    attempts            integer not null default 0,
+
+    -- Time of the next attempt
+    next_attempt_at     timestamptz not null default now(),
+
+    -- Time the receiver accepted the event
+    delivered_at        timestamptz,
+
+    -- Time the delivery was given up on after running out of attempts
+    failed_at           timestamptz,
+
+    CONSTRAINT fk_webhook_deliveries_event_id FOREIGN KEY(event_id) REFERENCES webhook_outbox(event_id) ON DELETE CASCADE,
+    CONSTRAINT fk_webhook_deliveries_webhook_id FOREIGN KEY(webhook_id) REFERENCES webhook_subscriptions(webhook_id) ON DELETE CASCADE,
+    CONSTRAINT webhook_deliveries_event_webhook UNIQUE (event_id, webhook_id)
+);
+
+create index webhook_deliveries_webhook_id_idx on webhook_deliveries (webhook_id);
+create index webhook_deliveries_pending_idx on webhook_deliveries (next_attempt_at) where delivered_at is null and failed_at is null;
+
+-- Apply our `updated_at` trigger from the first migration
+SELECT trigger_updated_at('"webhook_deliveries"');
+
+-- Log of every attempt to deliver an event
+create table webhook_delivery_attempts
+(
+    -- ID of the attempt
+    delivery_attempt_id uuid    primary key default uuidv7(),
+
+    -- Delivery the attempt was for
+    delivery_id         uuid    not null,
+
+    -- Time the attempt was made
+    attempted_at        timestamptz not null,
+
+    -- HTTP status the receiver responded with, null when it didn't respond
+    status_code         smallint,
+
+    -- Why the attempt failed, null when it succeeded
+    error               text,
+
+    -- How long the attempt took
+    duration_ms         integer not null,
+
+    CONSTRAINT fk_webhook_delivery_attempts_delivery_id FOREIGN KEY(delivery_id) REFERENCES webhook_deliveries(delivery_id) ON DELETE CASCADE
+);
+
+create index webhook_delivery_attempts_delivery_id_idx on webhook_delivery_attempts (delivery_id, attempted_at);
@@ -0,0 +1,3 @@
+drop index if exists webhook_outbox_user_id_idx;
+drop trigger if exists webhook_outbox_notify on webhook_outbox;
+drop function if exists notify_outbox_event();
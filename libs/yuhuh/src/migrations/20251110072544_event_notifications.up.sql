@@ -0,0 +1,28 @@
+-- Notify listeners of every event written to the outbox, so live feeds on
+-- any instance see changes as soon as they're committed. Only the IDs are
+-- sent as payloads are limited to 8000 bytes; listeners read the event back
+-- from the outbox.
+create or replace function notify_outbox_event()
+    returns trigger as
+$$
+begin
+    perform pg_notify(
+        'yuhuh_events',
+        json_build_object(
+            'event_id', NEW.event_id,
+            'user_id', NEW.user_id,
+            'event_type', NEW.event_type
+        )::text
+    );
+    return null;
+end;
+$$ language plpgsql;
+
+create trigger webhook_outbox_notify
+    after insert
+    on webhook_outbox
+    for each row
+execute function notify_outbox_event();
+
+-- Events a user's live feed is replayed from when reconnecting
+create index webhook_outbox_user_id_idx on webhook_outbox (user_id, event_id);
@@ -0,0 +1,16 @@
+create table discord_users
+(
+    discord_id  bigint      primary key,
+    username    text        not null,
+    user_id     uuid        unique not null references "users" (user_id)
+);
+
+-- Only one discord account per user fits, any others are lost
+insert into discord_users (discord_id, username, user_id)
+select external_id::bigint, coalesce(username, ''), user_id
+from identities
+where provider = 'discord'
+order by created_at
+on conflict do nothing;
+
+drop table if exists identities;
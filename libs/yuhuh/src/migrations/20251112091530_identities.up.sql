@@ -0,0 +1,41 @@
+-- Accounts at other providers users sign on with, replacing `discord_users`
+-- so a user can be reached through more than just discord
+create table identities
+(
+    -- ID of the identity
+    identity_id         uuid    primary key default uuidv7(),
+
+    -- Reference to the yuhuh user
+    user_id             uuid    not null,
+
+    -- Where the account is, one of 'discord', 'telegram', 'slack' or 'email'
+    provider            text    not null
+        constraint identities_provider_check check (provider in ('discord', 'telegram', 'slack', 'email')),
+
+    -- ID of the account at the provider, e.g. a discord snowflake or a
+    -- lowercased email address
+    external_id         text    not null,
+
+    -- Name of the account at the provider, if it has one
+    username            text,
+
+    -- Time the identity was linked
+    created_at          timestamptz not null default now(),
+
+    -- Last time the identity was updated, pretty self explanatory
+    updated_at          timestamptz,
+
+    CONSTRAINT fk_identities_user_id FOREIGN KEY(user_id) REFERENCES users(user_id) ON DELETE CASCADE,
+    CONSTRAINT identities_provider_external_id_key UNIQUE (provider, external_id)
+);
+
+create index identities_user_id_idx on identities (user_id);
+
+-- Apply our `updated_at` trigger from the first migration
+SELECT trigger_updated_at('"identities"');
+
+insert into identities (user_id, provider, external_id, username)
+select user_id, 'discord', discord_id::text, username
+from discord_users;
+
+drop table discord_users;
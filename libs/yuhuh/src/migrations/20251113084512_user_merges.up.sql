@@ -0,0 +1,24 @@
+-- Audit trail of users merged into one another. Neither user is a foreign
+-- key, so the trail outlives them
+create table user_merges
+(
+    -- ID of the merge
+    merge_id            uuid    primary key default uuidv7(),
+
+    -- User whose records were moved, deleted by the merge
+    source_user_id      uuid    not null,
+
+    -- User the records were moved to
+    target_user_id      uuid    not null,
+
+    -- Time of the merge
+    merged_at           timestamptz not null default now(),
+
+    -- Number of each kind of record moved
+    counts              jsonb   not null,
+
+    -- The source user as it was before the merge, with its identities
+    source_user         jsonb   not null
+);
+
+create index user_merges_target_user_id_idx on user_merges (target_user_id, merged_at desc);
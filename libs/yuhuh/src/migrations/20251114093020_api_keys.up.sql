@@ -0,0 +1,38 @@
+-- Keys service clients authenticate to the API with
+create table api_keys
+(
+    -- ID of the key
+    api_key_id          uuid    primary key default uuidv7(),
+
+    -- Time the key was created
+    created_at          timestamptz not null default now(),
+
+    -- Last time the key was updated, pretty self explanatory
+    updated_at          timestamptz,
+
+    -- What the key is for, e.g. 'discord bot'
+    name                text    not null,
+
+    -- First characters of the key, to tell keys apart without storing them
+    prefix              text    not null,
+
+    -- Hex encoded SHA-256 of the key, the key itself is never stored
+    key_hash            text    not null,
+
+    -- What the key can do, any of 'read', 'write' and 'admin'
+    scopes              text[]  not null,
+
+    -- Time the key stops working, never when null
+    expires_at          timestamptz,
+
+    -- Last time the key was used
+    last_used_at        timestamptz,
+
+    CONSTRAINT api_keys_key_hash_key UNIQUE (key_hash),
+    CONSTRAINT api_keys_scopes_check CHECK (
+        cardinality(scopes) > 0 AND scopes <@ ARRAY['read', 'write', 'admin']
+    )
+);
+
+-- Apply our `updated_at` trigger from the first migration
+SELECT trigger_updated_at('"api_keys"');
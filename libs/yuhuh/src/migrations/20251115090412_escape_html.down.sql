@@ -0,0 +1 @@
+drop function if exists escape_html(text);
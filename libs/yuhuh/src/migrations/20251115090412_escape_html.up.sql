@@ -0,0 +1,12 @@
+-- Escapes text for use in HTML, so search headlines can wrap matches in tags
+-- without anything users wrote in their records being read as markup.
+create or replace function escape_html(text)
+    returns text as
+$$
+    select replace(replace(replace(replace(replace($1,
+        '&', '&amp;'),
+        '<', '&lt;'),
+        '>', '&gt;'),
+        '"', '&quot;'),
+        '''', '&#39;');
+$$ language sql immutable strict;
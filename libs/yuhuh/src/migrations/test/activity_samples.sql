@@ -0,0 +1,64 @@
+-- Create users for activity_samples
+INSERT INTO
+    users (
+        user_id,
+        personalisation,
+        contact_email,
+        contact_name,
+        created_at,
+        updated_at,
+        timezone,
+        max_heart_rate,
+        resting_heart_rate
+    )
+VALUES
+    (
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        NULL,
+        'alice@example.com',
+        'Alice',
+        now() - interval '30 days',
+        now(),
+        'UTC',
+        190,
+        50
+    ),
+    (
+        '22222222-2222-2222-2222-222222222222'::uuid,
+        NULL,
+        'bobat@example.com',
+        'Bobat',
+        now() - interval '30 days',
+        now(),
+        'UTC',
+        NULL,
+        NULL
+    );
+
+-- Alice and Bobat each went for a run
+INSERT INTO
+    activity_records (
+        activity_record_id,
+        user_id,
+        activity,
+        activity_type,
+        activity_info,
+        logged_at
+    )
+VALUES
+    (
+        'aaaaaaaa-aaaa-aaaa-aaaa-aaaaaaaaaaaa'::uuid,
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        'morning run',
+        'Running',
+        '{"distance_km": 5.0, "duration_seconds": 1800}'::jsonb,
+        now() - interval '1 day'
+    ),
+    (
+        'bbbbbbbb-bbbb-bbbb-bbbb-bbbbbbbbbbbb'::uuid,
+        '22222222-2222-2222-2222-222222222222'::uuid,
+        'evening run',
+        'Running',
+        '{"distance_km": 3.0, "duration_seconds": 1200}'::jsonb,
+        now() - interval '1 day'
+    );
@@ -0,0 +1,79 @@
+-- Create users for correlations
+INSERT INTO
+    users (
+        user_id,
+        personalisation,
+        contact_email,
+        contact_name,
+        created_at,
+        updated_at,
+        timezone
+    )
+VALUES
+    (
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        NULL,
+        'alice@example.com',
+        'Alice',
+        now() - interval '30 days',
+        now(),
+        'UTC'
+    );
+
+-- Twenty days of ratings from 1 March 2025
+--
+-- Mood is whatever sleep was the day before, and energy never changes.
+-- Ratings are stored normalised.
+INSERT INTO
+    mood_records (
+        user_id,
+        mood,
+        energy,
+        sleep,
+        logged_at
+    )
+SELECT
+    '11111111-1111-1111-1111-111111111111'::uuid,
+    CASE
+        WHEN d = 0 THEN 0.5
+        ELSE ((d - 1) * 3 % 7) / 10.0
+    END,
+    0.5,
+    (d * 3 % 7) / 10.0,
+    (date '2025-03-01' + d + time '09:00') at time zone 'UTC'
+FROM generate_series(0, 19) AS d;
+
+-- Food for only the first ten days, two meals a day
+INSERT INTO
+    food_records (
+        user_id,
+        description,
+        calories,
+        protein,
+        logged_at
+    )
+SELECT
+    '11111111-1111-1111-1111-111111111111'::uuid,
+    'meal',
+    1000.0 + 50.0 * d,
+    40.0,
+    (date '2025-03-01' + d + meal) at time zone 'UTC'
+FROM generate_series(0, 9) AS d,
+    (VALUES (time '08:00'), (time '18:00')) AS meals (meal);
+
+-- Half hour runs every other day
+INSERT INTO
+    activity_records (
+        user_id,
+        activity,
+        activity_type,
+        activity_info,
+        logged_at
+    )
+SELECT
+    '11111111-1111-1111-1111-111111111111'::uuid,
+    'easy run',
+    'Running',
+    '{"distance_km": 5.0, "duration_seconds": 1800}'::jsonb,
+    (date '2025-03-01' + d + time '17:00') at time zone 'UTC'
+FROM generate_series(0, 19, 2) AS d;
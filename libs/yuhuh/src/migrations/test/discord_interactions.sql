@@ -0,0 +1,31 @@
+-- Create users for discord interactions
+INSERT INTO
+    users (
+        user_id,
+        personalisation,
+        contact_email,
+        contact_name,
+        created_at,
+        updated_at,
+        timezone
+    )
+VALUES
+    (
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        NULL,
+        'alice@example.com',
+        'Alice',
+        now() - interval '30 days',
+        now(),
+        'UTC'
+    );
+
+INSERT INTO
+    identities (user_id, provider, external_id, username)
+VALUES
+    (
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        'discord',
+        '100',
+        'alicediscord'
+    );
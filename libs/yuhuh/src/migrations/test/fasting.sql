@@ -0,0 +1,90 @@
+-- Create users for fasting
+INSERT INTO
+    users (
+        user_id,
+        personalisation,
+        contact_email,
+        contact_name,
+        created_at,
+        updated_at,
+        timezone
+    )
+VALUES
+    (
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        NULL,
+        'alice@example.com',
+        'Alice',
+        now() - interval '10 days',
+        now(),
+        'Australia/Brisbane'
+    ),
+    (
+        '22222222-2222-2222-2222-222222222222'::uuid,
+        NULL,
+        'bobat@example.com',
+        'Bobat',
+        now() - interval '10 days',
+        now(),
+        'UTC'
+    );
+
+-- Alice has fasted the last three days, broke a fast early five days ago and
+-- managed two days in a row the week before
+INSERT INTO
+    fasts (
+        user_id,
+        started_at,
+        ended_at,
+        target_hours
+    )
+VALUES
+    (
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        now() - interval '1 day 16 hours',
+        now() - interval '1 day',
+        16
+    ),
+    (
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        now() - interval '2 days 16 hours',
+        now() - interval '2 days',
+        16
+    ),
+    (
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        now() - interval '3 days 14 hours',
+        now() - interval '3 days',
+        NULL
+    ),
+    (
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        now() - interval '5 days 10 hours',
+        now() - interval '5 days',
+        16
+    ),
+    (
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        now() - interval '7 days 16 hours',
+        now() - interval '7 days',
+        16
+    ),
+    (
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        now() - interval '8 days 16 hours',
+        now() - interval '8 days',
+        16
+    );
+
+INSERT INTO
+    eating_windows (
+        user_id,
+        opens_at,
+        closes_at
+    )
+VALUES
+    (
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        '12:00',
+        '20:00'
+    );
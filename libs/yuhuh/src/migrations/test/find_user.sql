@@ -30,10 +30,11 @@ VALUES
     );
 
 INSERT INTO
-	discord_users (discord_id, username, user_id)
+    identities (user_id, provider, external_id, username)
 VALUES
-	(
-		100,
-		'alicediscord',
-		'11111111-1111-1111-1111-111111111111'::uuid
-	);
\ No newline at end of file
+    (
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        'discord',
+        '100',
+        'alicediscord'
+    );
\ No newline at end of file
@@ -0,0 +1,166 @@
+-- Create users for merge_users, Bobat registered a second time
+INSERT INTO
+    users (
+        user_id,
+        personalisation,
+        contact_email,
+        contact_name,
+        created_at,
+        updated_at,
+        timezone
+    )
+VALUES
+    (
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        NULL,
+        'alice@example.com',
+        'Alice',
+        now() - interval '1 day',
+        now(),
+        'UTC'
+    ),
+    (
+        '22222222-2222-2222-2222-222222222222'::uuid,
+        NULL,
+        'alice@example.com',
+        'Alice',
+        now(),
+        now(),
+        'UTC'
+    );
+
+INSERT INTO
+    identities (user_id, provider, external_id, username)
+VALUES
+    (
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        'discord',
+        '100',
+        'alicediscord'
+    ),
+    (
+        '22222222-2222-2222-2222-222222222222'::uuid,
+        'email',
+        'alice@example.com',
+        NULL
+    );
+
+INSERT INTO
+    food_records (user_id, description, calories, logged_at)
+VALUES
+    (
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        'porridge',
+        350,
+        now() - interval '1 day'
+    ),
+    (
+        '22222222-2222-2222-2222-222222222222'::uuid,
+        'toast',
+        200,
+        now() - interval '2 hours'
+    ),
+    (
+        '22222222-2222-2222-2222-222222222222'::uuid,
+        'soup',
+        300,
+        now() - interval '1 hour'
+    );
+
+INSERT INTO
+    food_favourites (user_id, description, calories)
+VALUES
+    ('11111111-1111-1111-1111-111111111111'::uuid, 'Porridge', 350),
+    ('22222222-2222-2222-2222-222222222222'::uuid, 'porridge', 300),
+    ('22222222-2222-2222-2222-222222222222'::uuid, 'toast', 200);
+
+INSERT INTO
+    mood_records (user_id, mood, notes, logged_at)
+VALUES
+    (
+        '22222222-2222-2222-2222-222222222222'::uuid,
+        6,
+        'fine',
+        now() - interval '3 hours'
+    );
+
+INSERT INTO
+    activity_types (user_id, name)
+VALUES
+    ('11111111-1111-1111-1111-111111111111'::uuid, 'Climbing'),
+    ('22222222-2222-2222-2222-222222222222'::uuid, 'climbing'),
+    ('22222222-2222-2222-2222-222222222222'::uuid, 'Juggling');
+
+INSERT INTO
+    activity_records (
+        user_id,
+        activity,
+        activity_type,
+        activity_info,
+        logged_at
+    )
+VALUES
+    (
+        '22222222-2222-2222-2222-222222222222'::uuid,
+        'bouldering',
+        'climbing',
+        '{}'::jsonb,
+        now() - interval '4 hours'
+    );
+
+INSERT INTO
+    fasts (user_id, started_at, ended_at, target_hours)
+VALUES
+    (
+        '22222222-2222-2222-2222-222222222222'::uuid,
+        now() - interval '2 days',
+        now() - interval '1 day 8 hours',
+        16
+    ),
+    (
+        '22222222-2222-2222-2222-222222222222'::uuid,
+        now() - interval '30 minutes',
+        NULL,
+        16
+    );
+
+INSERT INTO
+    reminder_rules (user_id, entry_kind, remind_at)
+VALUES
+    ('11111111-1111-1111-1111-111111111111'::uuid, 'mood', '20:00'),
+    ('22222222-2222-2222-2222-222222222222'::uuid, 'mood', '20:00'),
+    ('22222222-2222-2222-2222-222222222222'::uuid, 'food', '13:00');
+
+INSERT INTO
+    webhook_subscriptions (user_id, url, secret, event_types)
+VALUES
+    (
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        'https://example.com/hooks',
+        'alice-secret',
+        '{}'
+    ),
+    (
+        '22222222-2222-2222-2222-222222222222'::uuid,
+        'https://example.com/hooks',
+        'bobat-secret',
+        '{}'
+    ),
+    (
+        '22222222-2222-2222-2222-222222222222'::uuid,
+        'https://example.org/hooks',
+        'bobat-secret',
+        '{}'
+    );
+
+INSERT INTO
+    rating_scales (user_id, metric, min_rating, max_rating)
+VALUES
+    ('11111111-1111-1111-1111-111111111111'::uuid, 'mood', 1, 5),
+    ('22222222-2222-2222-2222-222222222222'::uuid, 'mood', 1, 10),
+    ('22222222-2222-2222-2222-222222222222'::uuid, 'energy', 1, 5);
+
+INSERT INTO
+    eating_windows (user_id, opens_at, closes_at)
+VALUES
+    ('22222222-2222-2222-2222-222222222222'::uuid, '08:00', '18:00');
@@ -0,0 +1,57 @@
+-- Create users for mood_stats
+INSERT INTO
+    users (
+        user_id,
+        personalisation,
+        contact_email,
+        contact_name,
+        created_at,
+        updated_at,
+        timezone
+    )
+VALUES
+    (
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        NULL,
+        'alice@example.com',
+        'Alice',
+        now() - interval '1 day',
+        now(),
+        'Pacific/Auckland'
+    );
+
+-- Create mood entries
+--
+-- Alice is 12 hours ahead of UTC in June, so the first entry lands on
+-- Monday 2 June and the other two on Tuesday 3 June. Ratings are stored
+-- normalised, and the last one has a mood rating out of range.
+INSERT INTO
+    mood_records (
+        user_id,
+        mood,
+        energy,
+        sleep,
+        logged_at
+    )
+VALUES
+    (
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        0.4,
+        0.5,
+        0.6,
+        '2025-06-01T22:00:00Z'
+    ),
+    (
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        0.8,
+        0.7,
+        NULL,
+        '2025-06-02T20:00:00Z'
+    ),
+    (
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        1.5,
+        0.6,
+        NULL,
+        '2025-06-03T01:00:00Z'
+    );
@@ -0,0 +1,123 @@
+-- Create users for personal_records
+INSERT INTO
+    users (
+        user_id,
+        personalisation,
+        contact_email,
+        contact_name,
+        created_at,
+        updated_at,
+        timezone
+    )
+VALUES
+    (
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        NULL,
+        'alice@example.com',
+        'Alice',
+        now() - interval '30 days',
+        now(),
+        'UTC'
+    ),
+    (
+        '22222222-2222-2222-2222-222222222222'::uuid,
+        NULL,
+        'bobat@example.com',
+        'Bobat',
+        now() - interval '30 days',
+        now(),
+        'UTC'
+    );
+
+-- Alice has squatted twice and benched once
+INSERT INTO
+    activity_records (
+        activity_record_id,
+        user_id,
+        activity,
+        activity_type,
+        activity_info,
+        logged_at
+    )
+VALUES
+    (
+        'aaaaaaaa-0000-0000-0000-000000000001'::uuid,
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        'leg day',
+        'WeightLifting',
+        '{"exercises": [{"name": "squat", "sets": [{"reps": 5, "weight_kg": 100.0}, {"reps": 5, "weight_kg": 100.0}, {"reps": 5, "weight_kg": 100.0}]}]}'::jsonb,
+        now() - interval '10 days'
+    ),
+    (
+        'aaaaaaaa-0000-0000-0000-000000000002'::uuid,
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        'heavy singles',
+        'WeightLifting',
+        '{"exercises": [{"name": "Squat", "sets": [{"reps": 1, "weight_kg": 110.0}, {"reps": 1, "weight_kg": 110.0}, {"reps": 1, "weight_kg": 110.0}]}, {"name": "bench press", "sets": [{"reps": 5, "weight_kg": 60.0}]}]}'::jsonb,
+        now() - interval '3 days'
+    ),
+    (
+        'aaaaaaaa-0000-0000-0000-000000000003'::uuid,
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        'walk',
+        'Walking',
+        '{"distance_km": 3.0}'::jsonb,
+        now() - interval '2 days'
+    );
+
+INSERT INTO
+    personal_records (
+        user_id,
+        activity_record_id,
+        exercise,
+        record_type,
+        value,
+        previous_value,
+        achieved_at
+    )
+VALUES
+    (
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        'aaaaaaaa-0000-0000-0000-000000000001'::uuid,
+        'squat',
+        'BestWeight',
+        100.0,
+        NULL,
+        now() - interval '10 days'
+    ),
+    (
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        'aaaaaaaa-0000-0000-0000-000000000001'::uuid,
+        'squat',
+        'EstimatedOneRepMax',
+        116.666664,
+        NULL,
+        now() - interval '10 days'
+    ),
+    (
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        'aaaaaaaa-0000-0000-0000-000000000001'::uuid,
+        'squat',
+        'BestVolume',
+        1500.0,
+        NULL,
+        now() - interval '10 days'
+    ),
+    (
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        'aaaaaaaa-0000-0000-0000-000000000002'::uuid,
+        'squat',
+        'BestWeight',
+        110.0,
+        100.0,
+        now() - interval '3 days'
+    ),
+    (
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        'aaaaaaaa-0000-0000-0000-000000000002'::uuid,
+        'bench press',
+        'BestWeight',
+        60.0,
+        NULL,
+        now() - interval '3 days'
+    );
@@ -0,0 +1,82 @@
+-- Create users for read_activity_entries
+INSERT INTO
+    users (
+        user_id,
+        personalisation,
+        contact_email,
+        contact_name,
+        created_at,
+        updated_at,
+        timezone
+    )
+VALUES
+    (
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        NULL,
+        'alice@example.com',
+        'Alice',
+        now() - interval '30 days',
+        now(),
+        'UTC'
+    ),
+    (
+        '22222222-2222-2222-2222-222222222222'::uuid,
+        NULL,
+        'bobat@example.com',
+        'Bobat',
+        now() - interval '30 days',
+        now(),
+        'UTC'
+    );
+
+INSERT INTO
+    activity_records (
+        user_id,
+        activity,
+        activity_type,
+        activity_info,
+        logged_at
+    )
+VALUES
+    (
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        'Long run',
+        'Running',
+        '{"distance_km": 21.1, "duration_seconds": 7200}'::jsonb,
+        now() - interval '5 days'
+    ),
+    (
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        'recovery run',
+        'Running',
+        '{"distance_km": 5.0}'::jsonb,
+        now() - interval '4 days'
+    ),
+    (
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        'walk to work',
+        'Walking',
+        '{"distance_km": 2.5}'::jsonb,
+        now() - interval '3 days'
+    ),
+    (
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        'gym',
+        'WeightLifting',
+        '{"exercises": [{"name": "squat", "sets": [{"reps": 5, "weight_kg": 100.0}]}]}'::jsonb,
+        now() - interval '2 days'
+    ),
+    (
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        'commute',
+        'Cycling',
+        '{"distance_km": 12.0}'::jsonb,
+        now() - interval '1 days'
+    ),
+    (
+        '22222222-2222-2222-2222-222222222222'::uuid,
+        'run club',
+        'Running',
+        '{"distance_km": 15.0}'::jsonb,
+        now() - interval '1 days'
+    );
@@ -34,6 +34,8 @@ VALUES
 -- Alice should have 200 calories, 15 carbs/fats/proteins and 
 -- three food entries.
 --
+-- Alice's micronutrients should total 1500mg of sodium and 3g of fibre.
+--
 -- Bobat should have one food entry.
 INSERT INTO
     food_records (
@@ -58,7 +60,7 @@ VALUES
         5.0::real,
         5.0::real,
         5.0::real,
-        '{}'::jsonb,
+        '{"sodium": {"amount": 1.0, "unit": "g"}, "fibre": {"amount": 3.0, "unit": "g"}}'::jsonb,
         now() - interval '1 day'
     ),
     (
@@ -82,7 +84,7 @@ VALUES
         5.0::real,
         5.0::real,
         5.0::real,
-        '{}'::jsonb,
+        '{"sodium": {"amount": 500.0, "unit": "mg"}, "lycopene": "some"}'::jsonb,
         now() - interval '5 days'
     ),
     (
@@ -96,4 +98,50 @@ VALUES
         5.0::real,
         '{}'::jsonb,
         now()
-    );
\ No newline at end of file
+    );
+-- Create activity entries
+--
+-- Alice burned 150 calories across two activities, with one more activity
+-- that couldn't be estimated.
+INSERT INTO
+    activity_records (
+        user_id,
+        activity,
+        activity_type,
+        activity_info,
+        calories_burned,
+        logged_at
+    )
+VALUES
+    (
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        'morning walk',
+        'Walking',
+        '{"distance_km": 2.0, "duration_seconds": 1800}'::jsonb,
+        100.0::real,
+        now() - interval '1 day'
+    ),
+    (
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        'stretching',
+        'Other',
+        '{"duration_seconds": 900}'::jsonb,
+        50.0::real,
+        now() - interval '1 day'
+    ),
+    (
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        'ride',
+        'Cycling',
+        '{"distance_km": 10.0}'::jsonb,
+        NULL,
+        now() - interval '1 day'
+    ),
+    (
+        '22222222-2222-2222-2222-222222222222'::uuid,
+        'bobats run',
+        'Running',
+        '{"distance_km": 5.0, "duration_seconds": 1500}'::jsonb,
+        400.0::real,
+        now() - interval '1 day'
+    );
@@ -54,9 +54,9 @@ VALUES
         '11111111-1111-1111-1111-111111111111'::uuid,
         now() - interval '5 day',
         null,
-        0::smallint,
-        1::smallint,
-        2::smallint,
+        0.0::real,
+        0.1::real,
+        0.2::real,
         null,
         now() - interval '5 day'
     ),
@@ -65,8 +65,8 @@ VALUES
         '11111111-1111-1111-1111-111111111111'::uuid,
         now() + interval '5 day',
         now(),
-        10::smallint,
-        10::smallint,
+        1.0::real,
+        1.0::real,
         null,
         'alices mood thoughts',
         now() + interval '5 day'
@@ -0,0 +1,93 @@
+-- Create users for recent_foods
+INSERT INTO
+    users (
+        user_id,
+        personalisation,
+        contact_email,
+        contact_name,
+        created_at,
+        updated_at,
+        timezone
+    )
+VALUES
+    (
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        NULL,
+        'alice@example.com',
+        'Alice',
+        now() - interval '1 day',
+        now(),
+        'UTC'
+    ),
+    (
+        '22222222-2222-2222-2222-222222222222'::uuid,
+        NULL,
+        'bobat@example.com',
+        'Bobat',
+        now() - interval '1 day',
+        now(),
+        'UTC'
+    );
+
+-- Create food entries
+--
+-- Alice has logged burgers three times under different casing, the latest
+-- being 650 calories, and a single flat white most recently.
+--
+-- Bobat has a burger which should never show up for Alice.
+INSERT INTO
+    food_records (
+        user_id,
+        description,
+        calories,
+        carbs,
+        protein,
+        fats,
+        logged_at
+    )
+VALUES
+    (
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        'burger',
+        600.0::real,
+        40.0::real,
+        30.0::real,
+        30.0::real,
+        now() - interval '5 days'
+    ),
+    (
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        'burger',
+        620.0::real,
+        40.0::real,
+        30.0::real,
+        30.0::real,
+        now() - interval '3 days'
+    ),
+    (
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        'Burger',
+        650.0::real,
+        45.0::real,
+        30.0::real,
+        32.0::real,
+        now() - interval '2 days'
+    ),
+    (
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        'flat white',
+        120.0::real,
+        10.0::real,
+        6.0::real,
+        6.0::real,
+        now() - interval '1 day'
+    ),
+    (
+        '22222222-2222-2222-2222-222222222222'::uuid,
+        'bobats burger',
+        100.0::real,
+        5.0::real,
+        5.0::real,
+        5.0::real,
+        now()
+    );
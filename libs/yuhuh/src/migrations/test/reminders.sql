@@ -0,0 +1,103 @@
+-- Create users for reminders
+INSERT INTO
+    users (
+        user_id,
+        personalisation,
+        contact_email,
+        contact_name,
+        created_at,
+        updated_at,
+        timezone
+    )
+VALUES
+    (
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        NULL,
+        'alice@example.com',
+        'Alice',
+        now() - interval '30 days',
+        now(),
+        'Pacific/Auckland'
+    ),
+    (
+        '22222222-2222-2222-2222-222222222222'::uuid,
+        NULL,
+        'bobat@example.com',
+        'Bobat',
+        now() - interval '30 days',
+        now(),
+        'UTC'
+    );
+
+-- Alice is asked her mood and reminded about dinner at 21:00, Bobat to log
+-- an activity at 06:00 and his mood at 21:00
+INSERT INTO
+    reminder_rules (
+        reminder_rule_id,
+        user_id,
+        entry_kind,
+        remind_at,
+        logged_since,
+        message
+    )
+VALUES
+    (
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        'mood',
+        '21:00',
+        NULL,
+        'How are you feeling?'
+    ),
+    (
+        '22222222-2222-2222-2222-222222222222'::uuid,
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        'food',
+        '21:00',
+        '17:00',
+        NULL
+    ),
+    (
+        '33333333-3333-3333-3333-333333333333'::uuid,
+        '22222222-2222-2222-2222-222222222222'::uuid,
+        'activity',
+        '06:00',
+        NULL,
+        NULL
+    ),
+    (
+        '44444444-4444-4444-4444-444444444444'::uuid,
+        '22222222-2222-2222-2222-222222222222'::uuid,
+        'mood',
+        '21:00',
+        NULL,
+        NULL
+    );
+
+-- Alice had dinner at 19:00 on 2 June, and her mood the day before doesn't
+-- count for that day
+INSERT INTO
+    food_records (
+        user_id,
+        description,
+        logged_at
+    )
+VALUES
+    (
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        'pasta',
+        '2025-06-02T07:00:00Z'
+    );
+
+INSERT INTO
+    mood_records (
+        user_id,
+        mood,
+        logged_at
+    )
+VALUES
+    (
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        0.7,
+        '2025-06-01T09:00:00Z'
+    );
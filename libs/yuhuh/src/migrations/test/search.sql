@@ -0,0 +1,106 @@
+-- Create users for search
+INSERT INTO
+    users (
+        user_id,
+        personalisation,
+        contact_email,
+        contact_name,
+        created_at,
+        updated_at,
+        timezone
+    )
+VALUES
+    (
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        NULL,
+        'alice@example.com',
+        'Alice',
+        now() - interval '30 days',
+        now(),
+        'UTC'
+    ),
+    (
+        '22222222-2222-2222-2222-222222222222'::uuid,
+        NULL,
+        'bobat@example.com',
+        'Bobat',
+        now() - interval '30 days',
+        now(),
+        'UTC'
+    );
+
+-- Alice has had sushi twice, Bobat once
+INSERT INTO
+    food_records (
+        food_record_id,
+        user_id,
+        description,
+        calories,
+        logged_at
+    )
+VALUES
+    (
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        'sushi platter with extra sushi',
+        900.0,
+        '2025-05-01T12:00:00Z'
+    ),
+    (
+        '22222222-2222-2222-2222-222222222222'::uuid,
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        'salmon sushi rolls',
+        450.0,
+        '2025-05-10T12:00:00Z'
+    ),
+    (
+        '33333333-3333-3333-3333-333333333333'::uuid,
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        'burger and fries',
+        1100.0,
+        '2025-05-12T12:00:00Z'
+    ),
+    (
+        '44444444-4444-4444-4444-444444444444'::uuid,
+        '22222222-2222-2222-2222-222222222222'::uuid,
+        'sushi',
+        300.0,
+        '2025-05-12T12:00:00Z'
+    );
+
+INSERT INTO
+    mood_records (
+        mood_record_id,
+        user_id,
+        mood,
+        notes,
+        logged_at
+    )
+VALUES
+    (
+        '55555555-5555-5555-5555-555555555555'::uuid,
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        0.8,
+        'Had sushi with friends after work, feeling great',
+        '2025-05-03T20:00:00Z'
+    );
+
+-- Only mentions sushi inside activity_info
+INSERT INTO
+    activity_records (
+        activity_record_id,
+        user_id,
+        activity,
+        activity_type,
+        activity_info,
+        logged_at
+    )
+VALUES
+    (
+        '66666666-6666-6666-6666-666666666666'::uuid,
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        'evening walk',
+        'Other',
+        '{"notes": "walked to the sushi place"}'::jsonb,
+        '2025-05-11T18:00:00Z'
+    );
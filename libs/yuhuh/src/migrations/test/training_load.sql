@@ -0,0 +1,82 @@
+-- Create users for training_load
+INSERT INTO
+    users (
+        user_id,
+        personalisation,
+        contact_email,
+        contact_name,
+        created_at,
+        updated_at,
+        timezone
+    )
+VALUES
+    (
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        NULL,
+        'alice@example.com',
+        'Alice',
+        now() - interval '30 days',
+        now(),
+        'UTC'
+    ),
+    (
+        '22222222-2222-2222-2222-222222222222'::uuid,
+        NULL,
+        'bobat@example.com',
+        'Bobat',
+        now() - interval '30 days',
+        now(),
+        'UTC'
+    );
+
+-- Three weeks of easy 30 minute runs
+INSERT INTO
+    activity_records (
+        user_id,
+        activity,
+        activity_type,
+        activity_info,
+        logged_at
+    )
+SELECT
+    '11111111-1111-1111-1111-111111111111'::uuid,
+    'easy run',
+    'Running',
+    '{"distance_km": 5.0, "duration_seconds": 1800, "rpe": 5}'::jsonb,
+    ((now() at time zone 'UTC')::date - d + time '12:00') at time zone 'UTC'
+FROM generate_series(7, 27) AS d;
+
+-- Then a week of hard hour long runs
+INSERT INTO
+    activity_records (
+        user_id,
+        activity,
+        activity_type,
+        activity_info,
+        logged_at
+    )
+SELECT
+    '11111111-1111-1111-1111-111111111111'::uuid,
+    'hard run',
+    'Running',
+    '{"distance_km": 13.0, "duration_seconds": 3600, "rpe": 8}'::jsonb,
+    ((now() at time zone 'UTC')::date - d + time '12:00') at time zone 'UTC'
+FROM generate_series(0, 6) AS d;
+
+-- Nothing to work load out from
+INSERT INTO
+    activity_records (
+        user_id,
+        activity,
+        activity_type,
+        activity_info,
+        logged_at
+    )
+VALUES
+    (
+        '11111111-1111-1111-1111-111111111111'::uuid,
+        'stretching',
+        'Other',
+        '{"stretches": 10}'::jsonb,
+        now() - interval '3 days'
+    );
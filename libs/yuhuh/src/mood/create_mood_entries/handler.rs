@@ -12,13 +12,19 @@ use axum::{Json, extract::State, http::StatusCode};
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use tracing::{debug, error, instrument};
+use tracing::instrument;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::{
+    auth::model::Caller,
     error::YuhuhError,
-    mood::{model::MoodEntry, rating::Rating, state::MoodState},
+    mood::{
+        model::{MoodEntry, MoodEntryRow},
+        rating::Rating,
+        state::MoodState,
+        tags::{Emotion, MAX_TAGS, normalise_context_tag},
+    },
     user::state::UserState,
 };
 
@@ -34,6 +40,12 @@ pub struct NewMoodEntry {
     pub mood: Option<Rating>,
     pub energy: Option<Rating>,
     pub sleep: Option<Rating>,
+    /// Emotions felt, from a fixed vocabulary.
+    #[serde(default)]
+    pub emotions: Vec<Emotion>,
+    /// Free-form tags for what was going on, e.g. "work" or "travel".
+    #[serde(default)]
+    pub context_tags: Vec<String>,
     pub logged_at: Option<DateTime<Utc>>,
 }
 
@@ -42,9 +54,29 @@ pub struct NewMoodEntry {
 // ============================================================================
 
 impl NewMoodEntry {
-    pub fn into(self, user_id: Uuid) -> MoodEntry {
-        MoodEntry {
-            mood_record_id: None,
+    /// Converts into a [`MoodEntry`], normalising and deduplicating its tags.
+    pub fn into(mut self, user_id: Uuid) -> Result<MoodEntry, YuhuhError> {
+        if self.emotions.len() > MAX_TAGS || self.context_tags.len() > MAX_TAGS {
+            return Err(YuhuhError::BadRequest(format!(
+                "at most {} emotions and {} context tags can be attached to an entry",
+                MAX_TAGS, MAX_TAGS
+            )));
+        }
+
+        self.emotions.sort();
+        self.emotions.dedup();
+
+        let mut context_tags = self
+            .context_tags
+            .iter()
+            .map(|t| normalise_context_tag(t))
+            .collect::<Result<Vec<String>, String>>()
+            .map_err(YuhuhError::BadRequest)?;
+        context_tags.sort();
+        context_tags.dedup();
+
+        Ok(MoodEntry {
+            mood_record_id: Some(Uuid::now_v7()),
             user_id,
             created_at: None,
             updated_at: None,
@@ -52,8 +84,10 @@ impl NewMoodEntry {
             energy: self.energy,
             sleep: self.sleep,
             notes: self.notes,
+            emotions: self.emotions,
+            context_tags,
             logged_at: self.logged_at.unwrap_or(Utc::now()),
-        }
+        })
     }
 }
 
@@ -67,32 +101,35 @@ impl NewMoodEntry {
     path = "mood", 
     tag = "mood", 
     responses(
-        (status = 201, description = "mood created successfully")
+        (status = 201, description = "mood created successfully"),
+        (status = 400, description = "Invalid tags, or ratings outside the user's scales"),
+        (status = 404, description = "User not found"),
 ))]
 #[instrument]
 pub async fn create_mood_entries(
     State(mood_state): State<Arc<MoodState>>,
     State(user_state): State<Arc<UserState>>,
+    caller: Caller,
     Json(request): Json<CreateMoodEntryRequest>,
 ) -> Result<StatusCode, YuhuhError> {
-    if (user_state
-        .find_user_repo
-        .find_user_by_id(&request.user_id)
-        .await?)
-        .is_none()
-    {
-        return Err(YuhuhError::NotFound("user not found".to_string()));
-    }
+    caller.authorise_user(&request.user_id)?;
+
+    user_state.require_user(&request.user_id).await?;
+
+    let scales = mood_state
+        .rating_scales_repo
+        .read_rating_scales(&request.user_id)
+        .await?;
+
+    let rows = request
+        .mood_entries
+        .into_iter()
+        .map(|f| Ok(f.into(request.user_id)?.into_row(&scales)?))
+        .collect::<Result<Vec<MoodEntryRow>, YuhuhError>>()?;
 
     mood_state
         .create_mood_entries_repo
-        .create_mood_entries(
-            request
-                .mood_entries
-                .into_iter()
-                .map(|f| f.into(request.user_id))
-                .collect(),
-        )
+        .create_mood_entries(rows, &scales)
         .await?;
 
     Ok(StatusCode::CREATED)
@@ -107,10 +144,40 @@ mod tests {
         body::Body,
         http::{self, Request, StatusCode},
     };
+    use http_body_util::BodyExt;
     use tower::ServiceExt;
     use uuid::uuid;
 
-    use crate::mood::create_mood_entries::CreateMoodEntryRequest;
+    use crate::mood::{
+        create_mood_entries::{CreateMoodEntryRequest, NewMoodEntry},
+        rating::Rating,
+        read_mood_entries::{MoodFilter, ReadMoodEntriesResponse},
+        scale::RatingScales,
+        tags::{Emotion, TagKind},
+    };
+
+    fn new_entry(mood: i32, emotions: Vec<Emotion>, context_tags: Vec<&str>) -> NewMoodEntry {
+        NewMoodEntry {
+            notes: None,
+            mood: Rating::new(mood),
+            energy: None,
+            sleep: None,
+            emotions,
+            context_tags: context_tags.into_iter().map(String::from).collect(),
+            logged_at: None,
+        }
+    }
+
+    fn create_request(request: &CreateMoodEntryRequest) -> Request<Body> {
+        Request::builder()
+            .method("POST")
+            .uri("/mood")
+            .header(http::header::CONTENT_TYPE, "application/json")
+            .body(Body::from(
+                serde_json::to_string(request).expect("request is valid body"),
+            ))
+            .unwrap()
+    }
 
     #[tokio::test]
     async fn invalid_user_returns_not_found() {
@@ -145,4 +212,124 @@ mod tests {
 
         assert_eq!(response.status(), StatusCode::NOT_FOUND);
     }
+
+    #[tokio::test]
+    async fn tags_stored_and_filterable() {
+        let (app, db, state) = crate::test::common::setup().await;
+
+        sqlx::raw_sql(include_str!(
+            "../../migrations/test/create_mood_entries.sql"
+        ))
+        .execute(&db)
+        .await
+        .expect("setup test sql ran successfully");
+
+        let request = CreateMoodEntryRequest {
+            user_id: uuid!("11111111-1111-1111-1111-111111111111"),
+            mood_entries: vec![
+                new_entry(3, vec![Emotion::Anxious, Emotion::Anxious], vec![" Work "]),
+                new_entry(
+                    5,
+                    vec![Emotion::Anxious, Emotion::Irritable],
+                    vec!["travel"],
+                ),
+                new_entry(9, vec![Emotion::Calm], vec!["family"]),
+            ],
+        };
+
+        let response = app.clone().oneshot(create_request(&request)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/mood?user_id=11111111-1111-1111-1111-111111111111&tags=anxious,WORK")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let dto: ReadMoodEntriesResponse =
+            serde_json::from_slice(&body).expect("valid ReadMoodEntriesResponse bytes");
+
+        assert_eq!(dto.found_mood_entries, 2);
+        assert!(
+            dto.found_entries
+                .iter()
+                .all(|e| e.emotions.contains(&Emotion::Anxious))
+        );
+
+        let anxious = dto
+            .tag_averages
+            .iter()
+            .find(|t| t.kind == TagKind::Emotion && t.tag == "anxious")
+            .expect("anxious has an average");
+        assert_eq!(anxious.count, 2);
+        assert_eq!(anxious.mood, Some(4.0));
+        assert!(dto.tag_averages.iter().any(|t| t.tag == "work"));
+        assert!(!dto.tag_averages.iter().any(|t| t.tag == "calm"));
+
+        // Averages cover every matching entry, not just the page returned
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/mood?user_id=11111111-1111-1111-1111-111111111111&tags=anxious&limit=1")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let page: ReadMoodEntriesResponse =
+            serde_json::from_slice(&body).expect("valid ReadMoodEntriesResponse bytes");
+
+        assert_eq!(page.found_mood_entries, 1);
+        assert_eq!(page.tag_averages, dto.tag_averages);
+
+        // Stored ratings out of range are left out, the same as in stats
+        sqlx::query(
+            "UPDATE mood_records SET mood = 1.5
+            WHERE mood_record_id IN (SELECT mood_record_id FROM mood_tags WHERE tag = 'travel')",
+        )
+        .execute(&db)
+        .await
+        .expect("rating stored out of range");
+
+        let averages = state
+            .mood
+            .read_mood_entries_repo
+            .find_tag_averages(
+                &uuid!("11111111-1111-1111-1111-111111111111"),
+                &MoodFilter {
+                    tags: vec!["anxious".to_string()],
+                    ..Default::default()
+                },
+                &RatingScales::default(),
+            )
+            .await
+            .expect("tag averages found");
+
+        let anxious = averages
+            .iter()
+            .find(|t| t.kind == TagKind::Emotion && t.tag == "anxious")
+            .expect("anxious has an average");
+        assert_eq!(anxious.count, 2);
+        assert_eq!(anxious.excluded, 1);
+        assert_eq!(anxious.mood, Some(3.0));
+
+        let invalid = CreateMoodEntryRequest {
+            user_id: uuid!("11111111-1111-1111-1111-111111111111"),
+            mood_entries: vec![new_entry(5, vec![], vec!["work/life"])],
+        };
+
+        let response = app.oneshot(create_request(&invalid)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
 }
@@ -4,7 +4,14 @@ use sqlx::PgPool;
 use tracing::{error, info};
 use uuid::Uuid;
 
-use crate::{error::YuhuhError, mood::model::MoodEntry};
+use crate::{
+    error::YuhuhError,
+    mood::{model::MoodEntryRow, scale::RatingScales, tags::TagKind},
+    webhooks::{
+        model::{EventType, WebhookEvent},
+        outbox::enqueue_events,
+    },
+};
 
 // =============================================================================
 // Traits
@@ -12,7 +19,13 @@ use crate::{error::YuhuhError, mood::model::MoodEntry};
 
 #[async_trait]
 pub trait CreateMoodEntryRepository: std::fmt::Debug + Send + Sync + 'static {
-    async fn create_mood_entries(&self, entries: Vec<MoodEntry>) -> Result<(), YuhuhError>;
+    /// Creates mood entries, with `scales` the user's rating scales their
+    /// ratings were normalised from.
+    async fn create_mood_entries(
+        &self,
+        entries: Vec<MoodEntryRow>,
+        scales: &RatingScales,
+    ) -> Result<(), YuhuhError>;
 }
 
 // =============================================================================
@@ -32,7 +45,11 @@ impl CreateMoodEntryRepositoryImpl {
 
 #[async_trait]
 impl CreateMoodEntryRepository for CreateMoodEntryRepositoryImpl {
-    async fn create_mood_entries(&self, entries: Vec<MoodEntry>) -> Result<(), YuhuhError> {
+    async fn create_mood_entries(
+        &self,
+        entries: Vec<MoodEntryRow>,
+        scales: &RatingScales,
+    ) -> Result<(), YuhuhError> {
         if entries.is_empty() {
             error!("create_mood_entries received an empty vec");
 
@@ -43,25 +60,56 @@ impl CreateMoodEntryRepository for CreateMoodEntryRepositoryImpl {
 
         let mut transaction = self.db.begin().await?;
 
+        let mut mood_record_id_vecs: Vec<Uuid> = vec![];
         let mut user_id_vecs: Vec<Uuid> = vec![];
-        let mut mood_vecs: Vec<Option<i16>> = vec![];
-        let mut energy_vecs: Vec<Option<i16>> = vec![];
-        let mut sleep_vecs: Vec<Option<i16>> = vec![];
-        let notes_vecs: Vec<Option<String>> = vec![];
+        let mut mood_vecs: Vec<Option<f32>> = vec![];
+        let mut energy_vecs: Vec<Option<f32>> = vec![];
+        let mut sleep_vecs: Vec<Option<f32>> = vec![];
+        let mut notes_vecs: Vec<Option<String>> = vec![];
         let mut logged_at_vecs: Vec<NaiveDateTime> = vec![];
 
-        entries.into_iter().for_each(|m| {
+        let mut tag_mood_record_id_vecs: Vec<Uuid> = vec![];
+        let mut tag_kind_vecs: Vec<String> = vec![];
+        let mut tag_vecs: Vec<String> = vec![];
+
+        let mut events: Vec<WebhookEvent> = vec![];
+
+        for mut m in entries {
             info!(mood_entrey=?m, "added mood entry to creation query");
-            mood_vecs.push(m.mood.map(|mood| mood.get() as i16));
-            energy_vecs.push(m.sleep.map(|sleep| sleep.get() as i16));
-            sleep_vecs.push(m.energy.map(|energy| energy.get() as i16));
+            let mood_record_id = *m.mood_record_id.get_or_insert_with(Uuid::now_v7);
+            mood_record_id_vecs.push(mood_record_id);
+
+            // Sent with ratings on the user's scales, as the API returns them
+            events.push(WebhookEvent::new(
+                EventType::MoodCreated,
+                m.user_id,
+                &m.clone().into_entry(scales)?,
+            )?);
+
+            let tags = m
+                .emotions
+                .into_iter()
+                .map(|e| (TagKind::Emotion, e))
+                .chain(m.context_tags.into_iter().map(|t| (TagKind::Context, t)));
+
+            for (kind, tag) in tags {
+                tag_mood_record_id_vecs.push(mood_record_id);
+                tag_kind_vecs.push(kind.to_string());
+                tag_vecs.push(tag);
+            }
+
+            mood_vecs.push(m.mood);
+            energy_vecs.push(m.energy);
+            sleep_vecs.push(m.sleep);
+            notes_vecs.push(m.notes);
             user_id_vecs.push(m.user_id);
             logged_at_vecs.push(m.logged_at.naive_utc());
-        });
+        }
 
         sqlx::query!(
             r#"
             INSERT INTO mood_records (
+                mood_record_id,
                 user_id, 
                 mood, 
                 energy, 
@@ -70,18 +118,20 @@ impl CreateMoodEntryRepository for CreateMoodEntryRepositoryImpl {
                 logged_at
             )
             SELECT * FROM UNNEST(
-                $1::uuid[], 
-                $2::smallint[],
-                $3::smallint[],
-                $4::smallint[],
-                $5::text[],
-                $6::timestamp[]
+                $1::uuid[],
+                $2::uuid[], 
+                $3::real[],
+                $4::real[],
+                $5::real[],
+                $6::text[],
+                $7::timestamp[]
             )
             "#,
+            &mood_record_id_vecs[..],
             &user_id_vecs[..],
-            &mood_vecs[..] as &[Option<i16>],
-            &energy_vecs[..] as &[Option<i16>],
-            &sleep_vecs[..] as &[Option<i16>],
+            &mood_vecs[..] as &[Option<f32>],
+            &energy_vecs[..] as &[Option<f32>],
+            &sleep_vecs[..] as &[Option<f32>],
             &notes_vecs[..] as &[Option<String>],
             &logged_at_vecs[..],
         )
@@ -93,6 +143,35 @@ impl CreateMoodEntryRepository for CreateMoodEntryRepositoryImpl {
             YuhuhError::DatabaseError(e)
         })?;
 
+        if !tag_vecs.is_empty() {
+            sqlx::query!(
+                r#"
+                INSERT INTO mood_tags (
+                    mood_record_id,
+                    kind,
+                    tag
+                )
+                SELECT * FROM UNNEST(
+                    $1::uuid[],
+                    $2::text[],
+                    $3::text[]
+                )
+                "#,
+                &tag_mood_record_id_vecs[..],
+                &tag_kind_vecs[..],
+                &tag_vecs[..]
+            )
+            .execute(&mut *transaction)
+            .await
+            .map_err(|e| {
+                error!(error = ?e, "database error while creating mood tags");
+
+                YuhuhError::DatabaseError(e)
+            })?;
+        }
+
+        enqueue_events(&mut transaction, &events).await?;
+
         // Commit the transaction to persist all changes
         transaction.commit().await?;
 
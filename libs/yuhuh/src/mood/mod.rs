@@ -1,6 +1,11 @@
 pub mod create_mood_entries;
 pub mod model;
 pub mod rating;
+pub mod rating_scales;
 pub mod read_mood_entries;
+pub mod read_mood_stats;
 pub mod router;
+pub mod scale;
 pub mod state;
+pub mod stats;
+pub mod tags;
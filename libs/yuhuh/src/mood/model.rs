@@ -3,7 +3,14 @@ use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
-use crate::{error::ConversionError, mood::rating::Rating};
+use crate::{
+    error::{ConversionError, RatingError},
+    mood::{
+        rating::Rating,
+        scale::{RatingMetric, RatingScales},
+        tags::Emotion,
+    },
+};
 
 #[derive(Debug, Serialize, Deserialize, ToSchema, sqlx::FromRow)]
 pub struct MoodEntry {
@@ -15,66 +22,86 @@ pub struct MoodEntry {
     pub energy: Option<Rating>,
     pub sleep: Option<Rating>,
     pub notes: Option<String>,
+    #[serde(default)]
+    #[sqlx(skip)]
+    pub emotions: Vec<Emotion>,
+    /// Free-form tags for what was going on, e.g. "work" or "travel".
+    #[serde(default)]
+    #[sqlx(skip)]
+    pub context_tags: Vec<String>,
     pub logged_at: DateTime<Utc>,
 }
 
 // =============================================================================
 // Row Structs
 // =============================================================================
-#[derive(Debug, sqlx::FromRow)]
+#[derive(Debug, Clone, sqlx::FromRow)]
 pub struct MoodEntryRow {
     pub mood_record_id: Option<Uuid>,
     pub user_id: Uuid,
     pub created_at: Option<DateTime<Utc>>,
     pub updated_at: Option<DateTime<Utc>>,
-    pub mood: Option<i16>,
-    pub energy: Option<i16>,
-    pub sleep: Option<i16>,
+    /// Ratings normalised from 0 to 1.
+    pub mood: Option<f32>,
+    pub energy: Option<f32>,
+    pub sleep: Option<f32>,
     pub notes: Option<String>,
     pub logged_at: DateTime<Utc>,
+    pub emotions: Vec<String>,
+    pub context_tags: Vec<String>,
 }
 
-impl From<MoodEntry> for MoodEntryRow {
-    fn from(value: MoodEntry) -> Self {
-        MoodEntryRow {
-            mood_record_id: value.mood_record_id,
-            user_id: value.user_id,
-            created_at: value.created_at,
-            updated_at: value.updated_at,
-            mood: value.mood.map(|r| r.get() as i16),
-            energy: value.energy.map(|r| r.get() as i16),
-            sleep: value.sleep.map(|r| r.get() as i16),
-            notes: value.notes,
-            logged_at: value.logged_at,
-        }
+impl MoodEntry {
+    /// Converts into a row, normalising ratings from the user's scales.
+    pub fn into_row(self, scales: &RatingScales) -> Result<MoodEntryRow, RatingError> {
+        let normalise = |rating: Option<Rating>, metric: RatingMetric| {
+            rating
+                .map(|r| scales.get(metric).normalise(r))
+                .transpose()
+                .map_err(|e| RatingError::new(format!("{} {}", metric, e)))
+        };
+
+        Ok(MoodEntryRow {
+            mood_record_id: self.mood_record_id,
+            user_id: self.user_id,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+            mood: normalise(self.mood, RatingMetric::Mood)?,
+            energy: normalise(self.energy, RatingMetric::Energy)?,
+            sleep: normalise(self.sleep, RatingMetric::Sleep)?,
+            notes: self.notes,
+            logged_at: self.logged_at,
+            emotions: self.emotions.iter().map(|e| e.to_string()).collect(),
+            context_tags: self.context_tags,
+        })
     }
 }
 
-impl TryInto<MoodEntry> for MoodEntryRow {
-    type Error = ConversionError;
+impl MoodEntryRow {
+    /// Converts into an entry, showing ratings on the user's scales.
+    pub fn into_entry(self, scales: &RatingScales) -> Result<MoodEntry, ConversionError> {
+        let rating = |value: Option<f32>, metric: RatingMetric| {
+            value
+                .map(|v| scales.get(metric).rating(v))
+                .transpose()
+                .map_err(|e| ConversionError::new(format!("failed to parse {} - {}", metric, e)))
+        };
 
-    fn try_into(self) -> Result<MoodEntry, Self::Error> {
         let r = MoodEntry {
             mood_record_id: self.mood_record_id,
             user_id: self.user_id,
             created_at: self.created_at,
             updated_at: self.updated_at,
-            mood: self
-                .mood
-                .map(Rating::try_from)
-                .transpose()
-                .map_err(|e| ConversionError::new(format!("failed to parse mood - {}", e)))?,
-            energy: self
-                .energy
-                .map(Rating::try_from)
-                .transpose()
-                .map_err(|e| ConversionError::new(format!("failed to parse energy - {}", e)))?,
-            sleep: self
-                .sleep
-                .map(Rating::try_from)
-                .transpose()
-                .map_err(|e| ConversionError::new(format!("failed to parse sleep - {}", e)))?,
+            mood: rating(self.mood, RatingMetric::Mood)?,
+            energy: rating(self.energy, RatingMetric::Energy)?,
+            sleep: rating(self.sleep, RatingMetric::Sleep)?,
             notes: self.notes,
+            emotions: self
+                .emotions
+                .iter()
+                .map(|e| e.parse())
+                .collect::<Result<_, _>>()?,
+            context_tags: self.context_tags,
             logged_at: self.logged_at,
         };
 
@@ -5,32 +5,41 @@ use utoipa::PartialSchema;
 
 use crate::error::RatingError;
 
+/// Lowest rating any scale can go down to.
+pub const MIN_RATING: i32 = -100;
+
+/// Highest rating any scale can go up to.
+pub const MAX_RATING: i32 = 100;
+
+/// A rating on the user's scale for a metric, see
+/// [`crate::mood::scale::RatingScale`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub struct Rating(u32);
+pub struct Rating(i32);
 
 impl Rating {
-    pub fn new(value: u32) -> Option<Rating> {
-        if value <= 10 { Some(Self(value)) } else { None }
+    pub fn new(value: i32) -> Option<Rating> {
+        if (MIN_RATING..=MAX_RATING).contains(&value) {
+            Some(Self(value))
+        } else {
+            None
+        }
     }
 
-    pub fn get(&self) -> u32 {
+    pub fn get(&self) -> i32 {
         self.0
     }
 }
 
+fn out_of_bounds(value: impl fmt::Display) -> RatingError {
+    RatingError::new(format!(
+        "must be between {} and {}, but got {} instead",
+        MIN_RATING, MAX_RATING, value
+    ))
+}
+
 impl TryFrom<i16> for Rating {
     fn try_from(value: i16) -> Result<Self, Self::Error> {
-        match value {
-            0..=10 => Ok(Self(value as u32)),
-            11.. => Err(RatingError::new(format!(
-                "must 0 and 10, but got {} instead",
-                value
-            ))),
-            _ => Err(RatingError::new(format!(
-                "ratings cannot be negative, but got {} instead",
-                value
-            ))),
-        }
+        Rating::new(value.into()).ok_or_else(|| out_of_bounds(value))
     }
 
     type Error = RatingError;
@@ -38,17 +47,10 @@ impl TryFrom<i16> for Rating {
 
 impl TryFrom<i64> for Rating {
     fn try_from(value: i64) -> Result<Self, Self::Error> {
-        match value {
-            0..=10 => Ok(Self(value as u32)),
-            11.. => Err(RatingError::new(format!(
-                "must 0 and 10, but got {} instead",
-                value
-            ))),
-            _ => Err(RatingError::new(format!(
-                "ratings cannot be negative, but got {} instead",
-                value
-            ))),
-        }
+        i32::try_from(value)
+            .ok()
+            .and_then(Rating::new)
+            .ok_or_else(|| out_of_bounds(value))
     }
 
     type Error = RatingError;
@@ -56,13 +58,10 @@ impl TryFrom<i64> for Rating {
 
 impl TryFrom<u32> for Rating {
     fn try_from(value: u32) -> Result<Self, Self::Error> {
-        match value {
-            0..=10 => Ok(Self(value)),
-            _ => Err(RatingError::new(format!(
-                "must 0 and 10, but got {} instead",
-                value
-            ))),
-        }
+        i32::try_from(value)
+            .ok()
+            .and_then(Rating::new)
+            .ok_or_else(|| out_of_bounds(value))
     }
 
     type Error = RatingError;
@@ -70,13 +69,10 @@ impl TryFrom<u32> for Rating {
 
 impl TryFrom<u64> for Rating {
     fn try_from(value: u64) -> Result<Self, Self::Error> {
-        match value {
-            0..=10 => Ok(Self(value as u32)),
-            _ => Err(RatingError::new(format!(
-                "must 0 and 10, but got {} instead",
-                value
-            ))),
-        }
+        i32::try_from(value)
+            .ok()
+            .and_then(Rating::new)
+            .ok_or_else(|| out_of_bounds(value))
     }
 
     type Error = RatingError;
@@ -93,7 +89,7 @@ impl<'de> Deserialize<'de> for Rating {
             type Value = Rating;
 
             fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-                formatter.write_str("an i16 or u32 integer between 0 and 10")
+                formatter.write_str("an integer rating on the user's scale")
             }
 
             fn visit_i16<E>(self, value: i16) -> Result<Self::Value, E>
@@ -129,13 +125,13 @@ impl<'de> Deserialize<'de> for Rating {
     }
 }
 
-// Serialize as u32
+// Serialize as i32
 impl Serialize for Rating {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        serializer.serialize_u32(self.0)
+        serializer.serialize_i32(self.0)
     }
 }
 
@@ -149,10 +145,13 @@ impl PartialSchema for Rating {
             .format(Some(utoipa::openapi::SchemaFormat::KnownFormat(
                 utoipa::openapi::KnownFormat::Int32,
             )))
-            .minimum(Some(0))
-            .maximum(Some(10))
-            .description(Some("A rating value"))
-            .examples(vec![0, 1, 5, 7, 10])
+            .minimum(Some(MIN_RATING))
+            .maximum(Some(MAX_RATING))
+            .description(Some(
+                "A rating on the user's scale for the metric, see `RatingScale`. \
+                Scales run from 0 to 10 unless the user has set their own.",
+            ))
+            .examples(vec![0, 5, 10, -5, 3])
             .into()
     }
 }
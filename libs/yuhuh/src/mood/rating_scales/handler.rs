@@ -0,0 +1,245 @@
+//! rating scales HTTP handlers
+//!
+//! This module provides HTTP endpoints for the scales a user rates mood,
+//! energy and sleep on.
+
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::StatusCode,
+};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, instrument};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::{
+    auth::model::Caller,
+    error::YuhuhError,
+    mood::{
+        scale::{RatingMetric, RatingScale, RatingScales},
+        state::MoodState,
+    },
+    user::state::UserState,
+};
+
+// ============================================================================
+// HTTP Request types
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UpdateRatingScaleRequest {
+    pub user_id: Uuid,
+    pub metric: RatingMetric,
+    pub scale: RatingScale,
+}
+
+/// Request parameters for finding rating scales.
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ReadRatingScalesRequest {
+    /// user ID to search by.
+    pub user_id: Uuid,
+}
+
+// ============================================================================
+// Implementations
+// ============================================================================
+
+// =============================================================================
+// HTTP Handlers
+// =============================================================================
+
+/// Set the scale a user rates a metric on
+///
+/// Entries already logged are shown on the new scale.
+#[utoipa::path(
+    put,
+    path = "mood/scales",
+    tag = "mood",
+    responses(
+        (status = 200, description = "Scale updated", body = RatingScales),
+        (status = 400, description = "Invalid scale"),
+        (status = 404, description = "User not found"),
+))]
+#[instrument]
+pub async fn update_rating_scale(
+    State(mood_state): State<Arc<MoodState>>,
+    State(user_state): State<Arc<UserState>>,
+    caller: Caller,
+    Json(request): Json<UpdateRatingScaleRequest>,
+) -> Result<(StatusCode, Json<RatingScales>), YuhuhError> {
+    debug!("entering update_rating_scale");
+
+    caller.authorise_user(&request.user_id)?;
+
+    let scale = RatingScale::new(request.scale.min, request.scale.max)?;
+
+    user_state.require_user(&request.user_id).await?;
+
+    mood_state
+        .rating_scales_repo
+        .upsert_rating_scale(&request.user_id, request.metric, scale)
+        .await?;
+
+    let scales = mood_state
+        .rating_scales_repo
+        .read_rating_scales(&request.user_id)
+        .await?;
+
+    Ok((StatusCode::OK, Json(scales)))
+}
+
+/// Find the scales a user rates mood, energy and sleep on
+#[utoipa::path(
+    get,
+    path = "mood/scales",
+    tag = "mood",
+    params(ReadRatingScalesRequest),
+    responses(
+        (status = 200, description = "Found rating scales", body = RatingScales),
+        (status = 404, description = "User not found"),
+))]
+#[instrument]
+pub async fn read_rating_scales(
+    State(mood_state): State<Arc<MoodState>>,
+    State(user_state): State<Arc<UserState>>,
+    caller: Caller,
+    Query(request): Query<ReadRatingScalesRequest>,
+) -> Result<(StatusCode, Json<RatingScales>), YuhuhError> {
+    debug!("entering read_rating_scales");
+
+    caller.authorise_user(&request.user_id)?;
+
+    user_state.require_user(&request.user_id).await?;
+
+    let scales = mood_state
+        .rating_scales_repo
+        .read_rating_scales(&request.user_id)
+        .await?;
+
+    Ok((StatusCode::OK, Json(scales)))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use axum::{
+        body::Body,
+        http::{self, Request, StatusCode},
+    };
+    use http_body_util::BodyExt;
+    use pretty_assertions::assert_eq;
+    use serde::Serialize;
+    use tower::ServiceExt;
+    use uuid::uuid;
+
+    use crate::mood::{
+        create_mood_entries::{CreateMoodEntryRequest, NewMoodEntry},
+        rating::Rating,
+        rating_scales::UpdateRatingScaleRequest,
+        read_mood_entries::ReadMoodEntriesResponse,
+        scale::{RatingMetric, RatingScale, RatingScales},
+    };
+
+    fn json_request(method: &str, uri: &str, body: &impl Serialize) -> Request<Body> {
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(http::header::CONTENT_TYPE, "application/json")
+            .body(Body::from(
+                serde_json::to_string(body).expect("request is valid body"),
+            ))
+            .unwrap()
+    }
+
+    fn set_scale(min: i16, max: i16) -> Request<Body> {
+        json_request(
+            "PUT",
+            "/mood/scales",
+            &UpdateRatingScaleRequest {
+                user_id: uuid!("11111111-1111-1111-1111-111111111111"),
+                metric: RatingMetric::Mood,
+                scale: RatingScale { min, max },
+            },
+        )
+    }
+
+    fn create_mood(mood: i32) -> Request<Body> {
+        json_request(
+            "POST",
+            "/mood",
+            &CreateMoodEntryRequest {
+                user_id: uuid!("11111111-1111-1111-1111-111111111111"),
+                mood_entries: vec![NewMoodEntry {
+                    notes: None,
+                    mood: Rating::new(mood),
+                    energy: None,
+                    sleep: None,
+                    emotions: vec![],
+                    context_tags: vec![],
+                    logged_at: None,
+                }],
+            },
+        )
+    }
+
+    async fn read_mood(app: axum::Router) -> Vec<Option<i32>> {
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/mood?user_id=11111111-1111-1111-1111-111111111111")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let dto: ReadMoodEntriesResponse =
+            serde_json::from_slice(&body).expect("valid ReadMoodEntriesResponse bytes");
+
+        dto.found_entries
+            .iter()
+            .map(|e| e.mood.map(|r| r.get()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn ratings_shown_on_the_users_scale() {
+        let (app, db, _) = crate::test::common::setup().await;
+
+        sqlx::raw_sql(include_str!(
+            "../../migrations/test/create_mood_entries.sql"
+        ))
+        .execute(&db)
+        .await
+        .expect("setup test sql ran successfully");
+
+        let response = app.clone().oneshot(set_scale(-5, 5)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let scales: RatingScales = serde_json::from_slice(&body).expect("valid RatingScales bytes");
+        assert_eq!(scales.mood, RatingScale { min: -5, max: 5 });
+        assert_eq!(scales.sleep, RatingScale::default());
+
+        let response = app.clone().oneshot(create_mood(-3)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert_eq!(read_mood(app.clone()).await, vec![Some(-3)]);
+
+        // Outside the user's scale, even though it'd fit the default
+        let response = app.clone().oneshot(create_mood(8)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        // The stored entry moves onto the new scale
+        let response = app.clone().oneshot(set_scale(0, 10)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(read_mood(app.clone()).await, vec![Some(2)]);
+
+        let response = app.oneshot(set_scale(5, 1)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+}
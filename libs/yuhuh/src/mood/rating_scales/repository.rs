@@ -0,0 +1,110 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use tracing::{debug, error};
+use uuid::Uuid;
+
+use crate::{
+    error::YuhuhError,
+    mood::scale::{RatingMetric, RatingScale, RatingScales},
+};
+
+// =============================================================================
+// Traits
+// =============================================================================
+
+#[async_trait]
+pub trait RatingScalesRepository: std::fmt::Debug + Send + Sync + 'static {
+    /// A user's scales, with the default for any they haven't set.
+    async fn read_rating_scales(&self, user_id: &Uuid) -> Result<RatingScales, YuhuhError>;
+
+    /// Sets the scale a user rates a metric on.
+    async fn upsert_rating_scale(
+        &self,
+        user_id: &Uuid,
+        metric: RatingMetric,
+        scale: RatingScale,
+    ) -> Result<(), YuhuhError>;
+}
+
+// =============================================================================
+// Production Implementation
+// =============================================================================
+
+#[derive(Debug)]
+pub struct RatingScalesRepositoryImpl {
+    pub db: PgPool,
+}
+
+impl RatingScalesRepositoryImpl {
+    pub fn new(db: PgPool) -> Self {
+        RatingScalesRepositoryImpl { db }
+    }
+}
+
+#[async_trait]
+impl RatingScalesRepository for RatingScalesRepositoryImpl {
+    async fn read_rating_scales(&self, user_id: &Uuid) -> Result<RatingScales, YuhuhError> {
+        debug!(user_id=?user_id, "received read rating scales");
+
+        let rows = sqlx::query!(
+            r#"
+            SELECT metric, min_rating, max_rating
+            FROM rating_scales
+            WHERE user_id = $1::uuid;
+            "#,
+            user_id
+        )
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, "database error while finding rating scales");
+
+            YuhuhError::DatabaseError(e)
+        })?;
+
+        let mut scales = RatingScales::default();
+
+        for row in rows {
+            let metric: RatingMetric = row.metric.parse()?;
+            let scale = RatingScale::new(row.min_rating, row.max_rating).inspect_err(
+                |e| error!(error = ?e, metric = %metric, "encountered invalid rating scale"),
+            )?;
+
+            scales.set(metric, scale);
+        }
+
+        Ok(scales)
+    }
+
+    async fn upsert_rating_scale(
+        &self,
+        user_id: &Uuid,
+        metric: RatingMetric,
+        scale: RatingScale,
+    ) -> Result<(), YuhuhError> {
+        debug!(user_id=?user_id, metric=%metric, scale=?scale, "received upsert rating scale");
+
+        sqlx::query!(
+            r#"
+            INSERT INTO rating_scales (user_id, metric, min_rating, max_rating)
+            VALUES ($1::uuid, $2::text, $3::smallint, $4::smallint)
+            ON CONFLICT (user_id, metric) DO UPDATE
+            SET min_rating = EXCLUDED.min_rating,
+                max_rating = EXCLUDED.max_rating;
+            "#,
+            user_id,
+            metric.to_string(),
+            scale.min,
+            scale.max
+        )
+        .execute(&self.db)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, "database error while upserting rating scale");
+
+            YuhuhError::DatabaseError(e)
+        })?;
+
+        Ok(())
+    }
+}
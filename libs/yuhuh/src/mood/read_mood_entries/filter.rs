@@ -0,0 +1,43 @@
+use chrono::{DateTime, Utc};
+
+/// Narrows down which mood entries are read.
+#[derive(Debug, Default)]
+pub struct MoodFilter {
+    /// Only entries logged at or before this time.
+    pub before: Option<DateTime<Utc>>,
+    /// Only entries logged at or after this time.
+    pub after: Option<DateTime<Utc>>,
+    /// Only entries with any of these emotions or context tags, or any entry
+    /// when empty.
+    pub tags: Vec<String>,
+}
+
+impl MoodFilter {
+    /// Builds a filter from request parameters, where tags are comma
+    /// separated and matched ignoring case.
+    pub fn parse(
+        before: Option<DateTime<Utc>>,
+        after: Option<DateTime<Utc>>,
+        tags: Option<&str>,
+    ) -> Self {
+        let tags = tags
+            .map(|tags| {
+                tags.split(',')
+                    .map(|t| {
+                        t.split_whitespace()
+                            .collect::<Vec<_>>()
+                            .join(" ")
+                            .to_lowercase()
+                    })
+                    .filter(|t| !t.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        MoodFilter {
+            before,
+            after,
+            tags,
+        }
+    }
+}
@@ -15,13 +15,14 @@ use axum::{
 };
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use tracing::{debug, error, instrument};
+use tracing::{debug, instrument};
 use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
 use crate::{
+    auth::model::Caller,
     error::YuhuhError,
-    mood::{model::MoodEntry, state::MoodState},
+    mood::{model::MoodEntry, read_mood_entries::MoodFilter, state::MoodState, tags::TagAverage},
     user::state::UserState,
 };
 
@@ -38,6 +39,9 @@ pub struct ReadMoodEntriesRequest {
     pub offset: Option<u32>,
     pub logged_before_date: Option<DateTime<Utc>>,
     pub logged_after_date: Option<DateTime<Utc>>,
+    /// Comma-separated emotions or context tags, matching entries with any of
+    /// them.
+    pub tags: Option<String>,
 }
 
 // ============================================================================
@@ -48,6 +52,9 @@ pub struct ReadMoodEntriesRequest {
 pub struct ReadMoodEntriesResponse {
     pub found_mood_entries: u32,
     pub found_entries: Vec<MoodEntry>,
+    /// Average ratings for each tag across every entry matching the filter,
+    /// not only the page of entries returned.
+    pub tag_averages: Vec<TagAverage>,
 }
 
 // =============================================================================
@@ -67,39 +74,51 @@ pub struct ReadMoodEntriesResponse {
 pub async fn read_mood_entries(
     State(mood_state): State<Arc<MoodState>>,
     State(user_state): State<Arc<UserState>>,
+    caller: Caller,
     Query(request): Query<ReadMoodEntriesRequest>,
 ) -> Result<(StatusCode, Json<ReadMoodEntriesResponse>), YuhuhError> {
     debug!("entering read_mood_entries");
 
-    if (user_state
-        .find_user_repo
-        .find_user_by_id(&request.user_id)
-        .await?)
-        .is_none()
-    {
-        error!(user_id = ?request.user_id, "failed to find user");
-        return Err(YuhuhError::NotFound("user not found".to_string()));
-    }
+    caller.authorise_user(&request.user_id)?;
+
+    user_state.require_user(&request.user_id).await?;
 
     let offset = request.offset.unwrap_or(0);
     let limit = request.limit.unwrap_or(10000);
     debug!(offset=?offset, limit=?limit, "calculated offset and limit");
 
+    let filter = MoodFilter::parse(
+        request.logged_before_date,
+        request.logged_after_date,
+        request.tags.as_deref(),
+    );
+
+    let scales = mood_state
+        .rating_scales_repo
+        .read_rating_scales(&request.user_id)
+        .await?;
+
     let records = mood_state
         .read_mood_entries_repo
         .find_mood_entries(
             &request.user_id,
-            request.logged_before_date,
-            request.logged_after_date,
+            &filter,
+            &scales,
             limit.into(),
             offset.into(),
         )
         .await?;
 
+    let tag_averages = mood_state
+        .read_mood_entries_repo
+        .find_tag_averages(&request.user_id, &filter, &scales)
+        .await?;
+
     Ok((
         StatusCode::OK,
         Json(ReadMoodEntriesResponse {
             found_mood_entries: records.len() as u32,
+            tag_averages,
             found_entries: records,
         }),
     ))
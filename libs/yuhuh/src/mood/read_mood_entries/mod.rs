@@ -1,5 +1,7 @@
+pub mod filter;
 pub mod handler;
 pub mod repository;
 
+pub use filter::*;
 pub use handler::*;
 pub use repository::*;
@@ -1,12 +1,16 @@
 use async_trait::async_trait;
-use chrono::{DateTime, Utc};
 use sqlx::PgPool;
 use tracing::{debug, error};
 use uuid::Uuid;
 
 use crate::{
     error::YuhuhError,
-    mood::model::{MoodEntry, MoodEntryRow},
+    mood::{
+        model::{MoodEntry, MoodEntryRow},
+        read_mood_entries::MoodFilter,
+        scale::RatingScales,
+        tags::{TagAverage, TagAverageRow},
+    },
 };
 
 // =============================================================================
@@ -18,11 +22,20 @@ pub trait ReadMoodEntriesRepository: std::fmt::Debug + Send + Sync + 'static {
     async fn find_mood_entries(
         &self,
         user_id: &Uuid,
-        before: Option<DateTime<Utc>>,
-        after: Option<DateTime<Utc>>,
+        filter: &MoodFilter,
+        scales: &RatingScales,
         limit: i64,
         offset: i64,
     ) -> Result<Vec<MoodEntry>, YuhuhError>;
+
+    /// Average ratings for each tag across every entry matching the filter,
+    /// emotions first and then by name.
+    async fn find_tag_averages(
+        &self,
+        user_id: &Uuid,
+        filter: &MoodFilter,
+        scales: &RatingScales,
+    ) -> Result<Vec<TagAverage>, YuhuhError>;
 }
 
 // =============================================================================
@@ -45,15 +58,14 @@ impl ReadMoodEntriesRepository for ReadMoodEntriesRepositoryImpl {
     async fn find_mood_entries(
         &self,
         user_id: &Uuid,
-        before: Option<DateTime<Utc>>,
-        after: Option<DateTime<Utc>>,
+        filter: &MoodFilter,
+        scales: &RatingScales,
         limit: i64,
         offset: i64,
     ) -> Result<Vec<MoodEntry>, YuhuhError> {
         debug!(
             user_id=?user_id,
-            before=?before,
-            after=?after,
+            filter=?filter,
             limit=?limit,
             offset=?offset,
             "received find request for mood entries"
@@ -62,20 +74,46 @@ impl ReadMoodEntriesRepository for ReadMoodEntriesRepositoryImpl {
         let records: Vec<MoodEntryRow> = sqlx::query_as!(
             MoodEntryRow,
             r#"
-            SELECT *
-            FROM mood_records
+            SELECT
+                m.mood_record_id,
+                m.user_id,
+                m.created_at,
+                m.updated_at,
+                m.mood,
+                m.energy,
+                m.sleep,
+                m.notes,
+                m.logged_at,
+                ARRAY(
+                    SELECT t.tag FROM mood_tags t
+                    WHERE t.mood_record_id = m.mood_record_id AND t.kind = 'emotion'
+                    ORDER BY t.tag
+                ) AS "emotions!",
+                ARRAY(
+                    SELECT t.tag FROM mood_tags t
+                    WHERE t.mood_record_id = m.mood_record_id AND t.kind = 'context'
+                    ORDER BY t.tag
+                ) AS "context_tags!"
+            FROM mood_records m
             WHERE user_id = $1::uuid
             AND ($2::timestamptz IS NULL
                 OR logged_at <= $2::timestamptz)
             AND ($3::timestamptz IS NULL
                 OR logged_at >= $3::timestamptz)
+            AND (cardinality($4::text[]) = 0
+                OR EXISTS (
+                    SELECT 1 FROM mood_tags t
+                    WHERE t.mood_record_id = m.mood_record_id
+                    AND t.tag = ANY($4::text[])
+                ))
             ORDER BY logged_at DESC
-            LIMIT $4
-            OFFSET $5;
+            LIMIT $5
+            OFFSET $6;
             "#,
             user_id,
-            before,
-            after,
+            filter.before,
+            filter.after,
+            &filter.tags[..],
             limit,
             offset
         )
@@ -94,7 +132,7 @@ impl ReadMoodEntriesRepository for ReadMoodEntriesRepositoryImpl {
         let mood_entries: Vec<MoodEntry> = records
             .into_iter()
             .filter_map(|row| {
-                row.try_into()
+                row.into_entry(scales)
                     .inspect_err(|e| {
                         error!(error=?e, "ecountered parsing error for mood entry");
                         errors_found = true;
@@ -111,4 +149,70 @@ impl ReadMoodEntriesRepository for ReadMoodEntriesRepositoryImpl {
 
         Ok(mood_entries)
     }
+
+    async fn find_tag_averages(
+        &self,
+        user_id: &Uuid,
+        filter: &MoodFilter,
+        scales: &RatingScales,
+    ) -> Result<Vec<TagAverage>, YuhuhError> {
+        debug!(
+            user_id=?user_id,
+            filter=?filter,
+            "received find request for mood tag averages"
+        );
+
+        let rows: Vec<TagAverageRow> = sqlx::query_as!(
+            TagAverageRow,
+            r#"
+            WITH found AS (
+                SELECT m.mood_record_id, m.mood, m.energy, m.sleep
+                FROM mood_records m
+                WHERE user_id = $1::uuid
+                AND ($2::timestamptz IS NULL
+                    OR logged_at <= $2::timestamptz)
+                AND ($3::timestamptz IS NULL
+                    OR logged_at >= $3::timestamptz)
+                AND (cardinality($4::text[]) = 0
+                    OR EXISTS (
+                        SELECT 1 FROM mood_tags t
+                        WHERE t.mood_record_id = m.mood_record_id
+                        AND t.tag = ANY($4::text[])
+                    ))
+            )
+            SELECT
+                t.kind,
+                t.tag,
+                count(*) AS "count!",
+                avg(f.mood) FILTER (WHERE f.mood BETWEEN 0 AND 1) AS mood,
+                avg(f.energy) FILTER (WHERE f.energy BETWEEN 0 AND 1) AS energy,
+                avg(f.sleep) FILTER (WHERE f.sleep BETWEEN 0 AND 1) AS sleep,
+                count(*) FILTER (WHERE f.mood NOT BETWEEN 0 AND 1)
+                    + count(*) FILTER (WHERE f.energy NOT BETWEEN 0 AND 1)
+                    + count(*) FILTER (WHERE f.sleep NOT BETWEEN 0 AND 1) AS "excluded!"
+            FROM found f
+            JOIN mood_tags t ON t.mood_record_id = f.mood_record_id
+            GROUP BY t.kind, t.tag
+            ORDER BY t.kind = 'context', t.tag;
+            "#,
+            user_id,
+            filter.before,
+            filter.after,
+            &filter.tags[..]
+        )
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, "database error while finding mood tag averages");
+
+            YuhuhError::DatabaseError(e)
+        })?;
+
+        debug!(tag_averages=?rows, "found mood tag averages");
+
+        Ok(rows
+            .into_iter()
+            .map(|row| row.into_average(scales))
+            .collect::<Result<_, _>>()?)
+    }
 }
@@ -0,0 +1,221 @@
+//! mood statistics HTTP handler
+//!
+//! This module provides an HTTP endpoint for mood, energy and sleep statistics
+//! over a window of days in the user's timezone.
+
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::StatusCode,
+};
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, instrument, warn};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::{
+    auth::model::Caller,
+    date_range::DateRange,
+    error::YuhuhError,
+    mood::{
+        state::MoodState,
+        stats::{ROLLING_DAYS, RatingStats, rating_stats},
+    },
+    user::state::UserState,
+};
+
+/// Window used when no start date is given.
+const DEFAULT_RANGE_DAYS: u64 = 29;
+
+// ============================================================================
+// HTTP Request types
+// ============================================================================
+
+/// Request parameters for finding mood statistics.
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ReadMoodStatsRequest {
+    /// user ID to search by.
+    pub user_id: Uuid,
+    /// First day in the user's timezone, defaulting to 30 days up to
+    /// `end_date`.
+    pub start_date: Option<NaiveDate>,
+    /// Last day in the user's timezone, defaulting to today.
+    pub end_date: Option<NaiveDate>,
+}
+
+// ============================================================================
+// HTTP Responsed types
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ReadMoodStatsResponse {
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub mood: RatingStats,
+    pub energy: RatingStats,
+    pub sleep: RatingStats,
+}
+
+// =============================================================================
+// HTTP Handlers
+// =============================================================================
+
+/// Find statistics on a user's mood, energy and sleep
+#[utoipa::path(
+    get,
+    path = "mood/stats",
+    tag = "mood",
+    params(ReadMoodStatsRequest),
+    responses(
+        (status = 200, description = "Found mood statistics", body = ReadMoodStatsResponse),
+        (status = 400, description = "Invalid date range"),
+        (status = 404, description = "User not found"),
+))]
+#[instrument]
+pub async fn read_mood_stats(
+    State(mood_state): State<Arc<MoodState>>,
+    State(user_state): State<Arc<UserState>>,
+    caller: Caller,
+    Query(request): Query<ReadMoodStatsRequest>,
+) -> Result<(StatusCode, Json<ReadMoodStatsResponse>), YuhuhError> {
+    debug!("entering read_mood_stats");
+
+    caller.authorise_user(&request.user_id)?;
+
+    let user = user_state.require_user(&request.user_id).await?;
+
+    let tz = user.tz();
+    let range = DateRange::from_request(
+        request.start_date,
+        request.end_date,
+        Utc::now().with_timezone(&tz).date_naive(),
+        DEFAULT_RANGE_DAYS,
+    )?;
+    let (start, end) = (range.start, range.end);
+
+    // The rolling average looks back before the window
+    let (before, after) = range.extended_back(ROLLING_DAYS - 1)?.utc_bounds()?;
+
+    let scales = mood_state
+        .rating_scales_repo
+        .read_rating_scales(&request.user_id)
+        .await?;
+
+    // Read a day either side in UTC, then place each entry on its local day
+    let rows = mood_state
+        .read_mood_stats_repo
+        .read_mood_rows(&request.user_id, before, after)
+        .await?;
+
+    let mut mood = vec![];
+    let mut energy = vec![];
+    let mut sleep = vec![];
+
+    for row in rows {
+        let date = row.logged_at.with_timezone(&tz).date_naive();
+
+        mood.extend(row.mood.map(|r| (date, r)));
+        energy.extend(row.energy.map(|r| (date, r)));
+        sleep.extend(row.sleep.map(|r| (date, r)));
+    }
+
+    let response = ReadMoodStatsResponse {
+        start_date: start,
+        end_date: end,
+        mood: rating_stats(&mood, &scales.mood, start, end),
+        energy: rating_stats(&energy, &scales.energy, start, end),
+        sleep: rating_stats(&sleep, &scales.sleep, start, end),
+    };
+
+    let excluded = response.mood.excluded + response.energy.excluded + response.sleep.excluded;
+    if excluded > 0 {
+        warn!(user_id = ?request.user_id, excluded, "left out-of-range ratings out of mood statistics");
+    }
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+    };
+    use chrono::NaiveDate;
+    use http_body_util::BodyExt;
+    use pretty_assertions::assert_eq;
+    use tower::ServiceExt;
+
+    use crate::mood::read_mood_stats::ReadMoodStatsResponse;
+
+    async fn read(app: axum::Router, query: &str) -> (StatusCode, Option<ReadMoodStatsResponse>) {
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/mood/stats?{}", query))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let status = response.status();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+
+        (status, serde_json::from_slice(&body).ok())
+    }
+
+    #[tokio::test]
+    async fn stats_use_local_days_and_count_invalid_ratings() {
+        let (app, db, _) = crate::test::common::setup().await;
+
+        sqlx::raw_sql(include_str!("../../migrations/test/mood_stats.sql"))
+            .execute(&db)
+            .await
+            .expect("setup test sql ran successfully");
+
+        let (status, dto) = read(
+            app.clone(),
+            "user_id=11111111-1111-1111-1111-111111111111&start_date=2025-06-02&end_date=2025-06-08",
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let dto = dto.expect("valid ReadMoodStatsResponse bytes");
+
+        assert_eq!(dto.mood.count, 2);
+        assert_eq!(dto.mood.excluded, 1);
+        assert_eq!(dto.mood.mean, Some(6.0));
+        assert_eq!(
+            dto.mood.best_day.map(|d| d.date),
+            NaiveDate::from_ymd_opt(2025, 6, 3)
+        );
+        assert_eq!(dto.mood.rolling_average.len(), 7);
+
+        // Monday and Tuesday in Auckland, rather than Sunday and Monday in UTC
+        assert_eq!(
+            dto.energy
+                .day_of_week
+                .iter()
+                .map(|d| d.average)
+                .take(3)
+                .collect::<Vec<_>>(),
+            vec![Some(5.0), Some(6.5), None]
+        );
+        assert_eq!(dto.sleep.count, 1);
+
+        let (status, _) = read(
+            app.clone(),
+            "user_id=11111111-1111-1111-1111-111111111111&start_date=2025-06-08&end_date=2025-06-02",
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, _) = read(app, "user_id=22222222-2222-2222-2222-222222222222").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,5 @@
+pub mod handler;
+pub mod repository;
+
+pub use handler::*;
+pub use repository::*;
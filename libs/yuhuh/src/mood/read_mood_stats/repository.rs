@@ -0,0 +1,95 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use tracing::{debug, error};
+use uuid::Uuid;
+
+use crate::{error::YuhuhError, mood::model::MoodEntryRow};
+
+// =============================================================================
+// Traits
+// =============================================================================
+
+#[async_trait]
+pub trait ReadMoodStatsRepository: std::fmt::Debug + Send + Sync + 'static {
+    /// Raw mood rows logged between `after` and `before`, oldest first.
+    ///
+    /// Rows aren't parsed into [`crate::mood::model::MoodEntry`] so ratings
+    /// out of range can be counted rather than failing the read.
+    async fn read_mood_rows(
+        &self,
+        user_id: &Uuid,
+        before: DateTime<Utc>,
+        after: DateTime<Utc>,
+    ) -> Result<Vec<MoodEntryRow>, YuhuhError>;
+}
+
+// =============================================================================
+// Production Implementation
+// =============================================================================
+
+#[derive(Debug)]
+pub struct ReadMoodStatsRepositoryImpl {
+    pub db: PgPool,
+}
+
+impl ReadMoodStatsRepositoryImpl {
+    pub fn new(db: PgPool) -> Self {
+        ReadMoodStatsRepositoryImpl { db }
+    }
+}
+
+#[async_trait]
+impl ReadMoodStatsRepository for ReadMoodStatsRepositoryImpl {
+    async fn read_mood_rows(
+        &self,
+        user_id: &Uuid,
+        before: DateTime<Utc>,
+        after: DateTime<Utc>,
+    ) -> Result<Vec<MoodEntryRow>, YuhuhError> {
+        debug!(user_id=?user_id, before=?before, after=?after, "received read mood rows");
+
+        let records: Vec<MoodEntryRow> = sqlx::query_as!(
+            MoodEntryRow,
+            r#"
+            SELECT
+                m.mood_record_id,
+                m.user_id,
+                m.created_at,
+                m.updated_at,
+                m.mood,
+                m.energy,
+                m.sleep,
+                m.notes,
+                m.logged_at,
+                ARRAY(
+                    SELECT t.tag FROM mood_tags t
+                    WHERE t.mood_record_id = m.mood_record_id AND t.kind = 'emotion'
+                    ORDER BY t.tag
+                ) AS "emotions!",
+                ARRAY(
+                    SELECT t.tag FROM mood_tags t
+                    WHERE t.mood_record_id = m.mood_record_id AND t.kind = 'context'
+                    ORDER BY t.tag
+                ) AS "context_tags!"
+            FROM mood_records m
+            WHERE user_id = $1::uuid
+            AND logged_at < $2::timestamptz
+            AND logged_at >= $3::timestamptz
+            ORDER BY logged_at;
+            "#,
+            user_id,
+            before,
+            after
+        )
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, "database error while finding mood records");
+
+            YuhuhError::DatabaseError(e)
+        })?;
+
+        Ok(records)
+    }
+}
@@ -1,13 +1,13 @@
 use axum::{
     Router,
-    routing::{get, post},
+    routing::{get, post, put},
 };
 use utoipa::OpenApi;
 
 use crate::{
     mood::{
         create_mood_entries::{self},
-        read_mood_entries,
+        rating_scales, read_mood_entries, read_mood_stats,
     },
     state::AppState,
 };
@@ -19,7 +19,10 @@ use crate::{
 #[derive(OpenApi)]
 #[openapi(paths(
     create_mood_entries::create_mood_entries,
-    read_mood_entries::read_mood_entries
+    read_mood_entries::read_mood_entries,
+    read_mood_stats::read_mood_stats,
+    rating_scales::read_rating_scales,
+    rating_scales::update_rating_scale
 ))]
 pub struct MoodApi;
 
@@ -31,4 +34,7 @@ pub fn mood_router() -> Router<AppState> {
     Router::new()
         .route("/mood", post(create_mood_entries::create_mood_entries))
         .route("/mood", get(read_mood_entries::read_mood_entries))
+        .route("/mood/stats", get(read_mood_stats::read_mood_stats))
+        .route("/mood/scales", get(rating_scales::read_rating_scales))
+        .route("/mood/scales", put(rating_scales::update_rating_scale))
 }
@@ -0,0 +1,196 @@
+//! Per-user rating scales for mood, energy and sleep.
+//!
+//! Ratings are given and shown on the user's scale, such as 1 to 5 or -5 to
+//! +5 for mood valence, but stored normalised between 0 and 1. Changing a
+//! scale doesn't touch stored entries, they're just shown on the new one.
+
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    error::{ConversionError, RatingError},
+    mood::rating::{MAX_RATING, MIN_RATING, Rating},
+};
+
+/// Metrics that can be rated on a mood entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum RatingMetric {
+    Mood,
+    Energy,
+    Sleep,
+}
+
+impl fmt::Display for RatingMetric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RatingMetric::Mood => write!(f, "mood"),
+            RatingMetric::Energy => write!(f, "energy"),
+            RatingMetric::Sleep => write!(f, "sleep"),
+        }
+    }
+}
+
+impl FromStr for RatingMetric {
+    type Err = ConversionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "mood" => Ok(RatingMetric::Mood),
+            "energy" => Ok(RatingMetric::Energy),
+            "sleep" => Ok(RatingMetric::Sleep),
+            _ => Err(ConversionError::new(format!("unknown rating metric {}", s))),
+        }
+    }
+}
+
+/// Lowest and highest rating a user gives for a metric.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[schema(examples(
+    json!({ "min": 0, "max": 10 }),
+    json!({ "min": 1, "max": 5 }),
+    json!({ "min": -5, "max": 5 })
+))]
+pub struct RatingScale {
+    #[schema(minimum = -100, maximum = 100)]
+    pub min: i16,
+    #[schema(minimum = -100, maximum = 100)]
+    pub max: i16,
+}
+
+impl Default for RatingScale {
+    fn default() -> Self {
+        RatingScale { min: 0, max: 10 }
+    }
+}
+
+impl RatingScale {
+    pub fn new(min: i16, max: i16) -> Result<Self, RatingError> {
+        if !(MIN_RATING..=MAX_RATING).contains(&min.into())
+            || !(MIN_RATING..=MAX_RATING).contains(&max.into())
+        {
+            return Err(RatingError::new(format!(
+                "scales must be between {} and {}",
+                MIN_RATING, MAX_RATING
+            )));
+        }
+
+        if min >= max {
+            return Err(RatingError::new(format!(
+                "scale minimum must be below its maximum, but got {} to {}",
+                min, max
+            )));
+        }
+
+        Ok(RatingScale { min, max })
+    }
+
+    fn span(&self) -> f32 {
+        (self.max - self.min) as f32
+    }
+
+    /// Converts a rating on this scale into a value from 0 to 1.
+    pub fn normalise(&self, rating: Rating) -> Result<f32, RatingError> {
+        let value = rating.get();
+
+        if value < self.min.into() || value > self.max.into() {
+            return Err(RatingError::new(format!(
+                "must be between {} and {}, but got {} instead",
+                self.min, self.max, value
+            )));
+        }
+
+        Ok((value - i32::from(self.min)) as f32 / self.span())
+    }
+
+    /// Whether a stored value is within 0 to 1.
+    pub fn is_normalised(value: f32) -> bool {
+        (0.0..=1.0).contains(&value)
+    }
+
+    /// Converts a normalised value onto this scale without rounding, for
+    /// analytics.
+    pub fn scale(&self, normalised: f32) -> f32 {
+        self.min as f32 + normalised * self.span()
+    }
+
+    /// Converts a normalised value into the nearest rating on this scale.
+    pub fn rating(&self, normalised: f32) -> Result<Rating, RatingError> {
+        if !Self::is_normalised(normalised) {
+            return Err(RatingError::new(format!(
+                "stored value must be between 0 and 1, but got {} instead",
+                normalised
+            )));
+        }
+
+        Rating::new(self.scale(normalised).round() as i32)
+            .ok_or_else(|| RatingError::new(format!("{} is not a valid rating", normalised)))
+    }
+}
+
+/// A user's scales, falling back to 0 to 10 where they haven't set one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct RatingScales {
+    pub mood: RatingScale,
+    pub energy: RatingScale,
+    pub sleep: RatingScale,
+}
+
+impl RatingScales {
+    pub fn get(&self, metric: RatingMetric) -> &RatingScale {
+        match metric {
+            RatingMetric::Mood => &self.mood,
+            RatingMetric::Energy => &self.energy,
+            RatingMetric::Sleep => &self.sleep,
+        }
+    }
+
+    pub fn set(&mut self, metric: RatingMetric, scale: RatingScale) {
+        match metric {
+            RatingMetric::Mood => self.mood = scale,
+            RatingMetric::Energy => self.energy = scale,
+            RatingMetric::Sleep => self.sleep = scale,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn ratings_round_trip_through_normalised_values() {
+        let valence = RatingScale::new(-5, 5).unwrap();
+        let five_point = RatingScale::new(1, 5).unwrap();
+
+        assert_eq!(valence.normalise(Rating::new(-5).unwrap()).unwrap(), 0.0);
+        assert_eq!(valence.normalise(Rating::new(0).unwrap()).unwrap(), 0.5);
+        assert_eq!(five_point.normalise(Rating::new(2).unwrap()).unwrap(), 0.25);
+
+        // Every rating comes back out as it went in
+        for value in 1..=5 {
+            let rating = Rating::new(value).unwrap();
+            let normalised = five_point.normalise(rating).unwrap();
+
+            assert_eq!(five_point.rating(normalised).unwrap(), rating);
+        }
+
+        // A 4 out of 5 shown on the default scale
+        assert_eq!(RatingScale::default().scale(0.75), 7.5);
+    }
+
+    #[test]
+    fn invalid_scales_and_ratings_rejected() {
+        assert!(RatingScale::new(5, 1).is_err());
+        assert!(RatingScale::new(0, 0).is_err());
+        assert!(RatingScale::new(0, 101).is_err());
+
+        let five_point = RatingScale::new(1, 5).unwrap();
+        assert!(five_point.normalise(Rating::new(0).unwrap()).is_err());
+        assert!(five_point.rating(1.5).is_err());
+    }
+}
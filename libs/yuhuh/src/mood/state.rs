@@ -4,13 +4,17 @@ use sqlx::PgPool;
 
 use crate::mood::{
     create_mood_entries::repository::{CreateMoodEntryRepository, CreateMoodEntryRepositoryImpl},
+    rating_scales::repository::{RatingScalesRepository, RatingScalesRepositoryImpl},
     read_mood_entries::repository::{ReadMoodEntriesRepository, ReadMoodEntriesRepositoryImpl},
+    read_mood_stats::repository::{ReadMoodStatsRepository, ReadMoodStatsRepositoryImpl},
 };
 
 #[derive(Debug)]
 pub struct MoodState {
     pub create_mood_entries_repo: Arc<dyn CreateMoodEntryRepository>,
     pub read_mood_entries_repo: Arc<dyn ReadMoodEntriesRepository>,
+    pub read_mood_stats_repo: Arc<dyn ReadMoodStatsRepository>,
+    pub rating_scales_repo: Arc<dyn RatingScalesRepository>,
 }
 
 impl MoodState {
@@ -18,6 +22,8 @@ impl MoodState {
         MoodState {
             create_mood_entries_repo: Arc::new(CreateMoodEntryRepositoryImpl::new(db.clone())),
             read_mood_entries_repo: Arc::new(ReadMoodEntriesRepositoryImpl::new(db.clone())),
+            read_mood_stats_repo: Arc::new(ReadMoodStatsRepositoryImpl::new(db.clone())),
+            rating_scales_repo: Arc::new(RatingScalesRepositoryImpl::new(db.clone())),
         }
     }
 }
@@ -0,0 +1,292 @@
+//! Statistics over mood, energy and sleep ratings.
+//!
+//! Ratings are grouped by the day they were logged in the user's timezone and
+//! reported on the user's scale. Stored ratings outside the valid range are
+//! left out of every statistic and counted instead, so one bad row doesn't
+//! hide the rest.
+
+use chrono::{Datelike, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::mood::scale::RatingScale;
+
+/// Days covered by the rolling average, including the day itself.
+pub const ROLLING_DAYS: u64 = 7;
+
+const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct RatingStats {
+    /// Ratings in the window.
+    pub count: u32,
+    /// Stored ratings left out for being out of range.
+    pub excluded: u32,
+    pub mean: Option<f32>,
+    pub median: Option<f32>,
+    /// Population standard deviation.
+    pub standard_deviation: Option<f32>,
+    /// Average of the last 7 days of ratings, for every day in the window.
+    pub rolling_average: Vec<RollingAverage>,
+    /// Day with the highest average, the earliest on a tie.
+    pub best_day: Option<DayAverage>,
+    /// Day with the lowest average, the earliest on a tie.
+    pub worst_day: Option<DayAverage>,
+    /// Monday first.
+    pub day_of_week: Vec<WeekdayAverage>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct RollingAverage {
+    pub date: NaiveDate,
+    /// `None` when nothing was rated in the last 7 days.
+    pub average: Option<f32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct DayAverage {
+    pub date: NaiveDate,
+    pub average: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct WeekdayAverage {
+    #[schema(value_type = String, example = "Mon")]
+    pub weekday: Weekday,
+    pub count: u32,
+    pub average: Option<f32>,
+}
+
+fn mean(values: &[f32]) -> Option<f32> {
+    (!values.is_empty()).then(|| values.iter().sum::<f32>() / values.len() as f32)
+}
+
+fn median(sorted: &[f32]) -> Option<f32> {
+    let middle = sorted.len() / 2;
+
+    match sorted.len() {
+        0 => None,
+        n if n % 2 == 0 => Some((sorted[middle - 1] + sorted[middle]) / 2.0),
+        _ => Some(sorted[middle]),
+    }
+}
+
+/// Whether a rating logged on `rated` counts towards the rolling average on
+/// `date`, compared without adding days so dates near the limits of
+/// [`NaiveDate`] can't overflow.
+fn in_rolling_window(rated: NaiveDate, date: NaiveDate) -> bool {
+    rated <= date && (date - rated).num_days() < ROLLING_DAYS as i64
+}
+
+fn standard_deviation(values: &[f32]) -> Option<f32> {
+    let mean = mean(values)?;
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f32>() / values.len() as f32;
+
+    Some(variance.sqrt())
+}
+
+/// Statistics for ratings logged from `start` to `end`, given as local dates
+/// and stored normalised values.
+///
+/// Ratings up to 6 days before `start` only feed the rolling average, so
+/// callers should pass those in as well.
+pub fn rating_stats(
+    ratings: &[(NaiveDate, f32)],
+    scale: &RatingScale,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> RatingStats {
+    let mut excluded = 0;
+
+    let valid: Vec<(NaiveDate, f32)> = ratings
+        .iter()
+        .filter(|(date, _)| *date <= end && (*date >= start || in_rolling_window(*date, start)))
+        .filter_map(|&(date, value)| {
+            if RatingScale::is_normalised(value) {
+                Some((date, scale.scale(value)))
+            } else {
+                if date >= start {
+                    excluded += 1;
+                }
+                None
+            }
+        })
+        .collect();
+
+    let in_window: Vec<(NaiveDate, f32)> = valid
+        .iter()
+        .copied()
+        .filter(|(date, _)| *date >= start)
+        .collect();
+
+    let mut values: Vec<f32> = in_window.iter().map(|(_, v)| *v).collect();
+    values.sort_by(f32::total_cmp);
+
+    let days = start.iter_days().take_while(|date| *date <= end);
+
+    let rolling_average = days
+        .clone()
+        .map(|date| {
+            let recent: Vec<f32> = valid
+                .iter()
+                .filter(|(d, _)| in_rolling_window(*d, date))
+                .map(|(_, v)| *v)
+                .collect();
+
+            RollingAverage {
+                date,
+                average: mean(&recent),
+            }
+        })
+        .collect();
+
+    let daily: Vec<DayAverage> = days
+        .filter_map(|date| {
+            let day: Vec<f32> = in_window
+                .iter()
+                .filter(|(d, _)| *d == date)
+                .map(|(_, v)| *v)
+                .collect();
+
+            mean(&day).map(|average| DayAverage { date, average })
+        })
+        .collect();
+
+    // Days are in date order, so keeping the first of equals picks the earliest
+    let best_day = daily
+        .iter()
+        .reduce(|best, day| {
+            if day.average > best.average {
+                day
+            } else {
+                best
+            }
+        })
+        .cloned();
+    let worst_day = daily
+        .iter()
+        .reduce(|worst, day| {
+            if day.average < worst.average {
+                day
+            } else {
+                worst
+            }
+        })
+        .cloned();
+
+    let day_of_week = WEEKDAYS
+        .iter()
+        .map(|&weekday| {
+            let day: Vec<f32> = in_window
+                .iter()
+                .filter(|(d, _)| d.weekday() == weekday)
+                .map(|(_, v)| *v)
+                .collect();
+
+            WeekdayAverage {
+                weekday,
+                count: day.len() as u32,
+                average: mean(&day),
+            }
+        })
+        .collect();
+
+    RatingStats {
+        count: values.len() as u32,
+        excluded,
+        mean: mean(&values),
+        median: median(&values),
+        standard_deviation: standard_deviation(&values),
+        rolling_average,
+        best_day,
+        worst_day,
+        day_of_week,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 9, day).unwrap()
+    }
+
+    #[test]
+    fn stats_leave_out_invalid_ratings() {
+        // 2025-09-01 is a Monday
+        let ratings = vec![
+            (date(1), 0.2),
+            (date(1), 0.4),
+            (date(2), 0.8),
+            (date(3), 0.8),
+            (date(3), 1.2),
+            (date(4), -0.1),
+        ];
+
+        let stats = rating_stats(&ratings, &RatingScale::default(), date(1), date(7));
+
+        assert_eq!(stats.count, 4);
+        assert_eq!(stats.excluded, 2);
+        assert_eq!(stats.mean, Some(5.5));
+        assert_eq!(stats.median, Some(6.0));
+        assert_eq!(stats.standard_deviation, Some(6.75_f32.sqrt()));
+        assert_eq!(
+            stats.best_day,
+            Some(DayAverage {
+                date: date(2),
+                average: 8.0
+            })
+        );
+        assert_eq!(
+            stats.worst_day,
+            Some(DayAverage {
+                date: date(1),
+                average: 3.0
+            })
+        );
+        assert_eq!(stats.day_of_week[0].average, Some(3.0));
+        assert_eq!(stats.day_of_week[3].count, 0);
+    }
+
+    #[test]
+    fn rolling_average_looks_back_a_week() {
+        // Shown on a 1 to 5 scale
+        let ratings = vec![(date(1), 0.25), (date(7), 0.75), (date(8), 1.0)];
+        let scale = RatingScale::new(1, 5).unwrap();
+
+        let stats = rating_stats(&ratings, &scale, date(7), date(8));
+
+        // The rating from the 1st still counts on the 7th, but not the 8th
+        assert_eq!(
+            stats
+                .rolling_average
+                .iter()
+                .map(|r| r.average)
+                .collect::<Vec<_>>(),
+            vec![Some(3.0), Some(4.5)]
+        );
+        assert_eq!(stats.count, 2);
+    }
+
+    #[test]
+    fn latest_dates_dont_overflow() {
+        let latest = NaiveDate::MAX.pred_opt().unwrap().pred_opt().unwrap();
+        let ratings = vec![(latest, 0.5), (NaiveDate::MAX, 0.5)];
+
+        let stats = rating_stats(&ratings, &RatingScale::default(), latest, latest);
+
+        assert_eq!(stats.count, 1);
+        assert_eq!(stats.rolling_average[0].average, Some(5.0));
+    }
+}
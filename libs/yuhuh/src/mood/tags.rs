@@ -0,0 +1,237 @@
+//! Emotions and context tags attached to mood entries.
+//!
+//! Emotions come from a fixed vocabulary so they can be compared across
+//! users, while context tags are free-form labels for what was going on, such
+//! as "work" or "travel".
+
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    error::ConversionError,
+    mood::scale::{RatingMetric, RatingScales},
+};
+
+/// Most emotions or context tags a single entry can have.
+pub const MAX_TAGS: usize = 20;
+
+/// Longest context tag.
+const MAX_TAG_LENGTH: usize = 32;
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, ToSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Emotion {
+    Angry,
+    Anxious,
+    Bored,
+    Calm,
+    Content,
+    Excited,
+    Frustrated,
+    Grateful,
+    Happy,
+    Hopeful,
+    Irritable,
+    Lonely,
+    Motivated,
+    Overwhelmed,
+    Relaxed,
+    Sad,
+    Stressed,
+}
+
+impl Emotion {
+    pub const ALL: [Emotion; 17] = [
+        Emotion::Angry,
+        Emotion::Anxious,
+        Emotion::Bored,
+        Emotion::Calm,
+        Emotion::Content,
+        Emotion::Excited,
+        Emotion::Frustrated,
+        Emotion::Grateful,
+        Emotion::Happy,
+        Emotion::Hopeful,
+        Emotion::Irritable,
+        Emotion::Lonely,
+        Emotion::Motivated,
+        Emotion::Overwhelmed,
+        Emotion::Relaxed,
+        Emotion::Sad,
+        Emotion::Stressed,
+    ];
+}
+
+impl fmt::Display for Emotion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Emotion::Angry => "angry",
+            Emotion::Anxious => "anxious",
+            Emotion::Bored => "bored",
+            Emotion::Calm => "calm",
+            Emotion::Content => "content",
+            Emotion::Excited => "excited",
+            Emotion::Frustrated => "frustrated",
+            Emotion::Grateful => "grateful",
+            Emotion::Happy => "happy",
+            Emotion::Hopeful => "hopeful",
+            Emotion::Irritable => "irritable",
+            Emotion::Lonely => "lonely",
+            Emotion::Motivated => "motivated",
+            Emotion::Overwhelmed => "overwhelmed",
+            Emotion::Relaxed => "relaxed",
+            Emotion::Sad => "sad",
+            Emotion::Stressed => "stressed",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Emotion {
+    type Err = ConversionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Emotion::ALL
+            .into_iter()
+            .find(|e| e.to_string().eq_ignore_ascii_case(s))
+            .ok_or_else(|| ConversionError::new(format!("unknown emotion {}", s)))
+    }
+}
+
+/// Whether a tag is an emotion or a context tag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum TagKind {
+    Emotion,
+    Context,
+}
+
+impl fmt::Display for TagKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TagKind::Emotion => write!(f, "emotion"),
+            TagKind::Context => write!(f, "context"),
+        }
+    }
+}
+
+impl FromStr for TagKind {
+    type Err = ConversionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "emotion" => Ok(TagKind::Emotion),
+            "context" => Ok(TagKind::Context),
+            _ => Err(ConversionError::new(format!("unknown tag kind {}", s))),
+        }
+    }
+}
+
+/// Trims and lowercases a context tag, checking it's made of letters,
+/// numbers, spaces, dashes and underscores.
+pub fn normalise_context_tag(tag: &str) -> Result<String, String> {
+    let tag = tag
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase();
+
+    if tag.is_empty() || tag.chars().count() > MAX_TAG_LENGTH {
+        return Err(format!(
+            "context tags must be between 1 and {} characters",
+            MAX_TAG_LENGTH
+        ));
+    }
+
+    if !tag
+        .chars()
+        .all(|c| c.is_alphanumeric() || matches!(c, ' ' | '-' | '_'))
+    {
+        return Err(format!(
+            "context tag {} can only have letters, numbers, spaces, dashes and underscores",
+            tag
+        ));
+    }
+
+    Ok(tag)
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct TagAverage {
+    pub kind: TagKind,
+    pub tag: String,
+    /// Entries with the tag.
+    pub count: u32,
+    /// Stored ratings left out for being out of range.
+    pub excluded: u32,
+    pub mood: Option<f32>,
+    pub energy: Option<f32>,
+    pub sleep: Option<f32>,
+}
+
+// =============================================================================
+// Row Structs
+// =============================================================================
+#[derive(Debug, sqlx::FromRow)]
+pub struct TagAverageRow {
+    pub kind: String,
+    pub tag: String,
+    pub count: i64,
+    pub excluded: i64,
+    /// Normalised averages of the ratings in range, from 0 to 1.
+    pub mood: Option<f64>,
+    pub energy: Option<f64>,
+    pub sleep: Option<f64>,
+}
+
+impl TagAverageRow {
+    /// Converts the normalised averages onto the user's scales.
+    pub fn into_average(self, scales: &RatingScales) -> Result<TagAverage, ConversionError> {
+        let scale = |value: Option<f64>, metric: RatingMetric| {
+            value.map(|v| scales.get(metric).scale(v as f32))
+        };
+
+        Ok(TagAverage {
+            kind: self.kind.parse()?,
+            tag: self.tag,
+            count: self.count as u32,
+            excluded: self.excluded as u32,
+            mood: scale(self.mood, RatingMetric::Mood),
+            energy: scale(self.energy, RatingMetric::Energy),
+            sleep: scale(self.sleep, RatingMetric::Sleep),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn emotions_round_trip() {
+        for emotion in Emotion::ALL {
+            assert_eq!(emotion.to_string().parse::<Emotion>().unwrap(), emotion);
+            assert_eq!(
+                serde_json::to_value(emotion).unwrap(),
+                serde_json::json!(emotion.to_string())
+            );
+        }
+
+        assert!("hangry".parse::<Emotion>().is_err());
+    }
+
+    #[test]
+    fn context_tags_normalised() {
+        assert_eq!(normalise_context_tag("  Day  Off ").unwrap(), "day off");
+        assert_eq!(normalise_context_tag("école").unwrap(), "école");
+        assert!(normalise_context_tag(" ").is_err());
+        assert!(normalise_context_tag("work; drop table").is_err());
+    }
+}
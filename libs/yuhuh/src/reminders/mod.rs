@@ -0,0 +1,6 @@
+pub mod model;
+pub mod reminder_rules;
+pub mod router;
+pub mod scheduler;
+pub mod sink;
+pub mod state;
@@ -0,0 +1,67 @@
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{error::ConversionError, search::model::RecordKind};
+
+/// A daily reminder to log an entry, e.g. "ask mood at 21:00" or "remind to
+/// log lunch if nothing by 14:00".
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ReminderRule {
+    pub reminder_rule_id: Uuid,
+    pub user_id: Uuid,
+    /// Kind of entry the reminder asks for.
+    pub entry_kind: RecordKind,
+    /// Local time the reminder is due, in the user's timezone.
+    pub remind_at: NaiveTime,
+    /// Local time from which an entry counts as already logged, the start of
+    /// the day when not set.
+    pub logged_since: Option<NaiveTime>,
+    pub message: Option<String>,
+    /// Local date the rule last fired or was skipped on.
+    pub last_fired_on: Option<NaiveDate>,
+}
+
+/// A reminder that has come due and should be sent to the user.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct DueReminder {
+    pub reminder_rule_id: Uuid,
+    pub user_id: Uuid,
+    pub entry_kind: RecordKind,
+    pub message: Option<String>,
+    /// Local date the reminder is for.
+    pub local_date: NaiveDate,
+    /// When the reminder was due.
+    pub due_at: DateTime<Utc>,
+}
+
+// =============================================================================
+// Row Structs
+// =============================================================================
+#[derive(Debug, sqlx::FromRow)]
+pub struct ReminderRuleRow {
+    pub reminder_rule_id: Uuid,
+    pub user_id: Uuid,
+    pub entry_kind: String,
+    pub remind_at: NaiveTime,
+    pub logged_since: Option<NaiveTime>,
+    pub message: Option<String>,
+    pub last_fired_on: Option<NaiveDate>,
+}
+
+impl TryFrom<ReminderRuleRow> for ReminderRule {
+    type Error = ConversionError;
+
+    fn try_from(value: ReminderRuleRow) -> Result<Self, Self::Error> {
+        Ok(ReminderRule {
+            reminder_rule_id: value.reminder_rule_id,
+            user_id: value.user_id,
+            entry_kind: value.entry_kind.parse()?,
+            remind_at: value.remind_at,
+            logged_since: value.logged_since,
+            message: value.message,
+            last_fired_on: value.last_fired_on,
+        })
+    }
+}
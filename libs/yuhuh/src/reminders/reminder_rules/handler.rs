@@ -0,0 +1,313 @@
+//! reminder rule HTTP handlers
+//!
+//! This module provides HTTP endpoints for managing a user's daily check-in
+//! reminders.
+
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::StatusCode,
+};
+use chrono::NaiveTime;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, instrument};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::{
+    auth::model::Caller,
+    error::YuhuhError,
+    reminders::{model::ReminderRule, state::ReminderState},
+    search::model::RecordKind,
+    user::state::UserState,
+};
+
+/// Most reminder rules a user can have.
+const MAX_RULES: usize = 20;
+
+/// Longest message a reminder can carry.
+const MAX_MESSAGE_LENGTH: usize = 200;
+
+// ============================================================================
+// HTTP Request types
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateReminderRuleRequest {
+    pub user_id: Uuid,
+    /// Kind of entry to remind the user to log.
+    pub entry_kind: RecordKind,
+    /// Local time the reminder is due, in the user's timezone.
+    pub remind_at: NaiveTime,
+    /// Local time from which an entry counts as already logged, e.g. 11:00
+    /// for lunch. Defaults to the start of the day.
+    pub logged_since: Option<NaiveTime>,
+    pub message: Option<String>,
+}
+
+/// Request parameters for finding a user's reminder rules.
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ReadReminderRulesRequest {
+    /// user ID to search by.
+    pub user_id: Uuid,
+}
+
+/// Request parameters identifying the reminder rule to remove.
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct DeleteReminderRuleRequest {
+    /// user ID the rule belongs to.
+    pub user_id: Uuid,
+    pub reminder_rule_id: Uuid,
+}
+
+// ============================================================================
+// HTTP Responsed types
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ReadReminderRulesResponse {
+    pub reminder_rules: Vec<ReminderRule>,
+}
+
+// =============================================================================
+// HTTP Handlers
+// =============================================================================
+
+/// Add a daily check-in reminder for a user
+#[utoipa::path(
+    post,
+    path = "reminders",
+    tag = "reminders",
+    responses(
+        (status = 201, description = "reminder rule created", body = ReminderRule),
+        (status = 400, description = "invalid reminder rule"),
+        (status = 404, description = "user not found")
+))]
+#[instrument]
+pub async fn create_reminder_rule(
+    State(reminder_state): State<Arc<ReminderState>>,
+    State(user_state): State<Arc<UserState>>,
+    caller: Caller,
+    Json(request): Json<CreateReminderRuleRequest>,
+) -> Result<(StatusCode, Json<ReminderRule>), YuhuhError> {
+    debug!("entering create_reminder_rule");
+
+    caller.authorise_user(&request.user_id)?;
+
+    user_state.require_user(&request.user_id).await?;
+
+    if let Some(logged_since) = request.logged_since
+        && logged_since >= request.remind_at
+    {
+        return Err(YuhuhError::BadRequest(
+            "logged_since must be before remind_at".to_string(),
+        ));
+    }
+
+    let message = request
+        .message
+        .map(|m| m.trim().to_string())
+        .filter(|m| !m.is_empty());
+    if let Some(message) = &message
+        && message.chars().count() > MAX_MESSAGE_LENGTH
+    {
+        return Err(YuhuhError::BadRequest(format!(
+            "message must be at most {} characters",
+            MAX_MESSAGE_LENGTH
+        )));
+    }
+
+    let existing = reminder_state
+        .reminder_rules_repo
+        .read_reminder_rules(&request.user_id)
+        .await?;
+    if existing.len() >= MAX_RULES {
+        return Err(YuhuhError::BadRequest(format!(
+            "users can have at most {} reminder rules",
+            MAX_RULES
+        )));
+    }
+
+    let rule = ReminderRule {
+        reminder_rule_id: Uuid::now_v7(),
+        user_id: request.user_id,
+        entry_kind: request.entry_kind,
+        remind_at: request.remind_at,
+        logged_since: request.logged_since,
+        message,
+        last_fired_on: None,
+    };
+
+    reminder_state
+        .reminder_rules_repo
+        .create_reminder_rule(&rule)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(rule)))
+}
+
+/// Find a user's daily check-in reminders
+#[utoipa::path(
+    get,
+    path = "reminders",
+    tag = "reminders",
+    params(ReadReminderRulesRequest),
+    responses(
+        (status = 200, description = "found reminder rules", body = ReadReminderRulesResponse),
+        (status = 404, description = "user not found")
+))]
+#[instrument]
+pub async fn read_reminder_rules(
+    State(reminder_state): State<Arc<ReminderState>>,
+    State(user_state): State<Arc<UserState>>,
+    caller: Caller,
+    Query(request): Query<ReadReminderRulesRequest>,
+) -> Result<(StatusCode, Json<ReadReminderRulesResponse>), YuhuhError> {
+    debug!("entering read_reminder_rules");
+
+    caller.authorise_user(&request.user_id)?;
+
+    user_state.require_user(&request.user_id).await?;
+
+    let reminder_rules = reminder_state
+        .reminder_rules_repo
+        .read_reminder_rules(&request.user_id)
+        .await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(ReadReminderRulesResponse { reminder_rules }),
+    ))
+}
+
+/// Remove one of a user's daily check-in reminders
+#[utoipa::path(
+    delete,
+    path = "reminders",
+    tag = "reminders",
+    params(DeleteReminderRuleRequest),
+    responses(
+        (status = 204, description = "reminder rule removed"),
+        (status = 404, description = "no such reminder rule")
+))]
+#[instrument]
+pub async fn delete_reminder_rule(
+    State(reminder_state): State<Arc<ReminderState>>,
+    caller: Caller,
+    Query(request): Query<DeleteReminderRuleRequest>,
+) -> Result<StatusCode, YuhuhError> {
+    debug!("entering delete_reminder_rule");
+
+    caller.authorise_user(&request.user_id)?;
+
+    if !reminder_state
+        .reminder_rules_repo
+        .delete_reminder_rule(&request.user_id, &request.reminder_rule_id)
+        .await?
+    {
+        return Err(YuhuhError::NotFound("no such reminder rule".to_string()));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use pretty_assertions::assert_eq;
+
+    use axum::{
+        body::Body,
+        http::{self, Request, StatusCode},
+    };
+    use http_body_util::BodyExt;
+    use tower::ServiceExt;
+    use uuid::uuid;
+
+    use crate::{
+        reminders::{model::ReminderRule, reminder_rules::CreateReminderRuleRequest},
+        search::model::RecordKind,
+    };
+
+    fn post(request: &CreateReminderRuleRequest) -> Request<Body> {
+        Request::builder()
+            .method("POST")
+            .uri("/reminders")
+            .header(http::header::CONTENT_TYPE, "application/json")
+            .body(Body::from(
+                serde_json::to_string(request).expect("request is valid body"),
+            ))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn reminder_rules_created_and_removed() {
+        let (app, db, state) = crate::test::common::setup().await;
+
+        sqlx::raw_sql(include_str!("../../migrations/test/reminders.sql"))
+            .execute(&db)
+            .await
+            .expect("setup test sql ran successfully");
+
+        let user_id = uuid!("22222222-2222-2222-2222-222222222222");
+        let lunch = CreateReminderRuleRequest {
+            user_id,
+            entry_kind: RecordKind::Food,
+            remind_at: "14:00:00".parse().unwrap(),
+            logged_since: Some("11:00:00".parse().unwrap()),
+            message: Some("  Had lunch yet? ".to_string()),
+        };
+
+        let response = app.clone().oneshot(post(&lunch)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let rule: ReminderRule = serde_json::from_slice(&body).expect("valid ReminderRule bytes");
+        assert_eq!(rule.message.as_deref(), Some("Had lunch yet?"));
+
+        // Earliest in the day first
+        let rules = state
+            .reminders
+            .reminder_rules_repo
+            .read_reminder_rules(&user_id)
+            .await
+            .expect("read reminder rules");
+        assert_eq!(
+            rules.iter().map(|r| r.remind_at).collect::<Vec<_>>(),
+            vec![
+                "06:00:00".parse().unwrap(),
+                "14:00:00".parse().unwrap(),
+                "21:00:00".parse().unwrap()
+            ]
+        );
+
+        let response = app
+            .clone()
+            .oneshot(post(&CreateReminderRuleRequest {
+                logged_since: Some("15:00:00".parse().unwrap()),
+                ..lunch
+            }))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let delete = || {
+            Request::builder()
+                .method("DELETE")
+                .uri(format!(
+                    "/reminders?user_id={}&reminder_rule_id={}",
+                    user_id, rule.reminder_rule_id
+                ))
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let response = app.clone().oneshot(delete()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+        let response = app.oneshot(delete()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
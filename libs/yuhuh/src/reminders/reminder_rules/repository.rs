@@ -0,0 +1,139 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use tracing::{debug, error};
+use uuid::Uuid;
+
+use crate::{
+    error::YuhuhError,
+    reminders::model::{ReminderRule, ReminderRuleRow},
+};
+
+// =============================================================================
+// Traits
+// =============================================================================
+
+#[async_trait]
+pub trait ReminderRulesRepository: std::fmt::Debug + Send + Sync + 'static {
+    async fn create_reminder_rule(&self, rule: &ReminderRule) -> Result<(), YuhuhError>;
+
+    /// A user's rules, earliest in the day first.
+    async fn read_reminder_rules(&self, user_id: &Uuid) -> Result<Vec<ReminderRule>, YuhuhError>;
+
+    /// Removes one of the user's rules, returning whether there was one.
+    async fn delete_reminder_rule(
+        &self,
+        user_id: &Uuid,
+        reminder_rule_id: &Uuid,
+    ) -> Result<bool, YuhuhError>;
+}
+
+// =============================================================================
+// Production Implementation
+// =============================================================================
+
+#[derive(Debug)]
+pub struct ReminderRulesRepositoryImpl {
+    pub db: PgPool,
+}
+
+impl ReminderRulesRepositoryImpl {
+    pub fn new(db: PgPool) -> Self {
+        ReminderRulesRepositoryImpl { db }
+    }
+}
+
+#[async_trait]
+impl ReminderRulesRepository for ReminderRulesRepositoryImpl {
+    async fn create_reminder_rule(&self, rule: &ReminderRule) -> Result<(), YuhuhError> {
+        debug!(rule=?rule, "creating reminder rule");
+
+        sqlx::query!(
+            r#"
+            INSERT INTO reminder_rules (
+                reminder_rule_id,
+                user_id,
+                entry_kind,
+                remind_at,
+                logged_since,
+                message
+            )
+            VALUES ($1::uuid, $2::uuid, $3::text, $4::time, $5::time, $6::text);
+            "#,
+            rule.reminder_rule_id,
+            rule.user_id,
+            rule.entry_kind.to_string(),
+            rule.remind_at,
+            rule.logged_since,
+            rule.message
+        )
+        .execute(&self.db)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, "database error while creating reminder rule");
+
+            YuhuhError::DatabaseError(e)
+        })?;
+
+        Ok(())
+    }
+
+    async fn read_reminder_rules(&self, user_id: &Uuid) -> Result<Vec<ReminderRule>, YuhuhError> {
+        let records: Vec<ReminderRuleRow> = sqlx::query_as!(
+            ReminderRuleRow,
+            r#"
+            SELECT
+                reminder_rule_id,
+                user_id,
+                entry_kind,
+                remind_at,
+                logged_since,
+                message,
+                last_fired_on
+            FROM reminder_rules
+            WHERE user_id = $1::uuid
+            ORDER BY remind_at, reminder_rule_id;
+            "#,
+            user_id
+        )
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, "database error while reading reminder rules");
+
+            YuhuhError::DatabaseError(e)
+        })?;
+
+        let rules = records
+            .into_iter()
+            .map(ReminderRule::try_from)
+            .collect::<Result<Vec<ReminderRule>, _>>()
+            .inspect_err(|e| error!(error=?e, "encountered parsing error for reminder rule"))?;
+
+        Ok(rules)
+    }
+
+    async fn delete_reminder_rule(
+        &self,
+        user_id: &Uuid,
+        reminder_rule_id: &Uuid,
+    ) -> Result<bool, YuhuhError> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM reminder_rules
+            WHERE user_id = $1::uuid
+            AND reminder_rule_id = $2::uuid;
+            "#,
+            user_id,
+            reminder_rule_id
+        )
+        .execute(&self.db)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, "database error while deleting reminder rule");
+
+            YuhuhError::DatabaseError(e)
+        })?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
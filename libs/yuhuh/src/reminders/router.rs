@@ -0,0 +1,29 @@
+use axum::{Router, routing::get};
+use utoipa::OpenApi;
+
+use crate::{reminders::reminder_rules, state::AppState};
+
+// =============================================================================
+// API Docs
+// =============================================================================
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    reminder_rules::create_reminder_rule,
+    reminder_rules::read_reminder_rules,
+    reminder_rules::delete_reminder_rule
+))]
+pub struct ReminderApi;
+
+// =============================================================================
+// Router
+// =============================================================================
+
+pub fn reminder_router() -> Router<AppState> {
+    Router::new().route(
+        "/reminders",
+        get(reminder_rules::read_reminder_rules)
+            .post(reminder_rules::create_reminder_rule)
+            .delete(reminder_rules::delete_reminder_rule),
+    )
+}
@@ -0,0 +1,239 @@
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::PgPool;
+use tracing::{debug, error};
+use uuid::Uuid;
+
+use crate::{
+    error::YuhuhError,
+    reminders::model::{ReminderRule, ReminderRuleRow},
+    search::model::RecordKind,
+};
+
+/// A rule that may be due, with its user's stored timezone.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingReminderRule {
+    pub rule: ReminderRule,
+    pub timezone: Option<String>,
+}
+
+// =============================================================================
+// Traits
+// =============================================================================
+
+#[async_trait]
+pub trait ReminderSchedulerRepository: std::fmt::Debug + Send + Sync + 'static {
+    /// Rules that haven't fired on or after `latest_local_date`, the latest
+    /// date it could be anywhere.
+    async fn find_pending_rules(
+        &self,
+        latest_local_date: NaiveDate,
+    ) -> Result<Vec<PendingReminderRule>, YuhuhError>;
+
+    /// Marks a rule as fired on `local_date`, as long as it still last fired
+    /// on `previous`. Returns whether this caller claimed it, so a rule only
+    /// fires once when several schedulers are running.
+    async fn claim_rule(
+        &self,
+        reminder_rule_id: &Uuid,
+        previous: Option<NaiveDate>,
+        local_date: NaiveDate,
+    ) -> Result<bool, YuhuhError>;
+
+    /// Undoes a claim so the rule is tried again.
+    async fn release_rule(
+        &self,
+        reminder_rule_id: &Uuid,
+        previous: Option<NaiveDate>,
+        local_date: NaiveDate,
+    ) -> Result<(), YuhuhError>;
+
+    /// Whether the user logged an entry of the kind from `since` up to
+    /// `until`.
+    async fn has_logged_entry(
+        &self,
+        user_id: &Uuid,
+        entry_kind: RecordKind,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Result<bool, YuhuhError>;
+}
+
+// =============================================================================
+// Production Implementation
+// =============================================================================
+
+#[derive(Debug)]
+pub struct ReminderSchedulerRepositoryImpl {
+    pub db: PgPool,
+}
+
+impl ReminderSchedulerRepositoryImpl {
+    pub fn new(db: PgPool) -> Self {
+        ReminderSchedulerRepositoryImpl { db }
+    }
+}
+
+#[async_trait]
+impl ReminderSchedulerRepository for ReminderSchedulerRepositoryImpl {
+    async fn find_pending_rules(
+        &self,
+        latest_local_date: NaiveDate,
+    ) -> Result<Vec<PendingReminderRule>, YuhuhError> {
+        debug!(latest_local_date=?latest_local_date, "finding pending reminder rules");
+
+        let records = sqlx::query!(
+            r#"
+            SELECT
+                r.reminder_rule_id,
+                r.user_id,
+                r.entry_kind,
+                r.remind_at,
+                r.logged_since,
+                r.message,
+                r.last_fired_on,
+                u.timezone
+            FROM reminder_rules r
+            JOIN users u ON u.user_id = r.user_id
+            WHERE r.last_fired_on IS NULL
+                OR r.last_fired_on < $1::date
+            ORDER BY r.remind_at, r.reminder_rule_id;
+            "#,
+            latest_local_date
+        )
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, "database error while finding pending reminder rules");
+
+            YuhuhError::DatabaseError(e)
+        })?;
+
+        let rules = records
+            .into_iter()
+            .map(|r| {
+                let rule = ReminderRule::try_from(ReminderRuleRow {
+                    reminder_rule_id: r.reminder_rule_id,
+                    user_id: r.user_id,
+                    entry_kind: r.entry_kind,
+                    remind_at: r.remind_at,
+                    logged_since: r.logged_since,
+                    message: r.message,
+                    last_fired_on: r.last_fired_on,
+                })?;
+
+                Ok(PendingReminderRule {
+                    rule,
+                    timezone: r.timezone,
+                })
+            })
+            .collect::<Result<Vec<PendingReminderRule>, _>>()
+            .inspect_err(
+                |e: &YuhuhError| error!(error=?e, "encountered parsing error for reminder rule"),
+            )?;
+
+        Ok(rules)
+    }
+
+    async fn claim_rule(
+        &self,
+        reminder_rule_id: &Uuid,
+        previous: Option<NaiveDate>,
+        local_date: NaiveDate,
+    ) -> Result<bool, YuhuhError> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE reminder_rules
+            SET last_fired_on = $3::date
+            WHERE reminder_rule_id = $1::uuid
+            AND last_fired_on IS NOT DISTINCT FROM $2::date;
+            "#,
+            reminder_rule_id,
+            previous,
+            local_date
+        )
+        .execute(&self.db)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, "database error while claiming reminder rule");
+
+            YuhuhError::DatabaseError(e)
+        })?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn release_rule(
+        &self,
+        reminder_rule_id: &Uuid,
+        previous: Option<NaiveDate>,
+        local_date: NaiveDate,
+    ) -> Result<(), YuhuhError> {
+        sqlx::query!(
+            r#"
+            UPDATE reminder_rules
+            SET last_fired_on = $2::date
+            WHERE reminder_rule_id = $1::uuid
+            AND last_fired_on = $3::date;
+            "#,
+            reminder_rule_id,
+            previous,
+            local_date
+        )
+        .execute(&self.db)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, "database error while releasing reminder rule");
+
+            YuhuhError::DatabaseError(e)
+        })?;
+
+        Ok(())
+    }
+
+    async fn has_logged_entry(
+        &self,
+        user_id: &Uuid,
+        entry_kind: RecordKind,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Result<bool, YuhuhError> {
+        let logged = sqlx::query_scalar!(
+            r#"
+            SELECT CASE $2::text
+                WHEN 'mood' THEN EXISTS (
+                    SELECT 1 FROM mood_records
+                    WHERE user_id = $1::uuid
+                    AND logged_at >= $3::timestamptz
+                    AND logged_at <= $4::timestamptz
+                )
+                WHEN 'food' THEN EXISTS (
+                    SELECT 1 FROM food_records
+                    WHERE user_id = $1::uuid
+                    AND logged_at >= $3::timestamptz
+                    AND logged_at <= $4::timestamptz
+                )
+                ELSE EXISTS (
+                    SELECT 1 FROM activity_records
+                    WHERE user_id = $1::uuid
+                    AND logged_at >= $3::timestamptz
+                    AND logged_at <= $4::timestamptz
+                )
+            END AS "logged!";
+            "#,
+            user_id,
+            entry_kind.to_string(),
+            since,
+            until
+        )
+        .fetch_one(&self.db)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, "database error while checking for logged entries");
+
+            YuhuhError::DatabaseError(e)
+        })?;
+
+        Ok(logged)
+    }
+}
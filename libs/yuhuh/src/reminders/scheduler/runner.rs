@@ -0,0 +1,273 @@
+//! Background scheduler that fires daily check-in reminders.
+//!
+//! Every tick looks for rules whose local time has passed in the user's
+//! timezone and that haven't fired yet that day. A rule is claimed before
+//! anything is sent, so it fires at most once a day even with several
+//! schedulers running, and is skipped when the user already logged an entry
+//! of that kind or the reminder is too stale to be useful.
+
+use std::{sync::Arc, time::Duration};
+
+use chrono::{DateTime, Days, NaiveDate, NaiveTime, TimeDelta, TimeZone, Utc};
+use chrono_tz::Tz;
+use tokio::time::MissedTickBehavior;
+use tracing::{debug, error, info};
+
+use crate::{
+    error::YuhuhError,
+    reminders::{
+        model::DueReminder,
+        scheduler::{PendingReminderRule, ReminderSchedulerRepository},
+        sink::ReminderSink,
+    },
+    user::model::parse_timezone,
+};
+
+/// Reminders later than this are skipped rather than sent, e.g. after the
+/// scheduler was down for a while.
+const MAX_LATENESS: TimeDelta = TimeDelta::hours(1);
+
+/// What happened to the rules looked at in a tick.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TickSummary {
+    pub sent: u32,
+    /// Already logged, or too late to send.
+    pub skipped: u32,
+    /// Left to be retried after the sink failed.
+    pub failed: u32,
+}
+
+#[derive(Debug)]
+pub struct ReminderScheduler {
+    repo: Arc<dyn ReminderSchedulerRepository>,
+    sink: Arc<dyn ReminderSink>,
+}
+
+/// The instant a local time falls on, moving times skipped by a daylight
+/// saving change to an hour later.
+fn local_instant(tz: Tz, date: NaiveDate, time: NaiveTime) -> DateTime<Utc> {
+    let local = date.and_time(time);
+
+    tz.from_local_datetime(&local)
+        .earliest()
+        .or_else(|| {
+            tz.from_local_datetime(&(local + TimeDelta::hours(1)))
+                .earliest()
+        })
+        .map(|instant| instant.with_timezone(&Utc))
+        .unwrap_or_else(|| local.and_utc())
+}
+
+impl ReminderScheduler {
+    pub fn new(repo: Arc<dyn ReminderSchedulerRepository>, sink: Arc<dyn ReminderSink>) -> Self {
+        ReminderScheduler { repo, sink }
+    }
+
+    /// Fires every rule that's due as of `now`.
+    pub async fn tick(&self, now: DateTime<Utc>) -> Result<TickSummary, YuhuhError> {
+        // No timezone is more than a day ahead of UTC
+        let latest_local_date = (now + Days::new(1)).date_naive();
+
+        let pending = self.repo.find_pending_rules(latest_local_date).await?;
+
+        let mut summary = TickSummary::default();
+
+        for PendingReminderRule { rule, timezone } in pending {
+            let tz = parse_timezone(timezone.as_deref());
+            let local = now.with_timezone(&tz);
+            let local_date = local.date_naive();
+
+            if local.time() < rule.remind_at || rule.last_fired_on >= Some(local_date) {
+                continue;
+            }
+
+            if !self
+                .repo
+                .claim_rule(&rule.reminder_rule_id, rule.last_fired_on, local_date)
+                .await?
+            {
+                debug!(reminder_rule_id = ?rule.reminder_rule_id, "reminder rule already claimed");
+                continue;
+            }
+
+            let due_at = local_instant(tz, local_date, rule.remind_at);
+            if now - due_at > MAX_LATENESS {
+                debug!(reminder_rule_id = ?rule.reminder_rule_id, due_at = ?due_at, "skipping stale reminder");
+                summary.skipped += 1;
+                continue;
+            }
+
+            let since = local_instant(tz, local_date, rule.logged_since.unwrap_or(NaiveTime::MIN));
+            if self
+                .repo
+                .has_logged_entry(&rule.user_id, rule.entry_kind, since, now)
+                .await?
+            {
+                debug!(reminder_rule_id = ?rule.reminder_rule_id, "skipping reminder as entry already logged");
+                summary.skipped += 1;
+                continue;
+            }
+
+            let reminder = DueReminder {
+                reminder_rule_id: rule.reminder_rule_id,
+                user_id: rule.user_id,
+                entry_kind: rule.entry_kind,
+                message: rule.message,
+                local_date,
+                due_at,
+            };
+
+            match self.sink.send(&reminder).await {
+                Ok(()) => summary.sent += 1,
+                Err(e) => {
+                    error!(error = ?e, reminder = ?reminder, "failed to send reminder, will retry");
+
+                    self.repo
+                        .release_rule(&rule.reminder_rule_id, rule.last_fired_on, local_date)
+                        .await?;
+                    summary.failed += 1;
+                }
+            }
+        }
+
+        Ok(summary)
+    }
+
+    /// Ticks every `period` until the process exits.
+    pub async fn run(self, period: Duration) {
+        info!(period = ?period, "starting reminder scheduler");
+
+        let mut interval = tokio::time::interval(period);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+
+            match self.tick(Utc::now()).await {
+                Ok(summary) if summary != TickSummary::default() => {
+                    info!(summary = ?summary, "fired reminders")
+                }
+                Ok(_) => {}
+                Err(e) => error!(error = ?e, "reminder scheduler tick failed"),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use async_trait::async_trait;
+    use chrono::{DateTime, Utc};
+    use pretty_assertions::assert_eq;
+    use uuid::uuid;
+
+    use super::*;
+    use crate::{
+        reminders::{scheduler::ReminderSchedulerRepositoryImpl, sink::RecordingSink},
+        search::model::RecordKind,
+    };
+
+    #[derive(Debug)]
+    struct FailingSink;
+
+    #[async_trait]
+    impl ReminderSink for FailingSink {
+        async fn send(&self, _: &DueReminder) -> Result<(), YuhuhError> {
+            Err(YuhuhError::InternalServerError("bot is down".to_string()))
+        }
+    }
+
+    fn at(time: &str) -> DateTime<Utc> {
+        time.parse().unwrap()
+    }
+
+    #[tokio::test]
+    async fn due_reminders_fire_once_in_local_time() {
+        let (_, db, _) = crate::test::common::setup().await;
+
+        sqlx::raw_sql(include_str!("../../migrations/test/reminders.sql"))
+            .execute(&db)
+            .await
+            .expect("setup test sql ran successfully");
+
+        let repo = Arc::new(ReminderSchedulerRepositoryImpl::new(db));
+        let sink = Arc::new(RecordingSink::default());
+        let scheduler = ReminderScheduler::new(repo.clone(), sink.clone());
+
+        // 21:30 on 2 June for Alice in Auckland, but 09:30 for Bobat in UTC
+        let now = at("2025-06-02T09:30:00Z");
+
+        // Alice's mood reminder fires, but she's already had dinner. Bobat's
+        // early activity reminder is too stale to send.
+        let summary = scheduler.tick(now).await.expect("tick succeeded");
+        assert_eq!(
+            summary,
+            TickSummary {
+                sent: 1,
+                skipped: 2,
+                failed: 0
+            }
+        );
+        assert_eq!(
+            sink.sent(),
+            vec![DueReminder {
+                reminder_rule_id: uuid!("11111111-1111-1111-1111-111111111111"),
+                user_id: uuid!("11111111-1111-1111-1111-111111111111"),
+                entry_kind: RecordKind::Mood,
+                message: Some("How are you feeling?".to_string()),
+                local_date: "2025-06-02".parse().unwrap(),
+                due_at: at("2025-06-02T09:00:00Z"),
+            }]
+        );
+
+        // Nothing fires twice in a day
+        let summary = scheduler.tick(now).await.expect("tick succeeded");
+        assert_eq!(summary, TickSummary::default());
+
+        // Bobat's evening mood reminder fails to send, and is retried
+        let evening = at("2025-06-02T21:10:00Z");
+        let failing = ReminderScheduler::new(repo.clone(), Arc::new(FailingSink));
+
+        let summary = failing.tick(evening).await.expect("tick succeeded");
+        assert_eq!(summary.failed, 1);
+
+        let summary = scheduler.tick(evening).await.expect("tick succeeded");
+        assert_eq!(summary.sent, 1);
+        assert_eq!(sink.sent().len(), 2);
+        assert_eq!(
+            sink.sent()[1].reminder_rule_id,
+            uuid!("44444444-4444-4444-4444-444444444444")
+        );
+
+        // Alice's reminders come round again the next day, with no dinner yet
+        let summary = scheduler
+            .tick(at("2025-06-03T09:05:00Z"))
+            .await
+            .expect("tick succeeded");
+        assert_eq!(
+            summary,
+            TickSummary {
+                sent: 2,
+                skipped: 1,
+                failed: 0
+            }
+        );
+    }
+
+    #[test]
+    fn skipped_local_times_move_an_hour_later() {
+        let tz: Tz = "Pacific/Auckland".parse().unwrap();
+
+        // Clocks went from 02:00 to 03:00 on 28 September 2025
+        assert_eq!(
+            local_instant(
+                tz,
+                "2025-09-28".parse().unwrap(),
+                "02:30:00".parse().unwrap()
+            ),
+            at("2025-09-27T14:30:00Z")
+        );
+    }
+}
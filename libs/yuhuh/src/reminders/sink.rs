@@ -0,0 +1,56 @@
+//! Where due reminders are delivered.
+//!
+//! The scheduler hands each due reminder to a [`ReminderSink`], so delivery
+//! can be swapped out for a bot, push notifications or a webhook without
+//! touching the rules.
+
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use tracing::info;
+
+use crate::{error::YuhuhError, reminders::model::DueReminder};
+
+#[async_trait]
+pub trait ReminderSink: std::fmt::Debug + Send + Sync + 'static {
+    /// Delivers a due reminder. Returning an error leaves the reminder to be
+    /// retried on the next tick.
+    async fn send(&self, reminder: &DueReminder) -> Result<(), YuhuhError>;
+}
+
+/// Logs due reminders, for when nothing else is listening.
+#[derive(Debug, Default)]
+pub struct LogSink;
+
+#[async_trait]
+impl ReminderSink for LogSink {
+    async fn send(&self, reminder: &DueReminder) -> Result<(), YuhuhError> {
+        info!(reminder = ?reminder, "reminder due");
+
+        Ok(())
+    }
+}
+
+/// Records every reminder it's sent, for tests.
+#[derive(Debug, Default)]
+pub struct RecordingSink {
+    sent: Mutex<Vec<DueReminder>>,
+}
+
+impl RecordingSink {
+    pub fn sent(&self) -> Vec<DueReminder> {
+        self.sent.lock().expect("sink lock not poisoned").clone()
+    }
+}
+
+#[async_trait]
+impl ReminderSink for RecordingSink {
+    async fn send(&self, reminder: &DueReminder) -> Result<(), YuhuhError> {
+        self.sent
+            .lock()
+            .expect("sink lock not poisoned")
+            .push(reminder.clone());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,18 @@
+use std::sync::Arc;
+
+use sqlx::PgPool;
+
+use crate::reminders::reminder_rules::{ReminderRulesRepository, ReminderRulesRepositoryImpl};
+
+#[derive(Debug)]
+pub struct ReminderState {
+    pub reminder_rules_repo: Arc<dyn ReminderRulesRepository>,
+}
+
+impl ReminderState {
+    pub fn new(db: PgPool) -> Self {
+        ReminderState {
+            reminder_rules_repo: Arc::new(ReminderRulesRepositoryImpl::new(db.clone())),
+        }
+    }
+}
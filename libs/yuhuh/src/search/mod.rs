@@ -0,0 +1,4 @@
+pub mod model;
+pub mod router;
+pub mod search_records;
+pub mod state;
@@ -0,0 +1,89 @@
+use std::{fmt, str::FromStr};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::ConversionError;
+
+/// Kinds of record that can be searched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum RecordKind {
+    Food,
+    Mood,
+    Activity,
+}
+
+impl fmt::Display for RecordKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecordKind::Food => write!(f, "food"),
+            RecordKind::Mood => write!(f, "mood"),
+            RecordKind::Activity => write!(f, "activity"),
+        }
+    }
+}
+
+impl FromStr for RecordKind {
+    type Err = ConversionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "food" => Ok(RecordKind::Food),
+            "mood" => Ok(RecordKind::Mood),
+            "activity" => Ok(RecordKind::Activity),
+            _ => Err(ConversionError::new(format!("unknown record kind {}", s))),
+        }
+    }
+}
+
+/// How search results are ordered.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchOrder {
+    /// Best match first, then most recent.
+    #[default]
+    Relevance,
+    /// Most recently logged first.
+    Recent,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct SearchResult {
+    pub kind: RecordKind,
+    /// ID of the food, mood or activity record.
+    pub record_id: Uuid,
+    pub logged_at: DateTime<Utc>,
+    /// How well the record matches, higher is better.
+    pub rank: f32,
+    /// Matching text as HTML, escaped, with each match wrapped in `<b>` tags.
+    pub headline: String,
+}
+
+// =============================================================================
+// Row Structs
+// =============================================================================
+#[derive(Debug, sqlx::FromRow)]
+pub struct SearchResultRow {
+    pub kind: String,
+    pub record_id: Uuid,
+    pub logged_at: DateTime<Utc>,
+    pub rank: f32,
+    pub headline: String,
+}
+
+impl TryFrom<SearchResultRow> for SearchResult {
+    type Error = ConversionError;
+
+    fn try_from(value: SearchResultRow) -> Result<Self, Self::Error> {
+        Ok(SearchResult {
+            kind: value.kind.parse()?,
+            record_id: value.record_id,
+            logged_at: value.logged_at,
+            rank: value.rank,
+            headline: value.headline,
+        })
+    }
+}
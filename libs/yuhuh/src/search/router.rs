@@ -0,0 +1,23 @@
+use axum::{Router, routing::get};
+use utoipa::OpenApi;
+
+use crate::{search::search_records, state::AppState};
+
+// =============================================================================
+// API Docs
+// =============================================================================
+
+#[derive(OpenApi)]
+#[openapi(paths(search_records::search_records))]
+pub struct SearchApi;
+
+// =============================================================================
+// Router
+// =============================================================================
+
+pub fn search_router() -> Router<AppState> {
+    Router::new().route(
+        "/users/{user_id}/search",
+        get(search_records::search_records),
+    )
+}
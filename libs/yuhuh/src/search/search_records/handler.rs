@@ -0,0 +1,291 @@
+//! record search HTTP handler
+//!
+//! This module provides an HTTP endpoint for full-text search over a user's
+//! food descriptions, mood notes and activities, so questions like "when did
+//! I last eat sushi" can be answered.
+
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, instrument};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::{
+    auth::model::Caller,
+    error::YuhuhError,
+    search::{
+        model::{RecordKind, SearchOrder, SearchResult},
+        state::SearchState,
+    },
+    user::state::UserState,
+};
+
+/// Longest search query accepted.
+const MAX_QUERY_LENGTH: usize = 200;
+
+/// Results returned when no limit is given.
+const DEFAULT_LIMIT: u32 = 20;
+
+/// Most results returned at once.
+const MAX_LIMIT: u32 = 100;
+
+// ============================================================================
+// HTTP Request types
+// ============================================================================
+
+/// Request parameters for searching records.
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct SearchRecordsRequest {
+    /// Words to search for. Quoted phrases, `or` and `-word` are supported.
+    pub q: String,
+    /// Comma separated record kinds to include, e.g. `food,mood`.
+    pub kinds: Option<String>,
+    /// Defaults to `relevance`.
+    pub order: Option<SearchOrder>,
+    /// Defaults to 20, at most 100.
+    pub limit: Option<u32>,
+    pub offset: Option<u32>,
+}
+
+// ============================================================================
+// HTTP Responsed types
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SearchRecordsResponse {
+    pub found_results: u32,
+    pub results: Vec<SearchResult>,
+}
+
+// =============================================================================
+// Implementations
+// =============================================================================
+
+fn parse_kinds(kinds: Option<&str>) -> Result<Vec<RecordKind>, YuhuhError> {
+    kinds
+        .into_iter()
+        .flat_map(|k| k.split(','))
+        .map(str::trim)
+        .filter(|k| !k.is_empty())
+        .map(|k| {
+            k.parse()
+                .map_err(|_| YuhuhError::BadRequest(format!("unknown record kind {}", k)))
+        })
+        .collect()
+}
+
+// =============================================================================
+// HTTP Handlers
+// =============================================================================
+
+/// Search a user's food, mood and activity records
+#[utoipa::path(
+    get,
+    path = "users/{user_id}/search",
+    tag = "search",
+    params(
+        ("user_id" = Uuid, Path, description = "User to search the records of"),
+        SearchRecordsRequest
+    ),
+    responses(
+        (status = 200, description = "Found matching records", body = SearchRecordsResponse),
+        (status = 400, description = "Invalid query, record kind or limit"),
+        (status = 404, description = "User not found"),
+))]
+#[instrument]
+pub async fn search_records(
+    State(search_state): State<Arc<SearchState>>,
+    State(user_state): State<Arc<UserState>>,
+    caller: Caller,
+    Path(user_id): Path<Uuid>,
+    Query(request): Query<SearchRecordsRequest>,
+) -> Result<(StatusCode, Json<SearchRecordsResponse>), YuhuhError> {
+    debug!("entering search_records");
+
+    caller.authorise_user(&user_id)?;
+
+    let query = request.q.trim();
+    if query.is_empty() || query.chars().count() > MAX_QUERY_LENGTH {
+        return Err(YuhuhError::BadRequest(format!(
+            "q must be between 1 and {} characters",
+            MAX_QUERY_LENGTH
+        )));
+    }
+
+    let limit = request.limit.unwrap_or(DEFAULT_LIMIT);
+    if !(1..=MAX_LIMIT).contains(&limit) {
+        return Err(YuhuhError::BadRequest(format!(
+            "limit must be between 1 and {}",
+            MAX_LIMIT
+        )));
+    }
+
+    let kinds = parse_kinds(request.kinds.as_deref())?;
+
+    user_state.require_user(&user_id).await?;
+
+    let results = search_state
+        .search_records_repo
+        .search_records(
+            &user_id,
+            query,
+            &kinds,
+            request.order.unwrap_or_default(),
+            limit.into(),
+            request.offset.unwrap_or(0).into(),
+        )
+        .await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(SearchRecordsResponse {
+            found_results: results.len() as u32,
+            results,
+        }),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+    };
+    use http_body_util::BodyExt;
+    use pretty_assertions::assert_eq;
+    use tower::ServiceExt;
+    use uuid::Uuid;
+
+    use crate::search::{
+        model::{RecordKind, SearchResult},
+        search_records::SearchRecordsResponse,
+    };
+
+    async fn search(app: axum::Router, query: &str) -> (StatusCode, Option<SearchRecordsResponse>) {
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri(format!(
+                        "/users/11111111-1111-1111-1111-111111111111/search?{}",
+                        query
+                    ))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let status = response.status();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+
+        (status, serde_json::from_slice(&body).ok())
+    }
+
+    fn ids(dto: &SearchRecordsResponse) -> Vec<(RecordKind, Uuid)> {
+        dto.results.iter().map(|r| (r.kind, r.record_id)).collect()
+    }
+
+    fn id(repeated: char) -> Uuid {
+        repeated.to_string().repeat(32).parse().unwrap()
+    }
+
+    #[tokio::test]
+    async fn search_ranks_and_highlights_matches() {
+        let (app, db, _) = crate::test::common::setup().await;
+
+        sqlx::raw_sql(include_str!("../../migrations/test/search.sql"))
+            .execute(&db)
+            .await
+            .expect("setup test sql ran successfully");
+
+        let (status, dto) = search(app.clone(), "q=sushi").await;
+        assert_eq!(status, StatusCode::OK);
+
+        // The food mentioning sushi twice ranks first, and the activity only
+        // mentioning it in activity_info last. Bobat's sushi isn't included.
+        let dto = dto.expect("valid SearchRecordsResponse bytes");
+        assert_eq!(dto.found_results, 4);
+        assert_eq!(dto.results[0].record_id, id('1'));
+        assert_eq!(
+            dto.results[3],
+            SearchResult {
+                kind: RecordKind::Activity,
+                record_id: id('6'),
+                logged_at: "2025-05-11T18:00:00Z".parse().unwrap(),
+                rank: dto.results[3].rank,
+                headline: "evening walk - walked to the <b>sushi</b> place".to_string(),
+            }
+        );
+        assert!(dto.results.windows(2).all(|w| w[0].rank >= w[1].rank));
+        assert!(
+            dto.results
+                .iter()
+                .all(|r| r.headline.contains("<b>sushi</b>"))
+        );
+
+        // Words are stemmed, and the most recent comes first when asked
+        let (_, dto) = search(app.clone(), "q=sushi+roll&order=recent").await;
+        assert_eq!(
+            ids(&dto.expect("valid SearchRecordsResponse bytes")),
+            vec![(RecordKind::Food, id('2'))]
+        );
+
+        let (_, dto) = search(app.clone(), "q=sushi&order=recent&kinds=food,mood").await;
+        assert_eq!(
+            ids(&dto.expect("valid SearchRecordsResponse bytes")),
+            vec![
+                (RecordKind::Food, id('2')),
+                (RecordKind::Mood, id('5')),
+                (RecordKind::Food, id('1')),
+            ]
+        );
+
+        let (_, dto) = search(app.clone(), "q=pizza").await;
+        assert_eq!(
+            dto.expect("valid SearchRecordsResponse bytes")
+                .found_results,
+            0
+        );
+
+        let (status, _) = search(app.clone(), "q=+").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, _) = search(app.clone(), "q=sushi&kinds=sleep").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        // Markup in a note is escaped, so only the highlighting is HTML
+        sqlx::query(
+            "INSERT INTO mood_records (user_id, mood, notes, logged_at)
+            VALUES ('11111111-1111-1111-1111-111111111111', 0.5, '<img src=x onerror=alert(1)> ramen & \"gyoza\"', now())",
+        )
+        .execute(&db)
+        .await
+        .expect("mood entry created");
+
+        let (_, dto) = search(app.clone(), "q=ramen").await;
+        let dto = dto.expect("valid SearchRecordsResponse bytes");
+        assert_eq!(dto.found_results, 1);
+        assert_eq!(
+            dto.results[0].headline,
+            "&lt;img src=x onerror=alert(1)&gt; <b>ramen</b> &amp; &quot;gyoza&quot;"
+        );
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/users/33333333-3333-3333-3333-333333333333/search?q=sushi")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
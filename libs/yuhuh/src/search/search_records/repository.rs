@@ -0,0 +1,148 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use tracing::{debug, error};
+use uuid::Uuid;
+
+use crate::{
+    error::YuhuhError,
+    search::model::{RecordKind, SearchOrder, SearchResult, SearchResultRow},
+};
+
+#[async_trait]
+pub trait SearchRecordsRepository: std::fmt::Debug + Send + Sync + 'static {
+    /// Searches a user's food, mood and activity records, only looking at
+    /// `kinds` when any are given.
+    async fn search_records(
+        &self,
+        user_id: &Uuid,
+        query: &str,
+        kinds: &[RecordKind],
+        order: SearchOrder,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<SearchResult>, YuhuhError>;
+}
+
+#[derive(Debug)]
+pub struct SearchRecordsRepositoryImpl {
+    pub db: PgPool,
+}
+
+impl SearchRecordsRepositoryImpl {
+    pub fn new(db: PgPool) -> Self {
+        SearchRecordsRepositoryImpl { db }
+    }
+}
+
+#[async_trait]
+impl SearchRecordsRepository for SearchRecordsRepositoryImpl {
+    async fn search_records(
+        &self,
+        user_id: &Uuid,
+        query: &str,
+        kinds: &[RecordKind],
+        order: SearchOrder,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<SearchResult>, YuhuhError> {
+        debug!(
+            user_id=?user_id,
+            query=?query,
+            kinds=?kinds,
+            order=?order,
+            limit=?limit,
+            offset=?offset,
+            "received search request"
+        );
+
+        let kinds: Vec<String> = kinds.iter().map(|k| k.to_string()).collect();
+
+        // Activities are highlighted on their name followed by any text inside
+        // activity_info, the same text their search vector is built from. Text
+        // is escaped before highlighting, so only the `<b>` tags are markup
+        let records: Vec<SearchResultRow> = sqlx::query_as!(
+            SearchResultRow,
+            r#"
+            WITH search AS (
+                SELECT websearch_to_tsquery('english', $2::text) AS query
+            ),
+            matches AS (
+                SELECT
+                    'food' AS kind,
+                    f.food_record_id AS record_id,
+                    f.logged_at,
+                    ts_rank(f.search_vector, s.query) AS rank,
+                    ts_headline('english', escape_html(f.description), s.query) AS headline
+                FROM food_records f, search s
+                WHERE f.user_id = $1::uuid
+                AND f.search_vector @@ s.query
+                UNION ALL
+                SELECT
+                    'mood',
+                    m.mood_record_id,
+                    m.logged_at,
+                    ts_rank(m.search_vector, s.query),
+                    ts_headline('english', escape_html(coalesce(m.notes, '')), s.query)
+                FROM mood_records m, search s
+                WHERE m.user_id = $1::uuid
+                AND m.search_vector @@ s.query
+                UNION ALL
+                SELECT
+                    'activity',
+                    a.activity_record_id,
+                    a.logged_at,
+                    ts_rank(a.search_vector, s.query),
+                    ts_headline(
+                        'english',
+                        escape_html(a.activity || coalesce(' - ' || (
+                            SELECT string_agg(v #>> '{}', ' ')
+                            FROM jsonb_path_query(a.activity_info, 'strict $.**') AS v
+                            WHERE jsonb_typeof(v) = 'string'
+                        ), '')),
+                        s.query
+                    )
+                FROM activity_records a, search s
+                WHERE a.user_id = $1::uuid
+                AND a.search_vector @@ s.query
+            )
+            SELECT
+                kind AS "kind!",
+                record_id AS "record_id!",
+                logged_at AS "logged_at!",
+                rank AS "rank!",
+                headline AS "headline!"
+            FROM matches
+            WHERE cardinality($3::text[]) = 0
+                OR kind = ANY($3::text[])
+            ORDER BY
+                CASE WHEN $4::bool THEN rank ELSE 0 END DESC,
+                logged_at DESC
+            LIMIT $5
+            OFFSET $6;
+            "#,
+            user_id,
+            query,
+            &kinds,
+            order == SearchOrder::Relevance,
+            limit,
+            offset
+        )
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, "database error while searching records");
+
+            YuhuhError::DatabaseError(e)
+        })?;
+
+        debug!(results=?records, "found matching records");
+
+        let results = records
+            .into_iter()
+            .map(SearchResult::try_from)
+            .collect::<Result<Vec<SearchResult>, _>>()
+            .inspect_err(|e| error!(error=?e, "encountered parsing error for search result"))?;
+
+        Ok(results)
+    }
+}
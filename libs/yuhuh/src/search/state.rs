@@ -0,0 +1,18 @@
+use std::sync::Arc;
+
+use sqlx::PgPool;
+
+use crate::search::search_records::{SearchRecordsRepository, SearchRecordsRepositoryImpl};
+
+#[derive(Debug)]
+pub struct SearchState {
+    pub search_records_repo: Arc<dyn SearchRecordsRepository>,
+}
+
+impl SearchState {
+    pub fn new(db: PgPool) -> Self {
+        SearchState {
+            search_records_repo: Arc::new(SearchRecordsRepositoryImpl::new(db.clone())),
+        }
+    }
+}
@@ -5,8 +5,10 @@ use sqlx::PgPool;
 use tracing::debug;
 
 use crate::{
-    activity::state::ActivityState, config::Config, food::state::FoodState, mood::state::MoodState,
-    user::state::*,
+    activity::state::ActivityState, auth::state::AuthState, config::Config,
+    fasting::state::FastingState, food::state::FoodState, live::state::LiveState,
+    mood::state::MoodState, reminders::state::ReminderState, search::state::SearchState,
+    user::state::*, webhooks::state::WebhookState,
 };
 
 #[derive(Clone)]
@@ -16,6 +18,12 @@ pub struct AppState {
     pub food: Arc<FoodState>,
     pub mood: Arc<MoodState>,
     pub activity: Arc<ActivityState>,
+    pub fasting: Arc<FastingState>,
+    pub search: Arc<SearchState>,
+    pub reminders: Arc<ReminderState>,
+    pub webhooks: Arc<WebhookState>,
+    pub live: Arc<LiveState>,
+    pub auth: Arc<AuthState>,
 }
 
 impl FromRef<AppState> for Arc<Config> {
@@ -48,6 +56,42 @@ impl FromRef<AppState> for Arc<ActivityState> {
     }
 }
 
+impl FromRef<AppState> for Arc<FastingState> {
+    fn from_ref(input: &AppState) -> Self {
+        input.fasting.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<SearchState> {
+    fn from_ref(input: &AppState) -> Self {
+        input.search.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<ReminderState> {
+    fn from_ref(input: &AppState) -> Self {
+        input.reminders.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<WebhookState> {
+    fn from_ref(input: &AppState) -> Self {
+        input.webhooks.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<LiveState> {
+    fn from_ref(input: &AppState) -> Self {
+        input.live.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<AuthState> {
+    fn from_ref(input: &AppState) -> Self {
+        input.auth.clone()
+    }
+}
+
 pub fn create_app_state(config: &Config, db: PgPool) -> AppState {
     let app_state = AppState {
         config: Arc::new(config.clone()),
@@ -55,6 +99,12 @@ pub fn create_app_state(config: &Config, db: PgPool) -> AppState {
         food: Arc::new(FoodState::new(db.clone())),
         mood: Arc::new(MoodState::new(db.clone())),
         activity: Arc::new(ActivityState::new(db.clone())),
+        fasting: Arc::new(FastingState::new(db.clone())),
+        search: Arc::new(SearchState::new(db.clone())),
+        reminders: Arc::new(ReminderState::new(db.clone())),
+        webhooks: Arc::new(WebhookState::new(db.clone())),
+        live: Arc::new(LiveState::new(db.clone())),
+        auth: Arc::new(AuthState::new(db.clone(), config)),
     };
 
     debug!("created app state");
@@ -4,14 +4,23 @@
 
 use std::sync::Arc;
 
-use axum::{Json, extract::State, http::StatusCode};
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
 use serde::{Deserialize, Serialize};
 use tracing::{info, instrument, warn};
 use utoipa::ToSchema;
 use uuid::Uuid;
 use validator::Validate;
 
-use crate::{error::YuhuhError, user::state::UserState};
+use crate::{
+    auth::model::Caller,
+    error::YuhuhError,
+    food::energy::EnergyUnit,
+    user::{identity::Provider, state::UserState},
+};
 
 // =============================================================================
 // Request/Response Types
@@ -37,6 +46,8 @@ pub struct CreateDiscordUserRequest {
     pub contact_email: Option<String>,
     /// Optional timezone preference
     pub timezone: Option<String>,
+    /// Optional preferred energy unit, defaults to kilocalories
+    pub energy_unit: Option<EnergyUnit>,
 }
 
 /// Response payload for successful Discord user creation.
@@ -49,6 +60,34 @@ pub struct CreateDiscordUserResponse {
     pub user_id: Uuid,
 }
 
+/// Request payload for creating a user signed on through a provider.
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
+pub struct CreateIdentityUserRequest {
+    /// ID of the account at the provider, e.g. a Telegram user ID, a Slack
+    /// member ID or an email address
+    pub external_id: String,
+    /// Optional name of the account at the provider
+    pub username: Option<String>,
+    /// Optional user personalization settings
+    pub personalisation: Option<String>,
+    /// Optional contact name
+    pub contact_name: Option<String>,
+    /// Optional contact email, defaults to the email address signed on with
+    #[validate(email)]
+    pub contact_email: Option<String>,
+    /// Optional timezone preference
+    pub timezone: Option<String>,
+    /// Optional preferred energy unit, defaults to kilocalories
+    pub energy_unit: Option<EnergyUnit>,
+}
+
+/// Response payload for successful user creation.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateUserResponse {
+    /// The UUID of the newly created user
+    pub user_id: Uuid,
+}
+
 // =============================================================================
 // HTTP Handlers
 // =============================================================================
@@ -67,12 +106,15 @@ pub struct CreateDiscordUserResponse {
 #[instrument]
 pub async fn create_discord_user(
     State(user_state): State<Arc<UserState>>,
+    caller: Caller,
     Json(request): Json<CreateDiscordUserRequest>,
 ) -> Result<(StatusCode, Json<CreateDiscordUserResponse>), YuhuhError> {
+    caller.authorise_service()?;
+
     // Check if a user with this Discord ID already exists
     if let Some(user) = user_state
         .find_user_repo
-        .find_user_by_discord_id(request.discord_id)
+        .find_user_by_identity(Provider::Discord, &request.discord_id.to_string())
         .await?
     {
         warn!(user = ?user, "existing user found");
@@ -86,13 +128,16 @@ pub async fn create_discord_user(
     // Create the user through the repository layer
     let created_user_id = user_state
         .create_user_repo
-        .create_discord_user(super::CreateDBDiscordUserRequest {
-            discord_id: request.discord_id,
-            discord_username: request.discord_username,
+        .create_user(super::CreateDBUserRequest {
+            provider: Provider::Discord,
+            external_id: Provider::Discord
+                .normalise_external_id(&request.discord_id.to_string())?,
+            username: Some(request.discord_username),
             personalisation: request.personalisation,
             contact_name: request.contact_name,
             contact_email: request.contact_email,
             timezone: request.timezone,
+            energy_unit: request.energy_unit.unwrap_or_default(),
         })
         .await?;
 
@@ -110,6 +155,79 @@ pub async fn create_discord_user(
     ))
 }
 
+/// Create user from a Telegram, Slack or email account.
+///
+/// Discord accounts are created through `users/create/discord`.
+#[utoipa::path(
+        post,
+        path = "users/create/{provider}",
+        tag = "users",
+        params(("provider" = Provider, Path, description = "Provider the account is at")),
+        responses(
+            (status = 201, description = "User created successfully", body = CreateUserResponse),
+            (status = 400, description = "Invalid account id, or user exists")
+        )
+    )]
+#[instrument]
+pub async fn create_identity_user(
+    State(user_state): State<Arc<UserState>>,
+    caller: Caller,
+    Path(provider): Path<Provider>,
+    Json(request): Json<CreateIdentityUserRequest>,
+) -> Result<(StatusCode, Json<CreateUserResponse>), YuhuhError> {
+    caller.authorise_service()?;
+
+    request.validate()?;
+
+    let external_id = provider.normalise_external_id(&request.external_id)?;
+
+    if let Some(user) = user_state
+        .find_user_repo
+        .find_user_by_identity(provider, &external_id)
+        .await?
+    {
+        warn!(user = ?user, "existing user found");
+
+        return Err(YuhuhError::BadRequest(format!(
+            "User with {} ID {} already exists",
+            provider, external_id,
+        )));
+    }
+
+    let contact_email = match provider {
+        Provider::Email => request.contact_email.or(Some(external_id.clone())),
+        _ => request.contact_email,
+    };
+
+    let created_user_id = user_state
+        .create_user_repo
+        .create_user(super::CreateDBUserRequest {
+            provider,
+            external_id: external_id.clone(),
+            username: request.username,
+            personalisation: request.personalisation,
+            contact_name: request.contact_name,
+            contact_email,
+            timezone: request.timezone,
+            energy_unit: request.energy_unit.unwrap_or_default(),
+        })
+        .await?;
+
+    info!(
+        user_id = %created_user_id,
+        provider = %provider,
+        external_id,
+        "Successfully created user"
+    );
+
+    Ok((
+        StatusCode::CREATED,
+        Json(CreateUserResponse {
+            user_id: created_user_id,
+        }),
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use axum::{
@@ -119,7 +237,14 @@ mod tests {
     use pretty_assertions::assert_eq;
     use tower::ServiceExt;
 
-    use crate::user::create_user::{CreateDiscordUserRequest, CreateDiscordUserResponse};
+    use crate::user::{
+        create_user::{
+            CreateDiscordUserRequest, CreateDiscordUserResponse, CreateIdentityUserRequest,
+            CreateUserResponse,
+        },
+        find_user::FindUserResponse,
+        identity::Provider,
+    };
     use http_body_util::BodyExt;
 
     #[tokio::test]
@@ -133,6 +258,7 @@ mod tests {
             contact_name: Some("testing_name".to_string()),
             contact_email: Some("testing_email@email.com".to_string()),
             timezone: Some("UTC".to_string()),
+            energy_unit: None,
         };
 
         let response = app
@@ -154,4 +280,87 @@ mod tests {
         let _dto: CreateDiscordUserResponse =
             serde_json::from_slice(&body).expect("valid CreateDiscordUserResponse bytes");
     }
+
+    #[tokio::test]
+    async fn create_users_from_other_providers() {
+        let (app, _, _) = crate::test::common::setup().await;
+
+        let create = |provider: &str, external_id: &str| {
+            Request::builder()
+                .method("POST")
+                .uri(format!("/users/create/{}", provider))
+                .header(http::header::CONTENT_TYPE, "application/json")
+                .body(Body::from(
+                    serde_json::to_string(&CreateIdentityUserRequest {
+                        external_id: external_id.to_string(),
+                        username: None,
+                        personalisation: None,
+                        contact_name: Some("Carol".to_string()),
+                        contact_email: None,
+                        timezone: None,
+                        energy_unit: None,
+                    })
+                    .expect("request is valid body"),
+                ))
+                .unwrap()
+        };
+
+        let response = app
+            .clone()
+            .oneshot(create("email", " Carol@Example.com "))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let created: CreateUserResponse =
+            serde_json::from_slice(&body).expect("valid CreateUserResponse bytes");
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/users?provider=email&external_id=carol@example.com")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let dto: FindUserResponse =
+            serde_json::from_slice(&body).expect("valid FindUserResponse bytes");
+        assert_eq!(dto.id, created.user_id);
+        assert_eq!(dto.contact_email, Some("carol@example.com".to_string()));
+        assert_eq!(dto.discord_id, None);
+        assert_eq!(
+            dto.identities
+                .iter()
+                .map(|i| (i.provider, i.external_id.as_str()))
+                .collect::<Vec<_>>(),
+            vec![(Provider::Email, "carol@example.com")]
+        );
+
+        for (provider, external_id) in [
+            // Already exists, in a different case
+            ("email", "CAROL@example.com"),
+            ("telegram", "not a number"),
+            ("myspace", "carol"),
+        ] {
+            let response = app
+                .clone()
+                .oneshot(create(provider, external_id))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{}", provider);
+        }
+
+        for (provider, external_id) in [("telegram", "123456789"), ("slack", "U024BE7LH")] {
+            let response = app
+                .clone()
+                .oneshot(create(provider, external_id))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::CREATED, "{}", provider);
+        }
+    }
 }
@@ -3,25 +3,36 @@
 //! This module provides functionality for creating users in the database.
 
 use async_trait::async_trait;
-use sqlx::{PgPool, prelude::FromRow};
+use sqlx::{PgConnection, PgPool, prelude::FromRow};
+use tracing::error;
 use uuid::Uuid;
 
-use crate::error::YuhuhError;
+use crate::{
+    error::YuhuhError,
+    food::energy::EnergyUnit,
+    user::identity::{Identity, IdentityRow, Provider},
+    webhooks::{
+        model::{EventType, WebhookEvent},
+        outbox::enqueue_events,
+    },
+};
 
 // =============================================================================
 // Public Types and Structs
 // =============================================================================
 
-/// Request struct for creating a Discord user with associated user data.
+/// Request struct for creating a user signed on through a provider.
 ///
 /// Contains all the necessary information to create both a user record and
-/// a linked Discord user record in the database.
+/// the identity linking it to the provider's account.
 #[derive(Debug)]
-pub struct CreateDBDiscordUserRequest {
-    /// Discord user ID (snowflake as i64)
-    pub discord_id: i64,
-    /// Discord username
-    pub discord_username: String,
+pub struct CreateDBUserRequest {
+    /// Provider the user signed on through
+    pub provider: Provider,
+    /// ID of the account at the provider, already normalised
+    pub external_id: String,
+    /// Name of the account at the provider
+    pub username: Option<String>,
     /// Optional user personalization settings
     pub personalisation: Option<String>,
     /// Optional contact name
@@ -30,6 +41,8 @@ pub struct CreateDBDiscordUserRequest {
     pub contact_email: Option<String>,
     /// Optional timezone preference
     pub timezone: Option<String>,
+    /// Preferred energy unit
+    pub energy_unit: EnergyUnit,
 }
 
 // =============================================================================
@@ -43,22 +56,83 @@ struct CreateUserRow {
     pub user_id: Uuid,
 }
 
+// =============================================================================
+// Shared Queries
+// =============================================================================
+
+/// Links an account at a provider to a user, as part of a wider transaction.
+///
+/// Returns a `Conflict` when the account is already linked to a user.
+pub async fn insert_identity(
+    connection: &mut PgConnection,
+    user_id: &Uuid,
+    provider: Provider,
+    external_id: &str,
+    username: Option<&str>,
+) -> Result<Identity, YuhuhError> {
+    let row = sqlx::query_as!(
+        IdentityRow,
+        r#"
+        INSERT INTO identities (
+            user_id,
+            provider,
+            external_id,
+            username
+        ) VALUES (
+            $1::uuid,
+            $2::text,
+            $3::text,
+            $4::text
+        )
+        RETURNING
+            identity_id,
+            user_id,
+            provider,
+            external_id,
+            username,
+            created_at
+        "#,
+        user_id,
+        provider.as_str(),
+        external_id,
+        username
+    )
+    .fetch_one(connection)
+    .await
+    .map_err(|e| {
+        if e.as_database_error()
+            .is_some_and(|d| d.is_unique_violation())
+        {
+            return YuhuhError::Conflict(format!(
+                "{} account {} is already linked to a user",
+                provider, external_id
+            ));
+        }
+
+        error!(error = ?e, "database error while linking identity");
+
+        YuhuhError::DatabaseError(e)
+    })?;
+
+    Ok(row.try_into()?)
+}
+
 // =============================================================================
 // Traits
 // =============================================================================
 
 /// Trait for repositories that handle user creation operations.
 ///
-/// This trait defines the interface for creating Discord users with associated
-/// user data. Implementations should handle both user and discord_user table
-/// insertions within a transaction to maintain data consistency.
+/// This trait defines the interface for creating users along with the
+/// identity they signed on with. Implementations should handle both user and
+/// identity insertions within a transaction to maintain data consistency.
 #[async_trait]
 pub trait CreateUserRepository: std::fmt::Debug + Send + Sync + 'static {
-    /// Creates a new user with associated Discord account information.
+    /// Creates a new user with the account they signed on with.
     ///
-    /// This method creates both a user record and a linked discord_user record
+    /// This method creates both a user record and a linked identity record
     /// within a database transaction to ensure data consistency. The user is
-    /// created first, and then the Discord user record is linked to it.
+    /// created first, and then the identity is linked to it.
     ///
     /// # Arguments
     /// * `request` - The user creation request containing all necessary data
@@ -71,12 +145,10 @@ pub trait CreateUserRepository: std::fmt::Debug + Send + Sync + 'static {
     /// This method will return an error if:
     /// - The database transaction fails to begin
     /// - The user insertion fails (e.g., constraint violations)
-    /// - The discord_user insertion fails
+    /// - The account is already linked to a user, as a `Conflict`
+    /// - The identity insertion fails
     /// - The transaction fails to commit
-    async fn create_discord_user(
-        &self,
-        request: CreateDBDiscordUserRequest,
-    ) -> Result<Uuid, YuhuhError>;
+    async fn create_user(&self, request: CreateDBUserRequest) -> Result<Uuid, YuhuhError>;
 }
 
 // =============================================================================
@@ -96,10 +168,7 @@ pub struct CreateUserRepositoryImpl {
 
 #[async_trait]
 impl CreateUserRepository for CreateUserRepositoryImpl {
-    async fn create_discord_user(
-        &self,
-        request: CreateDBDiscordUserRequest,
-    ) -> Result<Uuid, YuhuhError> {
+    async fn create_user(&self, request: CreateDBUserRequest) -> Result<Uuid, YuhuhError> {
         // Begin a database transaction to ensure atomicity
         let mut transaction = self.db.begin().await?;
 
@@ -111,43 +180,50 @@ impl CreateUserRepository for CreateUserRepositoryImpl {
                 personalisation,
                 contact_email,
                 contact_name,
-                timezone
+                timezone,
+                energy_unit
             ) VALUES (
                 $1, 
                 $2, 
                 $3, 
-                $4
+                $4,
+                $5
             ) RETURNING user_id
             "#,
             request.personalisation,
             request.contact_email,
             request.contact_name,
-            request.timezone
+            request.timezone,
+            request.energy_unit.to_string()
         )
         .fetch_one(&mut *transaction)
         .await?
         .user_id;
 
-        // Then, create the linked Discord user record
-        sqlx::query!(
-            r#"
-            INSERT INTO discord_users (
-                discord_id,
-                username,
-                user_id
-            ) VALUES (
-                $1,
-                $2,
-                $3
-            )
-            "#,
-            request.discord_id,
-            request.discord_username,
-            user_id
+        // Then, link the account they signed on with
+        let identity = insert_identity(
+            &mut transaction,
+            &user_id,
+            request.provider,
+            &request.external_id,
+            request.username.as_deref(),
         )
-        .execute(&mut *transaction)
         .await?;
 
+        let event = WebhookEvent::new(
+            EventType::UserCreated,
+            user_id,
+            &serde_json::json!({
+                "user_id": user_id,
+                "identities": [identity],
+                "contact_name": request.contact_name,
+                "contact_email": request.contact_email,
+                "timezone": request.timezone,
+                "energy_unit": request.energy_unit,
+            }),
+        )?;
+        enqueue_events(&mut transaction, &[event]).await?;
+
         // Commit the transaction to persist all changes
         transaction.commit().await?;
 
@@ -171,12 +247,9 @@ pub struct DummyCreateUserRepository {}
 
 #[async_trait]
 impl CreateUserRepository for DummyCreateUserRepository {
-    async fn create_discord_user(
-        &self,
-        _request: CreateDBDiscordUserRequest,
-    ) -> Result<Uuid, YuhuhError> {
+    async fn create_user(&self, _request: CreateDBUserRequest) -> Result<Uuid, YuhuhError> {
         panic!(
-            "DummyCreateUserRepository::create_discord_user called - this should be unreachable in tests"
+            "DummyCreateUserRepository::create_user called - this should be unreachable in tests"
         );
     }
 }
@@ -12,8 +12,14 @@ use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
 use crate::{
+    auth::model::Caller,
     error::YuhuhError,
-    user::{model::User, state::UserState},
+    food::energy::EnergyUnit,
+    user::{
+        identity::{Identity, Provider},
+        model::User,
+        state::UserState,
+    },
 };
 
 // ============================================================================
@@ -25,8 +31,11 @@ use crate::{
 pub struct FindUserRequest {
     /// Optional user ID to search by.
     pub id: Option<Uuid>,
-    /// Optional Discord ID to search by.
-    pub discord_id: Option<i64>,
+    /// Optional provider to search by, along with `external_id`.
+    pub provider: Option<Provider>,
+    /// Optional ID of an account at `provider` to search by, e.g. a Discord
+    /// ID or an email address.
+    pub external_id: Option<String>,
 }
 
 /// Response containing user information.
@@ -39,8 +48,14 @@ pub struct FindUserResponse {
     pub created_at: DateTime<Utc>,
     pub updated_at: Option<DateTime<Utc>>,
     pub timezone: Option<String>,
+    pub energy_unit: EnergyUnit,
+    pub body_weight_kg: Option<f32>,
+    pub max_heart_rate: Option<i16>,
+    pub resting_heart_rate: Option<i16>,
     pub discord_id: Option<i64>,
     pub discord_username: Option<String>,
+    /// Every account the user signs on with.
+    pub identities: Vec<Identity>,
 }
 
 // ============================================================================
@@ -50,8 +65,8 @@ pub struct FindUserResponse {
 impl From<User> for FindUserResponse {
     fn from(user: User) -> Self {
         let (discord_id, discord_username) = user
-            .discord_user
-            .map(|d| (Some(d.discord_id), Some(d.username)))
+            .identity(Provider::Discord)
+            .map(|d| (d.external_id.parse().ok(), d.username.clone()))
             .unwrap_or((None, None));
 
         Self {
@@ -62,8 +77,13 @@ impl From<User> for FindUserResponse {
             created_at: user.created_at,
             updated_at: user.updated_at,
             timezone: user.timezone,
+            energy_unit: user.energy_unit,
+            body_weight_kg: user.body_weight_kg,
+            max_heart_rate: user.max_heart_rate,
+            resting_heart_rate: user.resting_heart_rate,
             discord_id,
             discord_username,
+            identities: user.identities,
         }
     }
 }
@@ -98,12 +118,15 @@ impl From<User> for FindUserResponse {
 #[instrument]
 pub async fn find_user(
     State(user_state): State<Arc<UserState>>,
+    caller: Caller,
     Query(request): Query<FindUserRequest>,
 ) -> Result<Json<FindUserResponse>, YuhuhError> {
     debug!("entered find_user - request: {:?}", request);
 
     // Handle user ID search
     if let Some(id) = request.id {
+        caller.authorise_user(&id)?;
+
         let result = user_state
             .find_user_repo
             .find_user_by_id(&id)
@@ -119,13 +142,18 @@ pub async fn find_user(
         return result;
     }
 
-    // Handle Discord ID search (not yet implemented)
-    if let Some(discord_id) = request.discord_id {
+    // Handle search by an account at a provider
+    if let (Some(provider), Some(external_id)) = (request.provider, &request.external_id) {
+        let external_id = provider.normalise_external_id(external_id)?;
+
+        // Someone else's account is not found rather than forbidden, so user
+        // tokens can't find out who else is registered
         let result = user_state
             .find_user_repo
-            .find_user_by_discord_id(discord_id)
+            .find_user_by_identity(provider, &external_id)
             .await?
-            .ok_or_else(|| YuhuhError::NotFound("user not found from discord id".to_string()))
+            .filter(|u| caller.authorise_user(&u.user_id).is_ok())
+            .ok_or_else(|| YuhuhError::NotFound(format!("user not found from {} id", provider)))
             .map(|u| Json(FindUserResponse::from(u)));
 
         match &result {
@@ -137,7 +165,9 @@ pub async fn find_user(
     }
 
     // No valid query parameters provided
-    Err(YuhuhError::BadRequest("missing query".to_string()))
+    Err(YuhuhError::BadRequest(
+        "missing query, search by id or by provider and external_id".to_string(),
+    ))
 }
 
 #[cfg(test)]
@@ -207,7 +237,7 @@ mod tests {
             .oneshot(
                 Request::builder()
                     .method("GET")
-                    .uri("/users?discord_id=100")
+                    .uri("/users?provider=discord&external_id=100")
                     .body(Body::empty())
                     .unwrap(),
             )
@@ -7,12 +7,44 @@ use sqlx::PgPool;
 use tracing::{debug, error, info};
 use uuid::Uuid;
 
-use crate::{error::YuhuhError, user::model::User};
+use crate::{
+    error::YuhuhError,
+    user::{identity::Provider, model::User},
+};
+
+/// Columns of a [`User`], selected from `users u`.
+const USER_COLUMNS: &str = r#"
+    u.user_id,
+    u.personalisation,
+    u.contact_email,
+    u.contact_name,
+    u.created_at,
+    u.updated_at,
+    u.timezone,
+    u.energy_unit,
+    u.body_weight_kg,
+    u.max_heart_rate,
+    u.resting_heart_rate,
+    coalesce(
+        (
+            SELECT json_agg(i.* ORDER BY i.created_at, i.identity_id)
+            FROM identities i
+            WHERE i.user_id = u.user_id
+        ),
+        '[]'::json
+    ) AS identities
+"#;
 
 #[async_trait]
 pub trait FindUserRepository: std::fmt::Debug + Send + Sync + 'static {
     async fn find_user_by_id(&self, id: &Uuid) -> Result<Option<User>, YuhuhError>;
-    async fn find_user_by_discord_id(&self, id: i64) -> Result<Option<User>, YuhuhError>;
+    /// Finds the user linked to an account at a provider, where the
+    /// account's ID has already been normalised.
+    async fn find_user_by_identity(
+        &self,
+        provider: Provider,
+        external_id: &str,
+    ) -> Result<Option<User>, YuhuhError>;
 }
 
 #[derive(Debug)]
@@ -25,24 +57,13 @@ impl FindUserRepository for FindUserRepositoryImpl {
     async fn find_user_by_id(&self, id: &Uuid) -> Result<Option<User>, YuhuhError> {
         info!(id = ?id, "finding user by id");
 
-        let user: Option<User> = sqlx::query_as(
+        let user: Option<User> = sqlx::query_as(&format!(
             r#"
-            SELECT
-                u.user_id,
-                u.personalisation,
-                u.contact_email,
-                u.contact_name,
-                u.created_at,
-                u.updated_at,
-                u.timezone,
-                to_json(du.*) AS discord_user
-            FROM
-                users u
-                LEFT JOIN discord_users du ON u.user_id = du.user_id
-            WHERE
-                u.user_id = $1
-            "#,
-        )
+            SELECT {USER_COLUMNS}
+            FROM users u
+            WHERE u.user_id = $1
+            "#
+        ))
         .bind(id)
         .fetch_optional(&self.db)
         .await
@@ -59,32 +80,30 @@ impl FindUserRepository for FindUserRepositoryImpl {
         Ok(user)
     }
 
-    async fn find_user_by_discord_id(&self, id: i64) -> Result<Option<User>, YuhuhError> {
-        info!(id = ?id, "finding user by id");
+    async fn find_user_by_identity(
+        &self,
+        provider: Provider,
+        external_id: &str,
+    ) -> Result<Option<User>, YuhuhError> {
+        info!(provider = %provider, external_id, "finding user by identity");
 
-        let user: Option<User> = sqlx::query_as(
+        let user: Option<User> = sqlx::query_as(&format!(
             r#"
-            SELECT
-                u.user_id,
-                u.personalisation,
-                u.contact_email,
-                u.contact_name,
-                u.created_at,
-                u.updated_at,
-                u.timezone,
-                to_json(du.*) AS discord_user
-            FROM
-                users u
-                INNER JOIN discord_users du ON u.user_id = du.user_id
-            WHERE
-                du.discord_id = $1
-            "#,
-        )
-        .bind(id)
+            SELECT {USER_COLUMNS}
+            FROM users u
+            WHERE u.user_id = (
+                SELECT user_id
+                FROM identities
+                WHERE provider = $1 AND external_id = $2
+            )
+            "#
+        ))
+        .bind(provider.as_str())
+        .bind(external_id)
         .fetch_optional(&self.db)
         .await
         .map_err(|e| {
-            error!(error = ?e, user_id = ?id, "database error while finding user");
+            error!(error = ?e, provider = %provider, external_id, "database error while finding user");
 
             YuhuhError::ContextError {
                 context: "failed to find user".to_string(),
@@ -109,9 +128,13 @@ impl FindUserRepository for DummyFindUserRepository {
         );
     }
 
-    async fn find_user_by_discord_id(&self, _id: i64) -> Result<Option<User>, YuhuhError> {
+    async fn find_user_by_identity(
+        &self,
+        _provider: Provider,
+        _external_id: &str,
+    ) -> Result<Option<User>, YuhuhError> {
         panic!(
-            "DummyFindUserRepository::find_user_by_discord_id called - this should be unreachable in tests"
+            "DummyFindUserRepository::find_user_by_identity called - this should be unreachable in tests"
         );
     }
 }
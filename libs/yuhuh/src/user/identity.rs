@@ -0,0 +1,175 @@
+use std::{fmt, str::FromStr};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::ValidateEmail;
+
+use crate::error::{ConversionError, YuhuhError};
+
+/// Longest external ID accepted from any provider.
+const MAX_EXTERNAL_ID_LENGTH: usize = 254;
+
+/// Where an account a user signs on with lives.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, ToSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Provider {
+    Discord,
+    Telegram,
+    Slack,
+    Email,
+}
+
+impl Provider {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Provider::Discord => "discord",
+            Provider::Telegram => "telegram",
+            Provider::Slack => "slack",
+            Provider::Email => "email",
+        }
+    }
+
+    /// Checks an account ID is in the provider's format, returning it as it's
+    /// stored so the same account always matches.
+    ///
+    /// Discord and Telegram IDs are numeric and stored without leading zeros,
+    /// Slack member IDs are uppercase letters and digits like `U024BE7LH`, and
+    /// email addresses are lowercased.
+    pub fn normalise_external_id(&self, external_id: &str) -> Result<String, YuhuhError> {
+        let external_id = external_id.trim();
+
+        if external_id.is_empty() || external_id.len() > MAX_EXTERNAL_ID_LENGTH {
+            return Err(YuhuhError::BadRequest(format!(
+                "{} ids must be between 1 and {} characters",
+                self, MAX_EXTERNAL_ID_LENGTH
+            )));
+        }
+
+        let normalised = match self {
+            // Stored as parsed, so `0100` and `100` are the same account
+            Provider::Discord | Provider::Telegram => external_id
+                .parse::<i64>()
+                .ok()
+                .filter(|id| *id > 0)
+                .map(|id| id.to_string()),
+            Provider::Slack => external_id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric())
+                .then(|| external_id.to_ascii_uppercase()),
+            Provider::Email => external_id
+                .validate_email()
+                .then(|| external_id.to_lowercase()),
+        };
+
+        normalised.ok_or_else(|| {
+            YuhuhError::BadRequest(format!("{} is not a valid {} id", external_id, self))
+        })
+    }
+}
+
+impl fmt::Display for Provider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Provider {
+    type Err = ConversionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "discord" => Ok(Provider::Discord),
+            "telegram" => Ok(Provider::Telegram),
+            "slack" => Ok(Provider::Slack),
+            "email" => Ok(Provider::Email),
+            _ => Err(ConversionError::new(format!("unknown provider {}", s))),
+        }
+    }
+}
+
+/// An account at a provider linked to a user.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Identity {
+    pub identity_id: Uuid,
+    pub user_id: Uuid,
+    pub provider: Provider,
+    pub external_id: String,
+    /// Name of the account at the provider, if it has one.
+    pub username: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+// =============================================================================
+// Row Structs
+// =============================================================================
+#[derive(Debug, sqlx::FromRow)]
+pub struct IdentityRow {
+    pub identity_id: Uuid,
+    pub user_id: Uuid,
+    pub provider: String,
+    pub external_id: String,
+    pub username: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TryFrom<IdentityRow> for Identity {
+    type Error = ConversionError;
+
+    fn try_from(row: IdentityRow) -> Result<Self, Self::Error> {
+        Ok(Identity {
+            identity_id: row.identity_id,
+            user_id: row.user_id,
+            provider: row.provider.parse()?,
+            external_id: row.external_id,
+            username: row.username,
+            created_at: row.created_at,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn external_ids_are_checked_and_normalised() {
+        let valid = [
+            (
+                Provider::Discord,
+                " 80351110224678912 ",
+                "80351110224678912",
+            ),
+            (Provider::Telegram, "123456789", "123456789"),
+            (Provider::Discord, "0100", "100"),
+            (Provider::Telegram, "+100", "100"),
+            (Provider::Slack, "u024be7lh", "U024BE7LH"),
+            (Provider::Email, "Alice@Example.com", "alice@example.com"),
+        ];
+        for (provider, external_id, expected) in valid {
+            assert_eq!(
+                provider.normalise_external_id(external_id).unwrap(),
+                expected
+            );
+        }
+
+        let invalid = [
+            (Provider::Discord, "alice"),
+            (Provider::Telegram, "-5"),
+            (Provider::Slack, "U024 BE7LH"),
+            (Provider::Email, "not an email"),
+            (Provider::Email, ""),
+        ];
+        for (provider, external_id) in invalid {
+            assert!(
+                provider.normalise_external_id(external_id).is_err(),
+                "{} {}",
+                provider,
+                external_id
+            );
+        }
+    }
+}
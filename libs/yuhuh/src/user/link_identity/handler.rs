@@ -0,0 +1,178 @@
+//! identity linking HTTP handler
+//!
+//! This module provides HTTP endpoints for linking accounts at other
+//! providers to an existing user, so they can be found through any of them.
+
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, instrument};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    auth::model::Caller,
+    error::YuhuhError,
+    user::{
+        identity::{Identity, Provider},
+        state::UserState,
+    },
+};
+
+// ============================================================================
+// HTTP Request types
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct LinkIdentityRequest {
+    pub provider: Provider,
+    /// ID of the account at the provider, e.g. a Discord ID or an email
+    /// address.
+    pub external_id: String,
+    /// Name of the account at the provider, if it has one.
+    pub username: Option<String>,
+}
+
+// =============================================================================
+// HTTP Handlers
+// =============================================================================
+
+/// Link an account at a provider to a user
+#[utoipa::path(
+    post,
+    path = "users/{user_id}/identities",
+    tag = "users",
+    params(("user_id" = Uuid, Path, description = "User to link the account to")),
+    responses(
+        (status = 201, description = "Account linked", body = Identity),
+        (status = 400, description = "Invalid account id, or already linked to a user"),
+        (status = 404, description = "User not found")
+))]
+#[instrument]
+pub async fn link_identity(
+    State(user_state): State<Arc<UserState>>,
+    caller: Caller,
+    Path(user_id): Path<Uuid>,
+    Json(request): Json<LinkIdentityRequest>,
+) -> Result<(StatusCode, Json<Identity>), YuhuhError> {
+    debug!("entering link_identity");
+
+    // Users could otherwise claim accounts that aren't theirs, which the
+    // service linking them has to check
+    caller.authorise_service()?;
+
+    let external_id = request
+        .provider
+        .normalise_external_id(&request.external_id)?;
+
+    user_state.require_user(&user_id).await?;
+
+    let identity = user_state
+        .link_identity_repo
+        .link_identity(
+            &user_id,
+            request.provider,
+            &external_id,
+            request.username.as_deref(),
+        )
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(identity)))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::Body,
+        http::{self, Request, StatusCode},
+    };
+    use http_body_util::BodyExt;
+    use pretty_assertions::assert_eq;
+    use tower::ServiceExt;
+    use uuid::{Uuid, uuid};
+
+    use crate::user::{
+        identity::{Identity, Provider},
+        link_identity::LinkIdentityRequest,
+    };
+
+    const ALICE: Uuid = uuid!("11111111-1111-1111-1111-111111111111");
+    const BOBAT: Uuid = uuid!("22222222-2222-2222-2222-222222222222");
+
+    fn link(user_id: Uuid, provider: Provider, external_id: &str) -> Request<Body> {
+        Request::builder()
+            .method("POST")
+            .uri(format!("/users/{}/identities", user_id))
+            .header(http::header::CONTENT_TYPE, "application/json")
+            .body(Body::from(
+                serde_json::to_string(&LinkIdentityRequest {
+                    provider,
+                    external_id: external_id.to_string(),
+                    username: Some("alice".to_string()),
+                })
+                .expect("request is valid body"),
+            ))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn accounts_linked_to_existing_users() {
+        let (app, db, state) = crate::test::common::setup().await;
+
+        sqlx::raw_sql(include_str!("../../migrations/test/find_user.sql"))
+            .execute(&db)
+            .await
+            .expect("setup test sql ran successfully");
+
+        let response = app
+            .clone()
+            .oneshot(link(ALICE, Provider::Slack, "u024be7lh"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let identity: Identity = serde_json::from_slice(&body).expect("valid Identity bytes");
+        assert_eq!(identity.user_id, ALICE);
+        assert_eq!(identity.external_id, "U024BE7LH");
+
+        // Alice can now be found through either account
+        let user = state
+            .user
+            .find_user_repo
+            .find_user_by_identity(Provider::Slack, "U024BE7LH")
+            .await
+            .unwrap()
+            .expect("user found from slack id");
+        assert_eq!(user.user_id, ALICE);
+        assert_eq!(
+            user.identities
+                .iter()
+                .map(|i| i.provider)
+                .collect::<Vec<_>>(),
+            vec![Provider::Discord, Provider::Slack]
+        );
+
+        // Already linked to Alice
+        let response = app
+            .clone()
+            .oneshot(link(BOBAT, Provider::Slack, "U024BE7LH"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = app
+            .oneshot(link(
+                uuid!("33333333-3333-3333-3333-333333333333"),
+                Provider::Telegram,
+                "42",
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
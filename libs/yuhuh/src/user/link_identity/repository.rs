@@ -0,0 +1,72 @@
+//! Identity linking repository module
+//!
+//! This module provides functionality for linking accounts at other
+//! providers to existing users.
+
+use async_trait::async_trait;
+use sqlx::PgPool;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::{
+    error::YuhuhError,
+    user::{
+        create_user::insert_identity,
+        identity::{Identity, Provider},
+    },
+};
+
+// =============================================================================
+// Traits
+// =============================================================================
+
+#[async_trait]
+pub trait LinkIdentityRepository: std::fmt::Debug + Send + Sync + 'static {
+    /// Links an account at a provider, whose ID has already been normalised,
+    /// to an existing user.
+    ///
+    /// Returns a `Conflict` when the account is already linked to a user.
+    async fn link_identity(
+        &self,
+        user_id: &Uuid,
+        provider: Provider,
+        external_id: &str,
+        username: Option<&str>,
+    ) -> Result<Identity, YuhuhError>;
+}
+
+// =============================================================================
+// Production Implementation
+// =============================================================================
+
+#[derive(Debug)]
+pub struct LinkIdentityRepositoryImpl {
+    pub db: PgPool,
+}
+
+impl LinkIdentityRepositoryImpl {
+    pub fn new(db: PgPool) -> Self {
+        LinkIdentityRepositoryImpl { db }
+    }
+}
+
+#[async_trait]
+impl LinkIdentityRepository for LinkIdentityRepositoryImpl {
+    async fn link_identity(
+        &self,
+        user_id: &Uuid,
+        provider: Provider,
+        external_id: &str,
+        username: Option<&str>,
+    ) -> Result<Identity, YuhuhError> {
+        info!(user_id = ?user_id, provider = %provider, external_id, "linking identity");
+
+        let mut connection = self.db.acquire().await.map_err(|e| {
+            error!(error = ?e, "database error while linking identity");
+
+            YuhuhError::DatabaseError(e)
+        })?;
+
+        insert_identity(&mut connection, user_id, provider, external_id, username).await
+    }
+}
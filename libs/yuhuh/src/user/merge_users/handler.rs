@@ -0,0 +1,316 @@
+//! user merge HTTP handlers
+//!
+//! This module provides HTTP endpoints for merging a user who registered
+//! twice, e.g. through Discord and then through another client, into one.
+
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, instrument};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    auth::model::Caller,
+    error::YuhuhError,
+    user::{
+        model::{MergeCounts, UserMerge},
+        state::UserState,
+    },
+};
+
+// ============================================================================
+// HTTP Request types
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct MergeUsersRequest {
+    /// User to move the records of, deleted by the merge.
+    pub source_user_id: Uuid,
+    /// Only count what would be moved, without changing anything.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+// ============================================================================
+// HTTP Responsed types
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct MergeUsersResponse {
+    pub dry_run: bool,
+    /// Number of each kind of record moved, or that would be.
+    pub counts: MergeCounts,
+    /// Number of each kind of record the target already had its own of, so
+    /// were deleted with the source user, or would be.
+    pub dropped: MergeCounts,
+    /// Audit record of the merge, unless it was a dry run.
+    pub merge: Option<UserMerge>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ReadUserMergesResponse {
+    pub merges: Vec<UserMerge>,
+}
+
+// =============================================================================
+// HTTP Handlers
+// =============================================================================
+
+/// Merge another user into this one
+///
+/// Moves the source user's food, mood and activity records, favourites,
+/// custom activity types, fasts, reminders, webhooks, rating scales, eating
+/// window and identities to this user, then deletes the source. Anything this
+/// user already has its own of stays behind and is deleted, and is counted in
+/// `dropped` so a dry run shows what would be lost.
+#[utoipa::path(
+    post,
+    path = "users/{user_id}/merge",
+    tag = "users",
+    params(("user_id" = Uuid, Path, description = "User to merge into")),
+    responses(
+        (status = 200, description = "Users merged, or counted for a dry run", body = MergeUsersResponse),
+        (status = 400, description = "Merging a user into itself"),
+        (status = 404, description = "User not found")
+))]
+#[instrument]
+pub async fn merge_users(
+    State(user_state): State<Arc<UserState>>,
+    caller: Caller,
+    Path(user_id): Path<Uuid>,
+    Json(request): Json<MergeUsersRequest>,
+) -> Result<(StatusCode, Json<MergeUsersResponse>), YuhuhError> {
+    debug!("entering merge_users");
+
+    caller.authorise_user(&user_id)?;
+    caller.authorise_user(&request.source_user_id)?;
+
+    if request.source_user_id == user_id {
+        return Err(YuhuhError::BadRequest(
+            "a user can't be merged into itself".to_string(),
+        ));
+    }
+
+    let outcome = user_state
+        .merge_users_repo
+        .merge_users(&request.source_user_id, &user_id, request.dry_run)
+        .await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(MergeUsersResponse {
+            dry_run: request.dry_run,
+            counts: outcome.counts,
+            dropped: outcome.dropped,
+            merge: outcome.merge,
+        }),
+    ))
+}
+
+/// Find the users merged into a user
+#[utoipa::path(
+    get,
+    path = "users/{user_id}/merges",
+    tag = "users",
+    params(("user_id" = Uuid, Path, description = "User the others were merged into")),
+    responses(
+        (status = 200, description = "Found merges", body = ReadUserMergesResponse)
+))]
+#[instrument]
+pub async fn read_user_merges(
+    State(user_state): State<Arc<UserState>>,
+    caller: Caller,
+    Path(user_id): Path<Uuid>,
+) -> Result<(StatusCode, Json<ReadUserMergesResponse>), YuhuhError> {
+    debug!("entering read_user_merges");
+
+    caller.authorise_user(&user_id)?;
+
+    let merges = user_state.merge_users_repo.read_merges(&user_id).await?;
+
+    Ok((StatusCode::OK, Json(ReadUserMergesResponse { merges })))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        Router,
+        body::Body,
+        http::{self, Request, StatusCode},
+    };
+    use http_body_util::BodyExt;
+    use pretty_assertions::assert_eq;
+    use tower::ServiceExt;
+    use uuid::{Uuid, uuid};
+
+    use crate::user::{
+        identity::Provider,
+        merge_users::{MergeUsersRequest, MergeUsersResponse, ReadUserMergesResponse},
+        model::MergeCounts,
+    };
+
+    const ALICE: Uuid = uuid!("11111111-1111-1111-1111-111111111111");
+    const BOBAT: Uuid = uuid!("22222222-2222-2222-2222-222222222222");
+
+    async fn merge(
+        app: &Router,
+        target: Uuid,
+        source: Uuid,
+        dry_run: bool,
+    ) -> (StatusCode, Vec<u8>) {
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri(format!("/users/{}/merge", target))
+                    .header(http::header::CONTENT_TYPE, "application/json")
+                    .body(Body::from(
+                        serde_json::to_string(&MergeUsersRequest {
+                            source_user_id: source,
+                            dry_run,
+                        })
+                        .expect("request is valid body"),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+
+        (status, body.to_vec())
+    }
+
+    #[tokio::test]
+    async fn second_registrations_merged_into_the_first() {
+        let (app, db, state) = crate::test::common::setup().await;
+
+        sqlx::raw_sql(include_str!("../../migrations/test/merge_users.sql"))
+            .execute(&db)
+            .await
+            .expect("setup test sql ran successfully");
+
+        // Bobat's porridge favourite, climbing type, evening mood reminder,
+        // example.com webhook and mood scale clash with Alice's, so stay
+        // behind
+        let expected = MergeCounts {
+            food_records: 2,
+            food_favourites: 1,
+            mood_records: 1,
+            activity_records: 1,
+            activity_types: 1,
+            personal_records: 0,
+            fasts: 2,
+            reminder_rules: 1,
+            webhook_subscriptions: 1,
+            rating_scales: 1,
+            eating_windows: 1,
+            identities: 1,
+        };
+        let dropped = MergeCounts {
+            food_favourites: 1,
+            activity_types: 1,
+            reminder_rules: 1,
+            webhook_subscriptions: 1,
+            rating_scales: 1,
+            ..MergeCounts::default()
+        };
+
+        let (status, body) = merge(&app, ALICE, BOBAT, true).await;
+        assert_eq!(status, StatusCode::OK);
+        let preview: MergeUsersResponse =
+            serde_json::from_slice(&body).expect("valid MergeUsersResponse bytes");
+        assert!(preview.dry_run);
+        assert_eq!(preview.counts, expected);
+        assert_eq!(preview.dropped, dropped);
+        assert!(preview.merge.is_none());
+
+        // Nothing changed by the preview
+        let bobat = state
+            .user
+            .find_user_repo
+            .find_user_by_identity(Provider::Email, "alice@example.com")
+            .await
+            .unwrap()
+            .expect("bobat still exists");
+        assert_eq!(bobat.user_id, BOBAT);
+
+        let (status, body) = merge(&app, ALICE, BOBAT, false).await;
+        assert_eq!(status, StatusCode::OK);
+        let merged: MergeUsersResponse =
+            serde_json::from_slice(&body).expect("valid MergeUsersResponse bytes");
+        assert_eq!(merged.counts, expected);
+        assert_eq!(merged.dropped, dropped);
+        let audit = merged.merge.expect("merge recorded");
+        assert_eq!(audit.source_user_id, BOBAT);
+        assert_eq!(audit.target_user_id, ALICE);
+        assert_eq!(audit.source_user["contact_name"], "Alice");
+        assert_eq!(audit.source_user["identities"][0]["provider"], "email");
+
+        // Either account now finds Alice
+        let user = state
+            .user
+            .find_user_repo
+            .find_user_by_identity(Provider::Email, "alice@example.com")
+            .await
+            .unwrap()
+            .expect("user found from email");
+        assert_eq!(user.user_id, ALICE);
+        assert_eq!(user.identities.len(), 2);
+
+        let remaining: i64 = sqlx::query_scalar(
+            "SELECT count(*) FROM users WHERE user_id = '22222222-2222-2222-2222-222222222222'",
+        )
+        .fetch_one(&db)
+        .await
+        .unwrap();
+        assert_eq!(remaining, 0);
+
+        let food: i64 = sqlx::query_scalar(
+            "SELECT count(*) FROM food_records WHERE user_id = '11111111-1111-1111-1111-111111111111'",
+        )
+        .fetch_one(&db)
+        .await
+        .unwrap();
+        assert_eq!(food, 3);
+
+        let scale: i16 = sqlx::query_scalar(
+            "SELECT max_rating FROM rating_scales WHERE user_id = '11111111-1111-1111-1111-111111111111' AND metric = 'mood'",
+        )
+        .fetch_one(&db)
+        .await
+        .unwrap();
+        assert_eq!(scale, 5);
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/users/{}/merges", ALICE))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let merges: ReadUserMergesResponse =
+            serde_json::from_slice(&body).expect("valid ReadUserMergesResponse bytes");
+        assert_eq!(merges.merges.len(), 1);
+        assert_eq!(merges.merges[0].merge_id, audit.merge_id);
+
+        // Bobat's gone now
+        let (status, _) = merge(&app, ALICE, BOBAT, false).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (status, _) = merge(&app, ALICE, ALICE, true).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+}
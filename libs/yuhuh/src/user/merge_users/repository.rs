@@ -0,0 +1,572 @@
+//! User merge repository module
+//!
+//! This module provides functionality for merging one user into another in
+//! the database.
+
+use async_trait::async_trait;
+use sqlx::{PgConnection, PgPool};
+use tracing::{debug, error, info};
+use uuid::Uuid;
+
+use crate::{
+    error::YuhuhError,
+    user::model::{MergeCounts, UserMerge, UserMergeRow},
+};
+
+// =============================================================================
+// Public Types and Structs
+// =============================================================================
+
+/// What a merge did, or would have done.
+#[derive(Debug)]
+pub struct MergeOutcome {
+    pub counts: MergeCounts,
+    /// Number of each kind of record the source keeps, deleted along with it.
+    pub dropped: MergeCounts,
+    /// Audit record of the merge, `None` for a dry run.
+    pub merge: Option<UserMerge>,
+}
+
+// =============================================================================
+// Traits
+// =============================================================================
+
+#[async_trait]
+pub trait MergeUsersRepository: std::fmt::Debug + Send + Sync + 'static {
+    /// Moves the source user's records, settings, webhooks and identities to
+    /// the target, records the merge and deletes the source, all in one
+    /// transaction.
+    ///
+    /// A dry run does the same but rolls back, so the counts are exactly what
+    /// a merge would move. Returns `NotFound` when either user doesn't exist.
+    async fn merge_users(
+        &self,
+        source_user_id: &Uuid,
+        target_user_id: &Uuid,
+        dry_run: bool,
+    ) -> Result<MergeOutcome, YuhuhError>;
+
+    /// Merges into a user, most recent first.
+    async fn read_merges(&self, target_user_id: &Uuid) -> Result<Vec<UserMerge>, YuhuhError>;
+}
+
+// =============================================================================
+// Production Implementation
+// =============================================================================
+
+#[derive(Debug)]
+pub struct MergeUsersRepositoryImpl {
+    pub db: PgPool,
+}
+
+impl MergeUsersRepositoryImpl {
+    pub fn new(db: PgPool) -> Self {
+        MergeUsersRepositoryImpl { db }
+    }
+}
+
+/// Moves every record that can go from the source to the target.
+async fn move_records(
+    connection: &mut PgConnection,
+    source_user_id: &Uuid,
+    target_user_id: &Uuid,
+) -> Result<MergeCounts, YuhuhError> {
+    let food_records = sqlx::query!(
+        r#"
+        UPDATE food_records
+        SET user_id = $2::uuid
+        WHERE user_id = $1::uuid;
+        "#,
+        source_user_id,
+        target_user_id
+    )
+    .execute(&mut *connection)
+    .await
+    .map_err(|e| {
+        error!(error = ?e, "database error while moving food records");
+
+        YuhuhError::DatabaseError(e)
+    })?
+    .rows_affected();
+
+    let food_favourites = sqlx::query!(
+        r#"
+        UPDATE food_favourites f
+        SET user_id = $2::uuid
+        WHERE f.user_id = $1::uuid
+        AND NOT EXISTS (
+            SELECT 1
+            FROM food_favourites t
+            WHERE t.user_id = $2::uuid
+            AND lower(t.description) = lower(f.description)
+        );
+        "#,
+        source_user_id,
+        target_user_id
+    )
+    .execute(&mut *connection)
+    .await
+    .map_err(|e| {
+        error!(error = ?e, "database error while moving food favourites");
+
+        YuhuhError::DatabaseError(e)
+    })?
+    .rows_affected();
+
+    let mood_records = sqlx::query!(
+        r#"
+        UPDATE mood_records
+        SET user_id = $2::uuid
+        WHERE user_id = $1::uuid;
+        "#,
+        source_user_id,
+        target_user_id
+    )
+    .execute(&mut *connection)
+    .await
+    .map_err(|e| {
+        error!(error = ?e, "database error while moving mood records");
+
+        YuhuhError::DatabaseError(e)
+    })?
+    .rows_affected();
+
+    // Activities refer to their type by name, so they keep working with the
+    // target's type of the same name
+    let activity_types = sqlx::query!(
+        r#"
+        UPDATE activity_types a
+        SET user_id = $2::uuid
+        WHERE a.user_id = $1::uuid
+        AND NOT EXISTS (
+            SELECT 1
+            FROM activity_types t
+            WHERE t.user_id = $2::uuid
+            AND lower(t.name) = lower(a.name)
+        );
+        "#,
+        source_user_id,
+        target_user_id
+    )
+    .execute(&mut *connection)
+    .await
+    .map_err(|e| {
+        error!(error = ?e, "database error while moving activity types");
+
+        YuhuhError::DatabaseError(e)
+    })?
+    .rows_affected();
+
+    let activity_records = sqlx::query!(
+        r#"
+        UPDATE activity_records
+        SET user_id = $2::uuid
+        WHERE user_id = $1::uuid;
+        "#,
+        source_user_id,
+        target_user_id
+    )
+    .execute(&mut *connection)
+    .await
+    .map_err(|e| {
+        error!(error = ?e, "database error while moving activity records");
+
+        YuhuhError::DatabaseError(e)
+    })?
+    .rows_affected();
+
+    let personal_records = sqlx::query!(
+        r#"
+        UPDATE personal_records
+        SET user_id = $2::uuid
+        WHERE user_id = $1::uuid;
+        "#,
+        source_user_id,
+        target_user_id
+    )
+    .execute(&mut *connection)
+    .await
+    .map_err(|e| {
+        error!(error = ?e, "database error while moving personal records");
+
+        YuhuhError::DatabaseError(e)
+    })?
+    .rows_affected();
+
+    // Only one fast can be in progress at a time
+    let fasts = sqlx::query!(
+        r#"
+        UPDATE fasts f
+        SET user_id = $2::uuid
+        WHERE f.user_id = $1::uuid
+        AND (
+            f.ended_at IS NOT NULL
+            OR NOT EXISTS (
+                SELECT 1
+                FROM fasts t
+                WHERE t.user_id = $2::uuid
+                AND t.ended_at IS NULL
+            )
+        );
+        "#,
+        source_user_id,
+        target_user_id
+    )
+    .execute(&mut *connection)
+    .await
+    .map_err(|e| {
+        error!(error = ?e, "database error while moving fasts");
+
+        YuhuhError::DatabaseError(e)
+    })?
+    .rows_affected();
+
+    // Reminders, webhooks and settings the target already has its own of stay
+    // behind, so the target's own setup wins
+    let reminder_rules = sqlx::query!(
+        r#"
+        UPDATE reminder_rules r
+        SET user_id = $2::uuid
+        WHERE r.user_id = $1::uuid
+        AND NOT EXISTS (
+            SELECT 1
+            FROM reminder_rules t
+            WHERE t.user_id = $2::uuid
+            AND t.entry_kind = r.entry_kind
+            AND t.remind_at = r.remind_at
+        );
+        "#,
+        source_user_id,
+        target_user_id
+    )
+    .execute(&mut *connection)
+    .await
+    .map_err(|e| {
+        error!(error = ?e, "database error while moving reminder rules");
+
+        YuhuhError::DatabaseError(e)
+    })?
+    .rows_affected();
+
+    let webhook_subscriptions = sqlx::query!(
+        r#"
+        UPDATE webhook_subscriptions w
+        SET user_id = $2::uuid
+        WHERE w.user_id = $1::uuid
+        AND NOT EXISTS (
+            SELECT 1
+            FROM webhook_subscriptions t
+            WHERE t.user_id = $2::uuid
+            AND t.url = w.url
+        );
+        "#,
+        source_user_id,
+        target_user_id
+    )
+    .execute(&mut *connection)
+    .await
+    .map_err(|e| {
+        error!(error = ?e, "database error while moving webhook subscriptions");
+
+        YuhuhError::DatabaseError(e)
+    })?
+    .rows_affected();
+
+    let rating_scales = sqlx::query!(
+        r#"
+        UPDATE rating_scales r
+        SET user_id = $2::uuid
+        WHERE r.user_id = $1::uuid
+        AND NOT EXISTS (
+            SELECT 1
+            FROM rating_scales t
+            WHERE t.user_id = $2::uuid
+            AND t.metric = r.metric
+        );
+        "#,
+        source_user_id,
+        target_user_id
+    )
+    .execute(&mut *connection)
+    .await
+    .map_err(|e| {
+        error!(error = ?e, "database error while moving rating scales");
+
+        YuhuhError::DatabaseError(e)
+    })?
+    .rows_affected();
+
+    let eating_windows = sqlx::query!(
+        r#"
+        UPDATE eating_windows e
+        SET user_id = $2::uuid
+        WHERE e.user_id = $1::uuid
+        AND NOT EXISTS (
+            SELECT 1
+            FROM eating_windows t
+            WHERE t.user_id = $2::uuid
+        );
+        "#,
+        source_user_id,
+        target_user_id
+    )
+    .execute(&mut *connection)
+    .await
+    .map_err(|e| {
+        error!(error = ?e, "database error while moving eating windows");
+
+        YuhuhError::DatabaseError(e)
+    })?
+    .rows_affected();
+
+    let identities = sqlx::query!(
+        r#"
+        UPDATE identities
+        SET user_id = $2::uuid
+        WHERE user_id = $1::uuid;
+        "#,
+        source_user_id,
+        target_user_id
+    )
+    .execute(&mut *connection)
+    .await
+    .map_err(|e| {
+        error!(error = ?e, "database error while moving identities");
+
+        YuhuhError::DatabaseError(e)
+    })?
+    .rows_affected();
+
+    Ok(MergeCounts {
+        food_records,
+        food_favourites,
+        mood_records,
+        activity_records,
+        activity_types,
+        personal_records,
+        fasts,
+        reminder_rules,
+        webhook_subscriptions,
+        rating_scales,
+        eating_windows,
+        identities,
+    })
+}
+
+/// Counts what the source still has after [`move_records`], which is deleted
+/// along with it.
+async fn count_left(
+    connection: &mut PgConnection,
+    source_user_id: &Uuid,
+) -> Result<MergeCounts, YuhuhError> {
+    let row = sqlx::query!(
+        r#"
+        SELECT
+            (SELECT count(*) FROM food_records WHERE user_id = $1::uuid) AS "food_records!",
+            (SELECT count(*) FROM food_favourites WHERE user_id = $1::uuid) AS "food_favourites!",
+            (SELECT count(*) FROM mood_records WHERE user_id = $1::uuid) AS "mood_records!",
+            (SELECT count(*) FROM activity_records WHERE user_id = $1::uuid) AS "activity_records!",
+            (SELECT count(*) FROM activity_types WHERE user_id = $1::uuid) AS "activity_types!",
+            (SELECT count(*) FROM personal_records WHERE user_id = $1::uuid) AS "personal_records!",
+            (SELECT count(*) FROM fasts WHERE user_id = $1::uuid) AS "fasts!",
+            (SELECT count(*) FROM reminder_rules WHERE user_id = $1::uuid) AS "reminder_rules!",
+            (SELECT count(*) FROM webhook_subscriptions WHERE user_id = $1::uuid) AS "webhook_subscriptions!",
+            (SELECT count(*) FROM rating_scales WHERE user_id = $1::uuid) AS "rating_scales!",
+            (SELECT count(*) FROM eating_windows WHERE user_id = $1::uuid) AS "eating_windows!",
+            (SELECT count(*) FROM identities WHERE user_id = $1::uuid) AS "identities!";
+        "#,
+        source_user_id
+    )
+    .fetch_one(&mut *connection)
+    .await
+    .map_err(|e| {
+        error!(error = ?e, "database error while counting records left by merge");
+
+        YuhuhError::DatabaseError(e)
+    })?;
+
+    Ok(MergeCounts {
+        food_records: row.food_records as u64,
+        food_favourites: row.food_favourites as u64,
+        mood_records: row.mood_records as u64,
+        activity_records: row.activity_records as u64,
+        activity_types: row.activity_types as u64,
+        personal_records: row.personal_records as u64,
+        fasts: row.fasts as u64,
+        reminder_rules: row.reminder_rules as u64,
+        webhook_subscriptions: row.webhook_subscriptions as u64,
+        rating_scales: row.rating_scales as u64,
+        eating_windows: row.eating_windows as u64,
+        identities: row.identities as u64,
+    })
+}
+
+#[async_trait]
+impl MergeUsersRepository for MergeUsersRepositoryImpl {
+    async fn merge_users(
+        &self,
+        source_user_id: &Uuid,
+        target_user_id: &Uuid,
+        dry_run: bool,
+    ) -> Result<MergeOutcome, YuhuhError> {
+        debug!(
+            source_user_id=?source_user_id,
+            target_user_id=?target_user_id,
+            dry_run=?dry_run,
+            "received merge users"
+        );
+
+        let mut transaction = self.db.begin().await?;
+
+        // Locked in a consistent order so opposite merges can't deadlock
+        let locked = sqlx::query_scalar!(
+            r#"
+            SELECT user_id
+            FROM users
+            WHERE user_id = ANY(ARRAY[$1::uuid, $2::uuid])
+            ORDER BY user_id
+            FOR UPDATE;
+            "#,
+            source_user_id,
+            target_user_id
+        )
+        .fetch_all(&mut *transaction)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, "database error while locking users to merge");
+
+            YuhuhError::DatabaseError(e)
+        })?;
+
+        if locked.len() != 2 {
+            return Err(YuhuhError::NotFound("user not found".to_string()));
+        }
+
+        let source_user = sqlx::query_scalar!(
+            r#"
+            SELECT to_jsonb(u.*) || jsonb_build_object(
+                'identities',
+                coalesce(
+                    (
+                        SELECT jsonb_agg(i.* ORDER BY i.created_at, i.identity_id)
+                        FROM identities i
+                        WHERE i.user_id = u.user_id
+                    ),
+                    '[]'::jsonb
+                )
+            ) AS "source_user!"
+            FROM users u
+            WHERE u.user_id = $1::uuid;
+            "#,
+            source_user_id
+        )
+        .fetch_one(&mut *transaction)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, "database error while reading user to merge");
+
+            YuhuhError::DatabaseError(e)
+        })?;
+
+        let counts = move_records(&mut transaction, source_user_id, target_user_id).await?;
+        let dropped = count_left(&mut transaction, source_user_id).await?;
+
+        if dry_run {
+            transaction.rollback().await?;
+
+            return Ok(MergeOutcome {
+                counts,
+                dropped,
+                merge: None,
+            });
+        }
+
+        let counts_json = serde_json::to_value(&counts)
+            .map_err(|e| YuhuhError::InternalServerError(e.to_string()))?;
+
+        let row = sqlx::query_as!(
+            UserMergeRow,
+            r#"
+            INSERT INTO user_merges (
+                source_user_id,
+                target_user_id,
+                counts,
+                source_user
+            )
+            VALUES ($1::uuid, $2::uuid, $3::jsonb, $4::jsonb)
+            RETURNING
+                merge_id,
+                source_user_id,
+                target_user_id,
+                merged_at,
+                counts,
+                source_user;
+            "#,
+            source_user_id,
+            target_user_id,
+            counts_json,
+            source_user
+        )
+        .fetch_one(&mut *transaction)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, "database error while recording user merge");
+
+            YuhuhError::DatabaseError(e)
+        })?;
+
+        // Anything left, the `dropped` records, goes with the source
+        sqlx::query!(
+            r#"
+            DELETE FROM users
+            WHERE user_id = $1::uuid;
+            "#,
+            source_user_id
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, "database error while deleting merged user");
+
+            YuhuhError::DatabaseError(e)
+        })?;
+
+        transaction.commit().await?;
+
+        info!(source_user_id=?source_user_id, target_user_id=?target_user_id, counts=?counts, dropped=?dropped, "merged users");
+
+        Ok(MergeOutcome {
+            counts,
+            dropped,
+            merge: Some(row.try_into()?),
+        })
+    }
+
+    async fn read_merges(&self, target_user_id: &Uuid) -> Result<Vec<UserMerge>, YuhuhError> {
+        let rows = sqlx::query_as!(
+            UserMergeRow,
+            r#"
+            SELECT
+                merge_id,
+                source_user_id,
+                target_user_id,
+                merged_at,
+                counts,
+                source_user
+            FROM user_merges
+            WHERE target_user_id = $1::uuid
+            ORDER BY merged_at DESC, merge_id DESC;
+            "#,
+            target_user_id
+        )
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, "database error while reading user merges");
+
+            YuhuhError::DatabaseError(e)
+        })?;
+
+        rows.into_iter().map(|row| Ok(row.try_into()?)).collect()
+    }
+}
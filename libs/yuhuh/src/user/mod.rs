@@ -1,5 +1,9 @@
 pub mod create_user;
 pub mod find_user;
+pub mod identity;
+pub mod link_identity;
+pub mod merge_users;
 pub mod model;
 pub mod router;
 pub mod state;
+pub mod update_preferences;
@@ -1,7 +1,16 @@
 use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
+use tracing::warn;
+use utoipa::ToSchema;
 use uuid::Uuid;
 
+use crate::{
+    error::ConversionError,
+    food::energy::EnergyUnit,
+    user::identity::{Identity, Provider},
+};
+
 #[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
 pub struct User {
     pub user_id: Uuid,
@@ -11,13 +20,107 @@ pub struct User {
     pub created_at: DateTime<Utc>,
     pub updated_at: Option<DateTime<Utc>>,
     pub timezone: Option<String>,
-    #[sqlx(json(nullable))]
-    pub discord_user: Option<DiscordUser>,
+    #[sqlx(try_from = "String")]
+    pub energy_unit: EnergyUnit,
+    /// Used to estimate calories burned by activities.
+    pub body_weight_kg: Option<f32>,
+    /// Used with `resting_heart_rate` to work out heart rate zones.
+    pub max_heart_rate: Option<i16>,
+    pub resting_heart_rate: Option<i16>,
+    /// Accounts at other providers the user signs on with, oldest first.
+    #[sqlx(json)]
+    pub identities: Vec<Identity>,
 }
 
-#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
-pub struct DiscordUser {
-    pub discord_id: i64,
-    pub username: String,
-    pub user_id: Uuid,
+impl User {
+    /// Parsed timezone of the user, falling back to UTC when it's missing or
+    /// isn't a known IANA name.
+    pub fn tz(&self) -> Tz {
+        parse_timezone(self.timezone.as_deref())
+    }
+
+    /// The user's first linked account at a provider.
+    pub fn identity(&self, provider: Provider) -> Option<&Identity> {
+        self.identities.iter().find(|i| i.provider == provider)
+    }
+}
+
+/// Parses a stored timezone, falling back to UTC when it's missing or isn't a
+/// known IANA name.
+pub fn parse_timezone(timezone: Option<&str>) -> Tz {
+    timezone
+        .map(|timezone| {
+            timezone.parse().unwrap_or_else(|e| {
+                warn!(timezone, error=?e, "user has an unknown timezone");
+                Tz::UTC
+            })
+        })
+        .unwrap_or(Tz::UTC)
+}
+
+/// Number of each kind of record moved from one user to another by a merge.
+///
+/// Where the target already has its own favourite food or custom activity
+/// type of the same name, reminder for the same kind of entry at the same
+/// time, webhook to the same URL, rating scale for the same metric or eating
+/// window, it keeps its own and the source's is deleted along with it. So is
+/// the source's in-progress fast when the target has one too.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct MergeCounts {
+    pub food_records: u64,
+    pub food_favourites: u64,
+    pub mood_records: u64,
+    pub activity_records: u64,
+    pub activity_types: u64,
+    pub personal_records: u64,
+    pub fasts: u64,
+    pub reminder_rules: u64,
+    pub webhook_subscriptions: u64,
+    pub rating_scales: u64,
+    pub eating_windows: u64,
+    pub identities: u64,
+}
+
+/// Audit record of one user being merged into another.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct UserMerge {
+    pub merge_id: Uuid,
+    /// User whose records were moved, deleted by the merge.
+    pub source_user_id: Uuid,
+    /// User the records were moved to.
+    pub target_user_id: Uuid,
+    pub merged_at: DateTime<Utc>,
+    pub counts: MergeCounts,
+    /// The source user as it was before the merge, with its identities.
+    pub source_user: serde_json::Value,
+}
+
+// =============================================================================
+// Row Structs
+// =============================================================================
+#[derive(Debug, sqlx::FromRow)]
+pub struct UserMergeRow {
+    pub merge_id: Uuid,
+    pub source_user_id: Uuid,
+    pub target_user_id: Uuid,
+    pub merged_at: DateTime<Utc>,
+    pub counts: serde_json::Value,
+    pub source_user: serde_json::Value,
+}
+
+impl TryFrom<UserMergeRow> for UserMerge {
+    type Error = ConversionError;
+
+    fn try_from(row: UserMergeRow) -> Result<Self, Self::Error> {
+        Ok(UserMerge {
+            merge_id: row.merge_id,
+            source_user_id: row.source_user_id,
+            target_user_id: row.target_user_id,
+            merged_at: row.merged_at,
+            counts: serde_json::from_value(row.counts)
+                .map_err(|e| ConversionError::new(format!("invalid merge counts - {}", e)))?,
+            source_user: row.source_user,
+        })
+    }
 }
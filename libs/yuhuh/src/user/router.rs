@@ -1,12 +1,12 @@
 use axum::{
     Router,
-    routing::{get, post},
+    routing::{get, patch, post},
 };
 use utoipa::OpenApi;
 
 use crate::{
     state::AppState,
-    user::{create_user, find_user},
+    user::{create_user, find_user, link_identity, merge_users, update_preferences},
 };
 
 // =============================================================================
@@ -14,7 +14,15 @@ use crate::{
 // =============================================================================
 
 #[derive(OpenApi)]
-#[openapi(paths(find_user::find_user, create_user::create_discord_user))]
+#[openapi(paths(
+    find_user::find_user,
+    create_user::create_discord_user,
+    create_user::create_identity_user,
+    link_identity::link_identity,
+    merge_users::merge_users,
+    merge_users::read_user_merges,
+    update_preferences::update_preferences
+))]
 pub struct UserApi;
 
 // =============================================================================
@@ -28,6 +36,23 @@ pub fn user_router() -> Router<AppState> {
             "/users/create/discord",
             post(create_user::create_discord_user),
         )
+        .route(
+            "/users/create/{provider}",
+            post(create_user::create_identity_user),
+        )
+        .route(
+            "/users/{user_id}/identities",
+            post(link_identity::link_identity),
+        )
+        .route("/users/{user_id}/merge", post(merge_users::merge_users))
+        .route(
+            "/users/{user_id}/merges",
+            get(merge_users::read_user_merges),
+        )
+        .route(
+            "/users/preferences",
+            patch(update_preferences::update_preferences),
+        )
 }
 
 #[cfg(test)]
@@ -1,10 +1,19 @@
 use std::{fmt::Debug, sync::Arc};
 
 use sqlx::PgPool;
+use tracing::error;
+use uuid::Uuid;
 
-use crate::user::{
-    create_user::{CreateUserRepository, CreateUserRepositoryImpl},
-    find_user::{FindUserRepository, FindUserRepositoryImpl},
+use crate::{
+    error::YuhuhError,
+    user::{
+        create_user::{CreateUserRepository, CreateUserRepositoryImpl},
+        find_user::{FindUserRepository, FindUserRepositoryImpl},
+        link_identity::{LinkIdentityRepository, LinkIdentityRepositoryImpl},
+        merge_users::{MergeUsersRepository, MergeUsersRepositoryImpl},
+        model::User,
+        update_preferences::{UpdatePreferencesRepository, UpdatePreferencesRepositoryImpl},
+    },
 };
 
 #[derive(Debug)]
@@ -12,6 +21,9 @@ pub struct UserState {
     pub db: PgPool,
     pub create_user_repo: Arc<dyn CreateUserRepository>,
     pub find_user_repo: Arc<dyn FindUserRepository>,
+    pub link_identity_repo: Arc<dyn LinkIdentityRepository>,
+    pub merge_users_repo: Arc<dyn MergeUsersRepository>,
+    pub update_preferences_repo: Arc<dyn UpdatePreferencesRepository>,
 }
 
 impl UserState {
@@ -20,6 +32,20 @@ impl UserState {
             db: db.clone(),
             create_user_repo: Arc::new(CreateUserRepositoryImpl { db: db.clone() }),
             find_user_repo: Arc::new(FindUserRepositoryImpl { db: db.clone() }),
+            link_identity_repo: Arc::new(LinkIdentityRepositoryImpl::new(db.clone())),
+            merge_users_repo: Arc::new(MergeUsersRepositoryImpl::new(db.clone())),
+            update_preferences_repo: Arc::new(UpdatePreferencesRepositoryImpl::new(db.clone())),
         }
     }
+
+    /// Finds a user, returning `NotFound` when there's no such user.
+    pub async fn require_user(&self, user_id: &Uuid) -> Result<User, YuhuhError> {
+        self.find_user_repo
+            .find_user_by_id(user_id)
+            .await?
+            .ok_or_else(|| {
+                error!(user_id = ?user_id, "failed to find user");
+                YuhuhError::NotFound("user not found".to_string())
+            })
+    }
 }
@@ -0,0 +1,202 @@
+//! user preferences HTTP handler
+//!
+//! This module provides HTTP endpoints for updating a user's preferences.
+
+use std::sync::Arc;
+
+use axum::{Json, extract::State};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, error, instrument};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    auth::model::Caller,
+    error::YuhuhError,
+    food::energy::EnergyUnit,
+    user::{
+        find_user::FindUserResponse, state::UserState,
+        update_preferences::UpdateDBPreferencesRequest,
+    },
+};
+
+// =============================================================================
+// Request/Response Types
+// =============================================================================
+
+/// Request payload for updating a user's preferences.
+///
+/// Any preference left out of the request is left unchanged.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UpdatePreferencesRequest {
+    /// The user to update
+    pub user_id: Uuid,
+    /// Unit food energy should be displayed in
+    pub energy_unit: Option<EnergyUnit>,
+    /// Body weight in kilograms, used to estimate calories burned
+    pub body_weight_kg: Option<f32>,
+    /// Heart rates in beats per minute, used to work out heart rate zones
+    pub max_heart_rate: Option<i16>,
+    pub resting_heart_rate: Option<i16>,
+}
+
+// =============================================================================
+// HTTP Handlers
+// =============================================================================
+
+/// Update a user's preferences.
+#[utoipa::path(
+    patch,
+    path = "users/preferences",
+    tag = "users",
+    responses(
+        (status = 200, description = "Preferences updated", body = FindUserResponse),
+        (status = 400, description = "Invalid body weight or heart rates"),
+        (status = 404, description = "User not found")
+    )
+)]
+#[instrument]
+pub async fn update_preferences(
+    State(user_state): State<Arc<UserState>>,
+    caller: Caller,
+    Json(request): Json<UpdatePreferencesRequest>,
+) -> Result<Json<FindUserResponse>, YuhuhError> {
+    debug!("entering update_preferences");
+
+    caller.authorise_user(&request.user_id)?;
+
+    if let Some(body_weight_kg) = request.body_weight_kg
+        && !(body_weight_kg.is_finite() && body_weight_kg > 0.0)
+    {
+        return Err(YuhuhError::BadRequest(format!(
+            "body_weight_kg must be positive, but got {} instead",
+            body_weight_kg
+        )));
+    }
+
+    for (name, heart_rate) in [
+        ("max_heart_rate", request.max_heart_rate),
+        ("resting_heart_rate", request.resting_heart_rate),
+    ] {
+        if let Some(heart_rate) = heart_rate
+            && heart_rate <= 0
+        {
+            return Err(YuhuhError::BadRequest(format!(
+                "{} must be positive, but got {} instead",
+                name, heart_rate
+            )));
+        }
+    }
+
+    let updated = user_state
+        .update_preferences_repo
+        .update_preferences(
+            &request.user_id,
+            UpdateDBPreferencesRequest {
+                energy_unit: request.energy_unit,
+                body_weight_kg: request.body_weight_kg,
+                max_heart_rate: request.max_heart_rate,
+                resting_heart_rate: request.resting_heart_rate,
+            },
+        )
+        .await?;
+
+    if !updated {
+        error!(user_id = ?request.user_id, "failed to find user");
+        return Err(YuhuhError::NotFound("user not found".to_string()));
+    }
+
+    user_state
+        .require_user(&request.user_id)
+        .await
+        .map(|u| Json(FindUserResponse::from(u)))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::Body,
+        http::{self, Request, StatusCode},
+    };
+    use http_body_util::BodyExt;
+    use pretty_assertions::assert_eq;
+    use tower::ServiceExt;
+    use uuid::uuid;
+
+    use crate::{
+        food::energy::EnergyUnit,
+        user::{find_user::FindUserResponse, update_preferences::UpdatePreferencesRequest},
+    };
+
+    #[tokio::test]
+    async fn updates_energy_unit_and_body_weight() {
+        let (app, db, _) = crate::test::common::setup().await;
+
+        // Load test data into the database
+        sqlx::raw_sql(include_str!("../../migrations/test/find_user.sql"))
+            .execute(&db)
+            .await
+            .expect("setup test sql ran successfully");
+
+        let request = UpdatePreferencesRequest {
+            user_id: uuid!("11111111-1111-1111-1111-111111111111"),
+            energy_unit: Some(EnergyUnit::Kilojoules),
+            body_weight_kg: Some(80.5),
+            max_heart_rate: Some(190),
+            resting_heart_rate: Some(50),
+        };
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("PATCH")
+                    .uri("/users/preferences")
+                    .header(http::header::CONTENT_TYPE, "application/json")
+                    .body(Body::from(
+                        serde_json::to_string(&request).expect("request is valid body"),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let dto: FindUserResponse =
+            serde_json::from_slice(&body).expect("valid FindUserResponse bytes");
+
+        assert_eq!(dto.energy_unit, EnergyUnit::Kilojoules);
+        assert_eq!(dto.body_weight_kg, Some(80.5));
+        assert_eq!(dto.max_heart_rate, Some(190));
+        assert_eq!(dto.resting_heart_rate, Some(50));
+    }
+
+    #[tokio::test]
+    async fn invalid_user_returns_not_found() {
+        let (app, _, _) = crate::test::common::setup().await;
+
+        let request = UpdatePreferencesRequest {
+            user_id: uuid!("11111111-5555-3333-2222-111111111111"),
+            energy_unit: Some(EnergyUnit::Kilojoules),
+            body_weight_kg: None,
+            max_heart_rate: None,
+            resting_heart_rate: None,
+        };
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("PATCH")
+                    .uri("/users/preferences")
+                    .header(http::header::CONTENT_TYPE, "application/json")
+                    .body(Body::from(
+                        serde_json::to_string(&request).expect("request is valid body"),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,103 @@
+//! User preferences repository module
+//!
+//! This module provides functionality for updating a user's preferences.
+
+use async_trait::async_trait;
+use sqlx::PgPool;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::{error::YuhuhError, food::energy::EnergyUnit};
+
+// =============================================================================
+// Public Types and Structs
+// =============================================================================
+
+/// Preferences to update on a user. `None` leaves the existing value alone.
+#[derive(Debug, Default)]
+pub struct UpdateDBPreferencesRequest {
+    /// Unit food energy should be displayed in
+    pub energy_unit: Option<EnergyUnit>,
+    /// Body weight in kilograms, used to estimate calories burned
+    pub body_weight_kg: Option<f32>,
+    /// Heart rates in beats per minute, used to work out heart rate zones
+    pub max_heart_rate: Option<i16>,
+    pub resting_heart_rate: Option<i16>,
+}
+
+// =============================================================================
+// Traits
+// =============================================================================
+
+#[async_trait]
+pub trait UpdatePreferencesRepository: std::fmt::Debug + Send + Sync + 'static {
+    /// Updates the preferences of an existing user.
+    ///
+    /// # Returns
+    /// * `Ok(true)` - The user was found and updated
+    /// * `Ok(false)` - No user exists with the given ID
+    async fn update_preferences(
+        &self,
+        user_id: &Uuid,
+        request: UpdateDBPreferencesRequest,
+    ) -> Result<bool, YuhuhError>;
+}
+
+// =============================================================================
+// Production Implementation
+// =============================================================================
+
+#[derive(Debug)]
+pub struct UpdatePreferencesRepositoryImpl {
+    pub db: PgPool,
+}
+
+impl UpdatePreferencesRepositoryImpl {
+    pub fn new(db: PgPool) -> Self {
+        UpdatePreferencesRepositoryImpl { db }
+    }
+}
+
+#[async_trait]
+impl UpdatePreferencesRepository for UpdatePreferencesRepositoryImpl {
+    async fn update_preferences(
+        &self,
+        user_id: &Uuid,
+        request: UpdateDBPreferencesRequest,
+    ) -> Result<bool, YuhuhError> {
+        info!(user_id = ?user_id, request = ?request, "updating user preferences");
+
+        let result = sqlx::query!(
+            r#"
+            UPDATE users
+            SET energy_unit = COALESCE($2, energy_unit),
+                body_weight_kg = COALESCE($3, body_weight_kg),
+                max_heart_rate = COALESCE($4, max_heart_rate),
+                resting_heart_rate = COALESCE($5, resting_heart_rate)
+            WHERE user_id = $1
+            "#,
+            user_id,
+            request.energy_unit.map(|u| u.to_string()),
+            request.body_weight_kg,
+            request.max_heart_rate,
+            request.resting_heart_rate,
+        )
+        .execute(&self.db)
+        .await
+        .map_err(|e| {
+            if e.as_database_error()
+                .is_some_and(|d| d.is_check_violation())
+            {
+                return YuhuhError::BadRequest(
+                    "resting_heart_rate must be below max_heart_rate".to_string(),
+                );
+            }
+
+            error!(error = ?e, user_id = ?user_id, "database error while updating preferences");
+
+            YuhuhError::DatabaseError(e)
+        })?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
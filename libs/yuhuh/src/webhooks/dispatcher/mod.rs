@@ -0,0 +1,5 @@
+mod repository;
+mod runner;
+
+pub use repository::*;
+pub use runner::*;
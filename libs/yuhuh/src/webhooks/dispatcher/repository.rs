@@ -0,0 +1,243 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use tracing::{debug, error};
+use uuid::Uuid;
+
+use crate::{error::YuhuhError, webhooks::model::DeliveryAttempt};
+
+/// A delivery that's due, with everything needed to send it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingDelivery {
+    pub delivery_id: Uuid,
+    pub event_id: Uuid,
+    pub event_type: String,
+    /// Event as it's posted to the webhook.
+    pub payload: serde_json::Value,
+    pub webhook_id: Uuid,
+    pub url: String,
+    pub secret: String,
+    /// Attempts made before this one.
+    pub attempts: i32,
+}
+
+/// What's next for a delivery after an attempt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeliveryOutcome {
+    Delivered,
+    Retry {
+        next_attempt_at: DateTime<Utc>,
+    },
+    /// Out of attempts.
+    Failed,
+}
+
+// =============================================================================
+// Traits
+// =============================================================================
+
+#[async_trait]
+pub trait WebhookDispatcherRepository: std::fmt::Debug + Send + Sync + 'static {
+    /// Queues a delivery of up to `limit` new outbox events to every webhook
+    /// registered for them at the time, returning how many events were
+    /// processed.
+    async fn fan_out_events(&self, limit: i64) -> Result<u64, YuhuhError>;
+
+    /// Claims up to `limit` deliveries due by `now`, pushing their next
+    /// attempt back to `lease_until` so no other dispatcher sends them in the
+    /// meantime.
+    async fn claim_due_deliveries(
+        &self,
+        now: DateTime<Utc>,
+        lease_until: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<PendingDelivery>, YuhuhError>;
+
+    /// Logs an attempt and moves the delivery on to its outcome.
+    async fn record_attempt(
+        &self,
+        delivery_id: &Uuid,
+        attempt: &DeliveryAttempt,
+        outcome: DeliveryOutcome,
+    ) -> Result<(), YuhuhError>;
+}
+
+// =============================================================================
+// Production Implementation
+// =============================================================================
+
+#[derive(Debug)]
+pub struct WebhookDispatcherRepositoryImpl {
+    pub db: PgPool,
+}
+
+impl WebhookDispatcherRepositoryImpl {
+    pub fn new(db: PgPool) -> Self {
+        WebhookDispatcherRepositoryImpl { db }
+    }
+}
+
+#[async_trait]
+impl WebhookDispatcherRepository for WebhookDispatcherRepositoryImpl {
+    async fn fan_out_events(&self, limit: i64) -> Result<u64, YuhuhError> {
+        // Locked rows are being fanned out by another dispatcher. Deliveries
+        // are due from when the event happened, so they're sent the same tick
+        let result = sqlx::query!(
+            r#"
+            WITH events AS (
+                SELECT event_id, event_type, user_id, created_at
+                FROM webhook_outbox
+                WHERE processed_at IS NULL
+                ORDER BY event_id
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            ),
+            deliveries AS (
+                INSERT INTO webhook_deliveries (event_id, webhook_id, next_attempt_at)
+                SELECT e.event_id, w.webhook_id, e.created_at
+                FROM events e
+                JOIN webhook_subscriptions w
+                    ON (w.user_id IS NULL OR w.user_id = e.user_id)
+                    AND (cardinality(w.event_types) = 0 OR e.event_type = ANY(w.event_types))
+                    AND w.created_at <= e.created_at
+                ON CONFLICT DO NOTHING
+            )
+            UPDATE webhook_outbox o
+            SET processed_at = now()
+            FROM events e
+            WHERE o.event_id = e.event_id;
+            "#,
+            limit
+        )
+        .execute(&self.db)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, "database error while fanning out webhook events");
+
+            YuhuhError::DatabaseError(e)
+        })?;
+
+        debug!(events = result.rows_affected(), "fanned out webhook events");
+
+        Ok(result.rows_affected())
+    }
+
+    async fn claim_due_deliveries(
+        &self,
+        now: DateTime<Utc>,
+        lease_until: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<PendingDelivery>, YuhuhError> {
+        let deliveries = sqlx::query_as!(
+            PendingDelivery,
+            r#"
+            WITH due AS (
+                SELECT delivery_id
+                FROM webhook_deliveries
+                WHERE delivered_at IS NULL
+                AND failed_at IS NULL
+                AND next_attempt_at <= $1::timestamptz
+                ORDER BY next_attempt_at
+                LIMIT $3
+                FOR UPDATE SKIP LOCKED
+            )
+            UPDATE webhook_deliveries d
+            SET next_attempt_at = $2::timestamptz
+            FROM due, webhook_outbox o, webhook_subscriptions w
+            WHERE d.delivery_id = due.delivery_id
+            AND o.event_id = d.event_id
+            AND w.webhook_id = d.webhook_id
+            RETURNING
+                d.delivery_id,
+                d.event_id,
+                o.event_type,
+                o.payload,
+                d.webhook_id,
+                w.url,
+                w.secret,
+                d.attempts;
+            "#,
+            now,
+            lease_until,
+            limit
+        )
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, "database error while claiming webhook deliveries");
+
+            YuhuhError::DatabaseError(e)
+        })?;
+
+        Ok(deliveries)
+    }
+
+    async fn record_attempt(
+        &self,
+        delivery_id: &Uuid,
+        attempt: &DeliveryAttempt,
+        outcome: DeliveryOutcome,
+    ) -> Result<(), YuhuhError> {
+        debug!(delivery_id=?delivery_id, attempt=?attempt, outcome=?outcome, "recording webhook delivery attempt");
+
+        let mut transaction = self.db.begin().await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO webhook_delivery_attempts (
+                delivery_id,
+                attempted_at,
+                status_code,
+                error,
+                duration_ms
+            )
+            VALUES ($1::uuid, $2::timestamptz, $3::smallint, $4::text, $5::integer);
+            "#,
+            delivery_id,
+            attempt.attempted_at,
+            attempt.status_code.map(|s| s as i16),
+            attempt.error,
+            attempt.duration_ms.min(i32::MAX as u32) as i32
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, "database error while logging webhook delivery attempt");
+
+            YuhuhError::DatabaseError(e)
+        })?;
+
+        let (delivered_at, next_attempt_at, failed_at) = match outcome {
+            DeliveryOutcome::Delivered => (Some(attempt.attempted_at), None, None),
+            DeliveryOutcome::Retry { next_attempt_at } => (None, Some(next_attempt_at), None),
+            DeliveryOutcome::Failed => (None, None, Some(attempt.attempted_at)),
+        };
+
+        sqlx::query!(
+            r#"
+            UPDATE webhook_deliveries
+            SET
+                attempts = attempts + 1,
+                delivered_at = $2::timestamptz,
+                next_attempt_at = coalesce($3::timestamptz, next_attempt_at),
+                failed_at = $4::timestamptz
+            WHERE delivery_id = $1::uuid;
+            "#,
+            delivery_id,
+            delivered_at,
+            next_attempt_at,
+            failed_at
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, "database error while updating webhook delivery");
+
+            YuhuhError::DatabaseError(e)
+        })?;
+
+        transaction.commit().await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,516 @@
+//! Background dispatcher that delivers webhook events.
+//!
+//! Every tick fans new outbox events out into a delivery per matching
+//! webhook, then sends the deliveries that are due. Each is signed with the
+//! webhook's secret and retried with exponential backoff until the receiver
+//! responds with a 2xx status or attempts run out, with every attempt
+//! logged. Deliveries are claimed with a lease first, so several dispatchers
+//! can run at once without sending the same delivery twice.
+
+use std::{sync::Arc, time::Duration};
+
+use chrono::{DateTime, TimeDelta, Utc};
+use reqwest::{Client, Url, header::CONTENT_TYPE, redirect::Policy};
+use tokio::{task::JoinSet, time::MissedTickBehavior};
+use tracing::{debug, error, info, warn};
+
+use crate::{
+    error::YuhuhError,
+    webhooks::{
+        dispatcher::{DeliveryOutcome, PendingDelivery, WebhookDispatcherRepository},
+        model::DeliveryAttempt,
+        signature::{DELIVERY_ID_HEADER, EVENT_TYPE_HEADER, SIGNATURE_HEADER, sign},
+        target::{self, PublicResolver},
+    },
+};
+
+/// Most events fanned out, and deliveries sent, in a tick.
+const BATCH_SIZE: i64 = 50;
+
+/// How long a receiver has to respond.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long a claimed delivery is held before another dispatcher may try it,
+/// comfortably longer than a request can take.
+const LEASE: TimeDelta = TimeDelta::minutes(5);
+
+/// Wait before the first retry, doubling with every failed attempt.
+const BASE_RETRY_DELAY: TimeDelta = TimeDelta::seconds(30);
+
+/// Longest wait between retries.
+const MAX_RETRY_DELAY: TimeDelta = TimeDelta::hours(6);
+
+/// Attempts made before a delivery is given up on, spanning about a day.
+const MAX_ATTEMPTS: i32 = 12;
+
+/// What happened in a tick.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DispatchSummary {
+    /// New events fanned out to webhooks.
+    pub events: u32,
+    pub delivered: u32,
+    /// Failed, and left to be retried.
+    pub retried: u32,
+    /// Failed, and out of attempts.
+    pub failed: u32,
+}
+
+#[derive(Debug)]
+pub struct WebhookDispatcher {
+    repo: Arc<dyn WebhookDispatcherRepository>,
+    client: Client,
+    allow_private: bool,
+}
+
+/// Wait before retrying a delivery that has failed `attempts` times.
+fn retry_delay(attempts: i32) -> TimeDelta {
+    let exponent = (attempts - 1).clamp(0, 16) as u32;
+
+    (BASE_RETRY_DELAY * 2_i32.pow(exponent)).min(MAX_RETRY_DELAY)
+}
+
+/// What's next for a delivery after an attempt made at `now`.
+fn outcome(
+    delivery: &PendingDelivery,
+    attempt: &DeliveryAttempt,
+    now: DateTime<Utc>,
+) -> DeliveryOutcome {
+    let attempts = delivery.attempts + 1;
+
+    if attempt.succeeded() {
+        DeliveryOutcome::Delivered
+    } else if attempts >= MAX_ATTEMPTS {
+        DeliveryOutcome::Failed
+    } else {
+        DeliveryOutcome::Retry {
+            next_attempt_at: now + retry_delay(attempts),
+        }
+    }
+}
+
+/// Posts a delivery to its webhook, signed as of `now`.
+async fn send(
+    client: &Client,
+    delivery: &PendingDelivery,
+    allow_private: bool,
+    now: DateTime<Utc>,
+) -> DeliveryAttempt {
+    // Addresses in the URL itself aren't resolved, so are checked here
+    if !allow_private
+        && let Err(error) = Url::parse(&delivery.url)
+            .map_err(|e| e.to_string())
+            .and_then(|url| target::check_url(&url))
+    {
+        return DeliveryAttempt {
+            attempted_at: now,
+            status_code: None,
+            error: Some(error),
+            duration_ms: 0,
+        };
+    }
+
+    let body = delivery.payload.to_string().into_bytes();
+    let signature = sign(&delivery.secret, now, &body);
+
+    let started = std::time::Instant::now();
+    let response = client
+        .post(&delivery.url)
+        .header(CONTENT_TYPE, "application/json")
+        .header(SIGNATURE_HEADER, signature)
+        .header(EVENT_TYPE_HEADER, &delivery.event_type)
+        .header(DELIVERY_ID_HEADER, delivery.delivery_id.to_string())
+        .body(body)
+        .send()
+        .await;
+    let duration_ms = started.elapsed().as_millis().min(u32::MAX.into()) as u32;
+
+    let (status_code, error) = match response {
+        Ok(response) if response.status().is_success() => (Some(response.status().as_u16()), None),
+        Ok(response) => (
+            Some(response.status().as_u16()),
+            Some(format!("receiver responded with {}", response.status())),
+        ),
+        Err(e) => (e.status().map(|s| s.as_u16()), Some(e.to_string())),
+    };
+
+    DeliveryAttempt {
+        attempted_at: now,
+        status_code,
+        error,
+        duration_ms,
+    }
+}
+
+impl WebhookDispatcher {
+    /// Creates a dispatcher that only sends to public addresses, unless
+    /// `allow_private` is set for local development.
+    pub fn new(
+        repo: Arc<dyn WebhookDispatcherRepository>,
+        allow_private: bool,
+    ) -> Result<Self, YuhuhError> {
+        // Redirects aren't followed, as they could lead anywhere
+        let mut builder = Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .user_agent("yuhuh-webhooks")
+            .redirect(Policy::none());
+
+        if !allow_private {
+            builder = builder.dns_resolver(Arc::new(PublicResolver));
+        }
+
+        let client = builder.build().map_err(|e| {
+            error!(error = ?e, "failed to build webhook http client");
+
+            YuhuhError::InternalServerError(e.to_string())
+        })?;
+
+        Ok(WebhookDispatcher {
+            repo,
+            client,
+            allow_private,
+        })
+    }
+
+    /// Fans out new events and sends every delivery that's due as of `now`.
+    pub async fn tick(&self, now: DateTime<Utc>) -> Result<DispatchSummary, YuhuhError> {
+        let mut summary = DispatchSummary {
+            events: self.repo.fan_out_events(BATCH_SIZE).await? as u32,
+            ..Default::default()
+        };
+
+        let deliveries = self
+            .repo
+            .claim_due_deliveries(now, now + LEASE, BATCH_SIZE)
+            .await?;
+
+        // Sent concurrently so one slow receiver doesn't hold up the rest
+        let mut sends = JoinSet::new();
+        for delivery in deliveries {
+            let client = self.client.clone();
+            let allow_private = self.allow_private;
+
+            sends.spawn(async move {
+                let attempt = send(&client, &delivery, allow_private, now).await;
+
+                (delivery, attempt)
+            });
+        }
+
+        while let Some(sent) = sends.join_next().await {
+            let (delivery, attempt) = sent.map_err(|e| {
+                error!(error = ?e, "webhook delivery task failed");
+
+                YuhuhError::InternalServerError(e.to_string())
+            })?;
+
+            let outcome = outcome(&delivery, &attempt, now);
+            match outcome {
+                DeliveryOutcome::Delivered => {
+                    debug!(delivery_id = ?delivery.delivery_id, "delivered webhook event");
+                    summary.delivered += 1;
+                }
+                DeliveryOutcome::Retry { next_attempt_at } => {
+                    warn!(delivery_id = ?delivery.delivery_id, url = delivery.url, error = ?attempt.error, next_attempt_at = ?next_attempt_at, "failed to deliver webhook event, will retry");
+                    summary.retried += 1;
+                }
+                DeliveryOutcome::Failed => {
+                    error!(delivery_id = ?delivery.delivery_id, url = delivery.url, error = ?attempt.error, "failed to deliver webhook event, giving up");
+                    summary.failed += 1;
+                }
+            }
+
+            self.repo
+                .record_attempt(&delivery.delivery_id, &attempt, outcome)
+                .await?;
+        }
+
+        Ok(summary)
+    }
+
+    /// Ticks every `period` until the process exits.
+    pub async fn run(self, period: Duration) {
+        info!(period = ?period, "starting webhook dispatcher");
+
+        let mut interval = tokio::time::interval(period);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+
+            match self.tick(Utc::now()).await {
+                Ok(summary) if summary != DispatchSummary::default() => {
+                    info!(summary = ?summary, "dispatched webhook events")
+                }
+                Ok(_) => {}
+                Err(e) => error!(error = ?e, "webhook dispatcher tick failed"),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        Arc, Mutex,
+        atomic::{AtomicUsize, Ordering},
+    };
+
+    use axum::{
+        Router,
+        body::{Body, Bytes},
+        extract::State,
+        http::{self, HeaderMap, Request, StatusCode},
+        routing::post,
+    };
+    use http_body_util::BodyExt;
+    use pretty_assertions::assert_eq;
+    use tower::ServiceExt;
+    use uuid::{Uuid, uuid};
+
+    use super::*;
+    use crate::{
+        food::create_food_entries::{CreateFoodEntryRequest, NewFoodEntry},
+        webhooks::{
+            dispatcher::WebhookDispatcherRepositoryImpl,
+            model::{EventType, WebhookEvent, WebhookSubscription},
+            signature::verify,
+            webhook_subscriptions::ReadWebhookDeliveriesResponse,
+        },
+    };
+
+    const SECRET: &str = "whsec_test";
+
+    /// Records every request, failing the first with a 500.
+    #[derive(Debug, Default)]
+    struct Receiver {
+        received: Mutex<Vec<(HeaderMap, Bytes)>>,
+        calls: AtomicUsize,
+    }
+
+    async fn receive(
+        State(receiver): State<Arc<Receiver>>,
+        headers: HeaderMap,
+        body: Bytes,
+    ) -> StatusCode {
+        receiver.received.lock().unwrap().push((headers, body));
+
+        match receiver.calls.fetch_add(1, Ordering::SeqCst) {
+            0 => StatusCode::INTERNAL_SERVER_ERROR,
+            _ => StatusCode::NO_CONTENT,
+        }
+    }
+
+    async fn serve_receiver() -> (String, Arc<Receiver>) {
+        let receiver = Arc::new(Receiver::default());
+        let app = Router::new()
+            .route("/hooks", post(receive))
+            .with_state(receiver.clone());
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hooks", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        (url, receiver)
+    }
+
+    fn webhook(
+        user_id: Option<Uuid>,
+        url: &str,
+        event_types: Vec<EventType>,
+    ) -> WebhookSubscription {
+        WebhookSubscription {
+            webhook_id: Uuid::now_v7(),
+            user_id,
+            url: url.to_string(),
+            event_types,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn created_food_is_delivered_signed_and_retried() {
+        let (app, db, state) = crate::test::common::setup().await;
+
+        sqlx::raw_sql(include_str!("../../migrations/test/webhooks.sql"))
+            .execute(&db)
+            .await
+            .expect("setup test sql ran successfully");
+
+        let (url, receiver) = serve_receiver().await;
+
+        let alice = uuid!("11111111-1111-1111-1111-111111111111");
+        let bobat = uuid!("22222222-2222-2222-2222-222222222222");
+
+        // Only Alice's food webhook matches
+        let alice_food = webhook(Some(alice), &url, vec![EventType::FoodCreated]);
+        let repo = &state.webhooks.webhook_subscriptions_repo;
+        for (webhook, secret) in [
+            (&alice_food, SECRET),
+            (
+                &webhook(None, &url, vec![EventType::MoodCreated]),
+                "whsec_global",
+            ),
+            (&webhook(Some(bobat), &url, vec![]), "whsec_bobat"),
+        ] {
+            repo.create_webhook(webhook, secret)
+                .await
+                .expect("webhook created");
+        }
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/food/create")
+                    .header(http::header::CONTENT_TYPE, "application/json")
+                    .body(Body::from(
+                        serde_json::to_string(&CreateFoodEntryRequest {
+                            user_id: alice,
+                            food_entries: vec![NewFoodEntry {
+                                description: "sushi".to_string(),
+                                calories: Some(450.0),
+                                energy_unit: None,
+                                carbs: None,
+                                protein: None,
+                                fats: None,
+                                micronutrients: None,
+                                logged_at: None,
+                            }],
+                        })
+                        .unwrap(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        // The receiver is on loopback
+        let dispatcher = WebhookDispatcher::new(
+            Arc::new(WebhookDispatcherRepositoryImpl::new(db.clone())),
+            true,
+        )
+        .expect("dispatcher created");
+
+        let now = Utc::now();
+
+        // The receiver fails the first attempt
+        let summary = dispatcher.tick(now).await.expect("tick succeeded");
+        assert_eq!(
+            summary,
+            DispatchSummary {
+                events: 1,
+                retried: 1,
+                ..Default::default()
+            }
+        );
+
+        // Nothing is retried before the backoff is up
+        let summary = dispatcher.tick(now).await.expect("tick succeeded");
+        assert_eq!(summary, DispatchSummary::default());
+
+        let retry_at = now + TimeDelta::minutes(1);
+        let summary = dispatcher.tick(retry_at).await.expect("tick succeeded");
+        assert_eq!(
+            summary,
+            DispatchSummary {
+                delivered: 1,
+                ..Default::default()
+            }
+        );
+
+        let received = receiver.received.lock().unwrap().clone();
+        assert_eq!(received.len(), 2);
+        assert_eq!(
+            received[0].0[DELIVERY_ID_HEADER],
+            received[1].0[DELIVERY_ID_HEADER]
+        );
+
+        let (headers, body) = &received[1];
+        assert_eq!(headers[EVENT_TYPE_HEADER], "food.created");
+        assert!(verify(
+            SECRET,
+            headers[SIGNATURE_HEADER].to_str().unwrap(),
+            body,
+            retry_at,
+            TimeDelta::minutes(5)
+        ));
+
+        let event: WebhookEvent = serde_json::from_slice(body).expect("valid WebhookEvent bytes");
+        assert_eq!(event.event_type, EventType::FoodCreated);
+        assert_eq!(event.user_id, alice);
+        assert_eq!(event.data["description"], "sushi");
+        assert!(event.data["food_record_id"].is_string());
+
+        // The delivery log shows both attempts
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/webhooks/{}/deliveries", alice_food.webhook_id))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let dto: ReadWebhookDeliveriesResponse =
+            serde_json::from_slice(&body).expect("valid ReadWebhookDeliveriesResponse bytes");
+        assert_eq!(dto.deliveries.len(), 1);
+
+        let delivery = &dto.deliveries[0];
+        assert_eq!(delivery.event_id, event.event_id);
+        assert!(delivery.delivered_at.is_some());
+        assert_eq!(delivery.next_attempt_at, None);
+        assert_eq!(
+            delivery
+                .attempts
+                .iter()
+                .map(|a| a.status_code)
+                .collect::<Vec<_>>(),
+            vec![Some(500), Some(204)]
+        );
+    }
+
+    #[tokio::test]
+    async fn private_addresses_not_sent_to() {
+        let (url, receiver) = serve_receiver().await;
+        let port = Url::parse(&url).unwrap().port().unwrap();
+
+        let (_, db, _) = crate::test::common::setup().await;
+        let dispatcher = WebhookDispatcher::new(
+            Arc::new(WebhookDispatcherRepositoryImpl::new(db.clone())),
+            false,
+        )
+        .expect("dispatcher created");
+
+        for url in [url, format!("http://localhost:{}/hooks", port)] {
+            let delivery = PendingDelivery {
+                delivery_id: Uuid::now_v7(),
+                event_id: Uuid::now_v7(),
+                event_type: "food.created".to_string(),
+                payload: serde_json::json!({}),
+                webhook_id: Uuid::now_v7(),
+                url: url.clone(),
+                secret: SECRET.to_string(),
+                attempts: 0,
+            };
+
+            let attempt = send(&dispatcher.client, &delivery, false, Utc::now()).await;
+            assert_eq!(attempt.status_code, None, "{}", url);
+            assert!(attempt.error.is_some(), "{}", url);
+        }
+
+        assert_eq!(receiver.calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn retries_back_off_exponentially() {
+        assert_eq!(retry_delay(1), TimeDelta::seconds(30));
+        assert_eq!(retry_delay(2), TimeDelta::minutes(1));
+        assert_eq!(retry_delay(5), TimeDelta::minutes(8));
+        assert_eq!(retry_delay(11), MAX_RETRY_DELAY);
+    }
+}
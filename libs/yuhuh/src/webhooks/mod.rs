@@ -0,0 +1,8 @@
+pub mod dispatcher;
+pub mod model;
+pub mod outbox;
+pub mod router;
+pub mod signature;
+pub mod state;
+pub mod target;
+pub mod webhook_subscriptions;
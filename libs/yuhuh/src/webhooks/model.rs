@@ -0,0 +1,264 @@
+use std::{fmt, str::FromStr};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::error;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    error::{ConversionError, YuhuhError},
+    search::model::RecordKind,
+};
+
+/// Types of event sent to webhooks.
+///
+/// Food and mood records can't yet be changed or removed once logged, so
+/// their updated and deleted events are only sent once they can be.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ToSchema)]
+pub enum EventType {
+    #[serde(rename = "food.created")]
+    FoodCreated,
+    #[serde(rename = "food.updated")]
+    FoodUpdated,
+    #[serde(rename = "food.deleted")]
+    FoodDeleted,
+    #[serde(rename = "mood.created")]
+    MoodCreated,
+    #[serde(rename = "mood.updated")]
+    MoodUpdated,
+    #[serde(rename = "mood.deleted")]
+    MoodDeleted,
+    #[serde(rename = "activity.created")]
+    ActivityCreated,
+    /// Sent when an activity's samples are replaced.
+    #[serde(rename = "activity.updated")]
+    ActivityUpdated,
+    #[serde(rename = "activity.deleted")]
+    ActivityDeleted,
+    #[serde(rename = "user.created")]
+    UserCreated,
+}
+
+impl EventType {
+    const ALL: [EventType; 10] = [
+        EventType::FoodCreated,
+        EventType::FoodUpdated,
+        EventType::FoodDeleted,
+        EventType::MoodCreated,
+        EventType::MoodUpdated,
+        EventType::MoodDeleted,
+        EventType::ActivityCreated,
+        EventType::ActivityUpdated,
+        EventType::ActivityDeleted,
+        EventType::UserCreated,
+    ];
+
+    /// Kind of record the event is about, or `None` for user events.
+    pub fn record_kind(&self) -> Option<RecordKind> {
+        match self {
+            EventType::FoodCreated | EventType::FoodUpdated | EventType::FoodDeleted => {
+                Some(RecordKind::Food)
+            }
+            EventType::MoodCreated | EventType::MoodUpdated | EventType::MoodDeleted => {
+                Some(RecordKind::Mood)
+            }
+            EventType::ActivityCreated
+            | EventType::ActivityUpdated
+            | EventType::ActivityDeleted => Some(RecordKind::Activity),
+            EventType::UserCreated => None,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            EventType::FoodCreated => "food.created",
+            EventType::FoodUpdated => "food.updated",
+            EventType::FoodDeleted => "food.deleted",
+            EventType::MoodCreated => "mood.created",
+            EventType::MoodUpdated => "mood.updated",
+            EventType::MoodDeleted => "mood.deleted",
+            EventType::ActivityCreated => "activity.created",
+            EventType::ActivityUpdated => "activity.updated",
+            EventType::ActivityDeleted => "activity.deleted",
+            EventType::UserCreated => "user.created",
+        }
+    }
+}
+
+impl fmt::Display for EventType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for EventType {
+    type Err = ConversionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        EventType::ALL
+            .into_iter()
+            .find(|t| t.as_str() == s)
+            .ok_or_else(|| ConversionError::new(format!("unknown event type {}", s)))
+    }
+}
+
+/// An event as it's posted to webhooks.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct WebhookEvent {
+    pub event_id: Uuid,
+    #[serde(rename = "type")]
+    pub event_type: EventType,
+    /// User the event is about.
+    pub user_id: Uuid,
+    pub occurred_at: DateTime<Utc>,
+    /// The record or user the event is about, as returned by the API.
+    pub data: serde_json::Value,
+}
+
+impl WebhookEvent {
+    pub fn new(
+        event_type: EventType,
+        user_id: Uuid,
+        data: &impl Serialize,
+    ) -> Result<Self, YuhuhError> {
+        let data = serde_json::to_value(data).map_err(|e| {
+            error!(error = ?e, event_type = ?event_type, "failed to serialise webhook event");
+
+            YuhuhError::InternalServerError(e.to_string())
+        })?;
+
+        Ok(WebhookEvent {
+            event_id: Uuid::now_v7(),
+            event_type,
+            user_id,
+            occurred_at: Utc::now(),
+            data,
+        })
+    }
+}
+
+/// A URL events are posted to, for one user or every user.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct WebhookSubscription {
+    pub webhook_id: Uuid,
+    /// User whose events are sent, every user's when not set.
+    pub user_id: Option<Uuid>,
+    pub url: String,
+    /// Event types sent, every type when empty.
+    pub event_types: Vec<EventType>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// An event being delivered to a webhook, along with every attempt made.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct WebhookDelivery {
+    pub delivery_id: Uuid,
+    pub event_id: Uuid,
+    pub event_type: EventType,
+    pub created_at: DateTime<Utc>,
+    /// When the next attempt is due, if it's still being retried.
+    pub next_attempt_at: Option<DateTime<Utc>>,
+    pub delivered_at: Option<DateTime<Utc>>,
+    /// When the delivery was given up on after running out of attempts.
+    pub failed_at: Option<DateTime<Utc>>,
+    /// Attempts made so far, oldest first.
+    pub attempts: Vec<DeliveryAttempt>,
+}
+
+/// One attempt to deliver an event.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct DeliveryAttempt {
+    pub attempted_at: DateTime<Utc>,
+    /// HTTP status the receiver responded with, if it responded at all.
+    pub status_code: Option<u16>,
+    /// Why the attempt failed, if it did.
+    pub error: Option<String>,
+    pub duration_ms: u32,
+}
+
+impl DeliveryAttempt {
+    pub fn succeeded(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+// =============================================================================
+// Row Structs
+// =============================================================================
+#[derive(Debug, sqlx::FromRow)]
+pub struct WebhookSubscriptionRow {
+    pub webhook_id: Uuid,
+    pub user_id: Option<Uuid>,
+    pub url: String,
+    pub event_types: Vec<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TryFrom<WebhookSubscriptionRow> for WebhookSubscription {
+    type Error = ConversionError;
+
+    fn try_from(value: WebhookSubscriptionRow) -> Result<Self, Self::Error> {
+        Ok(WebhookSubscription {
+            webhook_id: value.webhook_id,
+            user_id: value.user_id,
+            url: value.url,
+            event_types: value
+                .event_types
+                .iter()
+                .map(|t| t.parse())
+                .collect::<Result<_, _>>()?,
+            created_at: value.created_at,
+        })
+    }
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct WebhookDeliveryRow {
+    pub delivery_id: Uuid,
+    pub event_id: Uuid,
+    pub event_type: String,
+    pub created_at: DateTime<Utc>,
+    pub next_attempt_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+    pub failed_at: Option<DateTime<Utc>>,
+}
+
+impl TryFrom<WebhookDeliveryRow> for WebhookDelivery {
+    type Error = ConversionError;
+
+    fn try_from(value: WebhookDeliveryRow) -> Result<Self, Self::Error> {
+        let pending = value.delivered_at.is_none() && value.failed_at.is_none();
+
+        Ok(WebhookDelivery {
+            delivery_id: value.delivery_id,
+            event_id: value.event_id,
+            event_type: value.event_type.parse()?,
+            created_at: value.created_at,
+            next_attempt_at: pending.then_some(value.next_attempt_at),
+            delivered_at: value.delivered_at,
+            failed_at: value.failed_at,
+            attempts: vec![],
+        })
+    }
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct DeliveryAttemptRow {
+    pub delivery_id: Uuid,
+    pub attempted_at: DateTime<Utc>,
+    pub status_code: Option<i16>,
+    pub error: Option<String>,
+    pub duration_ms: i32,
+}
+
+impl From<DeliveryAttemptRow> for DeliveryAttempt {
+    fn from(value: DeliveryAttemptRow) -> Self {
+        DeliveryAttempt {
+            attempted_at: value.attempted_at,
+            status_code: value.status_code.map(|s| s as u16),
+            error: value.error,
+            duration_ms: value.duration_ms.max(0) as u32,
+        }
+    }
+}
@@ -0,0 +1,76 @@
+//! Transactional outbox for webhook events.
+//!
+//! Events are written in the same transaction as the change they describe,
+//! then picked up by the [dispatcher](crate::webhooks::dispatcher), so an
+//! event is only ever sent for a change that was committed and is never lost
+//! when sending fails.
+
+use chrono::NaiveDateTime;
+use sqlx::PgConnection;
+use tracing::{debug, error};
+use uuid::Uuid;
+
+use crate::{error::YuhuhError, webhooks::model::WebhookEvent};
+
+/// Writes events to the outbox using the caller's transaction.
+pub async fn enqueue_events(
+    connection: &mut PgConnection,
+    events: &[WebhookEvent],
+) -> Result<(), YuhuhError> {
+    if events.is_empty() {
+        return Ok(());
+    }
+
+    debug!(events = events.len(), "enqueuing webhook events");
+
+    let mut event_id_vecs: Vec<Uuid> = vec![];
+    let mut event_type_vecs: Vec<String> = vec![];
+    let mut user_id_vecs: Vec<Uuid> = vec![];
+    let mut created_at_vecs: Vec<NaiveDateTime> = vec![];
+    let mut payload_vecs: Vec<serde_json::Value> = vec![];
+
+    for event in events {
+        event_id_vecs.push(event.event_id);
+        event_type_vecs.push(event.event_type.to_string());
+        user_id_vecs.push(event.user_id);
+        created_at_vecs.push(event.occurred_at.naive_utc());
+        payload_vecs.push(serde_json::to_value(event).map_err(|e| {
+            error!(error = ?e, "failed to serialise webhook event");
+
+            YuhuhError::InternalServerError(e.to_string())
+        })?);
+    }
+
+    sqlx::query!(
+        r#"
+        INSERT INTO webhook_outbox (
+            event_id,
+            event_type,
+            user_id,
+            created_at,
+            payload
+        )
+        SELECT * FROM UNNEST(
+            $1::uuid[],
+            $2::text[],
+            $3::uuid[],
+            $4::timestamp[],
+            $5::jsonb[]
+        )
+        "#,
+        &event_id_vecs[..],
+        &event_type_vecs[..],
+        &user_id_vecs[..],
+        &created_at_vecs[..],
+        &payload_vecs[..]
+    )
+    .execute(connection)
+    .await
+    .map_err(|e| {
+        error!(error = ?e, "database error while enqueuing webhook events");
+
+        YuhuhError::DatabaseError(e)
+    })?;
+
+    Ok(())
+}
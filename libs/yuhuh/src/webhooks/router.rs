@@ -0,0 +1,40 @@
+use axum::{
+    Router,
+    routing::{delete, get},
+};
+use utoipa::OpenApi;
+
+use crate::{state::AppState, webhooks::webhook_subscriptions};
+
+// =============================================================================
+// API Docs
+// =============================================================================
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    webhook_subscriptions::create_webhook,
+    webhook_subscriptions::read_webhooks,
+    webhook_subscriptions::delete_webhook,
+    webhook_subscriptions::read_webhook_deliveries
+))]
+pub struct WebhookApi;
+
+// =============================================================================
+// Router
+// =============================================================================
+
+pub fn webhook_router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/webhooks",
+            get(webhook_subscriptions::read_webhooks).post(webhook_subscriptions::create_webhook),
+        )
+        .route(
+            "/webhooks/{webhook_id}",
+            delete(webhook_subscriptions::delete_webhook),
+        )
+        .route(
+            "/webhooks/{webhook_id}/deliveries",
+            get(webhook_subscriptions::read_webhook_deliveries),
+        )
+}
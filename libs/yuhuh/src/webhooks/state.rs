@@ -0,0 +1,22 @@
+use std::sync::Arc;
+
+use sqlx::PgPool;
+
+use crate::webhooks::webhook_subscriptions::{
+    WebhookSubscriptionsRepository, WebhookSubscriptionsRepositoryImpl,
+};
+
+#[derive(Debug)]
+pub struct WebhookState {
+    pub webhook_subscriptions_repo: Arc<dyn WebhookSubscriptionsRepository>,
+}
+
+impl WebhookState {
+    pub fn new(db: PgPool) -> Self {
+        WebhookState {
+            webhook_subscriptions_repo: Arc::new(WebhookSubscriptionsRepositoryImpl::new(
+                db.clone(),
+            )),
+        }
+    }
+}
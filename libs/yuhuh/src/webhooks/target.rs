@@ -0,0 +1,161 @@
+//! Where webhooks are allowed to send to.
+//!
+//! Users can register webhooks for their own events, so without these checks
+//! anyone could have the server post to loopback, private network or cloud
+//! metadata addresses such as 169.254.169.254. URLs are checked when a
+//! webhook is registered, and because what a host resolves to can change
+//! afterwards, the addresses it resolves to are checked again on every send.
+
+use std::{
+    io,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+};
+
+use reqwest::{
+    Url,
+    dns::{Addrs, Name, Resolve, Resolving},
+};
+use url::Host;
+
+fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        // "This network", carrier-grade NAT, benchmarking and reserved
+        || a == 0
+        || (a == 100 && (64..128).contains(&b))
+        || (a == 198 && (18..20).contains(&b))
+        || a >= 240)
+}
+
+fn is_public_ipv6(ip: Ipv6Addr) -> bool {
+    if let Some(ip) = ip.to_ipv4_mapped() {
+        return is_public_ipv4(ip);
+    }
+
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_multicast()
+        || ip.is_unique_local()
+        || ip.is_unicast_link_local())
+}
+
+/// Whether an address is on the public internet, rather than loopback, a
+/// private network, link-local or otherwise reserved.
+pub fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_ipv4(ip),
+        IpAddr::V6(ip) => is_public_ipv6(ip),
+    }
+}
+
+/// Checks a webhook URL doesn't name a non-public address outright.
+///
+/// Hosts given by name are checked when they're resolved, by
+/// [`PublicResolver`].
+pub fn check_url(url: &Url) -> Result<(), String> {
+    let public = match url.host() {
+        Some(Host::Ipv4(ip)) => is_public_ipv4(ip),
+        Some(Host::Ipv6(ip)) => is_public_ipv6(ip),
+        Some(Host::Domain(domain)) => {
+            let domain = domain.trim_end_matches('.').to_ascii_lowercase();
+
+            domain != "localhost" && !domain.ends_with(".localhost")
+        }
+        None => false,
+    };
+
+    if !public {
+        return Err(format!(
+            "webhooks can't send to {}, as it isn't a public address",
+            url.host_str().unwrap_or_default()
+        ));
+    }
+
+    Ok(())
+}
+
+/// Resolves hosts for webhook deliveries, refusing any that resolve to a
+/// non-public address.
+///
+/// Checking the addresses the connection is made to, rather than resolving
+/// separately beforehand, means a host can't pass the check and then resolve
+/// somewhere else when connecting.
+#[derive(Debug, Default)]
+pub struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addrs: Vec<_> = tokio::net::lookup_host((name.as_str(), 0)).await?.collect();
+
+            if let Some(addr) = addrs.iter().find(|addr| !is_public(addr.ip())) {
+                return Err(io::Error::new(
+                    io::ErrorKind::PermissionDenied,
+                    format!(
+                        "{} resolves to {}, which isn't a public address",
+                        name.as_str(),
+                        addr.ip()
+                    ),
+                )
+                .into());
+            }
+
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_public_addresses_allowed() {
+        for ip in ["93.184.215.14", "8.8.8.8", "2606:4700:4700::1111"] {
+            assert!(is_public(ip.parse().unwrap()), "{}", ip);
+        }
+
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "255.255.255.255",
+            "::1",
+            "::",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+            "::ffff:169.254.169.254",
+        ] {
+            assert!(!is_public(ip.parse().unwrap()), "{}", ip);
+        }
+
+        let check = |url: &str| check_url(&Url::parse(url).unwrap());
+        assert!(check("https://example.com/hooks").is_ok());
+        assert!(check("https://93.184.215.14/hooks").is_ok());
+        assert!(check("http://169.254.169.254/latest/meta-data").is_err());
+        assert!(check("http://[::1]:8080/").is_err());
+        assert!(check("http://localhost:8080/").is_err());
+        assert!(check("http://api.localhost./").is_err());
+        assert!(check("http://0x7f000001/").is_err());
+    }
+
+    #[tokio::test]
+    async fn hosts_resolving_to_private_addresses_refused() {
+        let resolve = |host: &str| PublicResolver.resolve(host.parse().unwrap());
+
+        assert!(resolve("localhost").await.is_err());
+        assert!(resolve("127.0.0.1").await.is_err());
+    }
+}
@@ -0,0 +1,427 @@
+//! webhook HTTP handlers
+//!
+//! This module provides HTTP endpoints for integrators to register webhooks,
+//! either for one user's events or for every user's, and to inspect how
+//! delivery to them is going.
+
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+};
+use chrono::Utc;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, instrument};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::{
+    auth::model::Caller,
+    config::Config,
+    error::YuhuhError,
+    user::state::UserState,
+    webhooks::{
+        model::{EventType, WebhookDelivery, WebhookSubscription},
+        signature::generate_secret,
+        state::WebhookState,
+        target,
+    },
+};
+
+/// Most webhooks a user, or the global scope, can have.
+const MAX_WEBHOOKS: usize = 10;
+
+/// Longest URL a webhook can have.
+const MAX_URL_LENGTH: usize = 2048;
+
+/// Deliveries returned when no limit is given.
+const DEFAULT_DELIVERIES_LIMIT: u32 = 20;
+
+/// Most deliveries returned at once.
+const MAX_DELIVERIES_LIMIT: u32 = 100;
+
+// ============================================================================
+// HTTP Request types
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateWebhookRequest {
+    /// User whose events are sent, every user's when not set.
+    pub user_id: Option<Uuid>,
+    /// `http` or `https` URL events are posted to.
+    pub url: String,
+    /// Event types to send, every type when empty.
+    #[serde(default)]
+    pub event_types: Vec<EventType>,
+}
+
+/// Request parameters for finding webhooks.
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ReadWebhooksRequest {
+    /// user ID to search by, finding the global webhooks when not set.
+    pub user_id: Option<Uuid>,
+}
+
+/// Request parameters for finding a webhook's deliveries.
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ReadWebhookDeliveriesRequest {
+    /// Defaults to 20, at most 100.
+    pub limit: Option<u32>,
+}
+
+// ============================================================================
+// HTTP Responsed types
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateWebhookResponse {
+    pub webhook: WebhookSubscription,
+    /// Secret deliveries are signed with. Only ever returned here.
+    pub secret: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ReadWebhooksResponse {
+    pub webhooks: Vec<WebhookSubscription>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ReadWebhookDeliveriesResponse {
+    pub deliveries: Vec<WebhookDelivery>,
+}
+
+// =============================================================================
+// Implementations
+// =============================================================================
+
+/// Checks the caller can manage webhooks for a user, or the global ones
+/// when `user_id` is `None`, which get every user's events so are only for
+/// services.
+fn authorise_webhook_user(caller: &Caller, user_id: Option<&Uuid>) -> Result<(), YuhuhError> {
+    match user_id {
+        Some(user_id) => caller.authorise_user(user_id),
+        None => caller.authorise_service(),
+    }
+}
+
+fn validate_url(url: &str, allow_private: bool) -> Result<Url, YuhuhError> {
+    if url.len() > MAX_URL_LENGTH {
+        return Err(YuhuhError::BadRequest(format!(
+            "url must be at most {} characters",
+            MAX_URL_LENGTH
+        )));
+    }
+
+    let parsed = Url::parse(url)
+        .map_err(|e| YuhuhError::BadRequest(format!("invalid url {} - {}", url, e)))?;
+
+    if !matches!(parsed.scheme(), "http" | "https") || parsed.host().is_none() {
+        return Err(YuhuhError::BadRequest(format!(
+            "url must be an http or https url, got {}",
+            url
+        )));
+    }
+
+    if !allow_private {
+        target::check_url(&parsed).map_err(YuhuhError::BadRequest)?;
+    }
+
+    Ok(parsed)
+}
+
+// =============================================================================
+// HTTP Handlers
+// =============================================================================
+
+/// Register a webhook for a user's events, or every user's
+#[utoipa::path(
+    post,
+    path = "webhooks",
+    tag = "webhooks",
+    responses(
+        (status = 201, description = "webhook registered", body = CreateWebhookResponse),
+        (status = 400, description = "invalid url, or too many webhooks"),
+        (status = 404, description = "user not found")
+))]
+#[instrument(skip(config, request))]
+pub async fn create_webhook(
+    State(config): State<Arc<Config>>,
+    State(webhook_state): State<Arc<WebhookState>>,
+    State(user_state): State<Arc<UserState>>,
+    caller: Caller,
+    Json(request): Json<CreateWebhookRequest>,
+) -> Result<(StatusCode, Json<CreateWebhookResponse>), YuhuhError> {
+    debug!("entering create_webhook");
+
+    authorise_webhook_user(&caller, request.user_id.as_ref())?;
+
+    let url = validate_url(request.url.trim(), config.webhook_allow_private_urls)?;
+
+    if let Some(user_id) = &request.user_id {
+        user_state.require_user(user_id).await?;
+    }
+
+    let existing = webhook_state
+        .webhook_subscriptions_repo
+        .read_webhooks(request.user_id.as_ref())
+        .await?;
+    if existing.len() >= MAX_WEBHOOKS {
+        return Err(YuhuhError::BadRequest(format!(
+            "at most {} webhooks can be registered for a user, or globally",
+            MAX_WEBHOOKS
+        )));
+    }
+
+    let mut event_types = request.event_types;
+    event_types.sort();
+    event_types.dedup();
+
+    let webhook = WebhookSubscription {
+        webhook_id: Uuid::now_v7(),
+        user_id: request.user_id,
+        url: url.to_string(),
+        event_types,
+        created_at: Utc::now(),
+    };
+    let secret = generate_secret();
+
+    webhook_state
+        .webhook_subscriptions_repo
+        .create_webhook(&webhook, &secret)
+        .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(CreateWebhookResponse { webhook, secret }),
+    ))
+}
+
+/// Find a user's webhooks, or the global webhooks
+#[utoipa::path(
+    get,
+    path = "webhooks",
+    tag = "webhooks",
+    params(ReadWebhooksRequest),
+    responses(
+        (status = 200, description = "found webhooks", body = ReadWebhooksResponse)
+))]
+#[instrument]
+pub async fn read_webhooks(
+    State(webhook_state): State<Arc<WebhookState>>,
+    caller: Caller,
+    Query(request): Query<ReadWebhooksRequest>,
+) -> Result<(StatusCode, Json<ReadWebhooksResponse>), YuhuhError> {
+    debug!("entering read_webhooks");
+
+    authorise_webhook_user(&caller, request.user_id.as_ref())?;
+
+    let webhooks = webhook_state
+        .webhook_subscriptions_repo
+        .read_webhooks(request.user_id.as_ref())
+        .await?;
+
+    Ok((StatusCode::OK, Json(ReadWebhooksResponse { webhooks })))
+}
+
+/// Remove a webhook, abandoning any deliveries still pending
+#[utoipa::path(
+    delete,
+    path = "webhooks/{webhook_id}",
+    tag = "webhooks",
+    params(("webhook_id" = Uuid, Path, description = "Webhook to remove")),
+    responses(
+        (status = 204, description = "webhook removed"),
+        (status = 404, description = "no such webhook")
+))]
+#[instrument]
+pub async fn delete_webhook(
+    State(webhook_state): State<Arc<WebhookState>>,
+    caller: Caller,
+    Path(webhook_id): Path<Uuid>,
+) -> Result<StatusCode, YuhuhError> {
+    debug!("entering delete_webhook");
+
+    let webhook = webhook_state
+        .webhook_subscriptions_repo
+        .find_webhook(&webhook_id)
+        .await?
+        .ok_or_else(|| YuhuhError::NotFound("no such webhook".to_string()))?;
+    authorise_webhook_user(&caller, webhook.user_id.as_ref())?;
+
+    if !webhook_state
+        .webhook_subscriptions_repo
+        .delete_webhook(&webhook_id)
+        .await?
+    {
+        return Err(YuhuhError::NotFound("no such webhook".to_string()));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Find a webhook's most recent deliveries along with every attempt made
+#[utoipa::path(
+    get,
+    path = "webhooks/{webhook_id}/deliveries",
+    tag = "webhooks",
+    params(
+        ("webhook_id" = Uuid, Path, description = "Webhook to find the deliveries of"),
+        ReadWebhookDeliveriesRequest
+    ),
+    responses(
+        (status = 200, description = "found deliveries", body = ReadWebhookDeliveriesResponse),
+        (status = 400, description = "invalid limit"),
+        (status = 404, description = "no such webhook")
+))]
+#[instrument]
+pub async fn read_webhook_deliveries(
+    State(webhook_state): State<Arc<WebhookState>>,
+    caller: Caller,
+    Path(webhook_id): Path<Uuid>,
+    Query(request): Query<ReadWebhookDeliveriesRequest>,
+) -> Result<(StatusCode, Json<ReadWebhookDeliveriesResponse>), YuhuhError> {
+    debug!("entering read_webhook_deliveries");
+
+    let webhook = webhook_state
+        .webhook_subscriptions_repo
+        .find_webhook(&webhook_id)
+        .await?
+        .ok_or_else(|| YuhuhError::NotFound("no such webhook".to_string()))?;
+    authorise_webhook_user(&caller, webhook.user_id.as_ref())?;
+
+    let limit = request.limit.unwrap_or(DEFAULT_DELIVERIES_LIMIT);
+    if !(1..=MAX_DELIVERIES_LIMIT).contains(&limit) {
+        return Err(YuhuhError::BadRequest(format!(
+            "limit must be between 1 and {}",
+            MAX_DELIVERIES_LIMIT
+        )));
+    }
+
+    let deliveries = webhook_state
+        .webhook_subscriptions_repo
+        .read_deliveries(&webhook_id, limit.into())
+        .await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(ReadWebhookDeliveriesResponse { deliveries }),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use pretty_assertions::assert_eq;
+
+    use axum::{
+        body::Body,
+        http::{self, Request, StatusCode},
+    };
+    use http_body_util::BodyExt;
+    use tower::ServiceExt;
+    use uuid::uuid;
+
+    use crate::webhooks::{
+        model::EventType,
+        webhook_subscriptions::{CreateWebhookRequest, CreateWebhookResponse},
+    };
+
+    fn post(request: &CreateWebhookRequest) -> Request<Body> {
+        Request::builder()
+            .method("POST")
+            .uri("/webhooks")
+            .header(http::header::CONTENT_TYPE, "application/json")
+            .body(Body::from(
+                serde_json::to_string(request).expect("request is valid body"),
+            ))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn webhooks_registered_and_removed() {
+        let (app, db, state) = crate::test::common::setup().await;
+
+        sqlx::raw_sql(include_str!("../../migrations/test/webhooks.sql"))
+            .execute(&db)
+            .await
+            .expect("setup test sql ran successfully");
+
+        let user_id = uuid!("11111111-1111-1111-1111-111111111111");
+        let request = CreateWebhookRequest {
+            user_id: Some(user_id),
+            url: "https://example.com/hooks/yuhuh".to_string(),
+            event_types: vec![
+                EventType::MoodCreated,
+                EventType::FoodCreated,
+                EventType::MoodCreated,
+            ],
+        };
+
+        let response = app.clone().oneshot(post(&request)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let created: CreateWebhookResponse =
+            serde_json::from_slice(&body).expect("valid CreateWebhookResponse bytes");
+        assert!(created.secret.starts_with("whsec_"));
+        assert_eq!(
+            created.webhook.event_types,
+            vec![EventType::FoodCreated, EventType::MoodCreated]
+        );
+
+        // Only the user's webhooks, not the global ones
+        let repo = &state.webhooks.webhook_subscriptions_repo;
+        let webhooks = repo.read_webhooks(Some(&user_id)).await.unwrap();
+        assert_eq!(
+            webhooks.iter().map(|w| w.webhook_id).collect::<Vec<_>>(),
+            vec![created.webhook.webhook_id]
+        );
+        assert_eq!(repo.read_webhooks(None).await.unwrap(), vec![]);
+
+        for url in [
+            "ftp://example.com",
+            "not a url",
+            "https://",
+            "http://169.254.169.254/latest/meta-data",
+            "http://localhost:8080/hooks",
+        ] {
+            let response = app
+                .clone()
+                .oneshot(post(&CreateWebhookRequest {
+                    user_id: None,
+                    url: url.to_string(),
+                    event_types: vec![],
+                }))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{}", url);
+        }
+
+        let response = app
+            .clone()
+            .oneshot(post(&CreateWebhookRequest {
+                user_id: Some(uuid!("33333333-3333-3333-3333-333333333333")),
+                ..request
+            }))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let delete = || {
+            Request::builder()
+                .method("DELETE")
+                .uri(format!("/webhooks/{}", created.webhook.webhook_id))
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let response = app.clone().oneshot(delete()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+        let response = app.oneshot(delete()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,269 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use tracing::{debug, error};
+use uuid::Uuid;
+
+use crate::{
+    error::YuhuhError,
+    webhooks::model::{
+        DeliveryAttempt, DeliveryAttemptRow, WebhookDelivery, WebhookDeliveryRow,
+        WebhookSubscription, WebhookSubscriptionRow,
+    },
+};
+
+// =============================================================================
+// Traits
+// =============================================================================
+
+#[async_trait]
+pub trait WebhookSubscriptionsRepository: std::fmt::Debug + Send + Sync + 'static {
+    async fn create_webhook(
+        &self,
+        webhook: &WebhookSubscription,
+        secret: &str,
+    ) -> Result<(), YuhuhError>;
+
+    /// Webhooks for a user, or the global webhooks when `user_id` is `None`.
+    async fn read_webhooks(
+        &self,
+        user_id: Option<&Uuid>,
+    ) -> Result<Vec<WebhookSubscription>, YuhuhError>;
+
+    async fn find_webhook(
+        &self,
+        webhook_id: &Uuid,
+    ) -> Result<Option<WebhookSubscription>, YuhuhError>;
+
+    /// Removes a webhook along with its deliveries, returning whether there
+    /// was one.
+    async fn delete_webhook(&self, webhook_id: &Uuid) -> Result<bool, YuhuhError>;
+
+    /// A webhook's most recent deliveries, newest first.
+    async fn read_deliveries(
+        &self,
+        webhook_id: &Uuid,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>, YuhuhError>;
+}
+
+// =============================================================================
+// Production Implementation
+// =============================================================================
+
+#[derive(Debug)]
+pub struct WebhookSubscriptionsRepositoryImpl {
+    pub db: PgPool,
+}
+
+impl WebhookSubscriptionsRepositoryImpl {
+    pub fn new(db: PgPool) -> Self {
+        WebhookSubscriptionsRepositoryImpl { db }
+    }
+}
+
+#[async_trait]
+impl WebhookSubscriptionsRepository for WebhookSubscriptionsRepositoryImpl {
+    async fn create_webhook(
+        &self,
+        webhook: &WebhookSubscription,
+        secret: &str,
+    ) -> Result<(), YuhuhError> {
+        debug!(webhook=?webhook, "creating webhook");
+
+        let event_types: Vec<String> = webhook.event_types.iter().map(|t| t.to_string()).collect();
+
+        sqlx::query!(
+            r#"
+            INSERT INTO webhook_subscriptions (
+                webhook_id,
+                user_id,
+                created_at,
+                url,
+                secret,
+                event_types
+            )
+            VALUES ($1::uuid, $2::uuid, $3::timestamptz, $4::text, $5::text, $6::text[]);
+            "#,
+            webhook.webhook_id,
+            webhook.user_id,
+            webhook.created_at,
+            webhook.url,
+            secret,
+            &event_types
+        )
+        .execute(&self.db)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, "database error while creating webhook");
+
+            YuhuhError::DatabaseError(e)
+        })?;
+
+        Ok(())
+    }
+
+    async fn read_webhooks(
+        &self,
+        user_id: Option<&Uuid>,
+    ) -> Result<Vec<WebhookSubscription>, YuhuhError> {
+        let records: Vec<WebhookSubscriptionRow> = sqlx::query_as!(
+            WebhookSubscriptionRow,
+            r#"
+            SELECT
+                webhook_id,
+                user_id,
+                url,
+                event_types,
+                created_at
+            FROM webhook_subscriptions
+            WHERE user_id IS NOT DISTINCT FROM $1::uuid
+            ORDER BY webhook_id;
+            "#,
+            user_id
+        )
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, "database error while reading webhooks");
+
+            YuhuhError::DatabaseError(e)
+        })?;
+
+        let webhooks = records
+            .into_iter()
+            .map(WebhookSubscription::try_from)
+            .collect::<Result<Vec<WebhookSubscription>, _>>()
+            .inspect_err(|e| error!(error=?e, "encountered parsing error for webhook"))?;
+
+        Ok(webhooks)
+    }
+
+    async fn find_webhook(
+        &self,
+        webhook_id: &Uuid,
+    ) -> Result<Option<WebhookSubscription>, YuhuhError> {
+        let record: Option<WebhookSubscriptionRow> = sqlx::query_as!(
+            WebhookSubscriptionRow,
+            r#"
+            SELECT
+                webhook_id,
+                user_id,
+                url,
+                event_types,
+                created_at
+            FROM webhook_subscriptions
+            WHERE webhook_id = $1::uuid;
+            "#,
+            webhook_id
+        )
+        .fetch_optional(&self.db)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, "database error while finding webhook");
+
+            YuhuhError::DatabaseError(e)
+        })?;
+
+        let webhook = record
+            .map(WebhookSubscription::try_from)
+            .transpose()
+            .inspect_err(|e| error!(error=?e, "encountered parsing error for webhook"))?;
+
+        Ok(webhook)
+    }
+
+    async fn delete_webhook(&self, webhook_id: &Uuid) -> Result<bool, YuhuhError> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM webhook_subscriptions
+            WHERE webhook_id = $1::uuid;
+            "#,
+            webhook_id
+        )
+        .execute(&self.db)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, "database error while deleting webhook");
+
+            YuhuhError::DatabaseError(e)
+        })?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn read_deliveries(
+        &self,
+        webhook_id: &Uuid,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>, YuhuhError> {
+        let records: Vec<WebhookDeliveryRow> = sqlx::query_as!(
+            WebhookDeliveryRow,
+            r#"
+            SELECT
+                d.delivery_id,
+                d.event_id,
+                o.event_type,
+                d.created_at,
+                d.next_attempt_at,
+                d.delivered_at,
+                d.failed_at
+            FROM webhook_deliveries d
+            JOIN webhook_outbox o ON o.event_id = d.event_id
+            WHERE d.webhook_id = $1::uuid
+            ORDER BY d.delivery_id DESC
+            LIMIT $2;
+            "#,
+            webhook_id,
+            limit
+        )
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, "database error while reading webhook deliveries");
+
+            YuhuhError::DatabaseError(e)
+        })?;
+
+        let mut deliveries = records
+            .into_iter()
+            .map(WebhookDelivery::try_from)
+            .collect::<Result<Vec<WebhookDelivery>, _>>()
+            .inspect_err(|e| error!(error=?e, "encountered parsing error for webhook delivery"))?;
+
+        let delivery_ids: Vec<Uuid> = deliveries.iter().map(|d| d.delivery_id).collect();
+
+        let attempts: Vec<DeliveryAttemptRow> = sqlx::query_as!(
+            DeliveryAttemptRow,
+            r#"
+            SELECT
+                delivery_id,
+                attempted_at,
+                status_code,
+                error,
+                duration_ms
+            FROM webhook_delivery_attempts
+            WHERE delivery_id = ANY($1::uuid[])
+            ORDER BY attempted_at, delivery_attempt_id;
+            "#,
+            &delivery_ids
+        )
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, "database error while reading webhook delivery attempts");
+
+            YuhuhError::DatabaseError(e)
+        })?;
+
+        for attempt in attempts {
+            if let Some(delivery) = deliveries
+                .iter_mut()
+                .find(|d| d.delivery_id == attempt.delivery_id)
+            {
+                delivery.attempts.push(DeliveryAttempt::from(attempt));
+            }
+        }
+
+        Ok(deliveries)
+    }
+}